@@ -1,18 +1,20 @@
-use csl::{diffeq::ivp::RungeKutta4, linalg::ndarray::Vector};
+use csl::{
+    diffeq::solve::{solve_ivp, SolveOptions},
+    linalg::ndarray::Vector,
+};
 
 fn main() {
     let initial_state = Vector { data: [[100.0]] };
-    let mut solver = RungeKutta4::new(0.0, initial_state);
+    let t_eval = (0..=100).map(|i| i as f32 * 0.1).collect();
 
-    let delta_time = 0.1;
+    let solution = solve_ivp(
+        |_time, curr_t| -0.07 * (curr_t + -1.0 * Vector { data: [[20.0]] }),
+        (0.0, 10.0),
+        initial_state,
+        SolveOptions::new().with_t_eval(t_eval),
+    );
 
-    println!("{:>5.2}s: {:>7.3}°C", 0.0, initial_state[0]);
-    for time in (0..=100).into_iter().map(|i| i as f32 * delta_time) {
-        let curr_state = solver.next_step(
-            &|_time, curr_t| -0.07 * (curr_t + -1.0 * Vector { data: [[20.0]] }),
-            delta_time,
-        );
-
-        println!("{:>5.2}s: {:>7.3}°C", time, curr_state[0]);
+    for (time, state) in solution.times.iter().zip(&solution.states) {
+        println!("{:>5.2}s: {:>7.3}°C", time, state[0]);
     }
 }
@@ -0,0 +1,61 @@
+use csl::{
+    diffeq::ivp::RungeKutta4,
+    linalg::{
+        autograd::Tensor,
+        ndarray::{NdArray, Vector},
+    },
+    nn::{
+        activation::Tanh,
+        layer::{Linear, Module, Sequential},
+        loss::mse_loss,
+        optim::{Adam, Optimizer},
+    },
+};
+
+fn main() {
+    // Learn the right-hand side of Newton's law of cooling from samples
+    let temperatures = NdArray::from_fn(&[32, 1], |i| 20.0 + 80.0 * i[0] as f32 / 31.0);
+    let rates = temperatures.map(|temp| -0.07 * (temp - 20.0));
+
+    // Scale inputs and outputs to order one for training
+    let input = Tensor::new(temperatures.map(|temp| temp / 100.0));
+    let target = Tensor::new(rates.map(|rate| rate / 10.0));
+
+    let model = Sequential::new()
+        .with(Linear::new(1, 16))
+        .with(Tanh)
+        .with(Linear::new(16, 1));
+    let mut optimizer = Adam::new(model.parameters(), 0.01);
+
+    for epoch in 0..=2000 {
+        optimizer.zero_grad();
+        let loss = mse_loss(&model.forward(&input), &target);
+        loss.backward();
+        optimizer.step();
+
+        if epoch % 500 == 0 {
+            println!("epoch {:>4}: loss {:.3e}", epoch, loss.item());
+        }
+    }
+
+    // Use the trained network as the right-hand side of an initial value problem
+    let rhs = move |_time, temp: Vector<1>| {
+        let scaled = Tensor::new(NdArray::from(temp).map(|temp| temp / 100.0));
+        10.0 * model.forward(&scaled).value().to_vector::<1>()
+    };
+
+    let delta_time = 0.1;
+    let mut solver = RungeKutta4::new(0.0, Vector { data: [[100.0]] });
+    for step in 1..=100 {
+        let state = solver.next_step(&rhs, delta_time);
+        if step % 20 == 0 {
+            let exact = 20.0 + 80.0 * f32::exp(-0.07 * step as f32 * delta_time);
+            println!(
+                "{:>5.2}s: {:>7.3}°C (exact {:>7.3}°C)",
+                step as f32 * delta_time,
+                state[0],
+                exact
+            );
+        }
+    }
+}
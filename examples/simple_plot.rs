@@ -1,15 +1,14 @@
-use std::{f32::consts::PI, iter::zip};
-
 extern crate csl;
 
-#[cfg(feature="plotting")]
+#[cfg(feature = "plotting")]
 fn main() {
     use csl::plot::{
         figure::figure::FigureProperties,
         graph::{GraphProperties, Point},
         window::PlotWindowProperties,
     };
-    
+    use std::{f32::consts::PI, iter::zip};
+
     // Initialize data points
     let x = (0..=100).map(|x| (x as f32) / 100.0);
     let y = x.clone().map(|x| f32::sin(2.0 * PI * x));
@@ -39,6 +38,4 @@ fn main() {
 }
 
 #[cfg(not(feature = "plotting"))]
-fn main() {
-
-}
\ No newline at end of file
+fn main() {}
@@ -0,0 +1,43 @@
+extern crate csl;
+
+#[cfg(feature = "plotting")]
+fn main() {
+    use csl::{
+        diffeq::{adaptive::Rk45, control::Tolerances, stream::Stepper},
+        linalg::ndarray::Vector,
+        plot::{
+            figure::figure::FigureProperties, graph::GraphProperties, stream::PointStream,
+            window::PlotWindowProperties,
+        },
+    };
+
+    // Damped oscillator, whose phase portrait spirals into the origin
+    let solver = Rk45::new(0.0, Vector::from([0.9, 0.0]), Tolerances::new(1e-6, 1e-8));
+    let steps = solver
+        .into_steps(|_, y| Vector::from([y[1], -4.0 * y[0] - 0.3 * y[1]]), 0.02)
+        .take_while(|(time, _)| *time <= 30.0);
+
+    let mut window = csl::plot::window::PlotWindow::new(PlotWindowProperties {
+        width: 500,
+        height: 500,
+        title: "Streaming ODE solution".to_string(),
+        ..Default::default()
+    });
+
+    // The trajectory grows by three time units per second
+    window.add_figure(FigureProperties {
+        graphs: vec![GraphProperties {
+            stream: Some(
+                PointStream::trajectory(steps, |_, y: &Vector<2>| [y[0], 0.5 * y[1]])
+                    .with_time_scale(3.0),
+            ),
+            ..Default::default()
+        }],
+        ..Default::default()
+    });
+
+    window.run();
+}
+
+#[cfg(not(feature = "plotting"))]
+fn main() {}
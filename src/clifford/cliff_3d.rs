@@ -1,15 +1,25 @@
 // This is only a test module to see how this will evolve
 // This will change in the future!
 
-use std::ops::{Add, AddAssign};
+use std::ops::{Add, AddAssign, Mul, Sub};
 
 // TODO: Implement with ndarrays
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub struct Clifford {
     factors: [f32; 8], // these are the factors of the Clifford algebra in 3D
 }
 
+impl Clifford {
+    pub fn new(factors: [f32; 8]) -> Self {
+        Clifford { factors }
+    }
+
+    pub fn factors(&self) -> &[f32; 8] {
+        &self.factors
+    }
+}
+
 impl Add for Clifford {
     type Output = Self;
     fn add(self, rhs: Self) -> Self::Output {
@@ -28,3 +38,25 @@ impl AddAssign for Clifford {
         self.factors = (*self + rhs).factors;
     }
 }
+
+impl Sub for Clifford {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self::Output {
+        let mut factors = [0.0; 8];
+
+        for (i, (a, b)) in self.factors.iter().zip(rhs.factors).enumerate() {
+            factors[i] = a - b;
+        }
+
+        Clifford { factors }
+    }
+}
+
+impl Mul<f32> for Clifford {
+    type Output = Self;
+    fn mul(self, rhs: f32) -> Self::Output {
+        Clifford {
+            factors: self.factors.map(|factor| factor * rhs),
+        }
+    }
+}
@@ -0,0 +1,349 @@
+use std::marker::PhantomData;
+
+use super::{
+    control::{
+        error_norm, initial_step_size, min_step_size, Statistics, StepController, Tolerances,
+    },
+    dense::{DenseSolution, Interpolant},
+    error::SolverError,
+    event::{integrate_with_events, Event, EventLog},
+    ivp::{combine_stages, compute_stages, SolveFun},
+    solver::OdeSolver,
+    state::OdeState,
+    stream::{advance_adaptive, Stepper},
+    tableau::{BogackiShampine, DormandPrince, DormandPrince853, EmbeddedTableau, Tsitouras},
+};
+
+/// Explicit Runge-Kutta solver that adapts its step size to keep the local
+/// error estimate of the embedded pair `T` within the tolerances.
+pub struct AdaptiveRungeKutta<T: EmbeddedTableau, Y: OdeState> {
+    time: f32,
+    state: Y,
+    /// `f(time, state)`, reused as the first stage of the next step.
+    derivative: Option<Y>,
+    /// Magnitude of the next step to attempt.
+    step_size: Option<f32>,
+    last_step_size: f32,
+    /// Time and state at the start of the last accepted step.
+    previous: Option<(f32, Y)>,
+    /// Stage derivatives of the last accepted step followed by `f(t + h, y_next)`.
+    stages: Vec<Y>,
+    tolerances: Tolerances<Y>,
+    controller: StepController,
+    statistics: Statistics,
+    tableau: PhantomData<T>,
+}
+
+pub type Rk23<Y> = AdaptiveRungeKutta<BogackiShampine, Y>;
+pub type Rk45<Y> = AdaptiveRungeKutta<DormandPrince, Y>;
+pub type Tsit5<Y> = AdaptiveRungeKutta<Tsitouras, Y>;
+pub type Dop853<Y> = AdaptiveRungeKutta<DormandPrince853, Y>;
+
+impl<T: EmbeddedTableau, Y: OdeState> AdaptiveRungeKutta<T, Y> {
+    pub fn new(time: f32, initial_state: Y, tolerances: Tolerances<Y>) -> Self {
+        Self {
+            time,
+            stages: vec![initial_state.zero_like(); T::stages() + 1],
+            state: initial_state,
+            derivative: None,
+            step_size: None,
+            last_step_size: 0.0,
+            previous: None,
+            tolerances,
+            controller: StepController::default(),
+            statistics: Statistics::default(),
+            tableau: PhantomData,
+        }
+    }
+
+    /// Uses `step_size` for the first attempt instead of estimating it.
+    pub fn with_first_step(mut self, step_size: f32) -> Self {
+        assert!(step_size > 0.0, "the first step size must be positive");
+        self.step_size = Some(step_size);
+        self
+    }
+
+    pub fn with_max_step(mut self, max_step: f32) -> Self {
+        assert!(max_step > 0.0, "the maximum step size must be positive");
+        self.controller.max_step = max_step;
+        self
+    }
+
+    pub fn with_controller(mut self, controller: StepController) -> Self {
+        self.controller = controller;
+        self
+    }
+
+    pub fn time(&self) -> f32 {
+        self.time
+    }
+
+    pub fn state(&self) -> &Y {
+        &self.state
+    }
+
+    /// Magnitude of the step that will be attempted next, once known.
+    pub fn step_size(&self) -> Option<f32> {
+        self.step_size
+    }
+
+    /// Signed size of the last accepted step.
+    pub fn last_step_size(&self) -> f32 {
+        self.last_step_size
+    }
+
+    /// Stage derivatives of the last accepted step, followed by `f(t, y)` at
+    /// the end of the step.
+    pub fn stages(&self) -> &[Y] {
+        &self.stages
+    }
+
+    pub fn tolerances(&self) -> &Tolerances<Y> {
+        &self.tolerances
+    }
+
+    pub fn statistics(&self) -> &Statistics {
+        &self.statistics
+    }
+
+    /// Takes one accepted step towards `time_bound` without stepping past it.
+    ///
+    /// Rejected attempts are retried with a smaller step until the error
+    /// estimate meets the tolerances.
+    pub fn step(&mut self, fun: &SolveFun<Y>, time_bound: f32) -> Result<&Y, SolverError> {
+        if time_bound == self.time {
+            return Ok(&self.state);
+        }
+        let direction = (time_bound - self.time).signum();
+        let derivative = match &self.derivative {
+            Some(derivative) => derivative.clone(),
+            None => {
+                self.statistics.evaluations += 1;
+                fun(self.time, self.state.clone())
+            }
+        };
+        let mut step_size = match self.step_size {
+            Some(step_size) => step_size,
+            None => {
+                self.statistics.evaluations += 1;
+                initial_step_size(
+                    fun,
+                    self.time,
+                    &self.state,
+                    &derivative,
+                    direction,
+                    T::ERROR_ORDER,
+                    &self.tolerances,
+                )
+            }
+        }
+        .min(self.controller.max_step);
+
+        let min_step = min_step_size(self.time);
+        let mut rejected = false;
+        loop {
+            if step_size < min_step {
+                return Err(SolverError::StepSizeTooSmall {
+                    time: self.time,
+                    step_size,
+                });
+            }
+            let mut delta_time = step_size * direction;
+            let mut next_time = self.time + delta_time;
+            if direction * (next_time - time_bound) > 0.0 {
+                next_time = time_bound;
+            }
+            delta_time = next_time - self.time;
+            step_size = delta_time.abs();
+
+            let stages = T::stages();
+            compute_stages::<T, Y, f32>(
+                fun,
+                self.time,
+                &self.state,
+                delta_time,
+                &mut self.stages[..stages],
+                Some(derivative.clone()),
+            );
+            let next_state = combine_stages(&self.state, delta_time, &self.stages[..stages], T::B);
+            self.stages[stages] = fun(next_time, next_state.clone());
+            self.statistics.evaluations += stages;
+
+            let error = self.error_norm(delta_time, &next_state);
+            let factor = self.controller.factor(error, T::ERROR_ORDER, rejected);
+            if error < 1.0 {
+                self.statistics.accepted_steps += 1;
+                self.previous = Some((self.time, std::mem::replace(&mut self.state, next_state)));
+                self.time = next_time;
+                self.derivative = Some(self.stages[stages].clone());
+                self.step_size = Some(step_size * factor);
+                self.last_step_size = delta_time;
+                return Ok(&self.state);
+            }
+            self.statistics.rejected_steps += 1;
+            rejected = true;
+            step_size *= factor;
+        }
+    }
+
+    /// Steps until `end_time` is reached exactly.
+    pub fn integrate_to(&mut self, fun: &SolveFun<Y>, end_time: f32) -> Result<&Y, SolverError> {
+        while self.time != end_time {
+            self.step(fun, end_time)?;
+        }
+        Ok(&self.state)
+    }
+
+    /// Continues the integration from `time` and `state`, e.g. after the state
+    /// was changed discontinuously. The next step size is kept.
+    pub fn restart(&mut self, time: f32, state: Y) {
+        self.time = time;
+        self.state = state;
+        self.derivative = None;
+        self.previous = None;
+    }
+
+    /// Steps until `end_time` or a terminal event, see [`integrate_with_events`].
+    pub fn integrate_with_events(
+        &mut self,
+        fun: &SolveFun<Y>,
+        end_time: f32,
+        events: &[Event<Y>],
+    ) -> Result<EventLog<Y>, SolverError> {
+        integrate_with_events(self, fun, end_time, events)
+    }
+
+    /// Steps until `end_time` and keeps the interpolant of every step.
+    pub fn integrate_dense(
+        &mut self,
+        fun: &SolveFun<Y>,
+        end_time: f32,
+    ) -> Result<DenseSolution<Y>, SolverError> {
+        let mut solution = DenseSolution::new();
+        while self.time != end_time {
+            self.step(fun, end_time)?;
+            solution.push(self.interpolant(fun).unwrap());
+        }
+        Ok(solution)
+    }
+
+    /// Interpolant of the last accepted step, `None` before the first step.
+    ///
+    /// Uses the continuous extension of the tableau when it has one and a
+    /// cubic Hermite polynomial otherwise. DOP853 evaluates three extra stages.
+    pub fn interpolant(&mut self, fun: &SolveFun<Y>) -> Option<Interpolant<Y>> {
+        let (start_time, start_state) = self.previous.clone()?;
+        let delta_time = self.last_step_size;
+        if !T::D.is_empty() {
+            return Some(self.dop853_interpolant(fun, start_time, start_state));
+        }
+        if T::P.is_empty() {
+            return Some(Interpolant::hermite(
+                start_time,
+                start_state,
+                self.stages[0].clone(),
+                self.time,
+                self.state.clone(),
+                self.stages[T::stages()].clone(),
+            ));
+        }
+
+        let zero = self.state.zero_like();
+        let coefficients = (0..T::P[0].len())
+            .map(|j| {
+                let weights: Vec<f64> = T::P.iter().map(|row| row[j]).collect();
+                combine_stages(&zero, delta_time, &self.stages, &weights)
+            })
+            .collect();
+        Some(Interpolant::new(
+            start_time,
+            self.time,
+            start_state,
+            coefficients,
+        ))
+    }
+
+    fn dop853_interpolant(
+        &mut self,
+        fun: &SolveFun<Y>,
+        start_time: f32,
+        start_state: Y,
+    ) -> Interpolant<Y> {
+        let delta_time = self.last_step_size;
+        let mut stages = self.stages.clone();
+        for (row, c) in T::EXTRA_A.iter().zip(T::EXTRA_C) {
+            let stage_state = combine_stages(&start_state, delta_time, &stages, row);
+            stages.push(fun(start_time + delta_time * *c as f32, stage_state));
+        }
+        self.statistics.evaluations += T::EXTRA_C.len();
+
+        let delta_state = self.state.clone() - start_state.clone();
+        let start_slope = self.stages[0].clone() * delta_time;
+        let end_slope = self.stages[T::stages()].clone() * delta_time;
+        let zero = self.state.zero_like();
+        let mut nested = vec![
+            delta_state.clone(),
+            start_slope.clone() - delta_state.clone(),
+            delta_state * 2.0 - start_slope - end_slope,
+        ];
+        nested.extend(
+            T::D.iter()
+                .map(|row| combine_stages(&zero, delta_time, &stages, row)),
+        );
+        Interpolant::from_nested(start_time, self.time, start_state, &nested)
+    }
+
+    fn error_norm(&self, delta_time: f32, next_state: &Y) -> f32 {
+        let scale = self.tolerances.scale(&self.state, next_state);
+        let zero = self.state.zero_like();
+        let error = combine_stages(&zero, delta_time, &self.stages, T::E);
+        if T::E3.is_empty() {
+            return error_norm(&error, &scale);
+        }
+
+        // DOP853 blends its fifth and third order estimates, see Hairer's `dop853.f`
+        let error3 = combine_stages(&zero, delta_time, &self.stages, T::E3);
+        let norm5 = error_norm(&error, &scale).powi(2);
+        let norm3 = error_norm(&error3, &scale).powi(2);
+        if norm5 == 0.0 && norm3 == 0.0 {
+            return 0.0;
+        }
+        norm5 / (norm5 + 0.01 * norm3).sqrt()
+    }
+}
+
+impl<T: EmbeddedTableau, Y: OdeState> OdeSolver<Y> for AdaptiveRungeKutta<T, Y> {
+    fn time(&self) -> f32 {
+        self.time
+    }
+
+    fn state(&self) -> &Y {
+        &self.state
+    }
+
+    fn statistics(&self) -> &Statistics {
+        &self.statistics
+    }
+
+    fn step(&mut self, fun: &SolveFun<Y>, time_bound: f32) -> Result<&Y, SolverError> {
+        AdaptiveRungeKutta::step(self, fun, time_bound)
+    }
+
+    fn restart(&mut self, time: f32, state: Y) {
+        AdaptiveRungeKutta::restart(self, time, state)
+    }
+
+    fn interpolant(&mut self, fun: &SolveFun<Y>) -> Option<Interpolant<Y>> {
+        AdaptiveRungeKutta::interpolant(self, fun)
+    }
+}
+
+impl<T: EmbeddedTableau, Y: OdeState> Stepper<Y> for AdaptiveRungeKutta<T, Y> {
+    fn current(&self) -> (f32, Y) {
+        (self.time, self.state.clone())
+    }
+
+    fn advance(&mut self, fun: &SolveFun<Y>, delta_time: f32) -> Result<(), SolverError> {
+        advance_adaptive(self, fun, delta_time)
+    }
+}
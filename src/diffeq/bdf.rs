@@ -0,0 +1,404 @@
+use crate::linalg::{lu::LuDecomposition, ndarray::NdArray};
+
+use super::{
+    control::{error_norm, initial_step_size, min_step_size, Statistics, Tolerances},
+    error::SolverError,
+    ivp::SolveFun,
+    jacobian::{factorize_shifted, Jacobian},
+    newton::{newton_tolerance, solve_implicit},
+    solver::OdeSolver,
+    state::OdeState,
+    stream::{advance_adaptive, Stepper},
+};
+
+pub const MAX_ORDER: usize = 5;
+const NEWTON_MAX_ITERATIONS: usize = 4;
+const MIN_FACTOR: f32 = 0.2;
+const MAX_FACTOR: f32 = 10.0;
+
+/// Variable order (1 to 5), variable step backward differentiation formulas.
+///
+/// The solution history is kept as backward differences of a quasi-constant
+/// step size, which are rescaled whenever the step size changes, in the
+/// formulation of Shampine and Reichelt, "The MATLAB ODE Suite", also used by
+/// scipy's `BDF`. The order is raised or lowered after `order + 1` steps of
+/// equal size depending on which order promises the largest next step.
+pub struct Bdf<Y: OdeState> {
+    time: f32,
+    state: Y,
+    // Backward differences D_0 = y, D_1 .. D_(MAX_ORDER + 2)
+    differences: Vec<Y>,
+    order: usize,
+    step_size: Option<f32>,
+    max_step: f32,
+    equal_steps: usize,
+    jacobian: Jacobian<Y>,
+    jacobian_matrix: Option<NdArray>,
+    lu: Option<LuDecomposition>,
+    tolerances: Tolerances<Y>,
+    statistics: Statistics,
+    // Coefficients indexed by order
+    gamma: [f32; MAX_ORDER + 2],
+    error_constants: [f32; MAX_ORDER + 2],
+}
+
+impl<Y: OdeState> Bdf<Y> {
+    pub fn new(time: f32, initial_state: Y, tolerances: Tolerances<Y>) -> Self {
+        let (gamma, error_constants) = coefficients();
+        let mut differences = vec![initial_state.zero_like(); MAX_ORDER + 3];
+        differences[0] = initial_state.clone();
+        Self {
+            time,
+            state: initial_state,
+            differences,
+            order: 1,
+            step_size: None,
+            max_step: f32::INFINITY,
+            equal_steps: 0,
+            jacobian: Jacobian::FiniteDifference,
+            jacobian_matrix: None,
+            lu: None,
+            tolerances,
+            statistics: Statistics::default(),
+            gamma,
+            error_constants,
+        }
+    }
+
+    pub fn with_jacobian(mut self, jacobian: Jacobian<Y>) -> Self {
+        self.jacobian = jacobian;
+        self
+    }
+
+    pub fn with_first_step(mut self, step_size: f32) -> Self {
+        assert!(step_size > 0.0, "the first step size must be positive");
+        self.step_size = Some(step_size);
+        self
+    }
+
+    pub fn with_max_step(mut self, max_step: f32) -> Self {
+        assert!(max_step > 0.0, "the maximum step size must be positive");
+        self.max_step = max_step;
+        self
+    }
+
+    pub fn time(&self) -> f32 {
+        self.time
+    }
+
+    pub fn state(&self) -> &Y {
+        &self.state
+    }
+
+    /// Order of the formula used for the next step.
+    pub fn order(&self) -> usize {
+        self.order
+    }
+
+    pub fn step_size(&self) -> Option<f32> {
+        self.step_size
+    }
+
+    pub fn statistics(&self) -> &Statistics {
+        &self.statistics
+    }
+
+    /// Takes one accepted step towards `time_bound` without stepping past it.
+    pub fn step(&mut self, fun: &SolveFun<Y>, time_bound: f32) -> Result<&Y, SolverError> {
+        if time_bound == self.time {
+            return Ok(&self.state);
+        }
+        let direction = (time_bound - self.time).signum();
+        if self.jacobian_matrix.is_none() {
+            self.initialize(fun, direction);
+        }
+
+        let min_step = min_step_size(self.time);
+        let mut step_size = self.step_size.unwrap();
+        if step_size > self.max_step {
+            self.rescale(self.max_step / step_size);
+            step_size = self.max_step;
+        } else if step_size < min_step {
+            self.rescale(min_step / step_size);
+            step_size = min_step;
+        }
+
+        let order = self.order;
+        let alpha = self.gamma[order];
+        let tolerance = newton_tolerance(&self.tolerances);
+        let mut current_jacobian = false;
+        let (next_time, next_state, correction, error, safety, scale) = loop {
+            if step_size < min_step {
+                return Err(SolverError::StepSizeTooSmall {
+                    time: self.time,
+                    step_size,
+                });
+            }
+            let mut next_time = self.time + step_size * direction;
+            if direction * (next_time - time_bound) > 0.0 {
+                next_time = time_bound;
+                self.rescale((next_time - self.time).abs() / step_size);
+            }
+            let delta_time = next_time - self.time;
+            step_size = delta_time.abs();
+
+            let zero = self.state.zero_like();
+            let predicted = self.differences[..=order]
+                .iter()
+                .fold(zero.clone(), |sum, d| sum + d.clone());
+            let scale = self.tolerances.scale(&predicted, &predicted);
+            let psi = (1..=order).fold(zero, |sum, j| {
+                sum + self.differences[j].clone() * self.gamma[j]
+            }) * (1.0 / alpha);
+            let c = delta_time / alpha;
+
+            let solution = loop {
+                if self.lu.is_none() {
+                    self.statistics.lu_decompositions += 1;
+                    let jacobian = self.jacobian_matrix.as_ref().unwrap();
+                    self.lu = factorize_shifted(jacobian, 1.0, c).ok();
+                }
+                let solution = match &self.lu {
+                    Some(lu) => Some(solve_implicit(
+                        fun,
+                        next_time,
+                        &(predicted.clone() - psi.clone()),
+                        c,
+                        predicted.clone(),
+                        lu,
+                        &scale,
+                        tolerance,
+                        NEWTON_MAX_ITERATIONS,
+                        &mut self.statistics,
+                    )),
+                    None => None,
+                };
+                match solution {
+                    Some(solution) if solution.converged => break Some(solution),
+                    _ if current_jacobian => break None,
+                    _ => {
+                        self.jacobian_matrix = Some(self.jacobian.evaluate(
+                            fun,
+                            next_time,
+                            &predicted,
+                            None,
+                            &self.tolerances.absolute,
+                            &mut self.statistics,
+                        ));
+                        self.lu = None;
+                        current_jacobian = true;
+                    }
+                }
+            };
+
+            let Some(solution) = solution else {
+                self.statistics.rejected_steps += 1;
+                step_size *= 0.5;
+                self.rescale(0.5);
+                continue;
+            };
+
+            let safety = 0.9 * (2 * NEWTON_MAX_ITERATIONS + 1) as f32
+                / (2 * NEWTON_MAX_ITERATIONS + solution.iterations) as f32;
+            let correction = solution.state.clone() - predicted;
+            let scale = self.tolerances.scale(&solution.state, &solution.state);
+            let error = error_norm(&(correction.clone() * self.error_constants[order]), &scale);
+            if error > 1.0 {
+                self.statistics.rejected_steps += 1;
+                let factor = (safety * error.powf(-1.0 / (order as f32 + 1.0))).max(MIN_FACTOR);
+                step_size *= factor;
+                self.rescale(factor);
+                continue;
+            }
+            break (next_time, solution.state, correction, error, safety, scale);
+        };
+
+        self.statistics.accepted_steps += 1;
+        self.equal_steps += 1;
+        self.time = next_time;
+        self.state = next_state;
+        self.step_size = Some(step_size);
+
+        let d = &mut self.differences;
+        d[order + 2] = correction.clone() - d[order + 1].clone();
+        d[order + 1] = correction;
+        for i in (0..=order).rev() {
+            d[i] = d[i].clone() + d[i + 1].clone();
+        }
+
+        if self.equal_steps < order + 1 {
+            return Ok(&self.state);
+        }
+
+        // Compare the errors of the neighbouring orders to pick the next one
+        let lower_error = if order > 1 {
+            error_norm(
+                &(d[order].clone() * self.error_constants[order - 1]),
+                &scale,
+            )
+        } else {
+            f32::INFINITY
+        };
+        let higher_error = if order < MAX_ORDER {
+            error_norm(
+                &(d[order + 2].clone() * self.error_constants[order + 1]),
+                &scale,
+            )
+        } else {
+            f32::INFINITY
+        };
+        let factors = [lower_error, error, higher_error]
+            .iter()
+            .enumerate()
+            .map(|(i, e)| e.powf(-1.0 / (order + i) as f32))
+            .collect::<Vec<_>>();
+        let best = (0..3).fold(
+            0,
+            |best, i| if factors[i] > factors[best] { i } else { best },
+        );
+        self.order = order + best - 1;
+
+        let factor = (safety * factors[best]).min(MAX_FACTOR);
+        self.step_size = Some(step_size * factor);
+        self.rescale(factor);
+        Ok(&self.state)
+    }
+
+    /// Steps until `end_time` is reached exactly.
+    pub fn integrate_to(&mut self, fun: &SolveFun<Y>, end_time: f32) -> Result<&Y, SolverError> {
+        while self.time != end_time {
+            self.step(fun, end_time)?;
+        }
+        Ok(&self.state)
+    }
+
+    /// Continues the integration from `time` and `state`, e.g. after the state
+    /// was changed discontinuously. The next step size is
+    /// kept, the order starts again from one.
+    pub fn restart(&mut self, time: f32, state: Y) {
+        self.time = time;
+        self.differences.fill(state.zero_like());
+        self.differences[0] = state.clone();
+        self.state = state;
+        self.order = 1;
+        self.equal_steps = 0;
+        self.jacobian_matrix = None;
+        self.lu = None;
+    }
+
+    fn initialize(&mut self, fun: &SolveFun<Y>, direction: f32) {
+        self.statistics.evaluations += 1;
+        let derivative = fun(self.time, self.state.clone());
+        let step_size = match self.step_size {
+            Some(step_size) => step_size,
+            None => {
+                self.statistics.evaluations += 1;
+                initial_step_size(
+                    fun,
+                    self.time,
+                    &self.state,
+                    &derivative,
+                    direction,
+                    1,
+                    &self.tolerances,
+                )
+            }
+        };
+        self.step_size = Some(step_size);
+        self.differences[1] = derivative.clone() * (step_size * direction);
+        self.jacobian_matrix = Some(self.jacobian.evaluate(
+            fun,
+            self.time,
+            &self.state,
+            Some(&derivative),
+            &self.tolerances.absolute,
+            &mut self.statistics,
+        ));
+    }
+
+    /// Rescales the differences of the current order to a step size `factor` times as large.
+    fn rescale(&mut self, factor: f32) {
+        rescale_differences(&mut self.differences, self.order, factor);
+        self.equal_steps = 0;
+        self.lu = None;
+    }
+}
+
+/// Coefficients `gamma_k = sum_(j <= k) 1 / j` and error constants
+/// `1 / (k + 1)` of the formulas, indexed by order.
+pub(crate) fn coefficients() -> ([f32; MAX_ORDER + 2], [f32; MAX_ORDER + 2]) {
+    let mut gamma = [0.0; MAX_ORDER + 2];
+    let mut error_constants = [0.0; MAX_ORDER + 2];
+    for k in 1..MAX_ORDER + 2 {
+        gamma[k] = gamma[k - 1] + 1.0 / k as f32;
+    }
+    for (k, constant) in error_constants.iter_mut().enumerate() {
+        *constant = 1.0 / (k + 1) as f32;
+    }
+    (gamma, error_constants)
+}
+
+/// Rescales the backward differences `D_0 .. D_order` to a step size
+/// `factor` times as large.
+pub(crate) fn rescale_differences<Y: OdeState>(differences: &mut [Y], order: usize, factor: f32) {
+    let r = difference_transform(order, factor);
+    let u = difference_transform(order, 1.0);
+    let mut rescaled = vec![differences[0].zero_like(); order + 1];
+    for (i, output) in rescaled.iter_mut().enumerate() {
+        for (r_j, d_j) in r.iter().zip(differences.iter()).take(order + 1) {
+            // (R U)^T
+            let ru: f32 = (0..=order).map(|k| r_j[k] * u[k][i]).sum();
+            if ru != 0.0 {
+                *output = output.clone() + d_j.clone() * ru;
+            }
+        }
+    }
+    differences[..=order].clone_from_slice(&rescaled);
+}
+
+/// Matrix `R` that maps backward differences of step `h` to step `factor * h`.
+fn difference_transform(order: usize, factor: f32) -> [[f32; MAX_ORDER + 1]; MAX_ORDER + 1] {
+    let mut r = [[0.0; MAX_ORDER + 1]; MAX_ORDER + 1];
+    r[0][..=order].fill(1.0);
+    // Cumulative products down the columns of M with M_0j = 1, M_i0 = 0 and
+    // M_ij = (i - 1 - factor j) / i
+    for i in 1..=order {
+        let previous = r[i - 1];
+        for (j, value) in r[i].iter_mut().enumerate().take(order + 1).skip(1) {
+            *value = previous[j] * (i as f32 - 1.0 - factor * j as f32) / i as f32;
+        }
+    }
+    r
+}
+
+impl<Y: OdeState> OdeSolver<Y> for Bdf<Y> {
+    fn time(&self) -> f32 {
+        self.time
+    }
+
+    fn state(&self) -> &Y {
+        &self.state
+    }
+
+    fn statistics(&self) -> &Statistics {
+        &self.statistics
+    }
+
+    fn step(&mut self, fun: &SolveFun<Y>, time_bound: f32) -> Result<&Y, SolverError> {
+        Bdf::step(self, fun, time_bound)
+    }
+
+    fn restart(&mut self, time: f32, state: Y) {
+        Bdf::restart(self, time, state)
+    }
+}
+
+impl<Y: OdeState> Stepper<Y> for Bdf<Y> {
+    fn current(&self) -> (f32, Y) {
+        (self.time, self.state.clone())
+    }
+
+    fn advance(&mut self, fun: &SolveFun<Y>, delta_time: f32) -> Result<(), SolverError> {
+        advance_adaptive(self, fun, delta_time)
+    }
+}
@@ -0,0 +1,587 @@
+// Two-point boundary value problems
+//
+//     y' = f(x, y, p),    g(y(a), y(b), p) = 0
+//
+// with optional unknown parameters `p`, solved by shooting on top of the
+// initial value solvers or by collocation with mesh refinement.
+
+use std::rc::Rc;
+
+use crate::linalg::{lu::LuDecomposition, ndarray::Vector};
+
+use super::{
+    control::Tolerances,
+    dense::{DenseSolution, Interpolant},
+    error::BvpError,
+    solve::{solve_ivp, Method, SolveOptions, Status},
+};
+
+pub type BvpFun<const N: usize> = dyn Fn(f32, Vector<N>, &[f32]) -> Vector<N>;
+/// Residuals `g(y(a), y(b), p)`, `N` plus one per unknown parameter.
+pub type BoundaryFun<const N: usize> = dyn Fn(Vector<N>, Vector<N>, &[f32]) -> Vec<f32>;
+
+/// Options of the boundary value solvers, built with the `with_*` methods.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BvpOptions<const N: usize> {
+    tolerance: f32,
+    boundary_tolerance: f32,
+    max_nodes: usize,
+    max_iterations: usize,
+    method: Method,
+    ivp_tolerances: Tolerances<Vector<N>>,
+}
+
+impl<const N: usize> Default for BvpOptions<N> {
+    /// The tolerances and node limit of scipy's `solve_bvp`.
+    fn default() -> Self {
+        Self {
+            tolerance: 1e-3,
+            boundary_tolerance: 1e-3,
+            max_nodes: 1000,
+            max_iterations: 10,
+            method: Method::Dop853,
+            ivp_tolerances: Tolerances::new(1e-6, 1e-8),
+        }
+    }
+}
+
+impl<const N: usize> BvpOptions<N> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Bound on the relative residual of the collocation solution, and on the
+    /// Newton residuals of the shooting methods.
+    pub fn with_tolerance(mut self, tolerance: f32) -> Self {
+        assert!(tolerance > 0.0, "the tolerance must be positive");
+        self.tolerance = tolerance;
+        self
+    }
+
+    pub fn with_boundary_tolerance(mut self, boundary_tolerance: f32) -> Self {
+        assert!(
+            boundary_tolerance > 0.0,
+            "the boundary tolerance must be positive"
+        );
+        self.boundary_tolerance = boundary_tolerance;
+        self
+    }
+
+    /// Largest mesh the collocation solver may refine to.
+    pub fn with_max_nodes(mut self, max_nodes: usize) -> Self {
+        self.max_nodes = max_nodes;
+        self
+    }
+
+    /// Newton iterations per mesh, or per shooting solve.
+    pub fn with_max_iterations(mut self, max_iterations: usize) -> Self {
+        assert!(max_iterations > 0, "at least one iteration is required");
+        self.max_iterations = max_iterations;
+        self
+    }
+
+    /// Initial value solver used for shooting.
+    pub fn with_method(mut self, method: Method) -> Self {
+        self.method = method;
+        self
+    }
+
+    pub fn with_ivp_tolerances(mut self, tolerances: Tolerances<Vector<N>>) -> Self {
+        self.ivp_tolerances = tolerances;
+        self
+    }
+}
+
+/// Solution of a boundary value problem.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BvpSolution<const N: usize> {
+    /// Final mesh, or the shooting nodes.
+    pub nodes: Vec<f32>,
+    pub states: Vec<Vector<N>>,
+    pub parameters: Vec<f32>,
+    /// Continuous solution over the whole interval.
+    pub solution: DenseSolution<Vector<N>>,
+    /// Relative RMS residual of every collocation interval, empty for shooting.
+    pub rms_residuals: Vec<f32>,
+    /// Newton iterations summed over all meshes.
+    pub iterations: usize,
+}
+
+impl<const N: usize> BvpSolution<N> {
+    pub fn evaluate(&self, x: f32) -> Vector<N> {
+        self.solution.evaluate(x)
+    }
+}
+
+/// Boundary value problem for a `Vector<N>`. The collocation solver assembles
+/// one linear system from the components of every mesh point, which has not
+/// been generalized to other [`OdeState`](super::state::OdeState)s yet.
+pub struct BoundaryValueProblem<const N: usize> {
+    fun: Rc<BvpFun<N>>,
+    boundary: Rc<BoundaryFun<N>>,
+    parameters: Vec<f32>,
+}
+
+impl<const N: usize> BoundaryValueProblem<N> {
+    pub fn new(
+        fun: impl Fn(f32, Vector<N>, &[f32]) -> Vector<N> + 'static,
+        boundary: impl Fn(Vector<N>, Vector<N>, &[f32]) -> Vec<f32> + 'static,
+    ) -> Self {
+        Self {
+            fun: Rc::new(fun),
+            boundary: Rc::new(boundary),
+            parameters: Vec::new(),
+        }
+    }
+
+    /// Initial guess of the unknown parameters. The boundary function then
+    /// has to return one additional residual per parameter.
+    pub fn with_parameters(mut self, guess: Vec<f32>) -> Self {
+        self.parameters = guess;
+        self
+    }
+
+    /// Single shooting: finds `y(a)` and the parameters such that the
+    /// solution of the initial value problem meets the boundary conditions.
+    pub fn shoot(
+        &self,
+        x_span: (f32, f32),
+        initial_guess: Vector<N>,
+        options: &BvpOptions<N>,
+    ) -> Result<BvpSolution<N>, BvpError> {
+        self.multiple_shoot(&[x_span.0, x_span.1], &[initial_guess], options)
+    }
+
+    /// Multiple shooting from every node but the last, with continuity
+    /// conditions between the intervals. Better conditioned than single
+    /// shooting when the solutions grow quickly.
+    pub fn multiple_shoot(
+        &self,
+        nodes: &[f32],
+        guesses: &[Vector<N>],
+        options: &BvpOptions<N>,
+    ) -> Result<BvpSolution<N>, BvpError> {
+        assert!(nodes.len() >= 2, "at least two nodes are required");
+        assert!(
+            nodes.windows(2).all(|w| w[1] > w[0]),
+            "nodes must be strictly increasing"
+        );
+        assert_eq!(
+            guesses.len(),
+            nodes.len() - 1,
+            "expected one guess per shooting interval"
+        );
+        let intervals = guesses.len();
+        let residual = |z: &[f32]| -> Result<Vec<f32>, BvpError> {
+            let (starts, parameters) = unpack::<N>(z, intervals);
+            let mut residual = Vec::with_capacity(z.len());
+            let mut end = Vector::zeros();
+            for (i, start) in starts.iter().enumerate() {
+                end = self.propagate(nodes[i], *start, nodes[i + 1], parameters, options)?;
+                if i + 1 < intervals {
+                    residual.extend_from_slice((end - starts[i + 1]).data.as_flattened());
+                }
+            }
+            residual.extend(self.boundary_residual(starts[0], end, parameters));
+            Ok(residual)
+        };
+        let jacobian = |z: &[f32], r: &[f32]| finite_difference(&residual, z, r);
+
+        let z = pack(guesses, &self.parameters);
+        let (z, iterations) = newton(
+            &residual,
+            &jacobian,
+            z,
+            options.tolerance.min(options.boundary_tolerance),
+            options.max_iterations,
+        )?;
+
+        let (starts, parameters) = unpack::<N>(&z, intervals);
+        let mut solution = DenseSolution::new();
+        let mut states = starts.clone();
+        for (i, start) in starts.iter().enumerate() {
+            let fun = Rc::clone(&self.fun);
+            let owned = parameters.to_vec();
+            let ivp = solve_ivp(
+                move |x, y| fun(x, y, &owned),
+                (nodes[i], nodes[i + 1]),
+                *start,
+                self.ivp_options(options).with_dense_output(true),
+            );
+            for piece in ivp.dense.unwrap().pieces() {
+                solution.push(piece.clone());
+            }
+            if i + 1 == intervals {
+                states.push(*ivp.states.last().unwrap());
+            }
+        }
+        Ok(BvpSolution {
+            nodes: nodes.to_vec(),
+            states,
+            parameters: parameters.to_vec(),
+            solution,
+            rms_residuals: Vec::new(),
+            iterations,
+        })
+    }
+
+    /// Fourth order collocation with a cubic spline (Lobatto IIIA), as in
+    /// scipy's `solve_bvp`.
+    ///
+    /// After the collocation equations are solved on a mesh, the relative
+    /// residual `(S' - f) / (1 + |f|)` of the spline `S` is estimated on every
+    /// interval, and intervals above the tolerance are split in two or, if
+    /// the residual is more than a hundred times too large, in three.
+    pub fn collocate(
+        &self,
+        mesh: Vec<f32>,
+        guess: Vec<Vector<N>>,
+        options: &BvpOptions<N>,
+    ) -> Result<BvpSolution<N>, BvpError> {
+        assert!(mesh.len() >= 2, "the mesh needs at least two nodes");
+        assert!(
+            mesh.windows(2).all(|w| w[1] > w[0]),
+            "the mesh must be strictly increasing"
+        );
+        assert_eq!(mesh.len(), guess.len(), "expected one guess per node");
+        let mut mesh = mesh;
+        let mut z = pack(&guess, &self.parameters);
+        let mut iterations = 0;
+        loop {
+            let nodes = mesh.len();
+            let residual = |z: &[f32]| Ok(self.collocation_residual(&mesh, z));
+            let jacobian = |z: &[f32], r: &[f32]| Ok(self.collocation_jacobian(&mesh, z, r));
+            let (solved, newton_iterations) = newton(
+                &residual,
+                &jacobian,
+                z,
+                0.1 * options.tolerance,
+                options.max_iterations,
+            )?;
+            iterations += newton_iterations;
+
+            let (states, parameters) = unpack::<N>(&solved, nodes);
+            let spline = self.spline(&mesh, &states, parameters);
+            let rms_residuals: Vec<f32> = spline
+                .pieces()
+                .iter()
+                .map(|piece| self.rms_residual(piece, parameters))
+                .collect();
+            let boundary = self.boundary_residual(states[0], states[nodes - 1], parameters);
+            let boundary_met = boundary
+                .iter()
+                .all(|r| r.abs() <= options.boundary_tolerance);
+            if boundary_met && rms_residuals.iter().all(|&r| r <= options.tolerance) {
+                return Ok(BvpSolution {
+                    nodes: mesh,
+                    states: states.to_vec(),
+                    parameters: parameters.to_vec(),
+                    solution: spline,
+                    rms_residuals,
+                    iterations,
+                });
+            }
+
+            let mut refined_mesh = Vec::with_capacity(2 * nodes);
+            let mut refined_states = Vec::with_capacity(2 * nodes);
+            for (i, piece) in spline.pieces().iter().enumerate() {
+                refined_mesh.push(mesh[i]);
+                refined_states.push(states[i]);
+                let inserted = match rms_residuals[i] {
+                    r if r <= options.tolerance => 0,
+                    r if r <= 100.0 * options.tolerance => 1,
+                    _ => 2,
+                };
+                let width = mesh[i + 1] - mesh[i];
+                for k in 1..=inserted {
+                    let x = mesh[i] + width * k as f32 / (inserted + 1) as f32;
+                    refined_mesh.push(x);
+                    refined_states.push(piece.evaluate(x));
+                }
+            }
+            refined_mesh.push(mesh[nodes - 1]);
+            refined_states.push(states[nodes - 1]);
+            if refined_mesh.len() == nodes {
+                // Only the boundary conditions are violated and refining
+                // the mesh does not help
+                return Err(BvpError::NewtonDidNotConverge { iterations });
+            }
+            if refined_mesh.len() > options.max_nodes {
+                return Err(BvpError::MaxNodesExceeded {
+                    nodes: options.max_nodes,
+                });
+            }
+            z = pack(&refined_states, parameters);
+            mesh = refined_mesh;
+        }
+    }
+
+    fn boundary_residual(&self, start: Vector<N>, end: Vector<N>, parameters: &[f32]) -> Vec<f32> {
+        let residual = (self.boundary)(start, end, parameters);
+        assert_eq!(
+            residual.len(),
+            N + parameters.len(),
+            "expected {} boundary residuals",
+            N + parameters.len()
+        );
+        residual
+    }
+
+    fn ivp_options(&self, options: &BvpOptions<N>) -> SolveOptions<Vector<N>> {
+        SolveOptions::new()
+            .with_method(options.method)
+            .with_tolerances(options.ivp_tolerances)
+    }
+
+    /// Solves the initial value problem from `start` to `end`.
+    fn propagate(
+        &self,
+        start: f32,
+        state: Vector<N>,
+        end: f32,
+        parameters: &[f32],
+        options: &BvpOptions<N>,
+    ) -> Result<Vector<N>, BvpError> {
+        let fun = Rc::clone(&self.fun);
+        let parameters = parameters.to_vec();
+        let ivp = solve_ivp(
+            move |x, y| fun(x, y, &parameters),
+            (start, end),
+            state,
+            self.ivp_options(options),
+        );
+        match ivp.status {
+            Status::Failed(error) => Err(error.into()),
+            _ => Ok(*ivp.states.last().unwrap()),
+        }
+    }
+
+    /// Collocation residual of the interval `[x_0, x_1]`, divided by its
+    /// width: `(y_1 - y_0) / h - (f_0 + 4 f_mid + f_1) / 6` with the midpoint
+    /// of the cubic through both ends.
+    fn interval_residual(
+        &self,
+        x_0: f32,
+        y_0: Vector<N>,
+        x_1: f32,
+        y_1: Vector<N>,
+        parameters: &[f32],
+    ) -> Vector<N> {
+        let h = x_1 - x_0;
+        let f_0 = (self.fun)(x_0, y_0, parameters);
+        let f_1 = (self.fun)(x_1, y_1, parameters);
+        let y_mid = 0.5 * (y_0 + y_1) - (h / 8.0) * (f_1 - f_0);
+        let f_mid = (self.fun)(x_0 + 0.5 * h, y_mid, parameters);
+        (1.0 / h) * (y_1 - y_0) - (1.0 / 6.0) * (f_0 + 4.0 * f_mid + f_1)
+    }
+
+    fn collocation_residual(&self, mesh: &[f32], z: &[f32]) -> Vec<f32> {
+        let nodes = mesh.len();
+        let (states, parameters) = unpack::<N>(z, nodes);
+        let mut residual = Vec::with_capacity(z.len());
+        for i in 0..nodes - 1 {
+            let r =
+                self.interval_residual(mesh[i], states[i], mesh[i + 1], states[i + 1], parameters);
+            residual.extend_from_slice(r.data.as_flattened());
+        }
+        residual.extend(self.boundary_residual(states[0], states[nodes - 1], parameters));
+        residual
+    }
+
+    /// Finite difference Jacobian that only re-evaluates the two intervals
+    /// next to a perturbed node.
+    fn collocation_jacobian(&self, mesh: &[f32], z: &[f32], residual: &[f32]) -> Vec<f32> {
+        let nodes = mesh.len();
+        let size = z.len();
+        let boundary_row = N * (nodes - 1);
+        let mut jacobian = vec![0.0; size * size];
+        let mut perturbed = z.to_vec();
+        for column in 0..N * nodes {
+            let node = column / N;
+            let step = f32::EPSILON.sqrt() * z[column].abs().max(1.0);
+            perturbed[column] = z[column] + step;
+            let (states, parameters) = unpack::<N>(&perturbed, nodes);
+            let mut set = |row: usize, value: f32| {
+                jacobian[row * size + column] = (value - residual[row]) / step;
+            };
+            for interval in node.saturating_sub(1)..node.min(nodes - 2) + 1 {
+                let r = self.interval_residual(
+                    mesh[interval],
+                    states[interval],
+                    mesh[interval + 1],
+                    states[interval + 1],
+                    parameters,
+                );
+                for k in 0..N {
+                    set(interval * N + k, r[k]);
+                }
+            }
+            if node == 0 || node == nodes - 1 {
+                let r = self.boundary_residual(states[0], states[nodes - 1], parameters);
+                for (k, value) in r.into_iter().enumerate() {
+                    set(boundary_row + k, value);
+                }
+            }
+            perturbed[column] = z[column];
+        }
+        // Parameters enter every interval
+        for column in N * nodes..size {
+            let step = f32::EPSILON.sqrt() * z[column].abs().max(1.0);
+            perturbed[column] = z[column] + step;
+            let r = self.collocation_residual(mesh, &perturbed);
+            for (row, value) in r.into_iter().enumerate() {
+                jacobian[row * size + column] = (value - residual[row]) / step;
+            }
+            perturbed[column] = z[column];
+        }
+        jacobian
+    }
+
+    /// Cubic Hermite spline through the nodes with the slopes `f(x_i, y_i)`.
+    fn spline(
+        &self,
+        mesh: &[f32],
+        states: &[Vector<N>],
+        parameters: &[f32],
+    ) -> DenseSolution<Vector<N>> {
+        let slopes: Vec<Vector<N>> = mesh
+            .iter()
+            .zip(states)
+            .map(|(&x, &y)| (self.fun)(x, y, parameters))
+            .collect();
+        let mut spline = DenseSolution::new();
+        for i in 0..mesh.len() - 1 {
+            spline.push(Interpolant::hermite(
+                mesh[i],
+                states[i],
+                slopes[i],
+                mesh[i + 1],
+                states[i + 1],
+                slopes[i + 1],
+            ));
+        }
+        spline
+    }
+
+    /// RMS of the relative residual over one interval by five point Lobatto
+    /// quadrature. The residual vanishes at the nodes, so only the three
+    /// inner points are evaluated.
+    fn rms_residual(&self, piece: &Interpolant<Vector<N>>, parameters: &[f32]) -> f32 {
+        let (start, end) = (piece.start_time(), piece.end_time());
+        let middle = 0.5 * (start + end);
+        let offset = 0.5 * (end - start) * (3.0f32 / 7.0).sqrt();
+        let squared = |x: f32| -> f32 {
+            let f = (self.fun)(x, piece.evaluate(x), parameters);
+            let derivative = piece.derivative(x);
+            (0..N)
+                .map(|k| ((derivative[k] - f[k]) / (1.0 + f[k].abs())).powi(2))
+                .sum()
+        };
+        let sum = 32.0 / 45.0 * squared(middle)
+            + 49.0 / 90.0 * (squared(middle - offset) + squared(middle + offset));
+        (0.5 * sum).sqrt()
+    }
+}
+
+fn pack<const N: usize>(states: &[Vector<N>], parameters: &[f32]) -> Vec<f32> {
+    let mut z: Vec<f32> = states
+        .iter()
+        .flat_map(|state| state.data.as_flattened().iter().copied())
+        .collect();
+    z.extend_from_slice(parameters);
+    z
+}
+
+fn unpack<const N: usize>(z: &[f32], count: usize) -> (Vec<Vector<N>>, &[f32]) {
+    let states = z[..N * count]
+        .chunks_exact(N)
+        .map(|chunk| Vector::from_fn(|i, _| chunk[i]))
+        .collect();
+    (states, &z[N * count..])
+}
+
+type Residual<'a> = dyn Fn(&[f32]) -> Result<Vec<f32>, BvpError> + 'a;
+type ResidualJacobian<'a> = dyn Fn(&[f32], &[f32]) -> Result<Vec<f32>, BvpError> + 'a;
+
+/// Forward difference Jacobian of `residual` at `z`, where `r = residual(z)`.
+fn finite_difference(residual: &Residual, z: &[f32], r: &[f32]) -> Result<Vec<f32>, BvpError> {
+    let size = z.len();
+    let mut jacobian = vec![0.0; size * size];
+    let mut perturbed = z.to_vec();
+    for column in 0..size {
+        let step = f32::EPSILON.sqrt() * z[column].abs().max(1.0);
+        perturbed[column] = z[column] + step;
+        let r_column = residual(&perturbed)?;
+        for row in 0..size {
+            jacobian[row * size + column] = (r_column[row] - r[row]) / step;
+        }
+        perturbed[column] = z[column];
+    }
+    Ok(jacobian)
+}
+
+fn max_norm(values: &[f32]) -> f32 {
+    values.iter().fold(0.0, |max, value| max.max(value.abs()))
+}
+
+fn squared_norm(values: &[f32]) -> f32 {
+    values.iter().map(|value| value * value).sum()
+}
+
+/// Damped Newton iteration for `residual(z) = 0`. Steps are halved until the
+/// residual decreases, at most four times. Returns the solution and the
+/// number of iterations.
+fn newton(
+    residual: &Residual,
+    jacobian: &ResidualJacobian,
+    mut z: Vec<f32>,
+    tolerance: f32,
+    max_iterations: usize,
+) -> Result<(Vec<f32>, usize), BvpError> {
+    let size = z.len();
+    let mut r = residual(&z)?;
+    assert_eq!(
+        r.len(),
+        size,
+        "expected as many residuals as unknowns, got {} for {}",
+        r.len(),
+        size
+    );
+    for iteration in 0..max_iterations {
+        if max_norm(&r) <= tolerance {
+            return Ok((z, iteration));
+        }
+        let lu = LuDecomposition::new(size, jacobian(&z, &r)?)
+            .map_err(|_| BvpError::SingularJacobian)?;
+        let step = lu.solve(&r);
+        let norm = squared_norm(&r);
+        let mut damping = 1.0;
+        loop {
+            let trial: Vec<f32> = z.iter().zip(&step).map(|(z, s)| z - damping * s).collect();
+            // A failed initial value solve counts as no decrease
+            let trial_residual = residual(&trial).ok();
+            let decreased = trial_residual
+                .as_ref()
+                .is_some_and(|trial_residual| squared_norm(trial_residual) < norm);
+            if decreased || damping < 0.1 {
+                if let Some(trial_residual) = trial_residual {
+                    z = trial;
+                    r = trial_residual;
+                }
+                break;
+            }
+            damping *= 0.5;
+        }
+        // Stagnating at the floating point limit counts as converged
+        if damping == 1.0 && max_norm(&step) <= 1e-6 * (1.0 + max_norm(&z)) {
+            return Ok((z, iteration + 1));
+        }
+    }
+    if max_norm(&r) <= tolerance {
+        Ok((z, max_iterations))
+    } else {
+        Err(BvpError::NewtonDidNotConverge {
+            iterations: max_iterations,
+        })
+    }
+}
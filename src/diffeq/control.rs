@@ -0,0 +1,156 @@
+// Error control shared by the adaptive solvers.
+
+use super::{ivp::SolveFun, state::OdeState};
+
+/// Relative and absolute tolerances for every component of the state.
+///
+/// A local error `e_i` is acceptable when `|e_i| <= atol_i + rtol_i * |y_i|`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Tolerances<Y> {
+    pub relative: Y,
+    pub absolute: Y,
+}
+
+impl<Y: OdeState> Tolerances<Y> {
+    /// The same tolerances for every component.
+    pub fn new(relative: f32, absolute: f32) -> Self {
+        Self {
+            relative: Y::splat(relative),
+            absolute: Y::splat(absolute),
+        }
+    }
+
+    pub fn per_component(relative: Y, absolute: Y) -> Self {
+        Self { relative, absolute }
+    }
+
+    /// Error scale `atol_i + rtol_i * max(|y_i|, |y_next_i|)` of a step.
+    pub fn scale(&self, state: &Y, next_state: &Y) -> Y {
+        state.map_components(|i, y| {
+            let magnitude = y.abs().max(next_state.component(i).abs());
+            broadcast(&self.absolute, i) + broadcast(&self.relative, i) * magnitude
+        })
+    }
+}
+
+impl<Y: OdeState> Default for Tolerances<Y> {
+    /// `rtol = 1e-3` and `atol = 1e-6`, the defaults of scipy's `solve_ivp`.
+    fn default() -> Self {
+        Self::new(1e-3, 1e-6)
+    }
+}
+
+/// Component `index` of a tolerance, which may be a single splatted value.
+pub(crate) fn broadcast<Y: OdeState>(values: &Y, index: usize) -> f32 {
+    if values.dimension() == 1 {
+        values.component(0)
+    } else {
+        values.component(index)
+    }
+}
+
+/// Root mean square of `error / scale`; a step is accepted when this is at most one.
+pub fn error_norm<Y: OdeState>(error: &Y, scale: &Y) -> f32 {
+    error.error_norm(scale)
+}
+
+/// Multiplicative step size controller `h_next = h * safety * err^(-1 / (q + 1))`
+/// for an error estimate of order `q`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct StepController {
+    pub safety: f32,
+    /// Lower bound of the factor after a rejected step.
+    pub min_factor: f32,
+    /// Upper bound of the factor after an accepted step.
+    pub max_factor: f32,
+    pub max_step: f32,
+}
+
+impl Default for StepController {
+    fn default() -> Self {
+        Self {
+            safety: 0.9,
+            min_factor: 0.2,
+            max_factor: 10.0,
+            max_step: f32::INFINITY,
+        }
+    }
+}
+
+impl StepController {
+    /// Factor by which to scale the step size given the error norm of the
+    /// last attempt. Errors above one mean the step is rejected. The step is
+    /// not allowed to grow right after a rejection.
+    pub fn factor(&self, error_norm: f32, error_order: usize, after_rejection: bool) -> f32 {
+        let exponent = -1.0 / (error_order as f32 + 1.0);
+        if !error_norm.is_finite() {
+            self.min_factor
+        } else if error_norm < 1.0 {
+            let factor = if error_norm == 0.0 {
+                self.max_factor
+            } else {
+                (self.safety * error_norm.powf(exponent)).min(self.max_factor)
+            };
+            if after_rejection {
+                factor.min(1.0)
+            } else {
+                factor
+            }
+        } else {
+            (self.safety * error_norm.powf(exponent)).max(self.min_factor)
+        }
+    }
+}
+
+/// Smallest step size that still changes `time`.
+pub fn min_step_size(time: f32) -> f32 {
+    10.0 * f32::EPSILON * time.abs().max(f32::MIN_POSITIVE)
+}
+
+/// Initial step size for a method of the given order, following Hairer,
+/// Norsett and Wanner, "Solving Ordinary Differential Equations I", II.4.
+///
+/// `derivative` is `f(time, state)`; one additional evaluation of `fun` is made.
+/// `direction` is the sign of the integration direction.
+pub fn initial_step_size<Y: OdeState>(
+    fun: &SolveFun<Y>,
+    time: f32,
+    state: &Y,
+    derivative: &Y,
+    direction: f32,
+    order: usize,
+    tolerances: &Tolerances<Y>,
+) -> f32 {
+    let scale = tolerances.scale(state, state);
+    let d0 = error_norm(state, &scale);
+    let d1 = error_norm(derivative, &scale);
+    // The fallback sizes are kept well above the smallest step, since 1e-6 is
+    // already below the f32 resolution of times past one
+    let smallest = 10.0 * min_step_size(time).max(1e-7);
+    let h0 = if d0 < 1e-5 || d1 < 1e-5 {
+        smallest
+    } else {
+        0.01 * d0 / d1
+    };
+
+    let next_state = state.clone() + derivative.clone() * (h0 * direction);
+    let next_derivative = fun(time + h0 * direction, next_state);
+    let d2 = error_norm(&(next_derivative - derivative.clone()), &scale) / h0;
+
+    let h1 = if d1 <= 1e-15 && d2 <= 1e-15 {
+        (h0 * 1e-3).max(smallest)
+    } else {
+        (0.01 / d1.max(d2)).powf(1.0 / (order as f32 + 1.0))
+    };
+    (100.0 * h0).min(h1)
+}
+
+/// Number of function evaluations and steps taken by a solver.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Statistics {
+    pub evaluations: usize,
+    pub accepted_steps: usize,
+    pub rejected_steps: usize,
+    pub jacobian_evaluations: usize,
+    pub lu_decompositions: usize,
+}
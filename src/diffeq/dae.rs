@@ -0,0 +1,687 @@
+// Differential-algebraic equations of index one, in the implicit form
+//
+//     F(t, y, y') = 0
+//
+// or with a possibly singular mass matrix, M y' = f(t, y), which is the
+// residual F = M y' - f. Components whose derivative does not appear in F
+// are algebraic; for a mass matrix these are the zero columns of M.
+//
+// The BDF formulas of `bdf` apply unchanged: with the backward differences
+// of the solution the formula of order k defines y'_next = (y_next - base) / c
+// for a known `base` and c = h / gamma_k, so each step solves
+// F(t_next, y, (y - base) / c) = 0 by a simplified Newton iteration with the
+// matrix c dF/dy + dF/dy'. The iteration matrix is regular for index one
+// problems, which is what restricts the solver to them.
+//
+// The integration must start from consistent values, F(t0, y0, y'0) = 0.
+// `Dae::consistent_initial_values` keeps the differential components of y0
+// and solves for their derivatives and for the algebraic components, as in
+// IDA's `IDACalcIC`.
+
+use crate::linalg::{
+    lu::Lu,
+    ndarray::{Matrix, Vector},
+};
+
+use super::{
+    bdf::{coefficients, rescale_differences, MAX_ORDER},
+    control::{error_norm, min_step_size, Statistics, Tolerances},
+    error::SolverError,
+    ivp::SolveFun,
+    jacobian::Jacobian,
+    newton::{newton_tolerance, Convergence, NewtonCheck, NewtonSolution},
+    solve::Status,
+};
+
+const NEWTON_MAX_ITERATIONS: usize = 4;
+const MIN_FACTOR: f32 = 0.2;
+const MAX_FACTOR: f32 = 10.0;
+const INITIALIZATION_MAX_ITERATIONS: usize = 10;
+/// Scaled size of the last Newton correction of a consistent initialization.
+const INITIALIZATION_TOLERANCE: f32 = 1e-3;
+
+/// Residual `F(t, y, y')`.
+pub type ResidualFun<const N: usize> = dyn Fn(f32, Vector<N>, Vector<N>) -> Vector<N>;
+
+enum Form<const N: usize> {
+    Implicit(Box<ResidualFun<N>>),
+    MassMatrix {
+        mass: Matrix<N, N>,
+        fun: Box<SolveFun<Vector<N>>>,
+        jacobian: Jacobian<Vector<N>>,
+    },
+}
+
+/// Differential-algebraic equation of index one.
+///
+/// The state is a `Vector<N>`, because the mass matrix and the iteration
+/// matrix are `N x N` matrices. Support for any
+/// [`OdeState`](super::state::OdeState), as in the ODE solvers, is still
+/// missing.
+pub struct Dae<const N: usize> {
+    form: Form<N>,
+    algebraic: [bool; N],
+}
+
+impl<const N: usize> Dae<N> {
+    /// `F(t, y, y') = 0`, where `algebraic[i]` marks the components whose
+    /// derivative does not appear in `residual`.
+    pub fn implicit(
+        residual: impl Fn(f32, Vector<N>, Vector<N>) -> Vector<N> + 'static,
+        algebraic: [bool; N],
+    ) -> Self {
+        Self {
+            form: Form::Implicit(Box::new(residual)),
+            algebraic,
+        }
+    }
+
+    /// `M y' = fun(t, y)`. The components of the zero columns of `mass` are
+    /// algebraic.
+    pub fn mass_matrix(
+        mass: Matrix<N, N>,
+        fun: impl Fn(f32, Vector<N>) -> Vector<N> + 'static,
+    ) -> Self {
+        let algebraic = std::array::from_fn(|j| (0..N).all(|i| mass[(i, j)] == 0.0));
+        Self {
+            form: Form::MassMatrix {
+                mass,
+                fun: Box::new(fun),
+                jacobian: Jacobian::FiniteDifference,
+            },
+            algebraic,
+        }
+    }
+
+    /// Jacobian `df/dy` of the right-hand side of the mass matrix form.
+    pub fn with_jacobian(mut self, jacobian: Jacobian<Vector<N>>) -> Self {
+        match &mut self.form {
+            Form::MassMatrix { jacobian: slot, .. } => *slot = jacobian,
+            Form::Implicit(_) => panic!("a Jacobian can only be given for the mass matrix form"),
+        }
+        self
+    }
+
+    pub fn is_algebraic(&self, component: usize) -> bool {
+        self.algebraic[component]
+    }
+
+    pub fn residual(&self, time: f32, state: Vector<N>, derivative: Vector<N>) -> Vector<N> {
+        match &self.form {
+            Form::Implicit(residual) => residual(time, state, derivative),
+            Form::MassMatrix { mass, fun, .. } => *mass * derivative - fun(time, state),
+        }
+    }
+
+    /// Partial derivatives `dF/dy` and `dF/dy'`, given `residual = F(time,
+    /// state, derivative)`. Finite differences of the mass matrix form perturb
+    /// differential components smaller than `threshold` as if they were of
+    /// that size, and algebraic ones as in [`residual_difference`].
+    fn partials(
+        &self,
+        time: f32,
+        state: &Vector<N>,
+        derivative: &Vector<N>,
+        residual: &Vector<N>,
+        threshold: &Vector<N>,
+        statistics: &mut Statistics,
+    ) -> (Matrix<N, N>, Matrix<N, N>) {
+        match &self.form {
+            Form::Implicit(fun) => {
+                statistics.jacobian_evaluations += 1;
+                statistics.evaluations += 2 * N;
+                let by_state = residual_difference(|y| fun(time, y, *derivative), state, residual);
+                let by_derivative =
+                    residual_difference(|y| fun(time, *state, y), derivative, residual);
+                (by_state, by_derivative)
+            }
+            Form::MassMatrix {
+                mass,
+                fun,
+                jacobian,
+            } => {
+                // The Newton matrix has no column of `mass` for an algebraic
+                // component, so its column must not be lost to rounding
+                let threshold = Vector::from_fn(|j, _| {
+                    if self.algebraic[j] {
+                        threshold[j].max(1.0)
+                    } else {
+                        threshold[j]
+                    }
+                });
+                let value = *mass * *derivative - *residual;
+                let jacobian = jacobian.evaluate(
+                    fun.as_ref(),
+                    time,
+                    state,
+                    Some(&value),
+                    &threshold,
+                    statistics,
+                );
+                (-jacobian.to_matrix(), *mass)
+            }
+        }
+    }
+
+    /// Values consistent with the equation at `time`, starting from the
+    /// guesses `state` and `derivative`. The differential components of
+    /// `state` are kept, the algebraic ones and the derivatives of the
+    /// differential ones are solved for with Newton's method.
+    ///
+    /// A singular Newton matrix means the problem is not of index one.
+    pub fn consistent_initial_values(
+        &self,
+        time: f32,
+        state: Vector<N>,
+        derivative: Vector<N>,
+        tolerances: &Tolerances<Vector<N>>,
+    ) -> Result<(Vector<N>, Vector<N>), SolverError> {
+        let (mut state, mut derivative) = (state, derivative);
+        let mut statistics = Statistics::default();
+        for _ in 0..INITIALIZATION_MAX_ITERATIONS {
+            let residual = self.residual(time, state, derivative);
+            let (by_state, by_derivative) = self.partials(
+                time,
+                &state,
+                &derivative,
+                &residual,
+                &tolerances.absolute,
+                &mut statistics,
+            );
+            let matrix = Matrix::from_fn(|i, j| {
+                if self.algebraic[j] {
+                    by_state[(i, j)]
+                } else {
+                    by_derivative[(i, j)]
+                }
+            });
+            let lu = matrix
+                .lu()
+                .map_err(|_| SolverError::SingularIterationMatrix { time })?;
+            let correction = lu.solve(&residual);
+            for (j, &algebraic) in self.algebraic.iter().enumerate() {
+                if algebraic {
+                    state[j] -= correction[j];
+                } else {
+                    derivative[j] -= correction[j];
+                }
+            }
+            let scale = tolerances.scale(&state, &state);
+            let norm = error_norm(&correction, &scale);
+            if !norm.is_finite() {
+                break;
+            }
+            if norm <= INITIALIZATION_TOLERANCE {
+                return Ok((state, derivative));
+            }
+        }
+        Err(SolverError::NewtonDidNotConverge { time })
+    }
+}
+
+/// Forward difference approximation of the derivative of `fun` at `point`,
+/// where `value = fun(point)`.
+///
+/// Components are perturbed relative to `max(|x_j|, 1)`: the derivatives
+/// usually start from a guess of zero, and smaller perturbations of a
+/// residual of order one are lost to rounding.
+fn residual_difference<const N: usize>(
+    fun: impl Fn(Vector<N>) -> Vector<N>,
+    point: &Vector<N>,
+    value: &Vector<N>,
+) -> Matrix<N, N> {
+    let mut jacobian = Matrix::zeros();
+    for j in 0..N {
+        let step = f32::EPSILON.sqrt() * point[j].abs().max(1.0);
+        let mut perturbed = *point;
+        perturbed[j] += step;
+        let step = perturbed[j] - point[j];
+        let column = (fun(perturbed) - *value) / step;
+        for i in 0..N {
+            jacobian[(i, j)] = column[i];
+        }
+    }
+    jacobian
+}
+
+/// Solves `F(time, y, (y - base) / c) = 0` for `y = predicted + e`, where
+/// `base = predicted - psi` and `lu` factorizes `c dF/dy + dF/dy'`. The
+/// returned state is the correction `e`.
+///
+/// The iteration runs on `e` and forms `y' = (e + psi) / c` from it, since
+/// the small corrections of short steps are lost to rounding in `y`.
+#[allow(clippy::too_many_arguments)]
+fn solve_residual<const N: usize>(
+    dae: &Dae<N>,
+    time: f32,
+    predicted: &Vector<N>,
+    psi: &Vector<N>,
+    c: f32,
+    lu: &Lu<N>,
+    scale: &Vector<N>,
+    tolerance: f32,
+    statistics: &mut Statistics,
+) -> NewtonSolution<Vector<N>> {
+    let mut correction = Vector::zeros();
+    let mut convergence = Convergence::new(NEWTON_MAX_ITERATIONS, tolerance);
+    for iteration in 0..NEWTON_MAX_ITERATIONS {
+        statistics.evaluations += 1;
+        let residual = dae.residual(time, *predicted + correction, (correction + *psi) / c);
+        let change = -lu.solve(&(c * residual));
+        match convergence.check(iteration, error_norm(&change, scale)) {
+            NewtonCheck::Failed => break,
+            NewtonCheck::Continue => correction += change,
+            NewtonCheck::Converged => {
+                return NewtonSolution {
+                    converged: true,
+                    iterations: iteration + 1,
+                    state: correction + change,
+                }
+            }
+        }
+    }
+    NewtonSolution {
+        converged: false,
+        iterations: NEWTON_MAX_ITERATIONS,
+        state: correction,
+    }
+}
+
+/// Variable order, variable step BDF solver for index one DAEs, with the
+/// step size and order control of [`Bdf`](super::bdf::Bdf).
+pub struct DaeBdf<const N: usize> {
+    time: f32,
+    state: Vector<N>,
+    derivative: Vector<N>,
+    // Backward differences D_0 = y, D_1 .. D_(MAX_ORDER + 2)
+    differences: Vec<Vector<N>>,
+    order: usize,
+    step_size: Option<f32>,
+    max_step: f32,
+    equal_steps: usize,
+    // dF/dy and dF/dy'
+    partials: Option<(Matrix<N, N>, Matrix<N, N>)>,
+    lu: Option<Lu<N>>,
+    tolerances: Tolerances<Vector<N>>,
+    statistics: Statistics,
+    gamma: [f32; MAX_ORDER + 2],
+    error_constants: [f32; MAX_ORDER + 2],
+}
+
+impl<const N: usize> DaeBdf<N> {
+    /// Starts from consistent values, see [`Dae::consistent_initial_values`].
+    pub fn new(
+        time: f32,
+        initial_state: Vector<N>,
+        initial_derivative: Vector<N>,
+        tolerances: Tolerances<Vector<N>>,
+    ) -> Self {
+        let (gamma, error_constants) = coefficients();
+        let mut differences = vec![Vector::zeros(); MAX_ORDER + 3];
+        differences[0] = initial_state;
+        Self {
+            time,
+            state: initial_state,
+            derivative: initial_derivative,
+            differences,
+            order: 1,
+            step_size: None,
+            max_step: f32::INFINITY,
+            equal_steps: 0,
+            partials: None,
+            lu: None,
+            tolerances,
+            statistics: Statistics::default(),
+            gamma,
+            error_constants,
+        }
+    }
+
+    pub fn with_first_step(mut self, step_size: f32) -> Self {
+        assert!(step_size > 0.0, "the first step size must be positive");
+        self.step_size = Some(step_size);
+        self
+    }
+
+    pub fn with_max_step(mut self, max_step: f32) -> Self {
+        assert!(max_step > 0.0, "the maximum step size must be positive");
+        self.max_step = max_step;
+        self
+    }
+
+    pub fn time(&self) -> f32 {
+        self.time
+    }
+
+    pub fn state(&self) -> &Vector<N> {
+        &self.state
+    }
+
+    /// Derivative of the state as given by the last step's formula.
+    pub fn derivative(&self) -> &Vector<N> {
+        &self.derivative
+    }
+
+    /// Order of the formula used for the next step.
+    pub fn order(&self) -> usize {
+        self.order
+    }
+
+    pub fn statistics(&self) -> &Statistics {
+        &self.statistics
+    }
+
+    /// Takes one accepted step towards `time_bound` without stepping past it.
+    pub fn step(&mut self, dae: &Dae<N>, time_bound: f32) -> Result<&Vector<N>, SolverError> {
+        if time_bound == self.time {
+            return Ok(&self.state);
+        }
+        let direction = (time_bound - self.time).signum();
+        if self.partials.is_none() {
+            self.initialize(dae, direction);
+        }
+
+        let min_step = min_step_size(self.time);
+        let mut step_size = self.step_size.unwrap();
+        if step_size > self.max_step {
+            self.rescale(self.max_step / step_size);
+            step_size = self.max_step;
+        } else if step_size < min_step {
+            self.rescale(min_step / step_size);
+            step_size = min_step;
+        }
+
+        let order = self.order;
+        let alpha = self.gamma[order];
+        let tolerance = newton_tolerance(&self.tolerances);
+        let mut current_partials = false;
+        let (next_time, next_state, derivative, correction, error, safety, scale) = loop {
+            if step_size < min_step {
+                return Err(SolverError::StepSizeTooSmall {
+                    time: self.time,
+                    step_size,
+                });
+            }
+            let mut next_time = self.time + step_size * direction;
+            if direction * (next_time - time_bound) > 0.0 {
+                next_time = time_bound;
+                self.rescale((next_time - self.time).abs() / step_size);
+            }
+            let delta_time = next_time - self.time;
+            step_size = delta_time.abs();
+
+            let predicted = self.differences[..=order]
+                .iter()
+                .fold(Vector::zeros(), |sum, d| sum + *d);
+            let scale = self.tolerances.scale(&predicted, &predicted);
+            let psi = (1..=order).fold(Vector::zeros(), |sum, j| {
+                sum + self.gamma[j] * self.differences[j]
+            }) / alpha;
+            let c = delta_time / alpha;
+
+            let solution = loop {
+                if self.lu.is_none() {
+                    self.statistics.lu_decompositions += 1;
+                    let (by_state, by_derivative) = self.partials.unwrap();
+                    self.lu = (c * by_state + by_derivative).lu().ok();
+                }
+                let solution = self.lu.as_ref().map(|lu| {
+                    solve_residual(
+                        dae,
+                        next_time,
+                        &predicted,
+                        &psi,
+                        c,
+                        lu,
+                        &scale,
+                        tolerance,
+                        &mut self.statistics,
+                    )
+                });
+                match solution {
+                    Some(solution) if solution.converged => break Some(solution),
+                    _ if current_partials => break None,
+                    _ => {
+                        let derivative = psi / c;
+                        self.statistics.evaluations += 1;
+                        let residual = dae.residual(next_time, predicted, derivative);
+                        self.partials = Some(dae.partials(
+                            next_time,
+                            &predicted,
+                            &derivative,
+                            &residual,
+                            &self.tolerances.absolute,
+                            &mut self.statistics,
+                        ));
+                        self.lu = None;
+                        current_partials = true;
+                    }
+                }
+            };
+
+            let Some(solution) = solution else {
+                self.statistics.rejected_steps += 1;
+                step_size *= 0.5;
+                self.rescale(0.5);
+                continue;
+            };
+
+            let safety = 0.9 * (2 * NEWTON_MAX_ITERATIONS + 1) as f32
+                / (2 * NEWTON_MAX_ITERATIONS + solution.iterations) as f32;
+            let correction = solution.state;
+            let next_state = predicted + correction;
+            let scale = self.tolerances.scale(&next_state, &next_state);
+            let error = error_norm(&(self.error_constants[order] * correction), &scale);
+            if error > 1.0 {
+                self.statistics.rejected_steps += 1;
+                let factor = (safety * error.powf(-1.0 / (order as f32 + 1.0))).max(MIN_FACTOR);
+                step_size *= factor;
+                self.rescale(factor);
+                continue;
+            }
+            break (
+                next_time,
+                next_state,
+                (correction + psi) / c,
+                correction,
+                error,
+                safety,
+                scale,
+            );
+        };
+
+        self.statistics.accepted_steps += 1;
+        self.equal_steps += 1;
+        self.time = next_time;
+        self.state = next_state;
+        self.derivative = derivative;
+        self.step_size = Some(step_size);
+
+        let d = &mut self.differences;
+        d[order + 2] = correction - d[order + 1];
+        d[order + 1] = correction;
+        for i in (0..=order).rev() {
+            let next = d[i + 1];
+            d[i] += next;
+        }
+
+        if self.equal_steps < order + 1 {
+            return Ok(&self.state);
+        }
+
+        // Compare the errors of the neighbouring orders to pick the next one
+        let lower_error = if order > 1 {
+            error_norm(&(self.error_constants[order - 1] * d[order]), &scale)
+        } else {
+            f32::INFINITY
+        };
+        let higher_error = if order < MAX_ORDER {
+            error_norm(&(self.error_constants[order + 1] * d[order + 2]), &scale)
+        } else {
+            f32::INFINITY
+        };
+        let factors = [lower_error, error, higher_error]
+            .iter()
+            .enumerate()
+            .map(|(i, e)| e.powf(-1.0 / (order + i) as f32))
+            .collect::<Vec<_>>();
+        let best = (0..3).fold(
+            0,
+            |best, i| if factors[i] > factors[best] { i } else { best },
+        );
+        self.order = order + best - 1;
+
+        let factor = (safety * factors[best]).min(MAX_FACTOR);
+        self.step_size = Some(step_size * factor);
+        self.rescale(factor);
+        Ok(&self.state)
+    }
+
+    /// Steps until `end_time` is reached exactly.
+    pub fn integrate_to(&mut self, dae: &Dae<N>, end_time: f32) -> Result<&Vector<N>, SolverError> {
+        while self.time != end_time {
+            self.step(dae, end_time)?;
+        }
+        Ok(&self.state)
+    }
+
+    fn initialize(&mut self, dae: &Dae<N>, direction: f32) {
+        let step_size = match self.step_size {
+            Some(step_size) => step_size,
+            None => {
+                // The first guess of Hairer, Norsett and Wanner, without the
+                // second evaluation that needs y' as a function of y
+                let scale = self.tolerances.scale(&self.state, &self.state);
+                let d0 = error_norm(&self.state, &scale);
+                let d1 = error_norm(&self.derivative, &scale);
+                let guess = if d0 < 1e-5 || d1 < 1e-5 {
+                    1e-6
+                } else {
+                    0.01 * d0 / d1
+                };
+                guess.max(10.0 * min_step_size(self.time))
+            }
+        };
+        self.step_size = Some(step_size);
+        self.differences[1] = (step_size * direction) * self.derivative;
+        self.statistics.evaluations += 1;
+        let residual = dae.residual(self.time, self.state, self.derivative);
+        self.partials = Some(dae.partials(
+            self.time,
+            &self.state,
+            &self.derivative,
+            &residual,
+            &self.tolerances.absolute,
+            &mut self.statistics,
+        ));
+    }
+
+    /// Rescales the differences of the current order to a step size `factor` times as large.
+    fn rescale(&mut self, factor: f32) {
+        rescale_differences(&mut self.differences, self.order, factor);
+        self.equal_steps = 0;
+        self.lu = None;
+    }
+}
+
+/// Options of [`solve_dae`], built with the `with_*` methods.
+#[derive(Default)]
+pub struct DaeOptions<const N: usize> {
+    tolerances: Tolerances<Vector<N>>,
+    first_step: Option<f32>,
+    max_step: Option<f32>,
+}
+
+impl<const N: usize> DaeOptions<N> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_tolerances(mut self, tolerances: Tolerances<Vector<N>>) -> Self {
+        self.tolerances = tolerances;
+        self
+    }
+
+    pub fn with_first_step(mut self, step_size: f32) -> Self {
+        assert!(step_size > 0.0, "the first step size must be positive");
+        self.first_step = Some(step_size);
+        self
+    }
+
+    pub fn with_max_step(mut self, max_step: f32) -> Self {
+        assert!(max_step > 0.0, "the maximum step size must be positive");
+        self.max_step = Some(max_step);
+        self
+    }
+}
+
+/// Result of [`solve_dae`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct DaeSolution<const N: usize> {
+    pub times: Vec<f32>,
+    pub states: Vec<Vector<N>>,
+    pub derivatives: Vec<Vector<N>>,
+    pub status: Status,
+    pub statistics: Statistics,
+}
+
+impl<const N: usize> DaeSolution<N> {
+    pub fn success(&self) -> bool {
+        !matches!(self.status, Status::Failed(_))
+    }
+}
+
+/// Integrates the DAE over `t_span`, which may run backwards, after making
+/// `initial_state` and `initial_derivative` consistent.
+///
+/// A failed initialization leaves the solution empty.
+pub fn solve_dae<const N: usize>(
+    dae: &Dae<N>,
+    t_span: (f32, f32),
+    initial_state: Vector<N>,
+    initial_derivative: Vector<N>,
+    options: DaeOptions<N>,
+) -> DaeSolution<N> {
+    let (start_time, end_time) = t_span;
+    let mut solution = DaeSolution {
+        times: Vec::new(),
+        states: Vec::new(),
+        derivatives: Vec::new(),
+        status: Status::Finished,
+        statistics: Statistics::default(),
+    };
+    let (state, derivative) = match dae.consistent_initial_values(
+        start_time,
+        initial_state,
+        initial_derivative,
+        &options.tolerances,
+    ) {
+        Ok(values) => values,
+        Err(error) => {
+            solution.status = Status::Failed(error);
+            return solution;
+        }
+    };
+
+    let mut solver = DaeBdf::new(start_time, state, derivative, options.tolerances);
+    if let Some(step_size) = options.first_step {
+        solver = solver.with_first_step(step_size);
+    }
+    if let Some(max_step) = options.max_step {
+        solver = solver.with_max_step(max_step);
+    }
+    solution.times.push(start_time);
+    solution.states.push(state);
+    solution.derivatives.push(derivative);
+    while solver.time() != end_time {
+        if let Err(error) = solver.step(dae, end_time) {
+            solution.status = Status::Failed(error);
+            break;
+        }
+        solution.times.push(solver.time());
+        solution.states.push(*solver.state());
+        solution.derivatives.push(*solver.derivative());
+    }
+    solution.statistics = *solver.statistics();
+    solution
+}
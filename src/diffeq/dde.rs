@@ -0,0 +1,400 @@
+// Delay differential equations
+//
+//     y'(t) = f(t, y(t), y(t - tau_1), ..., y(t - tau_k))
+//
+// with y(t) = phi(t) for t <= t0, integrated by the method of steps on the
+// adaptive Runge-Kutta solvers. The delayed values come from the history
+// function before t0 and from the dense output of the accepted steps after
+// it. A delay shorter than the step reaches into the step being taken; such
+// steps are repeated with the interpolant of the previous attempt until the
+// delayed values settle.
+//
+// The solution has derivative discontinuities where a delayed argument
+// crosses an earlier discontinuity, starting from the jump of phi' at t0.
+// For a constant delay tau they lie at xi + tau and the steps are made to end
+// on them. For a state dependent delay the crossings of t - tau(t, y) = xi
+// are located on the interpolant of every step, which is cut short there.
+// Every propagation smooths the solution by one more derivative, so only a
+// limited number of levels is tracked.
+
+use std::{
+    cell::{Cell, RefCell},
+    rc::Rc,
+};
+
+use super::{
+    adaptive::{AdaptiveRungeKutta, Dop853, Rk23, Rk45, Tsit5},
+    control::{Statistics, Tolerances},
+    dense::{DenseSolution, Interpolant},
+    event::locate_root,
+    ivp::SolveFun,
+    solve::{Method, Status},
+    state::OdeState,
+    tableau::EmbeddedTableau,
+};
+
+/// Right-hand side `f(t, y(t), [y(t - tau_1), ..., y(t - tau_k)])`.
+pub type DdeFun<Y> = dyn Fn(f32, Y, &[Y]) -> Y;
+/// Solution `phi(t)` before the start of the integration.
+pub type HistoryFun<Y> = dyn Fn(f32) -> Y;
+pub type DelayFun<Y> = dyn Fn(f32, Y) -> f32;
+
+pub enum Delay<Y: OdeState> {
+    Constant(f32),
+    /// Delay `tau(t, y(t))`, which must not become negative.
+    StateDependent(Box<DelayFun<Y>>),
+}
+
+impl<Y: OdeState> Delay<Y> {
+    pub fn constant(delay: f32) -> Self {
+        assert!(delay > 0.0, "a constant delay must be positive");
+        Delay::Constant(delay)
+    }
+
+    pub fn state_dependent(delay: impl Fn(f32, Y) -> f32 + 'static) -> Self {
+        Delay::StateDependent(Box::new(delay))
+    }
+
+    pub fn evaluate(&self, time: f32, state: Y) -> f32 {
+        let delay = match self {
+            Delay::Constant(delay) => *delay,
+            Delay::StateDependent(delay) => delay(time, state),
+        };
+        assert!(
+            delay >= 0.0,
+            "negative delay {} at time {}, the solution would depend on the future",
+            delay,
+            time
+        );
+        delay
+    }
+}
+
+/// Options of [`solve_dde`], built with the `with_*` methods.
+pub struct DdeOptions<Y: OdeState> {
+    method: Method,
+    tolerances: Tolerances<Y>,
+    max_step: f32,
+    discontinuity_levels: usize,
+}
+
+impl<Y: OdeState> Default for DdeOptions<Y> {
+    fn default() -> Self {
+        Self {
+            method: Method::default(),
+            tolerances: Tolerances::default(),
+            max_step: f32::INFINITY,
+            discontinuity_levels: 5,
+        }
+    }
+}
+
+impl<Y: OdeState> DdeOptions<Y> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// One of the explicit Runge-Kutta methods.
+    pub fn with_method(mut self, method: Method) -> Self {
+        assert!(
+            !method.is_implicit(),
+            "the DDE solver is built on the explicit Runge-Kutta methods"
+        );
+        self.method = method;
+        self
+    }
+
+    pub fn with_tolerances(mut self, tolerances: Tolerances<Y>) -> Self {
+        self.tolerances = tolerances;
+        self
+    }
+
+    pub fn with_max_step(mut self, max_step: f32) -> Self {
+        assert!(max_step > 0.0, "the maximum step size must be positive");
+        self.max_step = max_step;
+        self
+    }
+
+    /// Number of times the discontinuity at the start is propagated through
+    /// the delays, 5 by default.
+    pub fn with_discontinuity_levels(mut self, levels: usize) -> Self {
+        self.discontinuity_levels = levels;
+        self
+    }
+}
+
+/// Result of [`solve_dde`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct DdeSolution<Y: OdeState> {
+    pub times: Vec<f32>,
+    pub states: Vec<Y>,
+    /// Interpolants of all steps, the history buffer of the integration.
+    pub dense: DenseSolution<Y>,
+    /// Derivative discontinuities within the integrated interval, including
+    /// the start.
+    pub discontinuities: Vec<f32>,
+    pub status: Status,
+    pub statistics: Statistics,
+}
+
+impl<Y: OdeState> DdeSolution<Y> {
+    pub fn success(&self) -> bool {
+        !matches!(self.status, Status::Failed(_))
+    }
+}
+
+/// Past values of the solution.
+struct History<Y: OdeState> {
+    start_time: f32,
+    initial: Box<HistoryFun<Y>>,
+    dense: DenseSolution<Y>,
+    /// Interpolant of the previous attempt at the current step.
+    extrapolation: Option<Interpolant<Y>>,
+    /// Whether a delayed value was needed beyond the accepted steps.
+    overlapped: Cell<bool>,
+}
+
+impl<Y: OdeState> History<Y> {
+    fn evaluate(&self, time: f32) -> Y {
+        if time <= self.start_time {
+            return (self.initial)(time);
+        }
+        if let Some(end_time) = self.dense.end_time() {
+            if time <= end_time {
+                return self.dense.evaluate(time);
+            }
+        }
+        self.overlapped.set(true);
+        match (&self.extrapolation, self.dense.pieces().last()) {
+            (Some(piece), _) | (None, Some(piece)) => piece.evaluate(time),
+            (None, None) => (self.initial)(self.start_time),
+        }
+    }
+}
+
+/// Repetitions of a step whose delayed values reach into the step itself.
+const OVERLAP_ITERATIONS: usize = 3;
+
+/// Integrates the delay differential equation over `t_span` from the
+/// history `phi`, starting with `y(t0) = phi(t0)`.
+///
+/// With constant delays the steps are limited to the shortest delay, so that
+/// the delayed values always come from accepted steps.
+pub fn solve_dde<Y: OdeState + 'static>(
+    fun: impl Fn(f32, Y, &[Y]) -> Y + 'static,
+    delays: Vec<Delay<Y>>,
+    history: impl Fn(f32) -> Y + 'static,
+    t_span: (f32, f32),
+    options: DdeOptions<Y>,
+) -> DdeSolution<Y> {
+    let (start_time, end_time) = t_span;
+    assert!(end_time > start_time, "DDEs are integrated forward in time");
+    let initial_state = history(start_time);
+    let history = Rc::new(RefCell::new(History {
+        start_time,
+        initial: Box::new(history),
+        dense: DenseSolution::new(),
+        extrapolation: None,
+        overlapped: Cell::new(false),
+    }));
+    let delays = Rc::new(delays);
+
+    let buffer = Rc::clone(&history);
+    let lags = Rc::clone(&delays);
+    let rhs = move |time: f32, state: Y| {
+        let buffer = buffer.borrow();
+        let delayed: Vec<Y> = lags
+            .iter()
+            .map(|delay| buffer.evaluate(time - delay.evaluate(time, state.clone())))
+            .collect();
+        fun(time, state, &delayed)
+    };
+
+    let shortest = delays
+        .iter()
+        .filter_map(|delay| match delay {
+            Delay::Constant(delay) => Some(*delay),
+            Delay::StateDependent(_) => None,
+        })
+        .fold(f32::INFINITY, f32::min);
+    let max_step = options.max_step.min(shortest);
+    let tolerances = options.tolerances;
+    let integration = Integration {
+        fun: &rhs,
+        history: &history,
+        delays: &delays,
+        end_time,
+        levels: options.discontinuity_levels,
+    };
+    match options.method {
+        Method::Rk23 => integration.run(Rk23::new(start_time, initial_state, tolerances), max_step),
+        Method::Rk45 => integration.run(Rk45::new(start_time, initial_state, tolerances), max_step),
+        Method::Tsit5 => {
+            integration.run(Tsit5::new(start_time, initial_state, tolerances), max_step)
+        }
+        Method::Dop853 => {
+            integration.run(Dop853::new(start_time, initial_state, tolerances), max_step)
+        }
+        method => panic!("{:?} is not an explicit Runge-Kutta method", method),
+    }
+}
+
+struct Integration<'a, Y: OdeState> {
+    fun: &'a SolveFun<Y>,
+    history: &'a RefCell<History<Y>>,
+    delays: &'a [Delay<Y>],
+    end_time: f32,
+    levels: usize,
+}
+
+impl<Y: OdeState> Integration<'_, Y> {
+    fn run<T: EmbeddedTableau>(
+        &self,
+        solver: AdaptiveRungeKutta<T, Y>,
+        max_step: f32,
+    ) -> DdeSolution<Y> {
+        let mut solver = solver.with_max_step(max_step);
+        let start_time = solver.time();
+        let mut times = vec![start_time];
+        let mut states = vec![solver.state().clone()];
+        // Discontinuities with the number of times they were propagated
+        let mut discontinuities = vec![(start_time, 0)];
+        let breakpoints = self.constant_breakpoints(start_time);
+        let mut next_breakpoint = 0;
+        let mut status = Status::Finished;
+
+        while solver.time() < self.end_time {
+            let (step_start, step_state) = (solver.time(), solver.state().clone());
+            while next_breakpoint < breakpoints.len()
+                && breakpoints[next_breakpoint].0 <= step_start
+            {
+                discontinuities.push(breakpoints[next_breakpoint]);
+                next_breakpoint += 1;
+            }
+            let mut bound = breakpoints
+                .get(next_breakpoint)
+                .map_or(self.end_time, |breakpoint| breakpoint.0);
+
+            let mut piece = None;
+            for iteration in 0..=OVERLAP_ITERATIONS {
+                self.history.borrow().overlapped.set(false);
+                if let Err(error) = solver.step(self.fun, bound) {
+                    status = Status::Failed(error);
+                    break;
+                }
+                let interpolant = solver.interpolant(self.fun).unwrap();
+                let overlapped = self.history.borrow().overlapped.get();
+                piece = Some(interpolant);
+                if !overlapped || iteration == OVERLAP_ITERATIONS {
+                    break;
+                }
+                // Repeat the step with the delayed values from this attempt
+                bound = solver.time();
+                self.history.borrow_mut().extrapolation = piece.clone();
+                solver.restart(step_start, step_state.clone());
+            }
+            self.history.borrow_mut().extrapolation = None;
+            let Some(mut piece) = piece.filter(|_| status == Status::Finished) else {
+                break;
+            };
+
+            if let Some((time, level)) = self.first_crossing(&piece, &discontinuities) {
+                let state = piece.evaluate(time);
+                piece = piece.truncated(time);
+                solver.restart(time, state);
+                discontinuities.push((time, level));
+            }
+            self.history.borrow_mut().dense.push(piece);
+            times.push(solver.time());
+            states.push(solver.state().clone());
+        }
+        discontinuities.extend(
+            breakpoints[next_breakpoint..]
+                .iter()
+                .filter(|breakpoint| breakpoint.0 <= solver.time()),
+        );
+
+        let mut discontinuities: Vec<f32> = discontinuities.into_iter().map(|(t, _)| t).collect();
+        discontinuities.sort_by(f32::total_cmp);
+        discontinuities.dedup();
+        DdeSolution {
+            times,
+            states,
+            dense: std::mem::take(&mut self.history.borrow_mut().dense),
+            discontinuities,
+            status,
+            statistics: *solver.statistics(),
+        }
+    }
+
+    /// Propagation of the start through the constant delays, sorted and
+    /// without the start itself.
+    fn constant_breakpoints(&self, start_time: f32) -> Vec<(f32, usize)> {
+        let constant: Vec<f32> = self
+            .delays
+            .iter()
+            .filter_map(|delay| match delay {
+                Delay::Constant(delay) => Some(*delay),
+                Delay::StateDependent(_) => None,
+            })
+            .collect();
+        let mut breakpoints: Vec<(f32, usize)> = Vec::new();
+        let mut frontier = vec![start_time];
+        for level in 1..=self.levels {
+            let mut next: Vec<f32> = Vec::new();
+            for time in frontier
+                .iter()
+                .flat_map(|xi| constant.iter().map(move |tau| xi + tau))
+            {
+                let known = breakpoints
+                    .iter()
+                    .map(|breakpoint| breakpoint.0)
+                    .chain(next.iter().copied())
+                    .any(|other| same_time(time, other));
+                if time <= self.end_time && !known {
+                    next.push(time);
+                }
+            }
+            breakpoints.extend(next.iter().map(|&time| (time, level)));
+            frontier = next;
+        }
+        breakpoints.sort_by(|a, b| a.0.total_cmp(&b.0));
+        breakpoints
+    }
+
+    /// Earliest time in the step of `piece` where the argument of a state
+    /// dependent delay crosses a discontinuity that is still tracked.
+    fn first_crossing(
+        &self,
+        piece: &Interpolant<Y>,
+        discontinuities: &[(f32, usize)],
+    ) -> Option<(f32, usize)> {
+        let (start, end) = (piece.start_time(), piece.end_time());
+        let tolerance = 4.0 * f32::EPSILON * (end - start);
+        let mut first: Option<(f32, usize)> = None;
+        for delay in self.delays.iter() {
+            if let Delay::Constant(_) = delay {
+                continue;
+            }
+            for &(xi, level) in discontinuities {
+                if level >= self.levels {
+                    continue;
+                }
+                let g = |t: f32| t - delay.evaluate(t, piece.evaluate(t)) - xi;
+                let (g_start, g_end) = (g(start), g(end));
+                if g_start == 0.0 || g_start.signum() * g_end > 0.0 {
+                    continue;
+                }
+                let time = locate_root(g, start, g_start, end, g_end, tolerance);
+                if time > start && first.is_none_or(|first| time < first.0) {
+                    first = Some((time, level + 1));
+                }
+            }
+        }
+        first
+    }
+}
+
+fn same_time(a: f32, b: f32) -> bool {
+    (a - b).abs() <= 16.0 * f32::EPSILON * a.abs().max(b.abs()).max(1.0)
+}
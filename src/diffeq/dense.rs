@@ -0,0 +1,203 @@
+// Dense output: polynomials that interpolate the solution inside accepted steps.
+
+use crate::linalg::real::Real;
+
+use super::state::OdeState;
+
+/// Polynomial `y(t) = y_0 + sum_k c_k theta^(k + 1)` with
+/// `theta = (t - t_0) / (t_1 - t_0)`, valid on one step `[t_0, t_1]`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Interpolant<Y, R = f32> {
+    start_time: R,
+    end_time: R,
+    start_state: Y,
+    coefficients: Vec<Y>,
+}
+
+impl<Y: OdeState<R>, R: Real> Interpolant<Y, R> {
+    pub fn new(start_time: R, end_time: R, start_state: Y, coefficients: Vec<Y>) -> Self {
+        Self {
+            start_time,
+            end_time,
+            start_state,
+            coefficients,
+        }
+    }
+
+    /// Cubic Hermite polynomial matching the states and derivatives at both
+    /// ends of a step. Third order accurate for any one-step method.
+    pub fn hermite(
+        start_time: R,
+        start_state: Y,
+        start_derivative: Y,
+        end_time: R,
+        end_state: Y,
+        end_derivative: Y,
+    ) -> Self {
+        let delta_time = end_time - start_time;
+        let delta_state = end_state - start_state.clone();
+        let slope_start = start_derivative * delta_time;
+        let slope_end = end_derivative * delta_time;
+        let (two, three) = (R::from_f64(2.0), R::from_f64(3.0));
+        let coefficients = vec![
+            slope_start.clone(),
+            delta_state.clone() * three - slope_start.clone() * two - slope_end.clone(),
+            slope_start + slope_end - delta_state * two,
+        ];
+        Self::new(start_time, end_time, start_state, coefficients)
+    }
+
+    /// Converts the nested form used by DOP853,
+    /// `F_0 + (1 - theta) (F_1 + theta (F_2 + (1 - theta) (F_3 + ...)))`
+    /// multiplied by `theta`, to the power basis.
+    pub(crate) fn from_nested(start_time: R, end_time: R, start_state: Y, nested: &[Y]) -> Self {
+        // polynomial[k] is the coefficient of theta^k
+        let zero = start_state.zero_like();
+        let mut polynomial = vec![zero.clone(); nested.len() + 1];
+        for (i, term) in nested.iter().enumerate().rev() {
+            polynomial[0] = polynomial[0].clone() + term.clone();
+            // Multiply by theta, then subtract the original for 1 - theta
+            let shifted: Vec<_> = std::iter::once(zero.clone())
+                .chain(polynomial[..nested.len()].iter().cloned())
+                .collect();
+            if i % 2 == 0 {
+                polynomial = shifted;
+            } else {
+                for (p, s) in polynomial.iter_mut().zip(shifted) {
+                    *p = p.clone() - s;
+                }
+            }
+        }
+        polynomial.remove(0);
+        Self::new(start_time, end_time, start_state, polynomial)
+    }
+
+    pub fn start_time(&self) -> R {
+        self.start_time
+    }
+
+    pub fn end_time(&self) -> R {
+        self.end_time
+    }
+
+    /// Polynomial degree.
+    pub fn degree(&self) -> usize {
+        self.coefficients.len()
+    }
+
+    /// The same polynomial restricted to `[start_time, end_time]`, for a step
+    /// that was cut short by an event.
+    pub fn truncated(&self, end_time: R) -> Self {
+        let ratio = (end_time - self.start_time) / (self.end_time - self.start_time);
+        let mut power = R::ONE;
+        let coefficients = self
+            .coefficients
+            .iter()
+            .map(|coefficient| {
+                power *= ratio;
+                coefficient.clone() * power
+            })
+            .collect();
+        Self::new(
+            self.start_time,
+            end_time,
+            self.start_state.clone(),
+            coefficients,
+        )
+    }
+
+    /// Evaluates the polynomial, also outside of its step.
+    pub fn evaluate(&self, time: R) -> Y {
+        let theta = (time - self.start_time) / (self.end_time - self.start_time);
+        let mut result = self.start_state.zero_like();
+        for coefficient in self.coefficients.iter().rev() {
+            result = (result + coefficient.clone()) * theta;
+        }
+        result + self.start_state.clone()
+    }
+
+    /// Time derivative of the polynomial.
+    pub fn derivative(&self, time: R) -> Y {
+        let delta_time = self.end_time - self.start_time;
+        let theta = (time - self.start_time) / delta_time;
+        let mut result = self.start_state.zero_like();
+        for (k, coefficient) in self.coefficients.iter().enumerate().rev() {
+            result = result * theta + coefficient.clone() * R::from_f64((k + 1) as f64);
+        }
+        result * (R::ONE / delta_time)
+    }
+}
+
+/// Continuous solution made of the interpolants of consecutive steps.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DenseSolution<Y> {
+    pieces: Vec<Interpolant<Y>>,
+}
+
+impl<Y: OdeState> Default for DenseSolution<Y> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<Y: OdeState> DenseSolution<Y> {
+    pub fn new() -> Self {
+        Self { pieces: Vec::new() }
+    }
+
+    /// Appends the interpolant of the step that follows the last one.
+    pub fn push(&mut self, piece: Interpolant<Y>) {
+        if let Some(last) = self.pieces.last() {
+            assert_eq!(
+                last.end_time, piece.start_time,
+                "steps must be contiguous, the solution ends at {}",
+                last.end_time
+            );
+            assert!(
+                (last.end_time - last.start_time) * (piece.end_time - piece.start_time) > 0.0,
+                "steps must all go in the same direction"
+            );
+        }
+        self.pieces.push(piece);
+    }
+
+    pub fn pieces(&self) -> &[Interpolant<Y>] {
+        &self.pieces
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pieces.is_empty()
+    }
+
+    pub fn start_time(&self) -> Option<f32> {
+        self.pieces.first().map(|piece| piece.start_time)
+    }
+
+    pub fn end_time(&self) -> Option<f32> {
+        self.pieces.last().map(|piece| piece.end_time)
+    }
+
+    /// Evaluates the solution at `time` inside the integration range.
+    pub fn evaluate(&self, time: f32) -> Y {
+        let (start, end) = match (self.start_time(), self.end_time()) {
+            (Some(start), Some(end)) => (start, end),
+            _ => panic!("cannot evaluate an empty dense solution"),
+        };
+        assert!(
+            time >= start.min(end) && time <= start.max(end),
+            "{} is outside of the integration range [{}, {}]",
+            time,
+            start.min(end),
+            start.max(end)
+        );
+        let direction = (end - start).signum();
+        let index = self
+            .pieces
+            .partition_point(|piece| direction * (piece.end_time - time) < 0.0);
+        self.pieces[index.min(self.pieces.len() - 1)].evaluate(time)
+    }
+
+    pub fn sample(&self, times: &[f32]) -> Vec<Y> {
+        times.iter().map(|&time| self.evaluate(time)).collect()
+    }
+}
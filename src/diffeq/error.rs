@@ -0,0 +1,112 @@
+use std::fmt;
+
+/// Failure of an adaptive solver.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SolverError {
+    /// The step size required to meet the tolerances fell below the smallest
+    /// step that can be distinguished from the current time.
+    StepSizeTooSmall { time: f32, step_size: f32 },
+    /// The Newton iteration of an implicit fixed step method did not converge,
+    /// even with a freshly evaluated Jacobian.
+    NewtonDidNotConverge { time: f32 },
+    /// The iteration matrix of an implicit method is singular.
+    SingularIterationMatrix { time: f32 },
+}
+
+impl fmt::Display for SolverError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SolverError::StepSizeTooSmall { time, step_size } => write!(
+                f,
+                "required step size {} is too small at time {}",
+                step_size, time
+            ),
+            SolverError::NewtonDidNotConverge { time } => {
+                write!(f, "Newton iteration did not converge at time {}", time)
+            }
+            SolverError::SingularIterationMatrix { time } => {
+                write!(f, "iteration matrix is singular at time {}", time)
+            }
+        }
+    }
+}
+
+impl std::error::Error for SolverError {}
+
+/// Failure of a boundary value problem solver.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BvpError {
+    /// The Jacobian of the nonlinear system is singular.
+    SingularJacobian,
+    /// The damped Newton iteration did not reduce the residuals enough.
+    NewtonDidNotConverge { iterations: usize },
+    /// Mesh refinement needed more nodes than allowed.
+    MaxNodesExceeded { nodes: usize },
+    /// An initial value problem solved while shooting failed.
+    Ivp(SolverError),
+}
+
+impl From<SolverError> for BvpError {
+    fn from(error: SolverError) -> Self {
+        BvpError::Ivp(error)
+    }
+}
+
+impl fmt::Display for BvpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BvpError::SingularJacobian => {
+                write!(f, "Jacobian of the boundary value problem is singular")
+            }
+            BvpError::NewtonDidNotConverge { iterations } => {
+                write!(
+                    f,
+                    "Newton iteration did not converge in {} iterations",
+                    iterations
+                )
+            }
+            BvpError::MaxNodesExceeded { nodes } => {
+                write!(f, "mesh refinement needs more than {} nodes", nodes)
+            }
+            BvpError::Ivp(error) => write!(f, "shooting failed: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for BvpError {}
+
+/// Failure of a parameter fit.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FitError {
+    /// The ODE could not be solved with the initial parameters.
+    Ivp(SolverError),
+    /// The iteration limit was reached before the convergence criteria were met.
+    DidNotConverge {
+        iterations: usize,
+        sum_of_squares: f32,
+    },
+}
+
+impl From<SolverError> for FitError {
+    fn from(error: SolverError) -> Self {
+        FitError::Ivp(error)
+    }
+}
+
+impl fmt::Display for FitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FitError::Ivp(error) => write!(f, "initial parameters: {}", error),
+            FitError::DidNotConverge {
+                iterations,
+                sum_of_squares,
+            } => write!(
+                f,
+                "fit did not converge in {} iterations, sum of squares {}",
+                iterations, sum_of_squares
+            ),
+        }
+    }
+}
+
+impl std::error::Error for FitError {}
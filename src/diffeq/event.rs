@@ -0,0 +1,363 @@
+// Events: zero crossings of a scalar function of the solution.
+
+use super::{
+    dense::Interpolant,
+    error::SolverError,
+    ivp::SolveFun,
+    solver::{step_interpolant, OdeSolver},
+    state::OdeState,
+};
+
+pub type EventFun<Y> = dyn Fn(f32, Y) -> f32;
+/// Maps the state at an event to the state the integration continues from.
+pub type EventAction<Y> = dyn Fn(f32, Y) -> Y;
+
+/// Which sign changes of an event function are reported.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Direction {
+    #[default]
+    Both,
+    /// From negative to zero or positive.
+    Rising,
+    /// From positive to zero or negative.
+    Falling,
+}
+
+/// Event that occurs when `g(t, y)` changes sign.
+///
+/// Terminal events stop the integration. An action replaces the state at
+/// the event, e.g. to reverse the velocity of a bouncing ball, and the
+/// integration restarts from there.
+pub struct Event<Y> {
+    function: Box<EventFun<Y>>,
+    direction: Direction,
+    terminal: bool,
+    action: Option<Box<EventAction<Y>>>,
+}
+
+impl<Y: OdeState> Event<Y> {
+    pub fn new(function: impl Fn(f32, Y) -> f32 + 'static) -> Self {
+        Self {
+            function: Box::new(function),
+            direction: Direction::Both,
+            terminal: false,
+            action: None,
+        }
+    }
+
+    pub fn with_direction(mut self, direction: Direction) -> Self {
+        self.direction = direction;
+        self
+    }
+
+    pub fn with_terminal(mut self, terminal: bool) -> Self {
+        self.terminal = terminal;
+        self
+    }
+
+    pub fn with_action(mut self, action: impl Fn(f32, Y) -> Y + 'static) -> Self {
+        self.action = Some(Box::new(action));
+        self
+    }
+
+    pub fn direction(&self) -> Direction {
+        self.direction
+    }
+
+    pub fn is_terminal(&self) -> bool {
+        self.terminal
+    }
+
+    pub fn has_action(&self) -> bool {
+        self.action.is_some()
+    }
+
+    pub fn evaluate(&self, time: f32, state: Y) -> f32 {
+        (self.function)(time, state)
+    }
+
+    /// Applies the action, if any, to the state at the event.
+    pub fn apply(&self, time: f32, state: Y) -> Y {
+        match &self.action {
+            Some(action) => action(time, state),
+            None => state,
+        }
+    }
+
+    /// Whether going from `before` to `after` is a crossing of this event.
+    /// A crossing has to start from a non-zero value, so an integration that
+    /// restarts exactly on an event does not report it again.
+    pub fn triggers(&self, before: f32, after: f32) -> bool {
+        let rising = before < 0.0 && after >= 0.0;
+        let falling = before > 0.0 && after <= 0.0;
+        match self.direction {
+            Direction::Both => rising || falling,
+            Direction::Rising => rising,
+            Direction::Falling => falling,
+        }
+    }
+}
+
+/// Event located during an integration.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct EventOccurrence<Y> {
+    /// Index of the event in the slice passed to the solver.
+    pub event: usize,
+    pub time: f32,
+    /// State at `time`, before the action of the event is applied.
+    pub state: Y,
+}
+
+/// Events found by an integration, in the order they occurred.
+#[derive(Clone, Debug, PartialEq)]
+pub struct EventLog<Y> {
+    pub occurrences: Vec<EventOccurrence<Y>>,
+    /// Index of the terminal event that stopped the integration.
+    pub terminated_by: Option<usize>,
+}
+
+impl<Y> Default for EventLog<Y> {
+    fn default() -> Self {
+        Self {
+            occurrences: Vec::new(),
+            terminated_by: None,
+        }
+    }
+}
+
+impl<Y> EventLog<Y> {
+    /// Occurrences of the event with index `event`.
+    pub fn of(&self, event: usize) -> impl Iterator<Item = &EventOccurrence<Y>> {
+        self.occurrences
+            .iter()
+            .filter(move |occurrence| occurrence.event == event)
+    }
+}
+
+/// Steps `solver` until `end_time` or a terminal event, locating the sign
+/// changes of the event functions on the interpolant of every step.
+///
+/// After an event with an action the integration restarts from the
+/// modified state, discarding the rest of the step.
+pub fn integrate_with_events<Y: OdeState>(
+    solver: &mut (impl OdeSolver<Y> + ?Sized),
+    fun: &SolveFun<Y>,
+    end_time: f32,
+    events: &[Event<Y>],
+) -> Result<EventLog<Y>, SolverError> {
+    let mut log = EventLog::default();
+    let mut tracker = EventTracker::new(events, solver.time(), solver.state().clone());
+    while solver.time() != end_time {
+        let (start_time, start_state) = (solver.time(), solver.state().clone());
+        solver.step(fun, end_time)?;
+        let mut interpolant = None;
+        if tracker.after_step(
+            solver,
+            fun,
+            start_time,
+            start_state,
+            &mut interpolant,
+            &mut log,
+        ) {
+            break;
+        }
+    }
+    Ok(log)
+}
+
+/// Values of the event functions at the start of the current step.
+pub(crate) struct EventTracker<'a, Y> {
+    events: &'a [Event<Y>],
+    values: Vec<f32>,
+    /// Events that rest on their root, with the largest `|g|` still counted
+    /// as zero. They are re-armed once `g` has moved away from the root.
+    resting: Vec<Option<f32>>,
+}
+
+/// Interior points at which the interpolant is sampled to find where a
+/// resting event leaves its root.
+const RESTING_SAMPLES: usize = 8;
+
+impl<'a, Y: OdeState> EventTracker<'a, Y> {
+    pub(crate) fn new(events: &'a [Event<Y>], time: f32, state: Y) -> Self {
+        let values: Vec<f32> = events
+            .iter()
+            .map(|event| event.evaluate(time, state.clone()))
+            .collect();
+        let resting = values
+            .iter()
+            .map(|&value| (value == 0.0).then_some(0.0))
+            .collect();
+        Self {
+            events,
+            values,
+            resting,
+        }
+    }
+
+    /// Records the events in the step that `solver` just took and returns
+    /// whether a terminal event stopped the integration.
+    ///
+    /// The solver is restarted at the first terminal event or event with an
+    /// action. `interpolant` is filled in when an event has to be located.
+    pub(crate) fn after_step(
+        &mut self,
+        solver: &mut (impl OdeSolver<Y> + ?Sized),
+        fun: &SolveFun<Y>,
+        start_time: f32,
+        start_state: Y,
+        interpolant: &mut Option<Interpolant<Y>>,
+        log: &mut EventLog<Y>,
+    ) -> bool {
+        let events = self.events;
+        let (end_time, end_state) = (solver.time(), solver.state().clone());
+        let next_values: Vec<f32> = events
+            .iter()
+            .map(|event| event.evaluate(end_time, end_state.clone()))
+            .collect();
+        if self.resting.iter().any(Option::is_some) {
+            interpolant.get_or_insert_with(|| {
+                step_interpolant(solver, fun, start_time, start_state.clone())
+            });
+        }
+
+        // Start of the bracket of every event that changed sign in the step
+        let mut triggered: Vec<(usize, f32)> = Vec::new();
+        for (i, event) in events.iter().enumerate() {
+            let before = match (self.resting[i], interpolant.as_ref()) {
+                (Some(threshold), Some(interpolant)) => {
+                    let g = |t| event.evaluate(t, interpolant.evaluate(t));
+                    let delta_time = (end_time - start_time) / RESTING_SAMPLES as f32;
+                    let departure = (1..RESTING_SAMPLES)
+                        .map(|k| start_time + k as f32 * delta_time)
+                        .map(|t| (t, g(t)))
+                        .chain(std::iter::once((end_time, next_values[i])))
+                        .find(|(_, value)| value.abs() > threshold);
+                    match departure {
+                        Some((time, value)) => {
+                            self.resting[i] = None;
+                            Some((time, value))
+                        }
+                        None => None,
+                    }
+                }
+                _ => Some((start_time, self.values[i])),
+            };
+            if let Some((time, value)) = before {
+                if time != end_time && event.triggers(value, next_values[i]) {
+                    triggered.push((i, time));
+                }
+            }
+        }
+        self.values = next_values;
+        if triggered.is_empty() {
+            return false;
+        }
+
+        let interpolant = interpolant
+            .get_or_insert_with(|| step_interpolant(solver, fun, start_time, start_state));
+        let tolerance = 4.0 * f32::EPSILON * (end_time - start_time).abs();
+        let mut crossings: Vec<(f32, usize)> = triggered
+            .into_iter()
+            .map(|(i, bracket_start)| {
+                let event = &events[i];
+                let g = |t| event.evaluate(t, interpolant.evaluate(t));
+                let (g_start, g_end) = (g(bracket_start), g(end_time));
+                let time = if event.triggers(g_start, g_end) {
+                    locate_root(g, bracket_start, g_start, end_time, g_end, tolerance)
+                } else {
+                    end_time
+                };
+                (time, i)
+            })
+            .collect();
+        let direction = (end_time - start_time).signum();
+        crossings.sort_by(|a, b| (direction * a.0).total_cmp(&(direction * b.0)));
+
+        for (time, i) in crossings {
+            let event = &events[i];
+            let state = if time == end_time {
+                end_state.clone()
+            } else {
+                interpolant.evaluate(time)
+            };
+            log.occurrences.push(EventOccurrence {
+                event: i,
+                time,
+                state: state.clone(),
+            });
+            if event.is_terminal() {
+                solver.restart(time, state);
+                log.terminated_by = Some(i);
+                return true;
+            }
+            if event.has_action() {
+                let residual = event.evaluate(time, state.clone());
+                let state = event.apply(time, state);
+                solver.restart(time, state.clone());
+                *self = Self::new(events, time, state);
+                // The root is only bracketed, so g is a small value of either
+                // sign here. Unless the action moved away from the root, the
+                // event rests on it instead of seeing the same crossing again.
+                if self.values[i].abs() <= residual.abs() {
+                    self.resting[i] = Some(residual.abs());
+                }
+                break;
+            }
+        }
+        false
+    }
+}
+
+/// Locates a sign change of `g` between `start` and `end`, where
+/// `g(start) = g_start` is non-zero and `g(end) = g_end` has the opposite sign
+/// or is zero. Uses the Illinois variant of regula falsi and returns the end
+/// of the final bracket, where `g` has already changed sign.
+pub fn locate_root(
+    g: impl Fn(f32) -> f32,
+    start: f32,
+    g_start: f32,
+    end: f32,
+    g_end: f32,
+    tolerance: f32,
+) -> f32 {
+    assert!(
+        g_start != 0.0 && g_start.signum() * g_end <= 0.0,
+        "g does not change sign between {} and {}",
+        start,
+        end
+    );
+    let (mut a, mut g_a, mut b, mut g_b) = (start, g_start, end, g_end);
+    let tolerance = tolerance.max(4.0 * f32::EPSILON * start.abs().max(end.abs()));
+    let mut last_side = 0;
+    for _ in 0..100 {
+        if (b - a).abs() <= tolerance || g_b == 0.0 {
+            break;
+        }
+        let mut t = (a * g_b - b * g_a) / (g_b - g_a);
+        // Fall back to bisection when the secant leaves the bracket
+        if t.is_nan() || (t - a) * (b - t) <= 0.0 {
+            t = 0.5 * (a + b);
+            if t == a || t == b {
+                break;
+            }
+        }
+        let g_t = g(t);
+        if g_a.signum() * g_t > 0.0 {
+            a = t;
+            g_a = g_t;
+            if last_side == -1 {
+                g_b *= 0.5;
+            }
+            last_side = -1;
+        } else {
+            b = t;
+            g_b = g_t;
+            if last_side == 1 {
+                g_a *= 0.5;
+            }
+            last_side = 1;
+        }
+    }
+    b
+}
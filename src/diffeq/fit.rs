@@ -0,0 +1,312 @@
+// Estimation of the parameters `p` of
+//
+//     y' = f(t, y, p),    y(t_0) = y_0
+//
+// from measurements `d_k` of the solution at the times `t_k`, minimising the
+// weighted sum of squares
+//
+//     S(p) = sum_k |W (y(t_k; p) - d_k)|^2
+//
+// with the Levenberg-Marquardt method. The Jacobian of the residuals comes
+// from the forward sensitivities `dy/dp`, so every iteration costs one
+// integration of the sensitivity equations.
+
+use std::rc::Rc;
+
+use crate::linalg::ndarray::{Matrix, Vector};
+
+use super::{
+    control::{Statistics, Tolerances},
+    error::{FitError, SolverError},
+    sensitivity::{forward_sensitivity, ParamFun, SensitivityOptions},
+    solve::Status,
+};
+
+/// Options of [`fit_parameters`], built with the `with_*` methods.
+#[derive(Clone)]
+pub struct FitOptions<const N: usize, const P: usize> {
+    sensitivity: SensitivityOptions<N, P>,
+    weights: Vector<N>,
+    max_iterations: usize,
+    cost_tolerance: f32,
+    step_tolerance: f32,
+    gradient_tolerance: f32,
+}
+
+impl<const N: usize, const P: usize> Default for FitOptions<N, P> {
+    fn default() -> Self {
+        Self {
+            // The integration error has to stay well below the misfit
+            sensitivity: SensitivityOptions::new().with_tolerances(Tolerances::new(1e-6, 1e-8)),
+            weights: Matrix::from_fn(|_, _| 1.0),
+            max_iterations: 100,
+            cost_tolerance: 1e-6,
+            step_tolerance: 1e-6,
+            gradient_tolerance: 1e-8,
+        }
+    }
+}
+
+impl<const N: usize, const P: usize> FitOptions<N, P> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Method, tolerances and Jacobians of the integrations.
+    pub fn with_sensitivity_options(mut self, options: SensitivityOptions<N, P>) -> Self {
+        self.sensitivity = options;
+        self
+    }
+
+    /// Weights `W` of the components, typically one over their measurement
+    /// errors. A zero weight leaves a component unobserved, and its data is
+    /// ignored, so it may be NaN.
+    pub fn with_weights(mut self, weights: Vector<N>) -> Self {
+        assert!(
+            (0..N).all(|i| weights[i] >= 0.0),
+            "the weights must not be negative"
+        );
+        self.weights = weights;
+        self
+    }
+
+    pub fn with_max_iterations(mut self, max_iterations: usize) -> Self {
+        assert!(max_iterations > 0, "at least one iteration is required");
+        self.max_iterations = max_iterations;
+        self
+    }
+
+    /// Stops when an accepted step reduces the sum of squares by less than
+    /// this fraction.
+    pub fn with_cost_tolerance(mut self, tolerance: f32) -> Self {
+        self.cost_tolerance = tolerance;
+        self
+    }
+
+    /// Stops when the step is shorter than this fraction of the parameters.
+    pub fn with_step_tolerance(mut self, tolerance: f32) -> Self {
+        self.step_tolerance = tolerance;
+        self
+    }
+
+    /// Stops when the largest component of the gradient `J^T r` is smaller.
+    pub fn with_gradient_tolerance(mut self, tolerance: f32) -> Self {
+        self.gradient_tolerance = tolerance;
+        self
+    }
+}
+
+/// Result of [`fit_parameters`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct FitResult<const N: usize, const P: usize> {
+    pub parameters: Vector<P>,
+    /// Weighted residuals `W (y(t_k) - d_k)` at the best fit.
+    pub residuals: Vec<Vector<N>>,
+    pub sum_of_squares: f32,
+    /// `s^2 (J^T J)^(-1)` with the residual variance `s^2 = S / (m - P)` of
+    /// the `m` observed components. `None` if `J^T J` is singular or there
+    /// are no more observations than parameters.
+    pub covariance: Option<Matrix<P, P>>,
+    pub iterations: usize,
+    /// Accumulated over all integrations.
+    pub statistics: Statistics,
+}
+
+impl<const N: usize, const P: usize> FitResult<N, P> {
+    /// Square roots of the diagonal of the covariance.
+    pub fn standard_errors(&self) -> Option<Vector<P>> {
+        let covariance = self.covariance?;
+        Some(Matrix::from_fn(|i, _| covariance[(i, i)].sqrt()))
+    }
+}
+
+/// Fits the parameters of `y' = fun(t, y, p)`, starting from `y(start_time) =
+/// initial_state`, to the measurements `data` at `times`, beginning the
+/// search at `initial_parameters`.
+///
+/// The times have to be sorted and must not lie before `start_time`. The
+/// residuals come from [`forward_sensitivity`], so the state is a `Vector<N>`
+/// here as well.
+pub fn fit_parameters<const N: usize, const P: usize>(
+    fun: impl Fn(f32, Vector<N>, &Vector<P>) -> Vector<N> + 'static,
+    start_time: f32,
+    initial_state: Vector<N>,
+    times: &[f32],
+    data: &[Vector<N>],
+    initial_parameters: Vector<P>,
+    options: FitOptions<N, P>,
+) -> Result<FitResult<N, P>, FitError> {
+    assert_eq!(times.len(), data.len(), "every time needs one measurement");
+    assert!(!times.is_empty(), "at least one measurement is required");
+    assert!(
+        times[0] >= start_time && times.windows(2).all(|w| w[1] >= w[0]),
+        "the times have to be sorted and must not lie before the start"
+    );
+    let fun: Rc<ParamFun<N, P>> = Rc::new(fun);
+    let mut statistics = Statistics::default();
+    let mut evaluate = |parameters: &Vector<P>| {
+        let fun = Rc::clone(&fun);
+        let sensitivity = options.sensitivity.clone().with_t_eval(times.to_vec());
+        let solution = forward_sensitivity(
+            move |time, state, parameters| fun(time, state, parameters),
+            (start_time, times[times.len() - 1]),
+            initial_state,
+            *parameters,
+            sensitivity,
+        );
+        statistics.evaluations += solution.statistics.evaluations;
+        statistics.accepted_steps += solution.statistics.accepted_steps;
+        statistics.rejected_steps += solution.statistics.rejected_steps;
+        statistics.jacobian_evaluations += solution.statistics.jacobian_evaluations;
+        Residuals::new(
+            solution.status,
+            &solution.states,
+            &solution.sensitivities,
+            data,
+            &options.weights,
+        )
+    };
+
+    let mut parameters = initial_parameters;
+    let mut current = evaluate(&parameters)?;
+    let (mut normal, mut gradient) = current.normal_equations();
+    // Marquardt's scaling by the largest diagonal of J^T J seen so far, which
+    // makes the steps invariant under rescaling of the parameters
+    let mut scale: Vector<P> = Matrix::from_fn(|i, _| normal[(i, i)]);
+    let mut damping = 1e-3;
+    let mut growth = 2.0;
+    let mut iterations = 0;
+    let converged = loop {
+        if current.sum_of_squares == 0.0 || max_abs(&gradient) <= options.gradient_tolerance {
+            break true;
+        }
+        if iterations == options.max_iterations {
+            break false;
+        }
+        iterations += 1;
+
+        for i in 0..P {
+            scale[i] = scale[i].max(normal[(i, i)]);
+        }
+        let mut damped = normal;
+        for i in 0..P {
+            damped[(i, i)] += damping * if scale[i] > 0.0 { scale[i] } else { 1.0 };
+        }
+        let Ok(step) = damped.solve(&-gradient) else {
+            damping *= growth;
+            growth *= 2.0;
+            continue;
+        };
+        if step.norm() <= options.step_tolerance * (parameters.norm() + options.step_tolerance) {
+            break true;
+        }
+
+        let trial = parameters + step;
+        match evaluate(&trial) {
+            Ok(next) if next.sum_of_squares < current.sum_of_squares => {
+                // Ratio of the actual to the predicted reduction of S / 2
+                let predicted: f32 = (0..P)
+                    .map(|i| {
+                        0.5 * step[i] * ((damped[(i, i)] - normal[(i, i)]) * step[i] - gradient[i])
+                    })
+                    .sum();
+                let actual = 0.5 * (current.sum_of_squares - next.sum_of_squares);
+                let ratio = actual / predicted;
+                damping *= (1.0 - (2.0 * ratio - 1.0).powi(3)).max(1.0 / 3.0);
+                growth = 2.0;
+
+                let reduction =
+                    (current.sum_of_squares - next.sum_of_squares) / current.sum_of_squares;
+                parameters = trial;
+                current = next;
+                (normal, gradient) = current.normal_equations();
+                if reduction <= options.cost_tolerance {
+                    break true;
+                }
+            }
+            _ => {
+                damping *= growth;
+                growth *= 2.0;
+            }
+        }
+    };
+    if !converged {
+        return Err(FitError::DidNotConverge {
+            iterations,
+            sum_of_squares: current.sum_of_squares,
+        });
+    }
+
+    let observations = times.len() * (0..N).filter(|&i| options.weights[i] > 0.0).count();
+    let covariance = (observations > P)
+        .then(|| normal.inverse().ok())
+        .flatten()
+        .map(|inverse| inverse * (current.sum_of_squares / (observations - P) as f32));
+    Ok(FitResult {
+        parameters,
+        residuals: current.residuals,
+        sum_of_squares: current.sum_of_squares,
+        covariance,
+        iterations,
+        statistics,
+    })
+}
+
+/// Weighted residuals and their Jacobians at the measurement times.
+struct Residuals<const N: usize, const P: usize> {
+    residuals: Vec<Vector<N>>,
+    jacobians: Vec<Matrix<N, P>>,
+    sum_of_squares: f32,
+}
+
+impl<const N: usize, const P: usize> Residuals<N, P> {
+    fn new(
+        status: Status,
+        states: &[Vector<N>],
+        sensitivities: &[Matrix<N, P>],
+        data: &[Vector<N>],
+        weights: &Vector<N>,
+    ) -> Result<Self, SolverError> {
+        if let Status::Failed(error) = status {
+            return Err(error);
+        }
+        let residuals: Vec<Vector<N>> = states
+            .iter()
+            .zip(data)
+            .map(|(state, measured)| {
+                Matrix::from_fn(|i, _| match weights[i] {
+                    0.0 => 0.0,
+                    weight => weight * (state[i] - measured[i]),
+                })
+            })
+            .collect();
+        let jacobians = sensitivities
+            .iter()
+            .map(|sensitivity| Matrix::from_fn(|i, j| weights[i] * sensitivity[(i, j)]))
+            .collect();
+        let sum_of_squares = residuals
+            .iter()
+            .map(|residual| (0..N).map(|i| residual[i].powi(2)).sum::<f32>())
+            .sum();
+        Ok(Self {
+            residuals,
+            jacobians,
+            sum_of_squares,
+        })
+    }
+
+    /// `J^T J` and the gradient `J^T r` of `S / 2`.
+    fn normal_equations(&self) -> (Matrix<P, P>, Vector<P>) {
+        let mut normal = Matrix::zeros();
+        let mut gradient = Matrix::zeros();
+        for (jacobian, residual) in self.jacobians.iter().zip(&self.residuals) {
+            normal += jacobian.transpose() * *jacobian;
+            gradient += jacobian.transpose() * *residual;
+        }
+        (normal, gradient)
+    }
+}
+
+fn max_abs<const P: usize>(vector: &Vector<P>) -> f32 {
+    (0..P).map(|i| vector[i].abs()).fold(0.0, f32::max)
+}
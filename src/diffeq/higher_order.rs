@@ -0,0 +1,123 @@
+// Higher order equations y^(K) = f(t, y, y', ..., y^(K-1)), reduced to the
+// first order system of the stacked derivatives
+//
+//     z = [y, y', ..., y^(K-1)]
+//     z' = [y', ..., y^(K-1), f(t, y, ..., y^(K-1))]
+//
+// so that every solver in `diffeq` applies. The derivatives are stacked in a
+// `StateArray<Y, K>`, so `y` can be any state.
+
+use super::{
+    solve::{solve_ivp, Solution, SolveOptions},
+    state::{OdeState, StateArray},
+};
+
+/// Derivative of the stacked state, given the highest derivative.
+fn stacked_derivative<Y: OdeState, const K: usize>(
+    state: StateArray<Y, K>,
+    highest: Y,
+) -> StateArray<Y, K> {
+    let mut derivatives = state.0.into_iter().skip(1);
+    StateArray(std::array::from_fn(|_| {
+        derivatives.next().unwrap_or_else(|| highest.clone())
+    }))
+}
+
+/// Right-hand side of the first order system equivalent to the equation
+/// `y^(K) = fun(t, [y, ..., y^(K-1)])`, for use with any solver.
+pub fn reduce_order<Y: OdeState, const K: usize>(
+    fun: impl Fn(f32, &[Y; K]) -> Y,
+) -> impl Fn(f32, StateArray<Y, K>) -> StateArray<Y, K> {
+    move |time, state| {
+        let highest = fun(time, &state.0);
+        stacked_derivative(state, highest)
+    }
+}
+
+/// Right-hand side of the first order system equivalent to
+/// `y'' = fun(t, y, y')`.
+pub fn reduce_second_order<Y: OdeState>(
+    fun: impl Fn(f32, Y, Y) -> Y,
+) -> impl Fn(f32, StateArray<Y, 2>) -> StateArray<Y, 2> {
+    reduce_order(move |time, [position, velocity]: &[Y; 2]| {
+        fun(time, position.clone(), velocity.clone())
+    })
+}
+
+/// Result of [`solve_higher_order`] and [`solve_second_order`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct HigherOrderSolution<Y, const K: usize> {
+    /// `derivatives[k][i]` is the `k`-th derivative at `solution.times[i]`.
+    pub derivatives: Vec<Vec<Y>>,
+    /// Solution of the stacked first order system, which also holds the
+    /// events, the status and the dense output.
+    pub solution: Solution<StateArray<Y, K>>,
+}
+
+impl<Y: Clone, const K: usize> HigherOrderSolution<Y, K> {
+    fn new(solution: Solution<StateArray<Y, K>>) -> Self {
+        let mut derivatives = vec![Vec::with_capacity(solution.states.len()); K];
+        for state in &solution.states {
+            for (k, derivative) in state.0.iter().enumerate() {
+                derivatives[k].push(derivative.clone());
+            }
+        }
+        Self {
+            derivatives,
+            solution,
+        }
+    }
+
+    pub fn times(&self) -> &[f32] {
+        &self.solution.times
+    }
+
+    pub fn positions(&self) -> &[Y] {
+        &self.derivatives[0]
+    }
+
+    pub fn velocities(&self) -> &[Y] {
+        &self.derivatives[1]
+    }
+
+    pub fn success(&self) -> bool {
+        self.solution.success()
+    }
+}
+
+/// Integrates `y^(K) = fun(t, [y, ..., y^(K-1)])` from the initial values
+/// `[y, ..., y^(K-1)]` with [`solve_ivp`]. The options, including events and
+/// the Jacobian, refer to the stacked state.
+pub fn solve_higher_order<Y: OdeState + 'static, const K: usize>(
+    mut fun: impl FnMut(f32, &[Y; K]) -> Y + 'static,
+    t_span: (f32, f32),
+    initial_values: [Y; K],
+    options: SolveOptions<StateArray<Y, K>>,
+) -> HigherOrderSolution<Y, K> {
+    let solution = solve_ivp(
+        move |time, state: StateArray<Y, K>| {
+            let highest = fun(time, &state.0);
+            stacked_derivative(state, highest)
+        },
+        t_span,
+        StateArray(initial_values),
+        options,
+    );
+    HigherOrderSolution::new(solution)
+}
+
+/// Integrates `y'' = fun(t, y, y')` from the initial position and velocity.
+pub fn solve_second_order<Y: OdeState + 'static>(
+    mut fun: impl FnMut(f32, Y, Y) -> Y + 'static,
+    t_span: (f32, f32),
+    position: Y,
+    velocity: Y,
+    options: SolveOptions<StateArray<Y, 2>>,
+) -> HigherOrderSolution<Y, 2> {
+    solve_higher_order(
+        move |time, [position, velocity]: &[Y; 2]| fun(time, position.clone(), velocity.clone()),
+        t_span,
+        [position, velocity],
+        options,
+    )
+}
@@ -0,0 +1,187 @@
+use crate::linalg::{lu::LuDecomposition, ndarray::NdArray};
+
+use super::{
+    control::{Statistics, Tolerances},
+    error::SolverError,
+    ivp::SolveFun,
+    jacobian::{factorize_shifted, Jacobian},
+    newton::{newton_tolerance, solve_implicit},
+    state::OdeState,
+    stream::Stepper,
+};
+
+/// Fixed step implicit theta method
+/// `y_next = y + h ((1 - theta) f(t, y) + theta f(t + h, y_next))`,
+/// solved with a simplified Newton iteration. `theta = 1` is backward Euler
+/// and `theta = 1/2` the trapezoidal rule. The Jacobian is only re-evaluated
+/// when the iteration fails to converge, and the iteration matrix is only
+/// factorized again when the Jacobian or the step size change.
+pub struct ThetaMethod<Y: OdeState> {
+    theta: f32,
+    time: f32,
+    state: Y,
+    derivative: Option<Y>,
+    jacobian: Jacobian<Y>,
+    jacobian_matrix: Option<NdArray>,
+    // Factorization of I - theta h J together with the step size h
+    lu: Option<(f32, LuDecomposition)>,
+    tolerances: Tolerances<Y>,
+    max_iterations: usize,
+    statistics: Statistics,
+}
+
+impl<Y: OdeState> ThetaMethod<Y> {
+    pub fn new(theta: f32, time: f32, initial_state: Y) -> Self {
+        assert!(
+            theta > 0.0 && theta <= 1.0,
+            "theta must be in (0, 1], got {}",
+            theta
+        );
+        Self {
+            theta,
+            time,
+            state: initial_state,
+            derivative: None,
+            jacobian: Jacobian::FiniteDifference,
+            jacobian_matrix: None,
+            lu: None,
+            tolerances: Tolerances::new(1e-6, 1e-8),
+            max_iterations: 10,
+            statistics: Statistics::default(),
+        }
+    }
+
+    /// First order, L-stable backward Euler method.
+    pub fn backward_euler(time: f32, initial_state: Y) -> Self {
+        Self::new(1.0, time, initial_state)
+    }
+
+    /// Second order, A-stable trapezoidal rule (Crank-Nicolson).
+    pub fn trapezoidal(time: f32, initial_state: Y) -> Self {
+        Self::new(0.5, time, initial_state)
+    }
+
+    pub fn with_jacobian(mut self, jacobian: Jacobian<Y>) -> Self {
+        self.jacobian = jacobian;
+        self
+    }
+
+    /// Tolerances that decide when the Newton iteration has converged.
+    pub fn with_tolerances(mut self, tolerances: Tolerances<Y>) -> Self {
+        self.tolerances = tolerances;
+        self
+    }
+
+    pub fn with_max_iterations(mut self, max_iterations: usize) -> Self {
+        assert!(max_iterations > 0, "at least one iteration is required");
+        self.max_iterations = max_iterations;
+        self
+    }
+
+    pub fn theta(&self) -> f32 {
+        self.theta
+    }
+
+    pub fn time(&self) -> f32 {
+        self.time
+    }
+
+    pub fn state(&self) -> &Y {
+        &self.state
+    }
+
+    pub fn statistics(&self) -> &Statistics {
+        &self.statistics
+    }
+
+    pub fn next_step(&mut self, fun: &SolveFun<Y>, delta_time: f32) -> Result<&Y, SolverError> {
+        let next_time = self.time + delta_time;
+        let c = self.theta * delta_time;
+        let mut base = self.state.clone();
+        if self.theta < 1.0 {
+            let derivative = self.derivative(fun);
+            base = base + derivative * ((1.0 - self.theta) * delta_time);
+        }
+        let scale = self.tolerances.scale(&self.state, &self.state);
+        let tolerance = newton_tolerance(&self.tolerances);
+
+        let mut fresh_jacobian = false;
+        let solution = loop {
+            if self.jacobian_matrix.is_none() {
+                self.jacobian_matrix = Some(self.jacobian.evaluate(
+                    fun,
+                    self.time,
+                    &self.state,
+                    self.derivative.as_ref(),
+                    &self.tolerances.absolute,
+                    &mut self.statistics,
+                ));
+                fresh_jacobian = true;
+                self.lu = None;
+            }
+            if !matches!(self.lu, Some((step, _)) if step == delta_time) {
+                self.statistics.lu_decompositions += 1;
+                let jacobian = self.jacobian_matrix.as_ref().unwrap();
+                match factorize_shifted(jacobian, 1.0, c) {
+                    Ok(lu) => self.lu = Some((delta_time, lu)),
+                    Err(_) if fresh_jacobian => {
+                        return Err(SolverError::SingularIterationMatrix { time: self.time })
+                    }
+                    Err(_) => {
+                        self.jacobian_matrix = None;
+                        continue;
+                    }
+                }
+            }
+
+            let (_, lu) = self.lu.as_ref().unwrap();
+            let solution = solve_implicit(
+                fun,
+                next_time,
+                &base,
+                c,
+                self.state.clone(),
+                lu,
+                &scale,
+                tolerance,
+                self.max_iterations,
+                &mut self.statistics,
+            );
+            if solution.converged {
+                break solution;
+            }
+            if fresh_jacobian {
+                return Err(SolverError::NewtonDidNotConverge { time: self.time });
+            }
+            self.jacobian_matrix = None;
+        };
+
+        self.statistics.accepted_steps += 1;
+        self.time = next_time;
+        self.state = solution.state;
+        self.derivative = None;
+        Ok(&self.state)
+    }
+
+    fn derivative(&mut self, fun: &SolveFun<Y>) -> Y {
+        match &self.derivative {
+            Some(derivative) => derivative.clone(),
+            None => {
+                self.statistics.evaluations += 1;
+                let derivative = fun(self.time, self.state.clone());
+                self.derivative = Some(derivative.clone());
+                derivative
+            }
+        }
+    }
+}
+
+impl<Y: OdeState> Stepper<Y> for ThetaMethod<Y> {
+    fn current(&self) -> (f32, Y) {
+        (self.time, self.state.clone())
+    }
+
+    fn advance(&mut self, fun: &SolveFun<Y>, delta_time: f32) -> Result<(), SolverError> {
+        self.next_step(fun, delta_time).map(|_| ())
+    }
+}
@@ -1,57 +1,146 @@
-use crate::linalg::ndarray::Vector;
+use std::marker::PhantomData;
 
-const B_J: [f32; 4] = [1.0 / 6.0, 1.0 / 3.0, 1.0 / 3.0, 1.0 / 6.0];
+use crate::linalg::real::Real;
 
-const C_J: [f32; 4] = [0.0, 0.5, 0.5, 1.0];
+use super::{
+    dense::Interpolant,
+    error::SolverError,
+    state::OdeState,
+    stream::Stepper,
+    tableau::{ButcherTableau, ClassicRk4},
+};
 
-const A_IJ: [[f32; 4]; 4] = [
-    [0.0, 0.0, 0.0, 0.0],
-    [1.0 / 2.0, 0.0, 0.0, 0.0],
-    [0.0, 1.0 / 2.0, 0.0, 0.0],
-    [0.0, 0.0, 1.0, 0.0],
-];
+pub type SolveFun<T, R = f32> = dyn Fn(R, T) -> T;
 
-pub type SolveFun<T> = dyn Fn(f32, T) -> T;
-
-pub struct RungeKutta4<const N: usize> {
-    time: f32,
-    state: Vector<N>,
+/// Fixed step explicit Runge-Kutta solver for the method described by `T`.
+///
+/// Time and state are `f32` by default. Other scalars `R`, such as `f64` for
+/// long integrations or `Dual` to differentiate the solution, are used with
+/// states of matching precision, e.g. `RungeKutta4<Vector<3, f64>, f64>`.
+pub struct ExplicitRungeKutta<T: ButcherTableau, Y: OdeState<R>, R: Real = f32> {
+    time: R,
+    state: Y,
+    /// Time and state at the start of the last step.
+    previous: Option<(R, Y)>,
+    stages: Vec<Y>,
+    evaluations: usize,
+    tableau: PhantomData<T>,
 }
 
-impl<const N: usize> RungeKutta4<N> {
-    pub fn new(time: f32, initial_state: Vector<N>) -> Self {
+pub type RungeKutta4<Y, R = f32> = ExplicitRungeKutta<ClassicRk4, Y, R>;
+
+impl<T: ButcherTableau, Y: OdeState<R>, R: Real> ExplicitRungeKutta<T, Y, R> {
+    pub fn new(time: R, initial_state: Y) -> Self {
         Self {
             time,
+            stages: vec![initial_state.zero_like(); T::stages()],
             state: initial_state,
+            previous: None,
+            evaluations: 0,
+            tableau: PhantomData,
         }
     }
+
+    pub fn time(&self) -> R {
+        self.time
+    }
+
+    pub fn state(&self) -> &Y {
+        &self.state
+    }
+
+    /// Number of right-hand side evaluations so far.
+    pub fn evaluations(&self) -> usize {
+        self.evaluations
+    }
+
+    /// Stage derivatives `k_i` of the last step.
+    pub fn stages(&self) -> &[Y] {
+        &self.stages
+    }
+
+    pub fn next_step(&mut self, fun: &SolveFun<Y, R>, delta_time: R) -> &Y {
+        compute_stages::<T, Y, R>(
+            fun,
+            self.time,
+            &self.state,
+            delta_time,
+            &mut self.stages,
+            None,
+        );
+        self.evaluations += T::stages();
+
+        let next_state = combine_stages(&self.state, delta_time, &self.stages, T::B);
+        self.previous = Some((self.time, std::mem::replace(&mut self.state, next_state)));
+        self.time += delta_time;
+        &self.state
+    }
+
+    /// Cubic Hermite interpolant of the last step, `None` before the first
+    /// step. Costs one evaluation of `fun` at the end of the step.
+    pub fn interpolant(&mut self, fun: &SolveFun<Y, R>) -> Option<Interpolant<Y, R>> {
+        let (start_time, start_state) = self.previous.clone()?;
+        self.evaluations += 1;
+        let end_derivative = fun(self.time, self.state.clone());
+        Some(Interpolant::hermite(
+            start_time,
+            start_state,
+            self.stages[0].clone(),
+            self.time,
+            self.state.clone(),
+            end_derivative,
+        ))
+    }
 }
 
-impl<const N: usize> RungeKutta4<N> {
-    fn calc_kj(&self, j: usize, fun: &SolveFun<Vector<N>>, delta_time: f32) -> Vector<N> {
-        let mut inner_sum = Vector { data: [[0.0]; N] };
+impl<T: ButcherTableau, Y: OdeState<R>, R: Real> Stepper<Y, R> for ExplicitRungeKutta<T, Y, R> {
+    fn current(&self) -> (R, Y) {
+        (self.time, self.state.clone())
+    }
 
-        for l in 0..4 {
-            if A_IJ[j][l] == 0.0 {
+    fn advance(&mut self, fun: &SolveFun<Y, R>, delta_time: R) -> Result<(), SolverError> {
+        self.next_step(fun, delta_time);
+        Ok(())
+    }
+}
+
+/// Evaluates the stage derivatives of one step into `stages`.
+///
+/// When `first_stage` is given it is used as `k_0 = f(t, y)` instead of
+/// evaluating `fun` again.
+pub(crate) fn compute_stages<T: ButcherTableau, Y: OdeState<R>, R: Real>(
+    fun: &SolveFun<Y, R>,
+    time: R,
+    state: &Y,
+    delta_time: R,
+    stages: &mut [Y],
+    mut first_stage: Option<Y>,
+) {
+    // Every stage is evaluated exactly once and reused by all later stages
+    for i in 0..T::stages() {
+        if i == 0 {
+            if let Some(k_0) = first_stage.take() {
+                stages[0] = k_0;
                 continue;
             }
-            inner_sum += A_IJ[j][l] * self.calc_kj(l, fun, delta_time);
         }
-
-        fun(
-            self.time + delta_time * C_J[j],
-            self.state + delta_time * inner_sum,
-        )
+        let stage_state = combine_stages(state, delta_time, &stages[..i], T::A[i]);
+        stages[i] = fun(time + delta_time * R::from_f64(T::C[i]), stage_state);
     }
+}
 
-    pub fn next_step(&mut self, fun: &SolveFun<Vector<N>>, delta_time: f32) -> &Vector<N> {
-        let mut sum = Vector { data: [[0.0]; N] };
-        for (j, b_j) in B_J.iter().enumerate() {
-            sum += *b_j * self.calc_kj(j, fun, delta_time);
+/// Computes `y + h * sum_i w_i k_i`, skipping zero weights.
+pub(crate) fn combine_stages<Y: OdeState<R>, R: Real>(
+    state: &Y,
+    delta_time: R,
+    stages: &[Y],
+    weights: &[f64],
+) -> Y {
+    let mut result = state.clone();
+    for (k_i, w_i) in stages.iter().zip(weights) {
+        if *w_i != 0.0 {
+            result = result + k_i.clone() * (delta_time * R::from_f64(*w_i));
         }
-
-        self.time += delta_time;
-        self.state += delta_time * sum;
-        &self.state
     }
+    result
 }
@@ -0,0 +1,122 @@
+// Jacobians `df/dy` of the right-hand side, as needed by the implicit solvers.
+//
+// The Jacobian of a state with `n` components is a dense `n x n` array in
+// the component order of `OdeState`, so the implicit solvers work on any
+// state. The linear systems with `I - c J` are solved through the components
+// of the right-hand side.
+
+use crate::linalg::{error::LinalgError, lu::LuDecomposition, ndarray::NdArray};
+
+use super::{
+    control::{broadcast, Statistics},
+    ivp::SolveFun,
+    state::OdeState,
+};
+
+pub type JacobianFun<Y> = dyn Fn(f32, Y) -> NdArray;
+
+/// Source of the Jacobian used by an implicit solver.
+#[derive(Default)]
+pub enum Jacobian<Y> {
+    /// Forward differences, costing one additional evaluation of `f` per
+    /// component of the state.
+    #[default]
+    FiniteDifference,
+    Analytic(Box<JacobianFun<Y>>),
+}
+
+impl<Y: OdeState> Jacobian<Y> {
+    /// `jacobian` returns the `n x n` matrix `df_i/dy_j` for a state with `n`
+    /// components, e.g. a `Matrix<N, N>` for `Vector<N>` or an [`NdArray`].
+    pub fn analytic<J: Into<NdArray>>(jacobian: impl Fn(f32, Y) -> J + 'static) -> Self {
+        Jacobian::Analytic(Box::new(move |time, state| jacobian(time, state).into()))
+    }
+
+    /// Jacobian at `(time, state)`. Finite differences reuse `derivative`,
+    /// which is `f(time, state)`, when it is known, and perturb components
+    /// smaller than `threshold` as if they were of that size.
+    pub fn evaluate(
+        &self,
+        fun: &SolveFun<Y>,
+        time: f32,
+        state: &Y,
+        derivative: Option<&Y>,
+        threshold: &Y,
+        statistics: &mut Statistics,
+    ) -> NdArray {
+        statistics.jacobian_evaluations += 1;
+        match self {
+            Jacobian::FiniteDifference => {
+                let derivative = derivative.cloned().unwrap_or_else(|| {
+                    statistics.evaluations += 1;
+                    fun(time, state.clone())
+                });
+                statistics.evaluations += state.dimension();
+                finite_difference_jacobian(fun, time, state, &derivative, threshold)
+            }
+            Jacobian::Analytic(jacobian) => {
+                let matrix = jacobian(time, state.clone());
+                let n = state.dimension();
+                assert_eq!(matrix.shape(), [n, n], "expected a {}x{} Jacobian", n, n);
+                matrix
+            }
+        }
+    }
+}
+
+/// Forward difference approximation of `df/dy`, perturbing one component at a time.
+///
+/// Component `j` is perturbed relative to `max(|y_j|, threshold_j)`, so the
+/// threshold should be the magnitude below which a component is negligible,
+/// typically its absolute tolerance. A threshold with a single component
+/// applies to every component.
+pub fn finite_difference_jacobian<Y: OdeState>(
+    fun: &SolveFun<Y>,
+    time: f32,
+    state: &Y,
+    derivative: &Y,
+    threshold: &Y,
+) -> NdArray {
+    let n = state.dimension();
+    let mut jacobian = NdArray::zeros(&[n, n]);
+    for j in 0..n {
+        let y_j = state.component(j);
+        // The square root of the machine epsilon balances truncation and rounding errors
+        let step = f32::EPSILON.sqrt() * y_j.abs().max(broadcast(threshold, j));
+        let perturbed = state.map_components(|i, y| if i == j { y + step } else { y });
+        // Use the step that was actually representable
+        let step = perturbed.component(j) - y_j;
+        let value = fun(time, perturbed);
+        for i in 0..n {
+            jacobian.data_mut()[i * n + j] = (value.component(i) - derivative.component(i)) / step;
+        }
+    }
+    jacobian
+}
+
+/// Factorizes `diagonal I - factor J`, the matrix of the linear systems of
+/// the implicit solvers.
+pub(crate) fn factorize_shifted(
+    jacobian: &NdArray,
+    diagonal: f32,
+    factor: f32,
+) -> Result<LuDecomposition, LinalgError> {
+    let n = jacobian.shape()[0];
+    let data = jacobian
+        .data()
+        .iter()
+        .enumerate()
+        .map(|(k, &j)| {
+            let identity = if k / n == k % n { diagonal } else { 0.0 };
+            identity - factor * j
+        })
+        .collect();
+    LuDecomposition::new(n, data)
+}
+
+/// Solves the factorized system for the components of `rhs`.
+pub(crate) fn solve_state<Y: OdeState>(lu: &LuDecomposition, rhs: &Y) -> Y {
+    let mut x: Vec<f32> = (0..rhs.dimension()).map(|i| rhs.component(i)).collect();
+    lu.solve_in_place(&mut x);
+    rhs.map_components(|i, _| x[i])
+}
@@ -1 +1,27 @@
+pub mod adaptive;
+pub mod bdf;
+pub mod bvp;
+pub mod control;
+pub mod dae;
+pub mod dde;
+pub mod dense;
+pub mod error;
+pub mod event;
+pub mod fit;
+pub mod higher_order;
+pub mod implicit;
 pub mod ivp;
+pub mod jacobian;
+pub mod multistep;
+mod newton;
+pub mod nystrom;
+pub mod radau;
+pub mod rosenbrock;
+pub mod sde;
+pub mod sensitivity;
+pub mod solve;
+pub mod solver;
+pub mod state;
+pub mod stream;
+pub mod symplectic;
+pub mod tableau;
@@ -0,0 +1,573 @@
+// Adams linear multistep methods for non-stiff problems.
+//
+// An Adams method advances with the integral of the polynomial interpolating
+// the derivatives of the latest steps,
+//
+//     y_next = y_n + integral from t_n to t_(n+1) of p(t) dt,
+//
+// where `p` interpolates `f` at `t_n, t_(n-1), ...` for the explicit
+// Adams-Bashforth formulas and additionally at `t_(n+1)` for the implicit
+// Adams-Moulton formulas. The weights are integrated numerically for the
+// actual step times, so the same code serves fixed and variable steps. Each
+// step needs only one or two new evaluations of `f`, independently of the
+// order.
+
+use super::{
+    adaptive::Rk45,
+    control::{error_norm, min_step_size, Statistics, StepController, Tolerances},
+    dense::Interpolant,
+    error::SolverError,
+    ivp::{combine_stages, compute_stages, SolveFun},
+    solver::OdeSolver,
+    state::OdeState,
+    stream::{advance_adaptive, Stepper},
+    tableau::{ButcherTableau, ClassicRk4},
+};
+
+pub const MAX_ORDER: usize = 8;
+/// Order of the variable order method after its Runge-Kutta startup.
+const STARTUP_ORDER: usize = 4;
+
+/// Gauss-Legendre nodes and weights on `[0, 1]`, exact for the polynomials of
+/// degree at most nine interpolating up to `MAX_ORDER + 2` derivatives.
+const GAUSS_NODES: [f64; 5] = [
+    0.046910077030668,
+    0.230765344947158,
+    0.5,
+    0.769234655052842,
+    0.953089922969332,
+];
+const GAUSS_WEIGHTS: [f64; 5] = [
+    0.118463442528095,
+    0.239314335249683,
+    0.284444444444444,
+    0.239314335249683,
+    0.118463442528095,
+];
+
+/// Integral from `start` to `end` of the polynomial interpolating the
+/// derivatives at `points`.
+fn integrate<Y: OdeState>(points: &[(f32, Y)], start: f32, end: f32) -> Y {
+    let length = end as f64 - start as f64;
+    let nodes: Vec<f64> = points
+        .iter()
+        .map(|(time, _)| (*time as f64 - start as f64) / length)
+        .collect();
+    let mut integral = points[0].1.zero_like();
+    for (i, (_, derivative)) in points.iter().enumerate() {
+        // Integral of the Lagrange polynomial of node i over the step
+        let weight: f64 = GAUSS_NODES
+            .iter()
+            .zip(GAUSS_WEIGHTS)
+            .map(|(s, w)| {
+                let lagrange: f64 = (0..nodes.len())
+                    .filter(|&j| j != i)
+                    .map(|j| (s - nodes[j]) / (nodes[i] - nodes[j]))
+                    .product();
+                w * lagrange
+            })
+            .sum();
+        integral = integral + derivative.clone() * (length * weight) as f32;
+    }
+    integral
+}
+
+/// One step of the classic Runge-Kutta method, given `f(t, y)`.
+fn runge_kutta_step<Y: OdeState>(
+    fun: &SolveFun<Y>,
+    time: f32,
+    state: &Y,
+    derivative: Y,
+    delta_time: f32,
+) -> Y {
+    let mut stages = vec![state.zero_like(); ClassicRk4::stages()];
+    compute_stages::<ClassicRk4, Y, f32>(
+        fun,
+        time,
+        state,
+        delta_time,
+        &mut stages,
+        Some(derivative),
+    );
+    combine_stages(state, delta_time, &stages, ClassicRk4::B)
+}
+
+fn check_order(order: usize) {
+    assert!(
+        (1..=MAX_ORDER).contains(&order),
+        "the order has to be between 1 and {}, not {}",
+        MAX_ORDER,
+        order
+    );
+}
+
+/// Fixed step Adams-Bashforth method of order 1 to `MAX_ORDER`, using the
+/// derivatives of the last `order` steps.
+///
+/// The first `order - 1` steps are taken with the classic Runge-Kutta method.
+/// Afterwards every step costs a single evaluation of `fun`.
+pub struct AdamsBashforth<Y: OdeState> {
+    time: f32,
+    state: Y,
+    order: usize,
+    // Times and derivatives of the latest steps, newest first
+    history: Vec<(f32, Y)>,
+    evaluations: usize,
+}
+
+impl<Y: OdeState> AdamsBashforth<Y> {
+    pub fn new(time: f32, initial_state: Y, order: usize) -> Self {
+        check_order(order);
+        Self {
+            time,
+            state: initial_state,
+            order,
+            history: Vec::with_capacity(order + 1),
+            evaluations: 0,
+        }
+    }
+
+    pub fn time(&self) -> f32 {
+        self.time
+    }
+
+    pub fn state(&self) -> &Y {
+        &self.state
+    }
+
+    pub fn order(&self) -> usize {
+        self.order
+    }
+
+    /// Number of right-hand side evaluations so far.
+    pub fn evaluations(&self) -> usize {
+        self.evaluations
+    }
+
+    pub fn next_step(&mut self, fun: &SolveFun<Y>, delta_time: f32) -> &Y {
+        if self.history.is_empty() {
+            self.evaluations += 1;
+            self.history
+                .push((self.time, fun(self.time, self.state.clone())));
+        }
+        let next_time = self.time + delta_time;
+        self.state = if self.history.len() < self.order {
+            self.evaluations += ClassicRk4::stages() - 1;
+            runge_kutta_step(
+                fun,
+                self.time,
+                &self.state,
+                self.history[0].1.clone(),
+                delta_time,
+            )
+        } else {
+            self.state.clone() + integrate(&self.history[..self.order], self.time, next_time)
+        };
+        self.time = next_time;
+
+        self.evaluations += 1;
+        self.history
+            .insert(0, (self.time, fun(self.time, self.state.clone())));
+        self.history.truncate(self.order);
+        &self.state
+    }
+}
+
+impl<Y: OdeState> Stepper<Y> for AdamsBashforth<Y> {
+    fn current(&self) -> (f32, Y) {
+        (self.time, self.state.clone())
+    }
+
+    fn advance(&mut self, fun: &SolveFun<Y>, delta_time: f32) -> Result<(), SolverError> {
+        self.next_step(fun, delta_time);
+        Ok(())
+    }
+}
+
+/// Fixed step Adams-Moulton method of order 1 to `MAX_ORDER`, using the
+/// derivatives of the last `order - 1` steps and of the new step.
+///
+/// The implicit formula is solved by `corrections` fixed-point iterations
+/// started from the Adams-Bashforth prediction of one order less, which
+/// converge for steps with `h L < 1 / b_0`, `L` the Lipschitz constant of
+/// `fun` and `b_0` the weight of the new derivative. One correction is the
+/// classic PECE scheme with two evaluations per step. The first
+/// `order - 2` steps are taken with the classic Runge-Kutta method.
+pub struct AdamsMoulton<Y: OdeState> {
+    time: f32,
+    state: Y,
+    order: usize,
+    corrections: usize,
+    history: Vec<(f32, Y)>,
+    evaluations: usize,
+}
+
+impl<Y: OdeState> AdamsMoulton<Y> {
+    pub fn new(time: f32, initial_state: Y, order: usize) -> Self {
+        check_order(order);
+        Self {
+            time,
+            state: initial_state,
+            order,
+            corrections: 1,
+            history: Vec::with_capacity(order),
+            evaluations: 0,
+        }
+    }
+
+    /// Number of corrector iterations per step, one by default.
+    pub fn with_corrections(mut self, corrections: usize) -> Self {
+        assert!(corrections > 0, "at least one correction is needed");
+        self.corrections = corrections;
+        self
+    }
+
+    pub fn time(&self) -> f32 {
+        self.time
+    }
+
+    pub fn state(&self) -> &Y {
+        &self.state
+    }
+
+    pub fn order(&self) -> usize {
+        self.order
+    }
+
+    /// Number of right-hand side evaluations so far.
+    pub fn evaluations(&self) -> usize {
+        self.evaluations
+    }
+
+    pub fn next_step(&mut self, fun: &SolveFun<Y>, delta_time: f32) -> &Y {
+        if self.history.is_empty() {
+            self.evaluations += 1;
+            self.history
+                .push((self.time, fun(self.time, self.state.clone())));
+        }
+        let next_time = self.time + delta_time;
+        let previous = (self.order - 1).max(1);
+        self.state = if self.history.len() < previous {
+            self.evaluations += ClassicRk4::stages() - 1;
+            runge_kutta_step(
+                fun,
+                self.time,
+                &self.state,
+                self.history[0].1.clone(),
+                delta_time,
+            )
+        } else {
+            let mut next_state =
+                self.state.clone() + integrate(&self.history[..previous], self.time, next_time);
+            let mut points = Vec::with_capacity(self.order);
+            for _ in 0..self.corrections {
+                self.evaluations += 1;
+                points.clear();
+                points.push((next_time, fun(next_time, next_state)));
+                points.extend_from_slice(&self.history[..self.order - 1]);
+                next_state = self.state.clone() + integrate(&points, self.time, next_time);
+            }
+            next_state
+        };
+        self.time = next_time;
+
+        self.evaluations += 1;
+        self.history
+            .insert(0, (self.time, fun(self.time, self.state.clone())));
+        self.history.truncate(previous);
+        &self.state
+    }
+}
+
+impl<Y: OdeState> Stepper<Y> for AdamsMoulton<Y> {
+    fn current(&self) -> (f32, Y) {
+        (self.time, self.state.clone())
+    }
+
+    fn advance(&mut self, fun: &SolveFun<Y>, delta_time: f32) -> Result<(), SolverError> {
+        self.next_step(fun, delta_time);
+        Ok(())
+    }
+}
+
+/// Variable step, variable order Adams-Bashforth-Moulton method.
+///
+/// Every step predicts with the Adams-Bashforth formula of the current order
+/// `k`, evaluates, corrects with the Adams-Moulton formula of order `k + 1`
+/// and evaluates again (PECE). The difference of predictor and corrector
+/// estimates the local error of order `k`, and the more accurate corrected
+/// value is kept. Estimates for the orders `k - 1` and `k + 1` come from the
+/// same derivatives, and the order promising the largest next step is taken
+/// once `k + 1` steps were made at the current order, similar to Shampine
+/// and Gordon's `DE`.
+///
+/// The first steps are taken with [`Rk45`] until the derivatives of
+/// `STARTUP_ORDER` points are known.
+pub struct Abm<Y: OdeState> {
+    time: f32,
+    state: Y,
+    // Time and state at the start of the last accepted step
+    previous: Option<(f32, Y)>,
+    // Times and derivatives of the latest steps, newest first
+    history: Vec<(f32, Y)>,
+    startup: Option<Rk45<Y>>,
+    order: usize,
+    steps_at_order: usize,
+    step_size: Option<f32>,
+    tolerances: Tolerances<Y>,
+    controller: StepController,
+    statistics: Statistics,
+}
+
+impl<Y: OdeState> Abm<Y> {
+    pub fn new(time: f32, initial_state: Y, tolerances: Tolerances<Y>) -> Self {
+        Self {
+            time,
+            state: initial_state,
+            previous: None,
+            history: Vec::with_capacity(MAX_ORDER + 2),
+            startup: None,
+            order: STARTUP_ORDER,
+            steps_at_order: 0,
+            step_size: None,
+            tolerances,
+            // Large step size changes spoil the accuracy of multistep formulas
+            controller: StepController {
+                max_factor: 2.0,
+                ..StepController::default()
+            },
+            statistics: Statistics::default(),
+        }
+    }
+
+    /// Uses `step_size` for the first attempt instead of estimating it.
+    pub fn with_first_step(mut self, step_size: f32) -> Self {
+        assert!(step_size > 0.0, "the first step size must be positive");
+        self.step_size = Some(step_size);
+        self
+    }
+
+    pub fn with_max_step(mut self, max_step: f32) -> Self {
+        assert!(max_step > 0.0, "the maximum step size must be positive");
+        self.controller.max_step = max_step;
+        self
+    }
+
+    pub fn with_controller(mut self, controller: StepController) -> Self {
+        self.controller = controller;
+        self
+    }
+
+    pub fn time(&self) -> f32 {
+        self.time
+    }
+
+    pub fn state(&self) -> &Y {
+        &self.state
+    }
+
+    /// Order of the predictor used for the next step.
+    pub fn order(&self) -> usize {
+        self.order
+    }
+
+    pub fn step_size(&self) -> Option<f32> {
+        self.step_size
+    }
+
+    pub fn statistics(&self) -> &Statistics {
+        &self.statistics
+    }
+
+    /// Takes one accepted step towards `time_bound` without stepping past it.
+    pub fn step(&mut self, fun: &SolveFun<Y>, time_bound: f32) -> Result<&Y, SolverError> {
+        if time_bound == self.time {
+            return Ok(&self.state);
+        }
+        if self.history.len() < STARTUP_ORDER {
+            return self.startup_step(fun, time_bound);
+        }
+        let direction = (time_bound - self.time).signum();
+        let min_step = min_step_size(self.time);
+        let mut step_size = self.step_size.unwrap().min(self.controller.max_step);
+        let mut rejected = false;
+        loop {
+            if step_size < min_step {
+                return Err(SolverError::StepSizeTooSmall {
+                    time: self.time,
+                    step_size,
+                });
+            }
+            let mut next_time = self.time + step_size * direction;
+            if direction * (next_time - time_bound) > 0.0 {
+                next_time = time_bound;
+            }
+            step_size = (next_time - self.time).abs();
+
+            let order = self.order;
+            let predict = |q: usize| integrate(&self.history[..q], self.time, next_time);
+            let predicted = self.state.clone() + predict(order);
+            self.statistics.evaluations += 1;
+            let mut points = Vec::with_capacity(self.history.len() + 1);
+            points.push((next_time, fun(next_time, predicted.clone())));
+            points.extend_from_slice(&self.history);
+            let correct = |q: usize| integrate(&points[..=q], self.time, next_time);
+            let corrected = self.state.clone() + correct(order);
+
+            // Error of the order q predictor against the order q + 1 corrector
+            let scale = self.tolerances.scale(&self.state, &corrected);
+            let estimate = |q: usize| error_norm(&(correct(q) - predict(q)), &scale);
+            let error = error_norm(&(corrected.clone() - predicted), &scale);
+            let lower = (order > 1).then(|| (order - 1, estimate(order - 1)));
+
+            if error >= 1.0 {
+                self.statistics.rejected_steps += 1;
+                let mut factor = self.controller.factor(error, order, true);
+                if let Some((lower_order, lower_error)) = lower {
+                    let lower_factor = self.controller.factor(lower_error, lower_order, true);
+                    if lower_factor > factor {
+                        self.order = lower_order;
+                        self.steps_at_order = 0;
+                        factor = lower_factor;
+                    }
+                }
+                rejected = true;
+                step_size *= factor;
+                continue;
+            }
+
+            self.steps_at_order += 1;
+            let mut best = (order, error);
+            if self.steps_at_order > order {
+                let higher = (order < MAX_ORDER && self.history.len() > order)
+                    .then(|| (order + 1, estimate(order + 1)));
+                for (q, e) in lower.into_iter().chain(higher) {
+                    if e.powf(-1.0 / (q as f32 + 1.0)) > best.1.powf(-1.0 / (best.0 as f32 + 1.0)) {
+                        best = (q, e);
+                    }
+                }
+            }
+            if best.0 != order {
+                self.order = best.0;
+                self.steps_at_order = 0;
+            }
+            let factor = self.controller.factor(best.1, best.0, rejected);
+
+            self.statistics.accepted_steps += 1;
+            self.statistics.evaluations += 1;
+            let derivative = fun(next_time, corrected.clone());
+            self.previous = Some((self.time, std::mem::replace(&mut self.state, corrected)));
+            self.time = next_time;
+            self.history.insert(0, (next_time, derivative));
+            self.history.truncate(MAX_ORDER + 1);
+            self.step_size = Some(step_size * factor);
+            return Ok(&self.state);
+        }
+    }
+
+    /// Steps until `end_time` is reached exactly.
+    pub fn integrate_to(&mut self, fun: &SolveFun<Y>, end_time: f32) -> Result<&Y, SolverError> {
+        while self.time != end_time {
+            self.step(fun, end_time)?;
+        }
+        Ok(&self.state)
+    }
+
+    /// Continues the integration from `time` and `state`, e.g. after the state
+    /// was changed discontinuously. The next step size is kept, the
+    /// derivatives of the previous steps are discarded and the method starts
+    /// again with Runge-Kutta steps.
+    pub fn restart(&mut self, time: f32, state: Y) {
+        self.time = time;
+        self.state = state;
+        self.previous = None;
+        self.history.clear();
+        self.startup = None;
+        self.order = STARTUP_ORDER;
+        self.steps_at_order = 0;
+    }
+
+    /// Cubic Hermite interpolant of the last step, `None` before the first
+    /// step. The derivatives at both ends are known, so no evaluations are needed.
+    pub fn interpolant(&self) -> Option<Interpolant<Y>> {
+        let (start_time, start_state) = self.previous.clone()?;
+        Some(Interpolant::hermite(
+            start_time,
+            start_state,
+            self.history[1].1.clone(),
+            self.time,
+            self.state.clone(),
+            self.history[0].1.clone(),
+        ))
+    }
+
+    fn startup_step(&mut self, fun: &SolveFun<Y>, time_bound: f32) -> Result<&Y, SolverError> {
+        let startup = self.startup.get_or_insert_with(|| {
+            let solver = Rk45::new(self.time, self.state.clone(), self.tolerances.clone())
+                .with_max_step(self.controller.max_step);
+            match self.step_size {
+                Some(step_size) => solver.with_first_step(step_size),
+                None => solver,
+            }
+        });
+        let before = *startup.statistics();
+        let result = startup.step(fun, time_bound).map(|_| ());
+        let after = *startup.statistics();
+        self.statistics.evaluations += after.evaluations - before.evaluations;
+        self.statistics.accepted_steps += after.accepted_steps - before.accepted_steps;
+        self.statistics.rejected_steps += after.rejected_steps - before.rejected_steps;
+        result?;
+
+        // The stages hold the derivatives at both ends of the step
+        let stages = startup.stages();
+        if self.history.is_empty() {
+            self.history.push((self.time, stages[0].clone()));
+        }
+        self.history
+            .insert(0, (startup.time(), stages.last().unwrap().clone()));
+        let state = startup.state().clone();
+        self.previous = Some((self.time, std::mem::replace(&mut self.state, state)));
+        self.time = startup.time();
+        self.step_size = startup.step_size();
+        if self.history.len() == STARTUP_ORDER {
+            self.startup = None;
+        }
+        Ok(&self.state)
+    }
+}
+
+impl<Y: OdeState> OdeSolver<Y> for Abm<Y> {
+    fn time(&self) -> f32 {
+        self.time
+    }
+
+    fn state(&self) -> &Y {
+        &self.state
+    }
+
+    fn statistics(&self) -> &Statistics {
+        &self.statistics
+    }
+
+    fn step(&mut self, fun: &SolveFun<Y>, time_bound: f32) -> Result<&Y, SolverError> {
+        Abm::step(self, fun, time_bound)
+    }
+
+    fn restart(&mut self, time: f32, state: Y) {
+        Abm::restart(self, time, state)
+    }
+
+    fn interpolant(&mut self, _fun: &SolveFun<Y>) -> Option<Interpolant<Y>> {
+        Abm::interpolant(self)
+    }
+}
+
+impl<Y: OdeState> Stepper<Y> for Abm<Y> {
+    fn current(&self) -> (f32, Y) {
+        (self.time, self.state.clone())
+    }
+
+    fn advance(&mut self, fun: &SolveFun<Y>, delta_time: f32) -> Result<(), SolverError> {
+        advance_adaptive(self, fun, delta_time)
+    }
+}
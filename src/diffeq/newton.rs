@@ -0,0 +1,119 @@
+// Simplified Newton iteration shared by the implicit solvers.
+//
+// The iteration matrix is factorized once and reused for every iteration, and
+// usually for several steps. Convergence is judged from the contraction rate
+// of the corrections, following Hairer and Wanner, "Solving Ordinary
+// Differential Equations II", IV.8.
+
+use crate::linalg::lu::LuDecomposition;
+
+use super::{
+    control::{error_norm, Statistics, Tolerances},
+    ivp::SolveFun,
+    jacobian::solve_state,
+    state::OdeState,
+};
+
+/// Tolerance on the scaled Newton correction, `max(10 eps / rtol, min(0.03, sqrt(rtol)))`.
+pub(crate) fn newton_tolerance<Y: OdeState>(tolerances: &Tolerances<Y>) -> f32 {
+    let relative = (0..tolerances.relative.dimension())
+        .map(|i| tolerances.relative.component(i))
+        .fold(f32::INFINITY, f32::min);
+    (10.0 * f32::EPSILON / relative).max(0.03f32.min(relative.sqrt()))
+}
+
+/// Tracks the contraction rate of a simplified Newton iteration.
+pub(crate) struct Convergence {
+    max_iterations: usize,
+    tolerance: f32,
+    previous_norm: Option<f32>,
+    pub rate: Option<f32>,
+}
+
+pub(crate) enum NewtonCheck {
+    /// Apply the correction and keep iterating.
+    Continue,
+    /// Apply the correction, the iteration has converged.
+    Converged,
+    /// The iteration diverges or will not converge within the iteration limit.
+    Failed,
+}
+
+impl Convergence {
+    pub fn new(max_iterations: usize, tolerance: f32) -> Self {
+        Self {
+            max_iterations,
+            tolerance,
+            previous_norm: None,
+            rate: None,
+        }
+    }
+
+    /// Classifies the scaled norm of the correction of iteration `iteration` (from zero).
+    pub fn check(&mut self, iteration: usize, norm: f32) -> NewtonCheck {
+        if !norm.is_finite() {
+            return NewtonCheck::Failed;
+        }
+        if let Some(previous) = self.previous_norm {
+            let rate = norm / previous;
+            self.rate = Some(rate);
+            let remaining = (self.max_iterations - iteration) as i32;
+            if rate >= 1.0 || rate.powi(remaining) / (1.0 - rate) * norm > self.tolerance {
+                return NewtonCheck::Failed;
+            }
+        }
+        self.previous_norm = Some(norm);
+        match self.rate {
+            _ if norm == 0.0 => NewtonCheck::Converged,
+            Some(rate) if rate / (1.0 - rate) * norm < self.tolerance => NewtonCheck::Converged,
+            _ => NewtonCheck::Continue,
+        }
+    }
+}
+
+/// Result of [`solve_implicit`].
+pub(crate) struct NewtonSolution<Y> {
+    pub converged: bool,
+    pub iterations: usize,
+    pub state: Y,
+}
+
+/// Solves `y = base + c f(time, y)` starting from `guess`, where `lu` factorizes
+/// `I - c J` for an approximation `J` of the Jacobian.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn solve_implicit<Y: OdeState>(
+    fun: &SolveFun<Y>,
+    time: f32,
+    base: &Y,
+    c: f32,
+    guess: Y,
+    lu: &LuDecomposition,
+    scale: &Y,
+    tolerance: f32,
+    max_iterations: usize,
+    statistics: &mut Statistics,
+) -> NewtonSolution<Y> {
+    let mut state = guess;
+    let mut convergence = Convergence::new(max_iterations, tolerance);
+    for iteration in 0..max_iterations {
+        statistics.evaluations += 1;
+        let residual = base.clone() + fun(time, state.clone()) * c - state.clone();
+        let correction = solve_state(lu, &residual);
+        match convergence.check(iteration, error_norm(&correction, scale)) {
+            NewtonCheck::Failed => break,
+            NewtonCheck::Continue => state = state + correction,
+            NewtonCheck::Converged => {
+                return NewtonSolution {
+                    converged: true,
+                    iterations: iteration + 1,
+                    state: state + correction,
+                }
+            }
+        }
+    }
+    NewtonSolution {
+        converged: false,
+        iterations: max_iterations,
+        state,
+    }
+}
@@ -0,0 +1,123 @@
+// Runge-Kutta-Nystrom methods for second order equations y'' = f(t, y)
+// whose right-hand side does not depend on the velocity.
+//
+// A method with `s` stages computes
+//
+//     k_i = f(t + c_i h, y + c_i h y' + h^2 * sum_{j < i} a_ij k_j)
+//     y_next = y + h y' + h^2 * sum_i b_bar_i k_i
+//     y'_next = y' + h * sum_i b_i k_i
+//
+// Working on the second order form directly needs fewer stages than a
+// Runge-Kutta method of the same order applied to the stacked first order
+// system. As in `tableau`, row `i` of `A` holds `a_i0 .. a_i(i-1)`.
+
+use std::marker::PhantomData;
+
+use super::{
+    ivp::{combine_stages, SolveFun},
+    state::OdeState,
+};
+
+pub trait NystromTableau {
+    /// Order of consistency of the method.
+    const ORDER: usize;
+    const A: &'static [&'static [f64]];
+    /// Position weights `b_bar_i`.
+    const B_BAR: &'static [f64];
+    /// Velocity weights `b_i`.
+    const B: &'static [f64];
+    const C: &'static [f64];
+
+    fn stages() -> usize {
+        Self::B.len()
+    }
+}
+
+/// Nystrom's three stage method of order four.
+pub struct Nystrom4;
+
+impl NystromTableau for Nystrom4 {
+    const ORDER: usize = 4;
+    const A: &'static [&'static [f64]] = &[&[], &[1.0 / 8.0], &[0.0, 1.0 / 2.0]];
+    const B_BAR: &'static [f64] = &[1.0 / 6.0, 1.0 / 3.0, 0.0];
+    const B: &'static [f64] = &[1.0 / 6.0, 2.0 / 3.0, 1.0 / 6.0];
+    const C: &'static [f64] = &[0.0, 0.5, 1.0];
+}
+
+/// Nystrom's four stage method of order five, from Hairer, Norsett and
+/// Wanner, "Solving Ordinary Differential Equations I", II.14.
+pub struct Nystrom5;
+
+impl NystromTableau for Nystrom5 {
+    const ORDER: usize = 5;
+    const A: &'static [&'static [f64]] = &[
+        &[],
+        &[1.0 / 50.0],
+        &[-1.0 / 27.0, 7.0 / 27.0],
+        &[3.0 / 10.0, -2.0 / 35.0, 9.0 / 35.0],
+    ];
+    const B_BAR: &'static [f64] = &[14.0 / 336.0, 100.0 / 336.0, 54.0 / 336.0, 0.0];
+    const B: &'static [f64] = &[14.0 / 336.0, 125.0 / 336.0, 162.0 / 336.0, 35.0 / 336.0];
+    const C: &'static [f64] = &[0.0, 0.2, 2.0 / 3.0, 1.0];
+}
+
+/// Fixed step Runge-Kutta-Nystrom solver for the method described by `T`.
+pub struct RungeKuttaNystrom<T: NystromTableau, Y: OdeState> {
+    time: f32,
+    position: Y,
+    velocity: Y,
+    stages: Vec<Y>,
+    evaluations: usize,
+    tableau: PhantomData<T>,
+}
+
+pub type RungeKuttaNystrom4<Y> = RungeKuttaNystrom<Nystrom4, Y>;
+pub type RungeKuttaNystrom5<Y> = RungeKuttaNystrom<Nystrom5, Y>;
+
+impl<T: NystromTableau, Y: OdeState> RungeKuttaNystrom<T, Y> {
+    pub fn new(time: f32, position: Y, velocity: Y) -> Self {
+        Self {
+            time,
+            stages: vec![position.zero_like(); T::stages()],
+            position,
+            velocity,
+            evaluations: 0,
+            tableau: PhantomData,
+        }
+    }
+
+    pub fn time(&self) -> f32 {
+        self.time
+    }
+
+    pub fn position(&self) -> &Y {
+        &self.position
+    }
+
+    pub fn velocity(&self) -> &Y {
+        &self.velocity
+    }
+
+    /// Number of right-hand side evaluations so far.
+    pub fn evaluations(&self) -> usize {
+        self.evaluations
+    }
+
+    /// Advances by `delta_time`, where `fun(t, y)` is the acceleration `y''`.
+    pub fn next_step(&mut self, fun: &SolveFun<Y>, delta_time: f32) -> (&Y, &Y) {
+        for i in 0..T::stages() {
+            let c_i = T::C[i] as f32;
+            let start = self.position.clone() + self.velocity.clone() * (c_i * delta_time);
+            let stage_position =
+                combine_stages(&start, delta_time * delta_time, &self.stages[..i], T::A[i]);
+            self.stages[i] = fun(self.time + c_i * delta_time, stage_position);
+        }
+        self.evaluations += T::stages();
+
+        let start = self.position.clone() + self.velocity.clone() * delta_time;
+        self.position = combine_stages(&start, delta_time * delta_time, &self.stages, T::B_BAR);
+        self.velocity = combine_stages(&self.velocity, delta_time, &self.stages, T::B);
+        self.time += delta_time;
+        (&self.position, &self.velocity)
+    }
+}
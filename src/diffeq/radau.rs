@@ -0,0 +1,483 @@
+use crate::linalg::{lu::LuDecomposition, ndarray::NdArray};
+
+use super::{
+    control::{error_norm, initial_step_size, min_step_size, Statistics, Tolerances},
+    dense::Interpolant,
+    error::SolverError,
+    ivp::SolveFun,
+    jacobian::{factorize_shifted, solve_state, Jacobian},
+    newton::{newton_tolerance, Convergence, NewtonCheck},
+    solver::OdeSolver,
+    state::OdeState,
+    stream::{advance_adaptive, Stepper},
+};
+
+const NEWTON_MAX_ITERATIONS: usize = 6;
+const MIN_FACTOR: f32 = 0.2;
+const MAX_FACTOR: f32 = 10.0;
+
+// Three stage Radau IIA collocation method of order 5
+const A: [[f64; 3]; 3] = [
+    [
+        0.19681547722366044,
+        -0.06553542585019838,
+        0.02377097434822015,
+    ],
+    [
+        0.3944243147390873,
+        0.29207341166522843,
+        -0.04154875212599792,
+    ],
+    [0.37640306270046725, 0.5124858261884216, 0.1111111111111111],
+];
+const C: [f64; 3] = [0.15505102572168222, 0.6449489742783178, 1.0];
+// Weights of the third order error estimate and the real eigenvalue of A^-1
+const E: [f64; 3] = [-10.048809399827414, 1.382142733160748, -0.3333333333333333];
+const MU_REAL: f64 = 3.637834252744496;
+// Coefficients of the collocation polynomial in powers of (t - t_old) / h
+const P: [[f64; 3]; 3] = [
+    [10.048809399827414, -25.62959144707664, 15.580782047249224],
+    [-1.382142733160748, 10.296258113743303, -8.914115380582556],
+    [0.3333333333333333, -2.6666666666666665, 3.3333333333333335],
+];
+
+/// Collocation polynomial of the last accepted step.
+struct Collocation<Y> {
+    time: f32,
+    delta_time: f32,
+    state: Y,
+    coefficients: [Y; 3],
+}
+
+impl<Y: OdeState> Collocation<Y> {
+    fn evaluate(&self, time: f32) -> Y {
+        let x = (time - self.time) / self.delta_time;
+        let mut output = self.state.clone();
+        let mut power = 1.0;
+        for coefficient in &self.coefficients {
+            power *= x;
+            output = output + coefficient.clone() * power;
+        }
+        output
+    }
+}
+
+/// Implicit Radau IIA method of order 5 with adaptive step size, after
+/// Hairer and Wanner's `RADAU5` and scipy's `Radau`.
+///
+/// The method is L-stable and stiffly accurate, which makes it a good choice
+/// for very stiff problems and for tight tolerances. The Newton iteration
+/// solves for all three stages at once with a `3n x 3n` real factorization of
+/// `I - h A (x) J` for a state with `n` components.
+pub struct Radau<Y: OdeState> {
+    time: f32,
+    state: Y,
+    derivative: Option<Y>,
+    step_size: Option<f32>,
+    previous: Option<(f32, f32)>,
+    max_step: f32,
+    jacobian: Jacobian<Y>,
+    jacobian_matrix: Option<NdArray>,
+    current_jacobian: bool,
+    // Signed step size with the factorizations of the stage system and of
+    // MU_REAL / h - J for the error estimate
+    lu: Option<(f32, LuDecomposition, LuDecomposition)>,
+    collocation: Option<Collocation<Y>>,
+    tolerances: Tolerances<Y>,
+    statistics: Statistics,
+}
+
+impl<Y: OdeState> Radau<Y> {
+    pub fn new(time: f32, initial_state: Y, tolerances: Tolerances<Y>) -> Self {
+        Self {
+            time,
+            state: initial_state,
+            derivative: None,
+            step_size: None,
+            previous: None,
+            max_step: f32::INFINITY,
+            jacobian: Jacobian::FiniteDifference,
+            jacobian_matrix: None,
+            current_jacobian: false,
+            lu: None,
+            collocation: None,
+            tolerances,
+            statistics: Statistics::default(),
+        }
+    }
+
+    pub fn with_jacobian(mut self, jacobian: Jacobian<Y>) -> Self {
+        self.jacobian = jacobian;
+        self
+    }
+
+    pub fn with_first_step(mut self, step_size: f32) -> Self {
+        assert!(step_size > 0.0, "the first step size must be positive");
+        self.step_size = Some(step_size);
+        self
+    }
+
+    pub fn with_max_step(mut self, max_step: f32) -> Self {
+        assert!(max_step > 0.0, "the maximum step size must be positive");
+        self.max_step = max_step;
+        self
+    }
+
+    pub fn time(&self) -> f32 {
+        self.time
+    }
+
+    pub fn state(&self) -> &Y {
+        &self.state
+    }
+
+    pub fn step_size(&self) -> Option<f32> {
+        self.step_size
+    }
+
+    pub fn statistics(&self) -> &Statistics {
+        &self.statistics
+    }
+
+    /// Takes one accepted step towards `time_bound` without stepping past it.
+    pub fn step(&mut self, fun: &SolveFun<Y>, time_bound: f32) -> Result<&Y, SolverError> {
+        if time_bound == self.time {
+            return Ok(&self.state);
+        }
+        let direction = (time_bound - self.time).signum();
+        let derivative = match &self.derivative {
+            Some(derivative) => derivative.clone(),
+            None => {
+                self.statistics.evaluations += 1;
+                let derivative = fun(self.time, self.state.clone());
+                self.derivative = Some(derivative.clone());
+                derivative
+            }
+        };
+        if self.jacobian_matrix.is_none() {
+            self.jacobian_matrix = Some(self.jacobian.evaluate(
+                fun,
+                self.time,
+                &self.state,
+                Some(&derivative),
+                &self.tolerances.absolute,
+                &mut self.statistics,
+            ));
+            self.current_jacobian = true;
+        }
+        let mut step_size = match self.step_size {
+            Some(step_size) => step_size,
+            None => {
+                self.statistics.evaluations += 1;
+                initial_step_size(
+                    fun,
+                    self.time,
+                    &self.state,
+                    &derivative,
+                    direction,
+                    3,
+                    &self.tolerances,
+                )
+            }
+        }
+        .min(self.max_step);
+
+        let min_step = min_step_size(self.time);
+        let tolerance = newton_tolerance(&self.tolerances);
+        let mut rejected = false;
+        loop {
+            if step_size < min_step {
+                return Err(SolverError::StepSizeTooSmall {
+                    time: self.time,
+                    step_size,
+                });
+            }
+            let mut next_time = self.time + step_size * direction;
+            if direction * (next_time - time_bound) > 0.0 {
+                next_time = time_bound;
+            }
+            let delta_time = next_time - self.time;
+            step_size = delta_time.abs();
+
+            let guess = match &self.collocation {
+                Some(collocation) => C.map(|c| {
+                    collocation.evaluate(self.time + delta_time * c as f32) - self.state.clone()
+                }),
+                None => C.map(|_| self.state.zero_like()),
+            };
+            let scale = self.tolerances.scale(&self.state, &self.state);
+
+            let solution = loop {
+                if !matches!(self.lu, Some((step, _, _)) if step == delta_time) {
+                    self.lu = self.factorize(delta_time);
+                }
+                let solution = self.lu.as_ref().map(|(_, lu, _)| {
+                    solve_stages(
+                        fun,
+                        self.time,
+                        &self.state,
+                        delta_time,
+                        guess.clone(),
+                        lu,
+                        &scale,
+                        tolerance,
+                        &mut self.statistics,
+                    )
+                });
+                match solution {
+                    Some(Some(solution)) => break Some(solution),
+                    _ if self.current_jacobian => break None,
+                    _ => {
+                        self.jacobian_matrix = Some(self.jacobian.evaluate(
+                            fun,
+                            self.time,
+                            &self.state,
+                            Some(&derivative),
+                            &self.tolerances.absolute,
+                            &mut self.statistics,
+                        ));
+                        self.current_jacobian = true;
+                        self.lu = None;
+                    }
+                }
+            };
+            let Some((stages, iterations, rate)) = solution else {
+                self.statistics.rejected_steps += 1;
+                step_size *= 0.5;
+                continue;
+            };
+
+            let next_state = self.state.clone() + stages[2].clone();
+            let (_, _, error_lu) = self.lu.as_ref().unwrap();
+            let stage_error = (0..3).fold(self.state.zero_like(), |sum, i| {
+                sum + stages[i].clone() * (E[i] as f32 / delta_time)
+            });
+            let scale = self.tolerances.scale(&self.state, &next_state);
+            let estimate = solve_state(error_lu, &(derivative.clone() + stage_error.clone()));
+            let mut error = error_norm(&estimate, &scale);
+            if rejected && error > 1.0 {
+                // Filter the estimate once more, which helps for very stiff components
+                self.statistics.evaluations += 1;
+                let filtered = fun(self.time, self.state.clone() + estimate) + stage_error;
+                error = error_norm(&solve_state(error_lu, &filtered), &scale);
+            }
+
+            let safety = 0.9 * (2 * NEWTON_MAX_ITERATIONS + 1) as f32
+                / (2 * NEWTON_MAX_ITERATIONS + iterations) as f32;
+            if error > 1.0 {
+                self.statistics.rejected_steps += 1;
+                step_size *= (safety * self.predict_factor(step_size, error)).max(MIN_FACTOR);
+                rejected = true;
+                continue;
+            }
+
+            self.statistics.accepted_steps += 1;
+            // Slow convergence means the Jacobian is outdated
+            let recompute_jacobian = iterations > 2 && rate > 1e-3;
+            let mut factor = (safety * self.predict_factor(step_size, error)).min(MAX_FACTOR);
+            if !recompute_jacobian && factor < 1.2 {
+                // Keep the step size and with it the factorization
+                factor = 1.0;
+            }
+
+            self.statistics.evaluations += 1;
+            let next_derivative = fun(next_time, next_state.clone());
+            if recompute_jacobian {
+                self.jacobian_matrix = Some(self.jacobian.evaluate(
+                    fun,
+                    next_time,
+                    &next_state,
+                    Some(&next_derivative),
+                    &self.tolerances.absolute,
+                    &mut self.statistics,
+                ));
+                self.current_jacobian = true;
+                self.lu = None;
+            } else {
+                self.current_jacobian = false;
+            }
+
+            let coefficients = [0, 1, 2].map(|k| {
+                stages
+                    .iter()
+                    .enumerate()
+                    .fold(self.state.zero_like(), |sum, (j, stage)| {
+                        sum + stage.clone() * P[j][k] as f32
+                    })
+            });
+            self.collocation = Some(Collocation {
+                time: self.time,
+                delta_time,
+                state: self.state.clone(),
+                coefficients,
+            });
+            self.previous = Some((step_size, error));
+            self.step_size = Some(step_size * factor);
+            self.time = next_time;
+            self.state = next_state;
+            self.derivative = Some(next_derivative);
+            return Ok(&self.state);
+        }
+    }
+
+    /// Steps until `end_time` is reached exactly.
+    pub fn integrate_to(&mut self, fun: &SolveFun<Y>, end_time: f32) -> Result<&Y, SolverError> {
+        while self.time != end_time {
+            self.step(fun, end_time)?;
+        }
+        Ok(&self.state)
+    }
+
+    /// Continues the integration from `time` and `state`, e.g. after the state
+    /// was changed discontinuously. The next step size is kept.
+    pub fn restart(&mut self, time: f32, state: Y) {
+        self.time = time;
+        self.state = state;
+        self.derivative = None;
+        self.previous = None;
+        self.collocation = None;
+        self.current_jacobian = false;
+    }
+
+    /// Collocation polynomial of the last accepted step, `None` before the
+    /// first step.
+    pub fn interpolant(&self) -> Option<Interpolant<Y>> {
+        self.collocation.as_ref().map(|collocation| {
+            Interpolant::new(
+                collocation.time,
+                collocation.time + collocation.delta_time,
+                collocation.state.clone(),
+                collocation.coefficients.to_vec(),
+            )
+        })
+    }
+
+    /// Step size factor `min(1, h / h_old (err_old / err)^(1/4)) err^(-1/4)`,
+    /// Gustafsson's predictive controller.
+    fn predict_factor(&self, step_size: f32, error: f32) -> f32 {
+        let multiplier = match self.previous {
+            Some((previous_step, previous_error)) if error != 0.0 => {
+                step_size / previous_step * (previous_error / error).powf(0.25)
+            }
+            _ => 1.0,
+        };
+        multiplier.min(1.0) * error.powf(-0.25)
+    }
+
+    fn factorize(&mut self, delta_time: f32) -> Option<(f32, LuDecomposition, LuDecomposition)> {
+        let jacobian = self.jacobian_matrix.as_ref().unwrap();
+        let n = self.state.dimension();
+        let size = 3 * n;
+        let mut data = vec![0.0; size * size];
+        for (i, row) in A.iter().enumerate() {
+            for (j, a_ij) in row.iter().enumerate() {
+                let coefficient = delta_time * *a_ij as f32;
+                for k in 0..n {
+                    for l in 0..n {
+                        let identity = if i == j && k == l { 1.0 } else { 0.0 };
+                        data[(i * n + k) * size + j * n + l] =
+                            identity - coefficient * jacobian.data()[k * n + l];
+                    }
+                }
+            }
+        }
+        self.statistics.lu_decompositions += 2;
+        let stages = LuDecomposition::new(size, data).ok()?;
+        let error = factorize_shifted(jacobian, MU_REAL as f32 / delta_time, 1.0).ok()?;
+        Some((delta_time, stages, error))
+    }
+}
+
+/// Simplified Newton iteration for the stage increments `Z_i = Y_i - y`,
+/// which satisfy `Z = h (A (x) I) F(Z)`. Returns the increments, the number
+/// of iterations and the final contraction rate on success.
+#[allow(clippy::too_many_arguments)]
+fn solve_stages<Y: OdeState>(
+    fun: &SolveFun<Y>,
+    time: f32,
+    state: &Y,
+    delta_time: f32,
+    guess: [Y; 3],
+    lu: &LuDecomposition,
+    scale: &Y,
+    tolerance: f32,
+    statistics: &mut Statistics,
+) -> Option<([Y; 3], usize, f32)> {
+    let n = state.dimension();
+    let mut stages = guess;
+    let mut convergence = Convergence::new(NEWTON_MAX_ITERATIONS, tolerance);
+    let mut residual = vec![0.0; 3 * n];
+    for iteration in 0..NEWTON_MAX_ITERATIONS {
+        statistics.evaluations += 3;
+        let derivatives = [0, 1, 2].map(|i| {
+            fun(
+                time + delta_time * C[i] as f32,
+                state.clone() + stages[i].clone(),
+            )
+        });
+        for i in 0..3 {
+            let stage_residual = (0..3).fold(stages[i].zero_like(), |sum, j| {
+                sum + derivatives[j].clone() * (delta_time * A[i][j] as f32)
+            }) - stages[i].clone();
+            for k in 0..n {
+                residual[i * n + k] = stage_residual.component(k);
+            }
+        }
+        lu.solve_in_place(&mut residual);
+
+        let mut norm_squared = 0.0;
+        for i in 0..3 {
+            for k in 0..n {
+                norm_squared += (residual[i * n + k] / scale.component(k)).powi(2);
+            }
+        }
+        let norm = (norm_squared / (3 * n).max(1) as f32).sqrt();
+        let check = convergence.check(iteration, norm);
+        if let NewtonCheck::Failed = check {
+            return None;
+        }
+        for (i, stage) in stages.iter_mut().enumerate() {
+            *stage = stage.map_components(|k, z| z + residual[i * n + k]);
+        }
+        if let NewtonCheck::Converged = check {
+            return Some((stages, iteration + 1, convergence.rate.unwrap_or(0.0)));
+        }
+    }
+    None
+}
+
+impl<Y: OdeState> OdeSolver<Y> for Radau<Y> {
+    fn time(&self) -> f32 {
+        self.time
+    }
+
+    fn state(&self) -> &Y {
+        &self.state
+    }
+
+    fn statistics(&self) -> &Statistics {
+        &self.statistics
+    }
+
+    fn step(&mut self, fun: &SolveFun<Y>, time_bound: f32) -> Result<&Y, SolverError> {
+        Radau::step(self, fun, time_bound)
+    }
+
+    fn restart(&mut self, time: f32, state: Y) {
+        Radau::restart(self, time, state)
+    }
+
+    fn interpolant(&mut self, _fun: &SolveFun<Y>) -> Option<Interpolant<Y>> {
+        Radau::interpolant(self)
+    }
+}
+
+impl<Y: OdeState> Stepper<Y> for Radau<Y> {
+    fn current(&self) -> (f32, Y) {
+        (self.time, self.state.clone())
+    }
+
+    fn advance(&mut self, fun: &SolveFun<Y>, delta_time: f32) -> Result<(), SolverError> {
+        advance_adaptive(self, fun, delta_time)
+    }
+}
@@ -0,0 +1,487 @@
+// Rosenbrock-Wanner methods, which replace the Newton iteration of implicit
+// Runge-Kutta methods by one linear solve per stage with the matrix
+// `I / (gamma h) - J`.
+//
+// The tableaux use the transformed formulation of Hairer and Wanner,
+// "Solving Ordinary Differential Equations II", IV.7, which avoids
+// matrix-vector products with the Jacobian:
+//
+//     (I / (gamma h) - J) u_i = f(t + c_i h, y + sum_{j < i} a_ij u_j)
+//                               + sum_{j < i} (coupling_ij / h) u_j + d_i h df/dt
+//     y_next = y + sum_i b_i u_i
+//
+// and `sum_i e_i u_i` estimates the local error.
+
+// Coefficients are kept with the digits they were published with
+#![allow(clippy::excessive_precision)]
+
+use std::marker::PhantomData;
+
+use super::{
+    control::{
+        error_norm, initial_step_size, min_step_size, Statistics, StepController, Tolerances,
+    },
+    error::SolverError,
+    ivp::{combine_stages, SolveFun},
+    jacobian::{factorize_shifted, solve_state, Jacobian},
+    solver::OdeSolver,
+    state::OdeState,
+    stream::{advance_adaptive, Stepper},
+};
+
+pub trait RosenbrockTableau {
+    /// Order of the solution.
+    const ORDER: usize;
+    /// Order of the embedded solution used for the error estimate.
+    const ERROR_ORDER: usize;
+    const GAMMA: f64;
+    /// Strictly lower triangle, row `i` holds `a_i0 .. a_i(i-1)`.
+    const A: &'static [&'static [f64]];
+    /// Strictly lower triangle of the stage coupling coefficients.
+    const COUPLING: &'static [&'static [f64]];
+    const B: &'static [f64];
+    const C: &'static [f64];
+    const D: &'static [f64];
+    const E: &'static [f64];
+
+    fn stages() -> usize {
+        Self::B.len()
+    }
+}
+
+/// ROS3P of Lang and Verwer, an A-stable third order method that does not
+/// suffer order reduction on parabolic problems.
+pub struct Ros3pTableau;
+
+impl RosenbrockTableau for Ros3pTableau {
+    const ORDER: usize = 3;
+    const ERROR_ORDER: usize = 2;
+    const GAMMA: f64 = 0.7886751345948129;
+    const A: &'static [&'static [f64]] = &[&[], &[1.2679491924311228], &[1.2679491924311228, 0.0]];
+    const COUPLING: &'static [&'static [f64]] = &[
+        &[],
+        &[-1.6076951545867362],
+        &[-3.4641016151377544, -1.7320508075688772],
+    ];
+    const B: &'static [f64] = &[2.0, 0.5773502691896258, 0.42264973081037416];
+    const C: &'static [f64] = &[0.0, 1.0, 1.0];
+    const D: &'static [f64] = &[
+        0.7886751345948129,
+        -0.21132486540518713,
+        -1.0773502691896257,
+    ];
+    const E: &'static [f64] = &[-0.1132486540518709, -0.42264973081037416, 0.0];
+}
+
+/// RODAS4 of Hairer and Wanner, an L-stable, stiffly accurate fourth order
+/// method with a third order embedded solution.
+pub struct Rodas4Tableau;
+
+impl RosenbrockTableau for Rodas4Tableau {
+    const ORDER: usize = 4;
+    const ERROR_ORDER: usize = 3;
+    const GAMMA: f64 = 0.25;
+    const A: &'static [&'static [f64]] = &[
+        &[],
+        &[1.544],
+        &[0.9466785280815826, 0.2557011698983284],
+        &[3.314825187068521, 2.896124015972201, 0.9986419139977817],
+        &[
+            1.221224509226641,
+            6.019134481288629,
+            12.53708332932087,
+            -0.6878860361058950,
+        ],
+        &[
+            1.221224509226641,
+            6.019134481288629,
+            12.53708332932087,
+            -0.6878860361058950,
+            1.0,
+        ],
+    ];
+    const COUPLING: &'static [&'static [f64]] = &[
+        &[],
+        &[-5.6688],
+        &[-2.430093356833875, -0.2063599157091915],
+        &[-0.1073529058151375, -9.594562251023355, -20.47028614809616],
+        &[
+            7.496443313967647,
+            -10.24680431464352,
+            -33.99990352819905,
+            11.70890893206160,
+        ],
+        &[
+            8.083246795921522,
+            -7.981132988064893,
+            -31.52159432874371,
+            16.31930543123136,
+            -6.058818238834054,
+        ],
+    ];
+    const B: &'static [f64] = &[
+        1.221224509226641,
+        6.019134481288629,
+        12.53708332932087,
+        -0.6878860361058950,
+        1.0,
+        1.0,
+    ];
+    const C: &'static [f64] = &[0.0, 0.386, 0.21, 0.63, 1.0, 1.0];
+    const D: &'static [f64] = &[0.25, -0.1043, 0.1035, -0.0362, 0.0, 0.0];
+    const E: &'static [f64] = &[0.0, 0.0, 0.0, 0.0, 0.0, 1.0];
+}
+
+/// Rodas5 of Di Marzo, an L-stable, stiffly accurate fifth order method
+/// with a fourth order embedded solution.
+pub struct Rodas5Tableau;
+
+const RODAS5_A6: [f64; 5] = [
+    -14.09640773051259,
+    6.925207756232704,
+    -41.47510893210728,
+    2.343771018586405,
+    24.13215229196062,
+];
+
+impl RosenbrockTableau for Rodas5Tableau {
+    const ORDER: usize = 5;
+    const ERROR_ORDER: usize = 4;
+    const GAMMA: f64 = 0.19;
+    const A: &'static [&'static [f64]] = &[
+        &[],
+        &[2.0],
+        &[3.040894194418781, 1.041747909077569],
+        &[2.576417536461461, 1.622083060776640, -0.9089668560264532],
+        &[
+            2.760842080225597,
+            1.446624659844071,
+            -0.3036980084553738,
+            0.2877498600325443,
+        ],
+        &RODAS5_A6,
+        &[
+            RODAS5_A6[0],
+            RODAS5_A6[1],
+            RODAS5_A6[2],
+            RODAS5_A6[3],
+            RODAS5_A6[4],
+            1.0,
+        ],
+        &[
+            RODAS5_A6[0],
+            RODAS5_A6[1],
+            RODAS5_A6[2],
+            RODAS5_A6[3],
+            RODAS5_A6[4],
+            1.0,
+            1.0,
+        ],
+    ];
+    const COUPLING: &'static [&'static [f64]] = &[
+        &[],
+        &[-10.31323885133993],
+        &[-21.04823117650003, -7.234992135176716],
+        &[32.22751541853323, -4.943732386540191, 19.44922031041879],
+        &[
+            -20.69865579590063,
+            -8.816374604402768,
+            1.260436877740897,
+            -0.7495647613787146,
+        ],
+        &[
+            -46.22004352711257,
+            -17.49534862857472,
+            -289.6389582892057,
+            93.60855400400906,
+            318.3822534212147,
+        ],
+        &[
+            34.20013733472935,
+            -14.15535402717690,
+            57.82335640988400,
+            25.83362985412365,
+            1.408950972071624,
+            -6.551835421242162,
+        ],
+        &[
+            42.57076742291101,
+            -13.80770672017997,
+            93.98938432427124,
+            18.77919633714503,
+            -31.58359187223370,
+            -6.685968952921985,
+            -5.810979938412932,
+        ],
+    ];
+    const B: &'static [f64] = &[
+        RODAS5_A6[0],
+        RODAS5_A6[1],
+        RODAS5_A6[2],
+        RODAS5_A6[3],
+        RODAS5_A6[4],
+        1.0,
+        1.0,
+        1.0,
+    ];
+    const C: &'static [f64] = &[
+        0.0,
+        0.38,
+        0.3878509998321533,
+        0.4839718937376414,
+        0.4570477008819580,
+        1.0,
+        1.0,
+        1.0,
+    ];
+    const D: &'static [f64] = &[
+        0.19,
+        -0.1823079225333714636,
+        -0.319231832186874912,
+        0.3449828624725343,
+        -0.377417564392089818,
+        0.0,
+        0.0,
+        0.0,
+    ];
+    const E: &'static [f64] = &[0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 1.0];
+}
+
+/// Adaptive Rosenbrock-Wanner solver for the method described by `T`.
+///
+/// Every step evaluates the Jacobian once, by finite differences unless an
+/// analytic Jacobian is given, and approximates `df/dt` with one additional
+/// evaluation of `f`.
+pub struct Rosenbrock<T: RosenbrockTableau, Y: OdeState> {
+    time: f32,
+    state: Y,
+    step_size: Option<f32>,
+    last_step_size: f32,
+    stages: Vec<Y>,
+    jacobian: Jacobian<Y>,
+    tolerances: Tolerances<Y>,
+    controller: StepController,
+    statistics: Statistics,
+    tableau: PhantomData<T>,
+}
+
+pub type Ros3p<Y> = Rosenbrock<Ros3pTableau, Y>;
+pub type Rodas4<Y> = Rosenbrock<Rodas4Tableau, Y>;
+pub type Rodas5<Y> = Rosenbrock<Rodas5Tableau, Y>;
+
+impl<T: RosenbrockTableau, Y: OdeState> Rosenbrock<T, Y> {
+    pub fn new(time: f32, initial_state: Y, tolerances: Tolerances<Y>) -> Self {
+        Self {
+            time,
+            stages: vec![initial_state.zero_like(); T::stages()],
+            state: initial_state,
+            step_size: None,
+            last_step_size: 0.0,
+            jacobian: Jacobian::FiniteDifference,
+            tolerances,
+            controller: StepController::default(),
+            statistics: Statistics::default(),
+            tableau: PhantomData,
+        }
+    }
+
+    pub fn with_jacobian(mut self, jacobian: Jacobian<Y>) -> Self {
+        self.jacobian = jacobian;
+        self
+    }
+
+    pub fn with_first_step(mut self, step_size: f32) -> Self {
+        assert!(step_size > 0.0, "the first step size must be positive");
+        self.step_size = Some(step_size);
+        self
+    }
+
+    pub fn with_max_step(mut self, max_step: f32) -> Self {
+        assert!(max_step > 0.0, "the maximum step size must be positive");
+        self.controller.max_step = max_step;
+        self
+    }
+
+    pub fn with_controller(mut self, controller: StepController) -> Self {
+        self.controller = controller;
+        self
+    }
+
+    pub fn time(&self) -> f32 {
+        self.time
+    }
+
+    pub fn state(&self) -> &Y {
+        &self.state
+    }
+
+    pub fn step_size(&self) -> Option<f32> {
+        self.step_size
+    }
+
+    /// Signed size of the last accepted step.
+    pub fn last_step_size(&self) -> f32 {
+        self.last_step_size
+    }
+
+    /// Stage increments `u_i` of the last accepted step.
+    pub fn stages(&self) -> &[Y] {
+        &self.stages
+    }
+
+    pub fn statistics(&self) -> &Statistics {
+        &self.statistics
+    }
+
+    /// Takes one accepted step towards `time_bound` without stepping past it.
+    pub fn step(&mut self, fun: &SolveFun<Y>, time_bound: f32) -> Result<&Y, SolverError> {
+        if time_bound == self.time {
+            return Ok(&self.state);
+        }
+        let direction = (time_bound - self.time).signum();
+        self.statistics.evaluations += 1;
+        let derivative = fun(self.time, self.state.clone());
+        let mut step_size = match self.step_size {
+            Some(step_size) => step_size,
+            None => {
+                self.statistics.evaluations += 1;
+                initial_step_size(
+                    fun,
+                    self.time,
+                    &self.state,
+                    &derivative,
+                    direction,
+                    T::ERROR_ORDER,
+                    &self.tolerances,
+                )
+            }
+        }
+        .min(self.controller.max_step);
+
+        let jacobian = self.jacobian.evaluate(
+            fun,
+            self.time,
+            &self.state,
+            Some(&derivative),
+            &self.tolerances.absolute,
+            &mut self.statistics,
+        );
+        let time_derivative = self.time_derivative(fun, &derivative, direction);
+
+        let min_step = min_step_size(self.time);
+        let mut rejected = false;
+        loop {
+            if step_size < min_step {
+                return Err(SolverError::StepSizeTooSmall {
+                    time: self.time,
+                    step_size,
+                });
+            }
+            let mut next_time = self.time + step_size * direction;
+            if direction * (next_time - time_bound) > 0.0 {
+                next_time = time_bound;
+            }
+            let delta_time = next_time - self.time;
+            step_size = delta_time.abs();
+
+            self.statistics.lu_decompositions += 1;
+            let diagonal = 1.0 / (T::GAMMA as f32 * delta_time);
+            let Ok(lu) = factorize_shifted(&jacobian, diagonal, 1.0) else {
+                self.statistics.rejected_steps += 1;
+                rejected = true;
+                step_size *= 0.5;
+                continue;
+            };
+
+            let zero = self.state.zero_like();
+            for i in 0..T::stages() {
+                let derivative = if i == 0 {
+                    derivative.clone()
+                } else {
+                    self.statistics.evaluations += 1;
+                    let stage_state = combine_stages(&self.state, 1.0, &self.stages[..i], T::A[i]);
+                    fun(self.time + delta_time * T::C[i] as f32, stage_state)
+                };
+                let coupling =
+                    combine_stages(&zero, 1.0 / delta_time, &self.stages[..i], T::COUPLING[i]);
+                let rhs =
+                    derivative + coupling + time_derivative.clone() * (T::D[i] as f32 * delta_time);
+                self.stages[i] = solve_state(&lu, &rhs);
+            }
+
+            let next_state = combine_stages(&self.state, 1.0, &self.stages, T::B);
+            let error = combine_stages(&zero, 1.0, &self.stages, T::E);
+            let scale = self.tolerances.scale(&self.state, &next_state);
+            let error = error_norm(&error, &scale);
+            let factor = self.controller.factor(error, T::ERROR_ORDER, rejected);
+            if error < 1.0 {
+                self.statistics.accepted_steps += 1;
+                self.time = next_time;
+                self.state = next_state;
+                self.step_size = Some(step_size * factor);
+                self.last_step_size = delta_time;
+                return Ok(&self.state);
+            }
+            self.statistics.rejected_steps += 1;
+            rejected = true;
+            step_size *= factor;
+        }
+    }
+
+    /// Continues the integration from `time` and `state`, e.g. after the state
+    /// was changed discontinuously. The next step size is kept.
+    pub fn restart(&mut self, time: f32, state: Y) {
+        self.time = time;
+        self.state = state;
+    }
+
+    /// Steps until `end_time` is reached exactly.
+    pub fn integrate_to(&mut self, fun: &SolveFun<Y>, end_time: f32) -> Result<&Y, SolverError> {
+        while self.time != end_time {
+            self.step(fun, end_time)?;
+        }
+        Ok(&self.state)
+    }
+
+    /// Forward difference approximation of `df/dt`.
+    fn time_derivative(&mut self, fun: &SolveFun<Y>, derivative: &Y, direction: f32) -> Y {
+        let step = direction * f32::EPSILON.sqrt() * self.time.abs().max(1.0);
+        // Use the step that was actually representable
+        let step = (self.time + step) - self.time;
+        self.statistics.evaluations += 1;
+        let value = fun(self.time + step, self.state.clone());
+        value.map_components(|i, f| (f - derivative.component(i)) / step)
+    }
+}
+
+impl<T: RosenbrockTableau, Y: OdeState> OdeSolver<Y> for Rosenbrock<T, Y> {
+    fn time(&self) -> f32 {
+        self.time
+    }
+
+    fn state(&self) -> &Y {
+        &self.state
+    }
+
+    fn statistics(&self) -> &Statistics {
+        &self.statistics
+    }
+
+    fn step(&mut self, fun: &SolveFun<Y>, time_bound: f32) -> Result<&Y, SolverError> {
+        Rosenbrock::step(self, fun, time_bound)
+    }
+
+    fn restart(&mut self, time: f32, state: Y) {
+        Rosenbrock::restart(self, time, state)
+    }
+}
+
+impl<T: RosenbrockTableau, Y: OdeState> Stepper<Y> for Rosenbrock<T, Y> {
+    fn current(&self) -> (f32, Y) {
+        (self.time, self.state.clone())
+    }
+
+    fn advance(&mut self, fun: &SolveFun<Y>, delta_time: f32) -> Result<(), SolverError> {
+        advance_adaptive(self, fun, delta_time)
+    }
+}
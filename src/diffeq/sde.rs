@@ -0,0 +1,554 @@
+// Stochastic differential equations
+//
+//     dy = f(t, y) dt + G(t, y) dW
+//
+// driven by an `M` dimensional Wiener process `W`, in the Ito or the
+// Stratonovich interpretation. Every scheme is written for the Ito form; a
+// Stratonovich equation is integrated through its Ito drift
+//
+//     f + 1/2 sum_j (dG_j/dy) G_j
+//
+// where `G_j` is column `j` of the diffusion and the directional derivatives
+// are approximated by finite differences.
+
+use crate::{
+    linalg::ndarray::{Matrix, Vector},
+    random::{
+        distributions::{Distribution, Normal},
+        rng::SeedableRng,
+        xoshiro::Xoshiro256StarStar,
+    },
+};
+
+use super::ivp::SolveFun;
+
+/// Interpretation of the stochastic integral.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Calculus {
+    #[default]
+    Ito,
+    Stratonovich,
+}
+
+/// Diagonal noise: component `i` of the diffusion multiplies `dW_i` only.
+pub type DiagonalDiffusion<const N: usize> = dyn Fn(f32, Vector<N>) -> Vector<N>;
+/// General noise: column `j` of the diffusion matrix multiplies `dW_j`.
+pub type GeneralDiffusion<const N: usize, const M: usize> = dyn Fn(f32, Vector<N>) -> Matrix<N, M>;
+
+enum Diffusion<const N: usize, const M: usize> {
+    Diagonal(Box<DiagonalDiffusion<N>>),
+    General(Box<GeneralDiffusion<N, M>>),
+}
+
+/// Stochastic differential equation with `N` states and `M` Wiener processes.
+///
+/// Unlike the ODE solvers, the SDE schemes only take `Vector<N>` states so
+/// far, since the general noise is an `N x M` matrix acting on `dW`. Other
+/// [`OdeState`](super::state::OdeState)s have to be flattened into a vector;
+/// generic drift and diagonal noise are a planned extension.
+pub struct Sde<const N: usize, const M: usize> {
+    drift: Box<SolveFun<Vector<N>>>,
+    diffusion: Diffusion<N, M>,
+    calculus: Calculus,
+}
+
+impl<const N: usize> Sde<N, N> {
+    pub fn diagonal(
+        drift: impl Fn(f32, Vector<N>) -> Vector<N> + 'static,
+        diffusion: impl Fn(f32, Vector<N>) -> Vector<N> + 'static,
+    ) -> Self {
+        Self {
+            drift: Box::new(drift),
+            diffusion: Diffusion::Diagonal(Box::new(diffusion)),
+            calculus: Calculus::Ito,
+        }
+    }
+}
+
+impl<const N: usize, const M: usize> Sde<N, M> {
+    pub fn general(
+        drift: impl Fn(f32, Vector<N>) -> Vector<N> + 'static,
+        diffusion: impl Fn(f32, Vector<N>) -> Matrix<N, M> + 'static,
+    ) -> Self {
+        Self {
+            drift: Box::new(drift),
+            diffusion: Diffusion::General(Box::new(diffusion)),
+            calculus: Calculus::Ito,
+        }
+    }
+
+    pub fn with_calculus(mut self, calculus: Calculus) -> Self {
+        self.calculus = calculus;
+        self
+    }
+
+    pub fn calculus(&self) -> Calculus {
+        self.calculus
+    }
+
+    pub fn is_diagonal(&self) -> bool {
+        matches!(self.diffusion, Diffusion::Diagonal(_))
+    }
+
+    pub fn drift(&self, time: f32, state: Vector<N>) -> Vector<N> {
+        (self.drift)(time, state)
+    }
+
+    /// Diffusion as an `N x M` matrix, also for diagonal noise.
+    pub fn diffusion(&self, time: f32, state: Vector<N>) -> Matrix<N, M> {
+        match &self.diffusion {
+            Diffusion::Diagonal(diffusion) => {
+                let diagonal = diffusion(time, state);
+                Matrix::from_fn(|i, j| if i == j { diagonal[i] } else { 0.0 })
+            }
+            Diffusion::General(diffusion) => diffusion(time, state),
+        }
+    }
+
+    /// Diffusion of the schemes that treat the noise component by component:
+    /// the diagonal, or the only column for scalar noise.
+    fn diffusion_components(&self, time: f32, state: Vector<N>) -> Vector<N> {
+        match &self.diffusion {
+            Diffusion::Diagonal(diffusion) => diffusion(time, state),
+            Diffusion::General(diffusion) => {
+                let matrix = diffusion(time, state);
+                Vector::from_fn(|i, _| matrix[(i, 0)])
+            }
+        }
+    }
+
+    /// Wiener increments matching [`Sde::diffusion_components`].
+    fn increment_components(&self, increment: &Vector<M>) -> Vector<N> {
+        match &self.diffusion {
+            Diffusion::Diagonal(_) => Vector::from_fn(|i, _| increment[i]),
+            Diffusion::General(_) => Vector::from_fn(|_, _| increment[0]),
+        }
+    }
+}
+
+/// Increment of the Wiener process over one step, with the time integral
+/// `I_(j,0) = int_t^(t+h) (W_j(s) - W_j(t)) ds` needed by the order 1.5
+/// schemes.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct WienerIncrement<const M: usize> {
+    pub step: f32,
+    pub dw: Vector<M>,
+    pub time_integral: Vector<M>,
+}
+
+impl<const M: usize> WienerIncrement<M> {
+    /// Increment over this step followed by `next`, which lets the same
+    /// Brownian path be integrated with several step sizes.
+    pub fn concat(&self, next: &Self) -> Self {
+        Self {
+            step: self.step + next.step,
+            dw: self.dw + next.dw,
+            time_integral: self.time_integral + next.time_integral + next.step * self.dw,
+        }
+    }
+}
+
+/// Seedable source of Wiener increments.
+#[derive(Clone, Debug)]
+pub struct WienerProcess<const M: usize> {
+    rng: Xoshiro256StarStar,
+}
+
+impl<const M: usize> WienerProcess<M> {
+    pub fn new(seed: u64) -> Self {
+        Self::from_rng(Xoshiro256StarStar::seed_from_u64(seed))
+    }
+
+    pub fn from_rng(rng: Xoshiro256StarStar) -> Self {
+        Self { rng }
+    }
+
+    pub fn increment(&mut self, step: f32) -> WienerIncrement<M> {
+        assert!(step > 0.0, "Wiener increments need a positive step");
+        let normal = Normal::standard();
+        let root = step.sqrt();
+        let mut dw = Vector::zeros();
+        let mut time_integral = Vector::zeros();
+        for j in 0..M {
+            let first: f32 = normal.sample(&mut self.rng);
+            let second: f32 = normal.sample(&mut self.rng);
+            dw[j] = root * first;
+            time_integral[j] = 0.5 * step * (dw[j] + root * second / 3f32.sqrt());
+        }
+        WienerIncrement {
+            step,
+            dw,
+            time_integral,
+        }
+    }
+}
+
+/// Fixed step scheme of [`SdeIntegrator`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SdeMethod {
+    /// Euler-Maruyama, for any noise.
+    #[default]
+    EulerMaruyama,
+    /// Milstein with finite difference derivatives of the diffusion. For
+    /// general noise the noise has to be commutative, since the Levy areas
+    /// are not simulated.
+    Milstein,
+    /// Rossler's SRIW1 for diagonal noise, where component `i` of the
+    /// diffusion depends on component `i` of the state only, or scalar noise.
+    Sri,
+    /// Rossler's SRA1 for additive noise, where the diffusion does not depend
+    /// on the state.
+    Sra,
+}
+
+impl SdeMethod {
+    /// Strong order of convergence for the noise the method is meant for.
+    pub fn strong_order(&self) -> f32 {
+        match self {
+            SdeMethod::EulerMaruyama => 0.5,
+            SdeMethod::Milstein => 1.0,
+            SdeMethod::Sri | SdeMethod::Sra => 1.5,
+        }
+    }
+}
+
+// Rossler, "Runge-Kutta methods for the strong approximation of solutions of
+// stochastic differential equations" (2010), stored like the Butcher tableaux
+// in `tableau`
+const SRI_C0: [f64; 4] = [0.0, 3.0 / 4.0, 0.0, 0.0];
+const SRI_C1: [f64; 4] = [0.0, 1.0 / 4.0, 1.0, 1.0 / 4.0];
+const SRI_A0: &[&[f64]] = &[&[], &[3.0 / 4.0], &[0.0, 0.0], &[0.0, 0.0, 0.0]];
+const SRI_B0: &[&[f64]] = &[&[], &[3.0 / 2.0], &[0.0, 0.0], &[0.0, 0.0, 0.0]];
+const SRI_A1: &[&[f64]] = &[&[], &[1.0 / 4.0], &[1.0, 0.0], &[0.0, 0.0, 1.0 / 4.0]];
+const SRI_B1: &[&[f64]] = &[&[], &[1.0 / 2.0], &[-1.0, 0.0], &[-5.0, 3.0, 1.0 / 2.0]];
+const SRI_ALPHA: [f64; 4] = [1.0 / 3.0, 2.0 / 3.0, 0.0, 0.0];
+const SRI_BETA1: [f64; 4] = [-1.0, 4.0 / 3.0, 2.0 / 3.0, 0.0];
+const SRI_BETA2: [f64; 4] = [-1.0, 4.0 / 3.0, -1.0 / 3.0, 0.0];
+const SRI_BETA3: [f64; 4] = [2.0, -4.0 / 3.0, -2.0 / 3.0, 0.0];
+const SRI_BETA4: [f64; 4] = [-2.0, 5.0 / 3.0, -2.0 / 3.0, 1.0];
+
+const SRA_C0: [f64; 2] = [0.0, 3.0 / 4.0];
+const SRA_C1: [f64; 2] = [1.0, 0.0];
+const SRA_A0: f64 = 3.0 / 4.0;
+const SRA_B0: f64 = 3.0 / 2.0;
+const SRA_ALPHA: [f64; 2] = [1.0 / 3.0, 2.0 / 3.0];
+const SRA_BETA1: [f64; 2] = [1.0, 0.0];
+const SRA_BETA2: [f64; 2] = [-1.0, 1.0];
+
+/// Fixed step integrator of an [`Sde`] along given Wiener increments.
+pub struct SdeIntegrator<const N: usize, const M: usize> {
+    method: SdeMethod,
+    time: f32,
+    state: Vector<N>,
+    drift_evaluations: usize,
+    diffusion_evaluations: usize,
+}
+
+impl<const N: usize, const M: usize> SdeIntegrator<N, M> {
+    pub fn new(method: SdeMethod, time: f32, state: Vector<N>) -> Self {
+        Self {
+            method,
+            time,
+            state,
+            drift_evaluations: 0,
+            diffusion_evaluations: 0,
+        }
+    }
+
+    pub fn time(&self) -> f32 {
+        self.time
+    }
+
+    pub fn state(&self) -> &Vector<N> {
+        &self.state
+    }
+
+    pub fn drift_evaluations(&self) -> usize {
+        self.drift_evaluations
+    }
+
+    pub fn diffusion_evaluations(&self) -> usize {
+        self.diffusion_evaluations
+    }
+
+    /// Advances by `increment.step` along the Wiener increment.
+    pub fn next_step(&mut self, sde: &Sde<N, M>, increment: &WienerIncrement<M>) -> &Vector<N> {
+        self.state = match self.method {
+            SdeMethod::EulerMaruyama => self.euler_maruyama(sde, increment),
+            SdeMethod::Milstein => self.milstein(sde, increment),
+            SdeMethod::Sri => {
+                assert!(
+                    sde.is_diagonal() || M == 1,
+                    "SRI needs diagonal or scalar noise"
+                );
+                self.sri(sde, increment)
+            }
+            SdeMethod::Sra => self.sra(sde, increment),
+        };
+        self.time += increment.step;
+        &self.state
+    }
+
+    fn drift(&mut self, sde: &Sde<N, M>, time: f32, state: Vector<N>) -> Vector<N> {
+        self.drift_evaluations += 1;
+        sde.drift(time, state)
+    }
+
+    fn diffusion(&mut self, sde: &Sde<N, M>, time: f32, state: Vector<N>) -> Matrix<N, M> {
+        self.diffusion_evaluations += 1;
+        sde.diffusion(time, state)
+    }
+
+    /// Directional derivatives `(dG/dy) G_j` of the diffusion `G` at `state`.
+    fn diffusion_derivatives(
+        &mut self,
+        sde: &Sde<N, M>,
+        time: f32,
+        state: Vector<N>,
+        diffusion: &Matrix<N, M>,
+    ) -> Vec<Matrix<N, M>> {
+        let scale = state.data.iter().fold(1.0f32, |max, [y]| max.max(y.abs()));
+        (0..M)
+            .map(|j| {
+                let direction: Vector<N> = Vector::from_fn(|i, _| diffusion[(i, j)]);
+                let length = direction
+                    .data
+                    .iter()
+                    .fold(0.0f32, |max, [g]| max.max(g.abs()));
+                if length == 0.0 {
+                    return Matrix::zeros();
+                }
+                let epsilon = f32::EPSILON.sqrt() * scale / length;
+                let perturbed = self.diffusion(sde, time, state + epsilon * direction);
+                (perturbed - *diffusion) / epsilon
+            })
+            .collect()
+    }
+
+    /// Ito drift `f + 1/2 sum_j (dG_j/dy) G_j` from the directional derivatives.
+    fn ito_correction(derivatives: &[Matrix<N, M>]) -> Vector<N> {
+        Vector::from_fn(|i, _| {
+            0.5 * derivatives
+                .iter()
+                .enumerate()
+                .map(|(j, derivative)| derivative[(i, j)])
+                .sum::<f32>()
+        })
+    }
+
+    fn ito_drift(&mut self, sde: &Sde<N, M>, time: f32, state: Vector<N>) -> Vector<N> {
+        let drift = self.drift(sde, time, state);
+        match sde.calculus() {
+            Calculus::Ito => drift,
+            Calculus::Stratonovich => {
+                let diffusion = self.diffusion(sde, time, state);
+                let derivatives = self.diffusion_derivatives(sde, time, state, &diffusion);
+                drift + Self::ito_correction(&derivatives)
+            }
+        }
+    }
+
+    fn euler_maruyama(&mut self, sde: &Sde<N, M>, increment: &WienerIncrement<M>) -> Vector<N> {
+        let (time, state, step) = (self.time, self.state, increment.step);
+        let drift = self.ito_drift(sde, time, state);
+        let diffusion = self.diffusion(sde, time, state);
+        state + step * drift + diffusion * increment.dw
+    }
+
+    fn milstein(&mut self, sde: &Sde<N, M>, increment: &WienerIncrement<M>) -> Vector<N> {
+        let (time, state, step) = (self.time, self.state, increment.step);
+        let dw = increment.dw;
+        let mut drift = self.drift(sde, time, state);
+        let diffusion = self.diffusion(sde, time, state);
+        let derivatives = self.diffusion_derivatives(sde, time, state, &diffusion);
+        if sde.calculus() == Calculus::Stratonovich {
+            drift += Self::ito_correction(&derivatives);
+        }
+        let mut next = state + step * drift + diffusion * dw;
+        // Iterated integrals I_(j,k), with the symmetric part for j != k
+        for (j, derivative) in derivatives.iter().enumerate() {
+            for k in 0..M {
+                let integral = if j == k {
+                    0.5 * (dw[j] * dw[j] - step)
+                } else {
+                    0.5 * dw[j] * dw[k]
+                };
+                next += integral * Vector::from_fn(|i, _| derivative[(i, k)]);
+            }
+        }
+        next
+    }
+
+    fn sri(&mut self, sde: &Sde<N, M>, increment: &WienerIncrement<M>) -> Vector<N> {
+        let (time, state, step) = (self.time, self.state, increment.step);
+        let root = step.sqrt();
+        let dw = sde.increment_components(&increment.dw);
+        let time_integral = sde.increment_components(&increment.time_integral);
+        let mut drifts = [Vector::<N>::zeros(); 4];
+        let mut diffusions = [Vector::<N>::zeros(); 4];
+        for i in 0..4 {
+            let mut drift_stage = state;
+            let mut diffusion_stage = state;
+            for j in 0..i {
+                let noise = hadamard(&diffusions[j], &time_integral);
+                drift_stage += (SRI_A0[i][j] as f32 * step) * drifts[j];
+                drift_stage += (SRI_B0[i][j] as f32 / step) * noise;
+                diffusion_stage += (SRI_A1[i][j] as f32 * step) * drifts[j];
+                diffusion_stage += (SRI_B1[i][j] as f32 * root) * diffusions[j];
+            }
+            drifts[i] = self.ito_drift(sde, time + SRI_C0[i] as f32 * step, drift_stage);
+            self.diffusion_evaluations += 1;
+            diffusions[i] =
+                sde.diffusion_components(time + SRI_C1[i] as f32 * step, diffusion_stage);
+        }
+
+        let mut next = state;
+        for i in 0..4 {
+            next += (SRI_ALPHA[i] as f32 * step) * drifts[i];
+            let weights = Vector::from_fn(|k, _| {
+                let (dw, time_integral) = (dw[k], time_integral[k]);
+                let i11 = 0.5 * (dw * dw - step);
+                let i111 = (dw * dw * dw - 3.0 * step * dw) / 6.0;
+                SRI_BETA1[i] as f32 * dw
+                    + SRI_BETA2[i] as f32 * i11 / root
+                    + SRI_BETA3[i] as f32 * time_integral / step
+                    + SRI_BETA4[i] as f32 * i111 / step
+            });
+            next += hadamard(&weights, &diffusions[i]);
+        }
+        next
+    }
+
+    fn sra(&mut self, sde: &Sde<N, M>, increment: &WienerIncrement<M>) -> Vector<N> {
+        let (time, state, step) = (self.time, self.state, increment.step);
+        // The diffusion does not depend on the state
+        let diffusions = SRA_C1.map(|c| self.diffusion(sde, time + c as f32 * step, state));
+        let first_drift = self.drift(sde, time, state);
+        let stage = state
+            + (SRA_A0 as f32 * step) * first_drift
+            + diffusions[0] * ((SRA_B0 as f32 / step) * increment.time_integral);
+        let drifts = [
+            first_drift,
+            self.drift(sde, time + SRA_C0[1] as f32 * step, stage),
+        ];
+
+        let mut next = state;
+        for i in 0..2 {
+            next += (SRA_ALPHA[i] as f32 * step) * drifts[i];
+            next += diffusions[i]
+                * (SRA_BETA1[i] as f32 * increment.dw
+                    + (SRA_BETA2[i] as f32 / step) * increment.time_integral);
+        }
+        next
+    }
+}
+
+fn hadamard<const N: usize>(a: &Vector<N>, b: &Vector<N>) -> Vector<N> {
+    Vector::from_fn(|i, _| a[i] * b[i])
+}
+
+/// Sample path of [`solve_sde`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct SdePath<const N: usize> {
+    pub times: Vec<f32>,
+    pub states: Vec<Vector<N>>,
+}
+
+/// Integrates the SDE over `t_span` with steps of `step_size`, the last one
+/// shortened to end on `t_span.1`, along increments drawn from `wiener`.
+pub fn solve_sde<const N: usize, const M: usize>(
+    sde: &Sde<N, M>,
+    method: SdeMethod,
+    t_span: (f32, f32),
+    initial_state: Vector<N>,
+    step_size: f32,
+    wiener: &mut WienerProcess<M>,
+) -> SdePath<N> {
+    let (start_time, end_time) = t_span;
+    assert!(end_time > start_time, "SDEs are integrated forward in time");
+    assert!(step_size > 0.0, "the step size must be positive");
+    // A quotient just above an integer from rounding does not add a tiny step
+    let steps = ((end_time - start_time) / step_size - 1e-3).ceil().max(1.0) as usize;
+    let mut integrator = SdeIntegrator::new(method, start_time, initial_state);
+    let mut times = vec![start_time];
+    let mut states = vec![initial_state];
+    for n in 1..=steps {
+        let next_time = if n == steps {
+            end_time
+        } else {
+            start_time + n as f32 * step_size
+        };
+        let increment = wiener.increment(next_time - integrator.time());
+        let state = *integrator.next_step(sde, &increment);
+        times.push(next_time);
+        states.push(state);
+    }
+    SdePath { times, states }
+}
+
+/// Pointwise statistics over the sample paths of [`ensemble`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct EnsembleStatistics<const N: usize> {
+    pub times: Vec<f32>,
+    pub mean: Vec<Vector<N>>,
+    /// Unbiased sample variance.
+    pub variance: Vec<Vector<N>>,
+    pub final_states: Vec<Vector<N>>,
+}
+
+impl<const N: usize> EnsembleStatistics<N> {
+    pub fn std_dev(&self) -> Vec<Vector<N>> {
+        self.variance
+            .iter()
+            .map(|variance| Vector::from_fn(|i, _| variance[i].sqrt()))
+            .collect()
+    }
+}
+
+/// Integrates `trajectories` sample paths with [`solve_sde`], each on its
+/// own stream of the generator seeded with `seed`.
+pub fn ensemble<const N: usize, const M: usize>(
+    sde: &Sde<N, M>,
+    method: SdeMethod,
+    t_span: (f32, f32),
+    initial_state: Vector<N>,
+    step_size: f32,
+    trajectories: usize,
+    seed: u64,
+) -> EnsembleStatistics<N> {
+    assert!(
+        trajectories >= 2,
+        "an ensemble needs at least two trajectories"
+    );
+    let mut streams = Xoshiro256StarStar::seed_from_u64(seed);
+    let mut times = Vec::new();
+    let mut mean: Vec<Vector<N>> = Vec::new();
+    let mut squares: Vec<Vector<N>> = Vec::new();
+    let mut final_states = Vec::with_capacity(trajectories);
+    // Welford's running mean and sum of squared deviations
+    for count in 1..=trajectories {
+        let mut wiener = WienerProcess::from_rng(streams.split());
+        let path = solve_sde(sde, method, t_span, initial_state, step_size, &mut wiener);
+        if count == 1 {
+            times = path.times;
+            mean = vec![Vector::zeros(); times.len()];
+            squares = vec![Vector::zeros(); times.len()];
+        }
+        for ((mean, squares), state) in mean.iter_mut().zip(&mut squares).zip(&path.states) {
+            let deviation = *state - *mean;
+            *mean += deviation / count as f32;
+            *squares += hadamard(&deviation, &(*state - *mean));
+        }
+        final_states.push(*path.states.last().unwrap());
+    }
+    let variance = squares
+        .into_iter()
+        .map(|squares| squares / (trajectories - 1) as f32)
+        .collect();
+    EnsembleStatistics {
+        times,
+        mean,
+        variance,
+        final_states,
+    }
+}
@@ -0,0 +1,529 @@
+// Derivatives of the solution of
+//
+//     y' = f(t, y, p),    y(t_0) = y_0
+//
+// with respect to the parameters `p`.
+//
+// The forward method integrates the sensitivity equations
+// `S' = df/dy S + df/dp` for `S = dy/dp` together with the solution, which
+// costs about `P` additional solutions. The adjoint method computes the
+// gradient of a loss `g(y(T))` by integrating `lambda' = -df/dy^T lambda`
+// backwards from `lambda(T) = dg/dy`, at a cost independent of `P`. Its
+// backward pass recomputes the solution from checkpoints stored during the
+// forward pass, so that only the dense output of one segment between two
+// checkpoints is kept in memory.
+
+use std::{cell::RefCell, rc::Rc};
+
+use crate::linalg::ndarray::{Matrix, Vector};
+
+use super::{
+    adaptive::{Dop853, Rk23, Rk45, Tsit5},
+    control::{Statistics, Tolerances},
+    dense::DenseSolution,
+    multistep::Abm,
+    solve::{Method, Status},
+    solver::{step_interpolant, OdeSolver},
+    state::{OdeState, StatePair},
+};
+
+/// Right-hand side `f(t, y, p)`.
+pub type ParamFun<const N: usize, const P: usize> = dyn Fn(f32, Vector<N>, &Vector<P>) -> Vector<N>;
+pub type StateJacobianFun<const N: usize, const P: usize> =
+    dyn Fn(f32, Vector<N>, &Vector<P>) -> Matrix<N, N>;
+pub type ParamJacobianFun<const N: usize, const P: usize> =
+    dyn Fn(f32, Vector<N>, &Vector<P>) -> Matrix<N, P>;
+
+/// Source of the Jacobians `df/dy` and `df/dp`.
+#[derive(Clone, Default)]
+pub enum ParamJacobian<const N: usize, const P: usize> {
+    /// Central differences, costing `2 (N + P)` evaluations of `f`.
+    #[default]
+    FiniteDifference,
+    Analytic {
+        state: Rc<StateJacobianFun<N, P>>,
+        parameters: Rc<ParamJacobianFun<N, P>>,
+    },
+}
+
+impl<const N: usize, const P: usize> ParamJacobian<N, P> {
+    pub fn analytic(
+        state: impl Fn(f32, Vector<N>, &Vector<P>) -> Matrix<N, N> + 'static,
+        parameters: impl Fn(f32, Vector<N>, &Vector<P>) -> Matrix<N, P> + 'static,
+    ) -> Self {
+        ParamJacobian::Analytic {
+            state: Rc::new(state),
+            parameters: Rc::new(parameters),
+        }
+    }
+
+    /// `(df/dy, df/dp)` at `(time, state)`.
+    pub fn evaluate(
+        &self,
+        fun: &ParamFun<N, P>,
+        time: f32,
+        state: &Vector<N>,
+        parameters: &Vector<P>,
+        statistics: &mut Statistics,
+    ) -> (Matrix<N, N>, Matrix<N, P>) {
+        statistics.jacobian_evaluations += 1;
+        match self {
+            ParamJacobian::FiniteDifference => {
+                statistics.evaluations += 2 * (N + P);
+                central_difference_jacobians(fun, time, state, parameters)
+            }
+            ParamJacobian::Analytic {
+                state: state_jacobian,
+                parameters: parameter_jacobian,
+            } => (
+                state_jacobian(time, *state, parameters),
+                parameter_jacobian(time, *state, parameters),
+            ),
+        }
+    }
+}
+
+/// Central difference approximations of `df/dy` and `df/dp`, perturbing one
+/// component at a time relative to `max(|x_j|, 1)`.
+pub fn central_difference_jacobians<const N: usize, const P: usize>(
+    fun: &ParamFun<N, P>,
+    time: f32,
+    state: &Vector<N>,
+    parameters: &Vector<P>,
+) -> (Matrix<N, N>, Matrix<N, P>) {
+    // The cube root of the machine epsilon balances truncation and rounding
+    // errors of central differences
+    let relative_step = f32::EPSILON.cbrt();
+    let mut state_jacobian = Matrix::zeros();
+    for j in 0..N {
+        let step = relative_step * state[j].abs().max(1.0);
+        let (mut plus, mut minus) = (*state, *state);
+        plus[j] += step;
+        minus[j] -= step;
+        let column =
+            (fun(time, plus, parameters) - fun(time, minus, parameters)) / (plus[j] - minus[j]);
+        for i in 0..N {
+            state_jacobian[(i, j)] = column[i];
+        }
+    }
+    let mut parameter_jacobian = Matrix::zeros();
+    for j in 0..P {
+        let step = relative_step * parameters[j].abs().max(1.0);
+        let (mut plus, mut minus) = (*parameters, *parameters);
+        plus[j] += step;
+        minus[j] -= step;
+        let column = (fun(time, *state, &plus) - fun(time, *state, &minus)) / (plus[j] - minus[j]);
+        for i in 0..N {
+            parameter_jacobian[(i, j)] = column[i];
+        }
+    }
+    (state_jacobian, parameter_jacobian)
+}
+
+/// Options of [`forward_sensitivity`] and [`adjoint_gradient`], built with
+/// the `with_*` methods.
+#[derive(Clone)]
+pub struct SensitivityOptions<const N: usize, const P: usize> {
+    method: Method,
+    tolerances: Tolerances<Vector<N>>,
+    adjoint_tolerances: Tolerances<StatePair<Vector<N>, Vector<P>>>,
+    max_step: f32,
+    jacobian: ParamJacobian<N, P>,
+    checkpoint_steps: usize,
+    t_eval: Option<Vec<f32>>,
+}
+
+impl<const N: usize, const P: usize> Default for SensitivityOptions<N, P> {
+    fn default() -> Self {
+        Self {
+            method: Method::default(),
+            tolerances: Tolerances::default(),
+            adjoint_tolerances: Tolerances::default(),
+            max_step: f32::INFINITY,
+            jacobian: ParamJacobian::FiniteDifference,
+            checkpoint_steps: 50,
+            t_eval: None,
+        }
+    }
+}
+
+impl<const N: usize, const P: usize> SensitivityOptions<N, P> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// One of the explicit methods.
+    pub fn with_method(mut self, method: Method) -> Self {
+        assert!(
+            !method.is_implicit(),
+            "sensitivity analysis is built on the explicit methods"
+        );
+        self.method = method;
+        self
+    }
+
+    /// Tolerances of the solution. Row `i` of the forward sensitivities uses
+    /// the tolerances of component `i`.
+    pub fn with_tolerances(mut self, tolerances: Tolerances<Vector<N>>) -> Self {
+        self.tolerances = tolerances;
+        self
+    }
+
+    /// Tolerances of the adjoint `lambda` and of the gradient in the backward pass.
+    pub fn with_adjoint_tolerances(
+        mut self,
+        tolerances: Tolerances<StatePair<Vector<N>, Vector<P>>>,
+    ) -> Self {
+        self.adjoint_tolerances = tolerances;
+        self
+    }
+
+    pub fn with_max_step(mut self, max_step: f32) -> Self {
+        assert!(max_step > 0.0, "the maximum step size must be positive");
+        self.max_step = max_step;
+        self
+    }
+
+    pub fn with_jacobian(mut self, jacobian: ParamJacobian<N, P>) -> Self {
+        self.jacobian = jacobian;
+        self
+    }
+
+    /// Accepted steps between the checkpoints of the adjoint method, 50 by
+    /// default. Longer segments need fewer checkpoints but more dense output.
+    pub fn with_checkpoint_steps(mut self, steps: usize) -> Self {
+        assert!(steps > 0, "checkpoints need at least one step between them");
+        self.checkpoint_steps = steps;
+        self
+    }
+
+    /// Times at which [`forward_sensitivity`] reports the solution instead of
+    /// the solver steps, sorted in the direction of integration.
+    pub fn with_t_eval(mut self, t_eval: Vec<f32>) -> Self {
+        self.t_eval = Some(t_eval);
+        self
+    }
+
+    fn solver<Y: OdeState + 'static>(
+        &self,
+        time: f32,
+        state: Y,
+        tolerances: Tolerances<Y>,
+    ) -> Box<dyn OdeSolver<Y>> {
+        let max_step = self.max_step;
+        match self.method {
+            Method::Rk23 => Box::new(Rk23::new(time, state, tolerances).with_max_step(max_step)),
+            Method::Rk45 => Box::new(Rk45::new(time, state, tolerances).with_max_step(max_step)),
+            Method::Tsit5 => Box::new(Tsit5::new(time, state, tolerances).with_max_step(max_step)),
+            Method::Dop853 => {
+                Box::new(Dop853::new(time, state, tolerances).with_max_step(max_step))
+            }
+            Method::Abm => Box::new(Abm::new(time, state, tolerances).with_max_step(max_step)),
+            method => panic!("{:?} is not an explicit method", method),
+        }
+    }
+}
+
+/// Result of [`forward_sensitivity`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct ForwardSensitivity<const N: usize, const P: usize> {
+    /// Solver steps, or the requested output times.
+    pub times: Vec<f32>,
+    pub states: Vec<Vector<N>>,
+    /// `dy/dp` at the times.
+    pub sensitivities: Vec<Matrix<N, P>>,
+    pub status: Status,
+    /// `evaluations` counts every call of `f`, including the ones for finite differences.
+    pub statistics: Statistics,
+}
+
+impl<const N: usize, const P: usize> ForwardSensitivity<N, P> {
+    pub fn success(&self) -> bool {
+        !matches!(self.status, Status::Failed(_))
+    }
+}
+
+/// Integrates `y' = fun(t, y, p)` over `t_span` together with the
+/// sensitivities `dy/dp`, which start at zero.
+///
+/// The sensitivities form a `Matrix<N, P>`, so the state has to be a
+/// `Vector<N>`; other [`OdeState`]s are not
+/// supported yet.
+pub fn forward_sensitivity<const N: usize, const P: usize>(
+    fun: impl Fn(f32, Vector<N>, &Vector<P>) -> Vector<N> + 'static,
+    t_span: (f32, f32),
+    initial_state: Vector<N>,
+    parameters: Vector<P>,
+    mut options: SensitivityOptions<N, P>,
+) -> ForwardSensitivity<N, P> {
+    let (start_time, end_time) = t_span;
+    let model = Rc::new(Model::new(fun, parameters, &mut options));
+    let rhs = {
+        let model = Rc::clone(&model);
+        move |time: f32, y: StatePair<Vector<N>, Matrix<N, P>>| {
+            let StatePair(state, sensitivity) = y;
+            let (state_jacobian, parameter_jacobian) = model.jacobians(time, &state);
+            StatePair(
+                model.evaluate(time, state),
+                state_jacobian * sensitivity + parameter_jacobian,
+            )
+        }
+    };
+
+    let Tolerances { relative, absolute } = options.tolerances;
+    let tolerances = Tolerances::per_component(
+        StatePair(relative, Matrix::from_fn(|i, _| relative[i])),
+        StatePair(absolute, Matrix::from_fn(|i, _| absolute[i])),
+    );
+    let direction = (end_time - start_time).signum();
+    let t_eval = options.t_eval.take();
+    if let Some(t_eval) = &t_eval {
+        assert!(
+            t_eval
+                .iter()
+                .all(|&time| direction * (time - start_time) >= 0.0
+                    && direction * (end_time - time) >= 0.0),
+            "t_eval values have to lie in the time span"
+        );
+        assert!(
+            t_eval.windows(2).all(|w| direction * (w[1] - w[0]) >= 0.0),
+            "t_eval has to be sorted in the direction of integration"
+        );
+    }
+
+    let initial = StatePair(initial_state, Matrix::zeros());
+    let mut solver = options.solver(start_time, initial, tolerances);
+    let mut outputs = Vec::new();
+    let mut next_output = 0;
+    match &t_eval {
+        Some(t_eval) => {
+            while next_output < t_eval.len() && t_eval[next_output] == start_time {
+                outputs.push((start_time, initial));
+                next_output += 1;
+            }
+        }
+        None => outputs.push((start_time, initial)),
+    }
+    let mut status = Status::Finished;
+    while solver.time() != end_time {
+        let (step_start, step_state) = (solver.time(), *solver.state());
+        if let Err(error) = solver.step(&rhs, end_time) {
+            status = Status::Failed(error);
+            break;
+        }
+        let step_end = solver.time();
+        match &t_eval {
+            Some(t_eval) => {
+                let mut interpolant = None;
+                while next_output < t_eval.len()
+                    && direction * (step_end - t_eval[next_output]) >= 0.0
+                {
+                    let time = t_eval[next_output];
+                    let state = if time == step_end {
+                        *solver.state()
+                    } else {
+                        interpolant
+                            .get_or_insert_with(|| {
+                                step_interpolant(solver.as_mut(), &rhs, step_start, step_state)
+                            })
+                            .evaluate(time)
+                    };
+                    outputs.push((time, state));
+                    next_output += 1;
+                }
+            }
+            None => outputs.push((step_end, *solver.state())),
+        }
+    }
+
+    let times = outputs.iter().map(|(time, _)| *time).collect();
+    let states = outputs.iter().map(|(_, y)| y.0).collect();
+    let sensitivities = outputs.iter().map(|(_, y)| y.1).collect();
+    ForwardSensitivity {
+        times,
+        states,
+        sensitivities,
+        status,
+        statistics: model.statistics(solver.statistics()),
+    }
+}
+
+/// Result of [`adjoint_gradient`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct AdjointGradient<const N: usize, const P: usize> {
+    /// `y(T)`, the argument of the loss.
+    pub final_state: Vector<N>,
+    /// `dg/dp`.
+    pub gradient: Vector<P>,
+    /// `dg/dy_0`, the adjoint at the start.
+    pub initial_adjoint: Vector<N>,
+    /// Number of segments the backward pass recomputed.
+    pub checkpoints: usize,
+    pub status: Status,
+    /// Accumulated over the forward pass, the recomputation and the backward
+    /// pass. `evaluations` counts every call of `f`.
+    pub statistics: Statistics,
+}
+
+impl<const N: usize, const P: usize> AdjointGradient<N, P> {
+    pub fn success(&self) -> bool {
+        !matches!(self.status, Status::Failed(_))
+    }
+}
+
+/// Gradient of the loss `g(y(T))` with respect to the parameters, where `y`
+/// solves `y' = fun(t, y, p)` over `t_span` and `loss_gradient(y)` is `dg/dy`.
+///
+/// If a pass fails, the gradients are zero and `status` holds the error.
+/// As for [`forward_sensitivity`], the state has to be a `Vector<N>`.
+pub fn adjoint_gradient<const N: usize, const P: usize>(
+    fun: impl Fn(f32, Vector<N>, &Vector<P>) -> Vector<N> + 'static,
+    t_span: (f32, f32),
+    initial_state: Vector<N>,
+    parameters: Vector<P>,
+    loss_gradient: impl Fn(Vector<N>) -> Vector<N>,
+    mut options: SensitivityOptions<N, P>,
+) -> AdjointGradient<N, P> {
+    let (start_time, end_time) = t_span;
+    let model = Rc::new(Model::new(fun, parameters, &mut options));
+    let forward_rhs = {
+        let model = Rc::clone(&model);
+        move |time: f32, state: Vector<N>| model.evaluate(time, state)
+    };
+
+    let mut checkpoints = vec![(start_time, initial_state)];
+    let mut solver = options.solver(start_time, initial_state, options.tolerances);
+    let mut steps = Statistics::default();
+    let mut status = Status::Finished;
+    while solver.time() != end_time {
+        if let Err(error) = solver.step(&forward_rhs, end_time) {
+            status = Status::Failed(error);
+            break;
+        }
+        if solver.statistics().accepted_steps % options.checkpoint_steps == 0
+            && solver.time() != end_time
+        {
+            checkpoints.push((solver.time(), *solver.state()));
+        }
+    }
+    let final_state = *solver.state();
+    accumulate(&mut steps, solver.statistics());
+
+    // The solution on the segment that is currently integrated backwards
+    let segment = Rc::new(RefCell::new(DenseSolution::new()));
+    let adjoint_rhs = {
+        let model = Rc::clone(&model);
+        let segment = Rc::clone(&segment);
+        move |time: f32, y: StatePair<Vector<N>, Vector<P>>| {
+            let segment = segment.borrow();
+            let (start, end) = (segment.start_time().unwrap(), segment.end_time().unwrap());
+            // Stage times may round to just outside of the segment
+            let state = segment.evaluate(time.clamp(start.min(end), start.max(end)));
+            let (state_jacobian, parameter_jacobian) = model.jacobians(time, &state);
+            let adjoint = y.0;
+            StatePair(
+                -(state_jacobian.transpose() * adjoint),
+                -(parameter_jacobian.transpose() * adjoint),
+            )
+        }
+    };
+    let end_adjoint = StatePair(loss_gradient(final_state), Vector::zeros());
+    let mut backward = options.solver(end_time, end_adjoint, options.adjoint_tolerances);
+    let mut segment_end = end_time;
+    for &(checkpoint_time, checkpoint_state) in checkpoints.iter().rev() {
+        if status != Status::Finished {
+            break;
+        }
+        // Recompute the solution from the checkpoint
+        let mut dense = DenseSolution::new();
+        let mut solver = options.solver(checkpoint_time, checkpoint_state, options.tolerances);
+        while solver.time() != segment_end {
+            let (step_start, step_state) = (solver.time(), *solver.state());
+            if let Err(error) = solver.step(&forward_rhs, segment_end) {
+                status = Status::Failed(error);
+                break;
+            }
+            dense.push(step_interpolant(
+                solver.as_mut(),
+                &forward_rhs,
+                step_start,
+                step_state,
+            ));
+        }
+        accumulate(&mut steps, solver.statistics());
+        *segment.borrow_mut() = dense;
+
+        while status == Status::Finished && backward.time() != checkpoint_time {
+            if let Err(error) = backward.step(&adjoint_rhs, checkpoint_time) {
+                status = Status::Failed(error);
+            }
+        }
+        segment_end = checkpoint_time;
+    }
+    accumulate(&mut steps, backward.statistics());
+
+    let StatePair(initial_adjoint, gradient) = match status {
+        Status::Failed(_) => StatePair(Vector::zeros(), Vector::zeros()),
+        _ => *backward.state(),
+    };
+    AdjointGradient {
+        final_state,
+        gradient,
+        initial_adjoint,
+        checkpoints: checkpoints.len(),
+        status,
+        statistics: model.statistics(&steps),
+    }
+}
+
+/// Right-hand side with its parameters, shared by the closures passed to the
+/// solvers. Counts every evaluation of `f`, including the finite differences.
+struct Model<const N: usize, const P: usize> {
+    fun: Box<ParamFun<N, P>>,
+    parameters: Vector<P>,
+    jacobian: ParamJacobian<N, P>,
+    statistics: RefCell<Statistics>,
+}
+
+impl<const N: usize, const P: usize> Model<N, P> {
+    fn new(
+        fun: impl Fn(f32, Vector<N>, &Vector<P>) -> Vector<N> + 'static,
+        parameters: Vector<P>,
+        options: &mut SensitivityOptions<N, P>,
+    ) -> Self {
+        Self {
+            fun: Box::new(fun),
+            parameters,
+            jacobian: std::mem::take(&mut options.jacobian),
+            statistics: RefCell::new(Statistics::default()),
+        }
+    }
+
+    fn evaluate(&self, time: f32, state: Vector<N>) -> Vector<N> {
+        self.statistics.borrow_mut().evaluations += 1;
+        (self.fun)(time, state, &self.parameters)
+    }
+
+    fn jacobians(&self, time: f32, state: &Vector<N>) -> (Matrix<N, N>, Matrix<N, P>) {
+        self.jacobian.evaluate(
+            &*self.fun,
+            time,
+            state,
+            &self.parameters,
+            &mut self.statistics.borrow_mut(),
+        )
+    }
+
+    /// Evaluation counts of the model with the step counts of `steps`.
+    fn statistics(&self, steps: &Statistics) -> Statistics {
+        let mut statistics = *self.statistics.borrow();
+        accumulate(&mut statistics, steps);
+        statistics
+    }
+}
+
+/// Adds the step counts of `part` to `total`.
+fn accumulate(total: &mut Statistics, part: &Statistics) {
+    total.accepted_steps += part.accepted_steps;
+    total.rejected_steps += part.rejected_steps;
+}
@@ -0,0 +1,335 @@
+// One-call interface to the initial value problem solvers, modelled after
+// scipy's `solve_ivp`.
+
+use std::{
+    cell::{Cell, RefCell},
+    rc::Rc,
+};
+
+use super::{
+    adaptive::{Dop853, Rk23, Rk45, Tsit5},
+    bdf::Bdf,
+    control::{Statistics, Tolerances},
+    dense::DenseSolution,
+    error::SolverError,
+    event::{Event, EventLog, EventTracker},
+    ivp::SolveFun,
+    jacobian::Jacobian,
+    multistep::Abm,
+    radau::Radau,
+    rosenbrock::{Rodas4, Rodas5, Ros3p},
+    solver::{step_interpolant, OdeSolver},
+    state::OdeState,
+};
+
+/// Integration method used by [`solve_ivp`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Method {
+    Rk23,
+    #[default]
+    Rk45,
+    Tsit5,
+    Dop853,
+    Abm,
+    Radau,
+    Bdf,
+    Ros3p,
+    Rodas4,
+    Rodas5,
+}
+
+impl Method {
+    /// Whether the method is meant for stiff problems and uses a Jacobian.
+    pub fn is_implicit(&self) -> bool {
+        !matches!(
+            self,
+            Method::Rk23 | Method::Rk45 | Method::Tsit5 | Method::Dop853 | Method::Abm
+        )
+    }
+}
+
+/// Options of [`solve_ivp`], built with the `with_*` methods.
+pub struct SolveOptions<Y: OdeState> {
+    method: Method,
+    tolerances: Tolerances<Y>,
+    first_step: Option<f32>,
+    max_step: f32,
+    jacobian: Jacobian<Y>,
+    t_eval: Option<Vec<f32>>,
+    dense_output: bool,
+    events: Vec<Event<Y>>,
+}
+
+impl<Y: OdeState> Default for SolveOptions<Y> {
+    fn default() -> Self {
+        Self {
+            method: Method::default(),
+            tolerances: Tolerances::default(),
+            first_step: None,
+            max_step: f32::INFINITY,
+            jacobian: Jacobian::FiniteDifference,
+            t_eval: None,
+            dense_output: false,
+            events: Vec::new(),
+        }
+    }
+}
+
+impl<Y: OdeState + 'static> SolveOptions<Y> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_method(mut self, method: Method) -> Self {
+        self.method = method;
+        self
+    }
+
+    pub fn with_tolerances(mut self, tolerances: Tolerances<Y>) -> Self {
+        self.tolerances = tolerances;
+        self
+    }
+
+    pub fn with_first_step(mut self, step_size: f32) -> Self {
+        assert!(step_size > 0.0, "the first step size must be positive");
+        self.first_step = Some(step_size);
+        self
+    }
+
+    pub fn with_max_step(mut self, max_step: f32) -> Self {
+        assert!(max_step > 0.0, "the maximum step size must be positive");
+        self.max_step = max_step;
+        self
+    }
+
+    /// Jacobian for the implicit methods, ignored by the explicit ones.
+    pub fn with_jacobian(mut self, jacobian: Jacobian<Y>) -> Self {
+        self.jacobian = jacobian;
+        self
+    }
+
+    /// Times at which the solution is reported instead of the solver steps.
+    /// They have to lie in the integration interval, in its direction.
+    pub fn with_t_eval(mut self, t_eval: Vec<f32>) -> Self {
+        self.t_eval = Some(t_eval);
+        self
+    }
+
+    /// Also returns a [`DenseSolution`] covering the whole integration.
+    pub fn with_dense_output(mut self, dense_output: bool) -> Self {
+        self.dense_output = dense_output;
+        self
+    }
+
+    pub fn with_events(mut self, events: Vec<Event<Y>>) -> Self {
+        self.events = events;
+        self
+    }
+
+    fn solver(self, time: f32, state: Y) -> Box<dyn OdeSolver<Y>> {
+        let Self {
+            method,
+            tolerances,
+            first_step,
+            max_step,
+            jacobian,
+            ..
+        } = self;
+        // Every solver has the same builder methods, but no common trait for them
+        macro_rules! build {
+            ($solver:expr) => {{
+                let solver = $solver.with_max_step(max_step);
+                match first_step {
+                    Some(step_size) => Box::new(solver.with_first_step(step_size)),
+                    None => Box::new(solver),
+                }
+            }};
+        }
+        match method {
+            Method::Rk23 => build!(Rk23::new(time, state, tolerances)),
+            Method::Rk45 => build!(Rk45::new(time, state, tolerances)),
+            Method::Tsit5 => build!(Tsit5::new(time, state, tolerances)),
+            Method::Dop853 => build!(Dop853::new(time, state, tolerances)),
+            Method::Abm => build!(Abm::new(time, state, tolerances)),
+            Method::Radau => build!(Radau::new(time, state, tolerances).with_jacobian(jacobian)),
+            Method::Bdf => build!(Bdf::new(time, state, tolerances).with_jacobian(jacobian)),
+            Method::Ros3p => build!(Ros3p::new(time, state, tolerances).with_jacobian(jacobian)),
+            Method::Rodas4 => build!(Rodas4::new(time, state, tolerances).with_jacobian(jacobian)),
+            Method::Rodas5 => build!(Rodas5::new(time, state, tolerances).with_jacobian(jacobian)),
+        }
+    }
+}
+
+/// How an integration ended.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Status {
+    /// The end of the time span was reached.
+    Finished,
+    /// A terminal event stopped the integration.
+    Terminated,
+    Failed(SolverError),
+}
+
+/// Result of [`solve_ivp`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct Solution<Y> {
+    /// Solver steps, or the requested output times.
+    ///
+    /// Without `t_eval`, a step that ends at an event with an action appears
+    /// twice, with the state before the action and then the one after it.
+    /// A requested output time at such an event gets the state after it.
+    pub times: Vec<f32>,
+    pub states: Vec<Y>,
+    pub events: EventLog<Y>,
+    pub status: Status,
+    /// `evaluations` counts every call of the right-hand side, including the
+    /// ones needed for the output.
+    pub statistics: Statistics,
+    pub dense: Option<DenseSolution<Y>>,
+}
+
+impl<Y> Solution<Y> {
+    /// Whether the integration finished or was stopped by a terminal event.
+    pub fn success(&self) -> bool {
+        !matches!(self.status, Status::Failed(_))
+    }
+
+    /// Time and state where the integration ended.
+    pub fn last(&self) -> Option<(f32, &Y)> {
+        Some((*self.times.last()?, self.states.last()?))
+    }
+}
+
+/// Integrates `y' = fun(t, y)` from `y(t_span.0) = initial_state` to
+/// `t_span.1`, which may lie before the start.
+///
+/// A failing solver does not panic: the solution up to the failure is
+/// returned with [`Status::Failed`].
+pub fn solve_ivp<Y: OdeState + 'static>(
+    fun: impl FnMut(f32, Y) -> Y + 'static,
+    t_span: (f32, f32),
+    initial_state: Y,
+    mut options: SolveOptions<Y>,
+) -> Solution<Y> {
+    let (start_time, end_time) = t_span;
+    let direction = (end_time - start_time).signum();
+    let t_eval = options.t_eval.take();
+    if let Some(t_eval) = &t_eval {
+        for &time in t_eval {
+            assert!(
+                direction * (time - start_time) >= 0.0 && direction * (end_time - time) >= 0.0,
+                "t_eval value {} is outside of the time span",
+                time
+            );
+        }
+        assert!(
+            t_eval.windows(2).all(|w| direction * (w[1] - w[0]) >= 0.0),
+            "t_eval has to be sorted in the direction of integration"
+        );
+    }
+    let dense_output = options.dense_output;
+    let events = std::mem::take(&mut options.events);
+
+    // The solvers take `Fn`, so the closure is called through a `RefCell`
+    let evaluations = Rc::new(Cell::new(0));
+    let counter = Rc::clone(&evaluations);
+    let fun = RefCell::new(fun);
+    let counted = move |time: f32, state: Y| {
+        counter.set(counter.get() + 1);
+        (fun.borrow_mut())(time, state)
+    };
+    let fun: &SolveFun<Y> = &counted;
+
+    let mut solver = options.solver(start_time, initial_state.clone());
+    let mut times = Vec::new();
+    let mut states = Vec::new();
+    let mut next_output = 0;
+    match &t_eval {
+        Some(t_eval) => {
+            while next_output < t_eval.len() && t_eval[next_output] == start_time {
+                times.push(start_time);
+                states.push(initial_state.clone());
+                next_output += 1;
+            }
+        }
+        None => {
+            times.push(start_time);
+            states.push(initial_state.clone());
+        }
+    }
+
+    let mut log = EventLog::default();
+    let mut tracker = EventTracker::new(&events, start_time, initial_state);
+    let mut dense = dense_output.then(DenseSolution::new);
+    let mut status = Status::Finished;
+    while solver.time() != end_time {
+        let (step_start, step_state) = (solver.time(), solver.state().clone());
+        if let Err(error) = solver.step(fun, end_time) {
+            status = Status::Failed(error);
+            break;
+        }
+        let mut interpolant = None;
+        let logged = log.occurrences.len();
+        let terminated = tracker.after_step(
+            solver.as_mut(),
+            fun,
+            step_start,
+            step_state.clone(),
+            &mut interpolant,
+            &mut log,
+        );
+        // An event may have cut the step short
+        let step_end = solver.time();
+        // Only the last event of a step can have applied an action
+        let before_action = log.occurrences[logged..]
+            .last()
+            .filter(|occurrence| !terminated && events[occurrence.event].has_action())
+            .map(|occurrence| occurrence.state.clone());
+
+        if t_eval.is_some() || dense_output {
+            let interpolant = interpolant.get_or_insert_with(|| {
+                step_interpolant(solver.as_mut(), fun, step_start, step_state)
+            });
+            if let Some(t_eval) = &t_eval {
+                while next_output < t_eval.len()
+                    && direction * (step_end - t_eval[next_output]) >= 0.0
+                {
+                    let time = t_eval[next_output];
+                    times.push(time);
+                    states.push(if time == step_end {
+                        solver.state().clone()
+                    } else {
+                        interpolant.evaluate(time)
+                    });
+                    next_output += 1;
+                }
+            }
+            if let Some(dense) = &mut dense {
+                dense.push(interpolant.truncated(step_end));
+            }
+        }
+        if t_eval.is_none() {
+            if let Some(state) = before_action {
+                times.push(step_end);
+                states.push(state);
+            }
+            times.push(step_end);
+            states.push(solver.state().clone());
+        }
+        if terminated {
+            status = Status::Terminated;
+            break;
+        }
+    }
+
+    let mut statistics = *solver.statistics();
+    statistics.evaluations = evaluations.get();
+    Solution {
+        times,
+        states,
+        events: log,
+        status,
+        statistics,
+        dense,
+    }
+}
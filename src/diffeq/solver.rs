@@ -0,0 +1,49 @@
+// Interface shared by the adaptive solvers, so that drivers such as
+// `solve_ivp` can work with any of them.
+
+use super::{
+    control::Statistics, dense::Interpolant, error::SolverError, ivp::SolveFun, state::OdeState,
+};
+
+pub trait OdeSolver<Y: OdeState> {
+    fn time(&self) -> f32;
+
+    fn state(&self) -> &Y;
+
+    fn statistics(&self) -> &Statistics;
+
+    /// Takes one accepted step towards `time_bound` without stepping past it.
+    fn step(&mut self, fun: &SolveFun<Y>, time_bound: f32) -> Result<&Y, SolverError>;
+
+    /// Continues the integration from `time` and `state`, discarding the
+    /// history of previous steps.
+    fn restart(&mut self, time: f32, state: Y);
+
+    /// Interpolant of the last accepted step for methods with dense output.
+    fn interpolant(&mut self, _fun: &SolveFun<Y>) -> Option<Interpolant<Y>> {
+        None
+    }
+}
+
+/// Interpolant of the step `solver` just took from `start_time` and
+/// `start_state`, falling back to a cubic Hermite polynomial that costs two
+/// evaluations of `fun` for methods without dense output.
+pub fn step_interpolant<Y: OdeState>(
+    solver: &mut (impl OdeSolver<Y> + ?Sized),
+    fun: &SolveFun<Y>,
+    start_time: f32,
+    start_state: Y,
+) -> Interpolant<Y> {
+    if let Some(interpolant) = solver.interpolant(fun) {
+        return interpolant;
+    }
+    let (end_time, end_state) = (solver.time(), solver.state().clone());
+    Interpolant::hermite(
+        start_time,
+        start_state.clone(),
+        fun(start_time, start_state),
+        end_time,
+        end_state.clone(),
+        fun(end_time, end_state),
+    )
+}
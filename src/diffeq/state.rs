@@ -0,0 +1,300 @@
+// States the solvers can integrate.
+//
+// The explicit solvers only add states and scale them by step sizes and
+// weights, so they work on any real vector space. Error control looks at
+// the individual components, and the implicit solvers build dense `f32`
+// Jacobians from them. Higher order equations stack their derivatives in a
+// `StateArray`. The problem types that are defined through matrices acting on
+// the state, SDEs with their noise matrix, DAEs, boundary value problems, the
+// sensitivity analysis and the parameter fits built on it, are still written
+// for `Vector<N>`, as their documentation notes.
+//
+// The scalar type `R` is `f32` everywhere except in the fixed step
+// `ExplicitRungeKutta` methods, e.g. `RungeKutta4<Vector<2, f64>, f64>`, and
+// the streaming and interpolation built on them. The adaptive, multistep,
+// symplectic, Nystrom and implicit solvers only integrate `f32` states.
+
+use std::ops::{Add, Mul, Sub};
+
+use crate::{
+    clifford::cliff_3d::Clifford,
+    linalg::{
+        ndarray::{Matrix, NdArray},
+        real::Real,
+    },
+};
+
+/// Element of a real vector space with a fixed number of components.
+pub trait OdeState<R: Real = f32>:
+    Clone + Add<Output = Self> + Sub<Output = Self> + Mul<R, Output = Self>
+{
+    /// State with every component equal to `value`. Dynamically sized states
+    /// return a single component, which stands for all components wherever
+    /// it is used as tolerance.
+    fn splat(value: R) -> Self;
+
+    /// Number of scalar components.
+    fn dimension(&self) -> usize;
+
+    fn component(&self, index: usize) -> R;
+
+    /// State of the same shape with the components `fun(i, x_i)`.
+    fn map_components(&self, fun: impl FnMut(usize, R) -> R) -> Self;
+
+    /// Zero of the same shape.
+    fn zero_like(&self) -> Self {
+        self.map_components(|_, _| R::ZERO)
+    }
+
+    /// Root mean square of `x_i / scale_i`, the norm used for error control.
+    fn error_norm(&self, scale: &Self) -> R {
+        let dimension = self.dimension();
+        if dimension == 0 {
+            return R::ZERO;
+        }
+        let sum = (0..dimension).fold(R::ZERO, |sum, i| {
+            sum + (self.component(i) / scale.component(i)).powi(2)
+        });
+        (sum / R::from_f64(dimension as f64)).sqrt()
+    }
+}
+
+impl<R: Real> OdeState<R> for R {
+    fn splat(value: R) -> Self {
+        value
+    }
+
+    fn dimension(&self) -> usize {
+        1
+    }
+
+    fn component(&self, _index: usize) -> R {
+        *self
+    }
+
+    fn map_components(&self, mut fun: impl FnMut(usize, R) -> R) -> Self {
+        fun(0, *self)
+    }
+}
+
+/// Components in row-major order, which covers `Vector<N>`.
+impl<const M: usize, const N: usize, R: Real> OdeState<R> for Matrix<M, N, R> {
+    fn splat(value: R) -> Self {
+        Matrix::from_fn(|_, _| value)
+    }
+
+    fn dimension(&self) -> usize {
+        M * N
+    }
+
+    fn component(&self, index: usize) -> R {
+        self.data[index / N][index % N]
+    }
+
+    fn map_components(&self, mut fun: impl FnMut(usize, R) -> R) -> Self {
+        let mut result = *self;
+        for (i, component) in result.data.as_flattened_mut().iter_mut().enumerate() {
+            *component = fun(i, *component);
+        }
+        result
+    }
+
+    fn zero_like(&self) -> Self {
+        Matrix::zeros()
+    }
+}
+
+macro_rules! impl_glam_state {
+    ($($vector:ty),*) => {
+        $(
+            impl OdeState for $vector {
+                fn splat(value: f32) -> Self {
+                    <$vector>::splat(value)
+                }
+
+                fn dimension(&self) -> usize {
+                    self.to_array().len()
+                }
+
+                fn component(&self, index: usize) -> f32 {
+                    self[index]
+                }
+
+                fn map_components(&self, mut fun: impl FnMut(usize, f32) -> f32) -> Self {
+                    let mut components = self.to_array();
+                    for (i, component) in components.iter_mut().enumerate() {
+                        *component = fun(i, *component);
+                    }
+                    <$vector>::from_array(components)
+                }
+            }
+        )*
+    };
+}
+
+impl_glam_state!(glam::Vec2, glam::Vec3, glam::Vec4);
+
+impl OdeState for NdArray {
+    fn splat(value: f32) -> Self {
+        NdArray::scalar(value)
+    }
+
+    fn dimension(&self) -> usize {
+        self.len()
+    }
+
+    fn component(&self, index: usize) -> f32 {
+        self.data()[index]
+    }
+
+    fn map_components(&self, mut fun: impl FnMut(usize, f32) -> f32) -> Self {
+        let data = self
+            .data()
+            .iter()
+            .enumerate()
+            .map(|(i, &x)| fun(i, x))
+            .collect();
+        NdArray::from_vec(self.shape(), data)
+    }
+}
+
+impl OdeState for Clifford {
+    fn splat(value: f32) -> Self {
+        Clifford::new([value; 8])
+    }
+
+    fn dimension(&self) -> usize {
+        8
+    }
+
+    fn component(&self, index: usize) -> f32 {
+        self.factors()[index]
+    }
+
+    fn map_components(&self, mut fun: impl FnMut(usize, f32) -> f32) -> Self {
+        let mut factors = *self.factors();
+        for (i, factor) in factors.iter_mut().enumerate() {
+            *factor = fun(i, *factor);
+        }
+        Clifford::new(factors)
+    }
+}
+
+/// Two states integrated together, such as a solution and its sensitivities.
+/// The components of the first come first.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct StatePair<A, B>(pub A, pub B);
+
+impl<A: Add<Output = A>, B: Add<Output = B>> Add for StatePair<A, B> {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self::Output {
+        StatePair(self.0 + rhs.0, self.1 + rhs.1)
+    }
+}
+
+impl<A: Sub<Output = A>, B: Sub<Output = B>> Sub for StatePair<A, B> {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self::Output {
+        StatePair(self.0 - rhs.0, self.1 - rhs.1)
+    }
+}
+
+impl<R: Real, A: Mul<R, Output = A>, B: Mul<R, Output = B>> Mul<R> for StatePair<A, B> {
+    type Output = Self;
+    fn mul(self, rhs: R) -> Self::Output {
+        StatePair(self.0 * rhs, self.1 * rhs)
+    }
+}
+
+impl<R: Real, A: OdeState<R>, B: OdeState<R>> OdeState<R> for StatePair<A, B> {
+    fn splat(value: R) -> Self {
+        StatePair(A::splat(value), B::splat(value))
+    }
+
+    fn dimension(&self) -> usize {
+        self.0.dimension() + self.1.dimension()
+    }
+
+    fn component(&self, index: usize) -> R {
+        let first = self.0.dimension();
+        if index < first {
+            self.0.component(index)
+        } else {
+            self.1.component(index - first)
+        }
+    }
+
+    fn map_components(&self, mut fun: impl FnMut(usize, R) -> R) -> Self {
+        let first = self.0.dimension();
+        StatePair(
+            self.0.map_components(&mut fun),
+            self.1.map_components(|i, x| fun(first + i, x)),
+        )
+    }
+
+    fn zero_like(&self) -> Self {
+        StatePair(self.0.zero_like(), self.1.zero_like())
+    }
+}
+
+/// `K` states integrated together, such as the derivatives of a higher order
+/// equation. The components of the first come first.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct StateArray<Y, const K: usize>(pub [Y; K]);
+
+impl<Y: Add<Output = Y>, const K: usize> Add for StateArray<Y, K> {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self::Output {
+        let mut rhs = rhs.0.into_iter();
+        StateArray(self.0.map(|y| y + rhs.next().unwrap()))
+    }
+}
+
+impl<Y: Sub<Output = Y>, const K: usize> Sub for StateArray<Y, K> {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self::Output {
+        let mut rhs = rhs.0.into_iter();
+        StateArray(self.0.map(|y| y - rhs.next().unwrap()))
+    }
+}
+
+impl<R: Real, Y: Mul<R, Output = Y>, const K: usize> Mul<R> for StateArray<Y, K> {
+    type Output = Self;
+    fn mul(self, rhs: R) -> Self::Output {
+        StateArray(self.0.map(|y| y * rhs))
+    }
+}
+
+impl<R: Real, Y: OdeState<R>, const K: usize> OdeState<R> for StateArray<Y, K> {
+    fn splat(value: R) -> Self {
+        StateArray(std::array::from_fn(|_| Y::splat(value)))
+    }
+
+    fn dimension(&self) -> usize {
+        self.0.iter().map(Y::dimension).sum()
+    }
+
+    fn component(&self, index: usize) -> R {
+        let mut remaining = index;
+        for y in &self.0 {
+            if remaining < y.dimension() {
+                return y.component(remaining);
+            }
+            remaining -= y.dimension();
+        }
+        panic!("component {} is out of range", index)
+    }
+
+    fn map_components(&self, mut fun: impl FnMut(usize, R) -> R) -> Self {
+        let mut offset = 0;
+        StateArray(self.0.each_ref().map(|y| {
+            let mapped = y.map_components(|i, x| fun(offset + i, x));
+            offset += y.dimension();
+            mapped
+        }))
+    }
+
+    fn zero_like(&self) -> Self {
+        StateArray(self.0.each_ref().map(Y::zero_like))
+    }
+}
@@ -0,0 +1,132 @@
+// Lazy integration: the solvers as iterators over `(time, state)`, which
+// advance only as far as the consumer asks, e.g.
+//
+//     for (t, y) in solver.iter(&fun, 0.1).take_while(|(t, _)| *t < 10.0) { ... }
+
+use std::{
+    marker::PhantomData,
+    ops::{Deref, DerefMut},
+};
+
+use crate::linalg::real::Real;
+
+use super::{error::SolverError, ivp::SolveFun, solver::OdeSolver, state::OdeState};
+
+/// Solver that can advance its solution by a given time, in `f32` unless
+/// the solver works in another precision `R`.
+pub trait Stepper<Y: OdeState<R>, R: Real = f32> {
+    /// Current time and state.
+    fn current(&self) -> (R, Y);
+
+    /// Advances the solution by `delta_time`, which is one step of a fixed
+    /// step method and the output interval of an adaptive one.
+    fn advance(&mut self, fun: &SolveFun<Y, R>, delta_time: R) -> Result<(), SolverError>;
+
+    /// Iterator over the current time and state, followed by the solution
+    /// every `delta_time`. It ends only when the solver fails.
+    fn iter<'a>(
+        &'a mut self,
+        fun: &'a SolveFun<Y, R>,
+        delta_time: R,
+    ) -> Steps<&'a mut Self, &'a SolveFun<Y, R>, Y, R>
+    where
+        Self: Sized,
+    {
+        Steps::new(self, fun, delta_time)
+    }
+
+    /// Like [`Stepper::iter`], but owning the solver and the right-hand
+    /// side, so that the iterator can outlive them, e.g. in a plot.
+    fn into_steps(
+        self,
+        fun: impl Fn(R, Y) -> Y + 'static,
+        delta_time: R,
+    ) -> Steps<Box<Self>, Box<SolveFun<Y, R>>, Y, R>
+    where
+        Self: Sized,
+    {
+        Steps::new(Box::new(self), Box::new(fun), delta_time)
+    }
+}
+
+/// [`Stepper::advance`] of the adaptive solvers, which step to the output
+/// times exactly, so that `delta_time` also bounds their step size.
+pub(crate) fn advance_adaptive<Y: OdeState>(
+    solver: &mut impl OdeSolver<Y>,
+    fun: &SolveFun<Y>,
+    delta_time: f32,
+) -> Result<(), SolverError> {
+    let end_time = solver.time() + delta_time;
+    while solver.time() != end_time {
+        solver.step(fun, end_time)?;
+    }
+    Ok(())
+}
+
+/// Iterator returned by [`Stepper::iter`] and [`Stepper::into_steps`].
+pub struct Steps<S, F, Y, R = f32> {
+    solver: S,
+    fun: F,
+    delta_time: R,
+    started: bool,
+    error: Option<SolverError>,
+    state: PhantomData<(Y, R)>,
+}
+
+impl<S, F, Y, R> Steps<S, F, Y, R>
+where
+    Y: OdeState<R>,
+    R: Real,
+    S: DerefMut,
+    S::Target: Stepper<Y, R>,
+    F: Deref<Target = SolveFun<Y, R>>,
+{
+    pub fn new(solver: S, fun: F, delta_time: R) -> Self {
+        assert!(delta_time != R::ZERO, "the time increment must not be zero");
+        Self {
+            solver,
+            fun,
+            delta_time,
+            started: false,
+            error: None,
+            state: PhantomData,
+        }
+    }
+
+    /// The failure that ended the iteration, if any.
+    pub fn error(&self) -> Option<SolverError> {
+        self.error
+    }
+
+    pub fn solver(&self) -> &S::Target {
+        &self.solver
+    }
+}
+
+impl<S, F, Y, R> Iterator for Steps<S, F, Y, R>
+where
+    Y: OdeState<R>,
+    R: Real,
+    S: DerefMut,
+    S::Target: Stepper<Y, R>,
+    F: Deref<Target = SolveFun<Y, R>>,
+{
+    type Item = (R, Y);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if !self.started {
+            self.started = true;
+            return Some(self.solver.current());
+        }
+        if self.error.is_some() {
+            return None;
+        }
+        match self.solver.advance(&*self.fun, self.delta_time) {
+            Ok(()) => Some(self.solver.current()),
+            Err(error) => {
+                self.error = Some(error);
+                None
+            }
+        }
+    }
+}
@@ -0,0 +1,210 @@
+// Symplectic splitting methods for separable Hamiltonians H(q, p) = T(p) + V(q).
+//
+// A scheme alternates drifts and kicks,
+//
+//     q = q + c_i h dT/dp(p)
+//     p = p + d_i h F(t_i, q)    with the force F = -dV/dq
+//
+// for i = 0 .. s - 1, where `t_i` is the time reached by the drifts so far.
+// Every such composition of exact flows is symplectic, so the energy error
+// stays bounded over long integrations instead of drifting.
+
+// Coefficients are kept with the digits they were published with
+#![allow(clippy::excessive_precision)]
+
+use std::marker::PhantomData;
+
+use super::state::OdeState;
+
+/// Force `F(t, q) = -dV/dq` acting on the momenta.
+pub type ForceFun<Y> = dyn Fn(f32, Y) -> Y;
+/// Velocity `dq/dt = dT/dp` of the positions, `p / m` for particles.
+pub type VelocityFun<Y> = dyn Fn(Y) -> Y;
+
+pub trait SplittingScheme {
+    const ORDER: usize;
+    /// Drift coefficients `c_i`.
+    const DRIFT: &'static [f64];
+    /// Kick coefficients `d_i`.
+    const KICK: &'static [f64];
+
+    fn stages() -> usize {
+        Self::DRIFT.len()
+    }
+}
+
+/// Symplectic Euler: a full drift followed by a full kick. First order and
+/// not time-reversible.
+pub struct SymplecticEulerScheme;
+
+impl SplittingScheme for SymplecticEulerScheme {
+    const ORDER: usize = 1;
+    const DRIFT: &'static [f64] = &[1.0];
+    const KICK: &'static [f64] = &[1.0];
+}
+
+/// Velocity Verlet (kick-drift-kick leapfrog), second order and
+/// time-reversible with one force evaluation per step.
+pub struct VerletScheme;
+
+impl SplittingScheme for VerletScheme {
+    const ORDER: usize = 2;
+    const DRIFT: &'static [f64] = &[0.0, 1.0];
+    const KICK: &'static [f64] = &[0.5, 0.5];
+}
+
+// Triple jump weights of Yoshida's fourth order composition
+const YOSHIDA4_W1: f64 = 1.3512071919596578;
+const YOSHIDA4_W0: f64 = -1.7024143839193155;
+
+/// Fourth order method of Forest and Ruth in its position form, which
+/// starts and ends with a drift.
+pub struct ForestRuthScheme;
+
+impl SplittingScheme for ForestRuthScheme {
+    const ORDER: usize = 4;
+    const DRIFT: &'static [f64] = &[
+        YOSHIDA4_W1 / 2.0,
+        (YOSHIDA4_W1 + YOSHIDA4_W0) / 2.0,
+        (YOSHIDA4_W0 + YOSHIDA4_W1) / 2.0,
+        YOSHIDA4_W1 / 2.0,
+    ];
+    const KICK: &'static [f64] = &[YOSHIDA4_W1, YOSHIDA4_W0, YOSHIDA4_W1, 0.0];
+}
+
+/// Yoshida's fourth order composition of three velocity Verlet steps.
+pub struct Yoshida4Scheme;
+
+impl SplittingScheme for Yoshida4Scheme {
+    const ORDER: usize = 4;
+    const DRIFT: &'static [f64] = &[0.0, YOSHIDA4_W1, YOSHIDA4_W0, YOSHIDA4_W1];
+    const KICK: &'static [f64] = &[
+        YOSHIDA4_W1 / 2.0,
+        (YOSHIDA4_W1 + YOSHIDA4_W0) / 2.0,
+        (YOSHIDA4_W0 + YOSHIDA4_W1) / 2.0,
+        YOSHIDA4_W1 / 2.0,
+    ];
+}
+
+// Solution A of Yoshida, "Construction of higher order symplectic
+// integrators" (1990)
+const YOSHIDA6_W1: f64 = -1.17767998417887;
+const YOSHIDA6_W2: f64 = 0.235573213359357;
+const YOSHIDA6_W3: f64 = 0.784513610477560;
+const YOSHIDA6_W0: f64 = 1.0 - 2.0 * (YOSHIDA6_W1 + YOSHIDA6_W2 + YOSHIDA6_W3);
+
+/// Yoshida's sixth order composition of seven velocity Verlet steps.
+pub struct Yoshida6Scheme;
+
+impl SplittingScheme for Yoshida6Scheme {
+    const ORDER: usize = 6;
+    const DRIFT: &'static [f64] = &[
+        0.0,
+        YOSHIDA6_W3,
+        YOSHIDA6_W2,
+        YOSHIDA6_W1,
+        YOSHIDA6_W0,
+        YOSHIDA6_W1,
+        YOSHIDA6_W2,
+        YOSHIDA6_W3,
+    ];
+    const KICK: &'static [f64] = &[
+        YOSHIDA6_W3 / 2.0,
+        (YOSHIDA6_W3 + YOSHIDA6_W2) / 2.0,
+        (YOSHIDA6_W2 + YOSHIDA6_W1) / 2.0,
+        (YOSHIDA6_W1 + YOSHIDA6_W0) / 2.0,
+        (YOSHIDA6_W0 + YOSHIDA6_W1) / 2.0,
+        (YOSHIDA6_W1 + YOSHIDA6_W2) / 2.0,
+        (YOSHIDA6_W2 + YOSHIDA6_W3) / 2.0,
+        YOSHIDA6_W3 / 2.0,
+    ];
+}
+
+/// Fixed step integrator for the splitting scheme `S` on positions `q` and
+/// momenta `p`.
+pub struct SymplecticIntegrator<S: SplittingScheme, Y: OdeState> {
+    time: f32,
+    position: Y,
+    momentum: Y,
+    /// Force at the current time and position, reused by schemes that end
+    /// with a kick and start with one.
+    force: Option<Y>,
+    force_evaluations: usize,
+    velocity_evaluations: usize,
+    scheme: PhantomData<S>,
+}
+
+pub type SymplecticEuler<Y> = SymplecticIntegrator<SymplecticEulerScheme, Y>;
+pub type VelocityVerlet<Y> = SymplecticIntegrator<VerletScheme, Y>;
+pub type Leapfrog<Y> = VelocityVerlet<Y>;
+pub type ForestRuth<Y> = SymplecticIntegrator<ForestRuthScheme, Y>;
+pub type Yoshida4<Y> = SymplecticIntegrator<Yoshida4Scheme, Y>;
+pub type Yoshida6<Y> = SymplecticIntegrator<Yoshida6Scheme, Y>;
+
+impl<S: SplittingScheme, Y: OdeState> SymplecticIntegrator<S, Y> {
+    pub fn new(time: f32, position: Y, momentum: Y) -> Self {
+        Self {
+            time,
+            position,
+            momentum,
+            force: None,
+            force_evaluations: 0,
+            velocity_evaluations: 0,
+            scheme: PhantomData,
+        }
+    }
+
+    pub fn time(&self) -> f32 {
+        self.time
+    }
+
+    pub fn position(&self) -> &Y {
+        &self.position
+    }
+
+    pub fn momentum(&self) -> &Y {
+        &self.momentum
+    }
+
+    pub fn force_evaluations(&self) -> usize {
+        self.force_evaluations
+    }
+
+    pub fn velocity_evaluations(&self) -> usize {
+        self.velocity_evaluations
+    }
+
+    /// Advances positions and momenta by `delta_time`, which may be negative.
+    pub fn next_step(
+        &mut self,
+        force: &ForceFun<Y>,
+        velocity: &VelocityFun<Y>,
+        delta_time: f32,
+    ) -> (&Y, &Y) {
+        let mut time = self.time;
+        let mut drifted = 0.0;
+        for (c_i, d_i) in S::DRIFT.iter().zip(S::KICK) {
+            if *c_i != 0.0 {
+                self.velocity_evaluations += 1;
+                let drift = velocity(self.momentum.clone()) * (*c_i as f32 * delta_time);
+                self.position = self.position.clone() + drift;
+                drifted += c_i;
+                time = self.time + delta_time * drifted as f32;
+                self.force = None;
+            }
+            if *d_i != 0.0 {
+                let force = match self.force.take() {
+                    Some(force) => force,
+                    None => {
+                        self.force_evaluations += 1;
+                        force(time, self.position.clone())
+                    }
+                };
+                self.momentum = self.momentum.clone() + force.clone() * (*d_i as f32 * delta_time);
+                self.force = Some(force);
+            }
+        }
+        self.time += delta_time;
+        (&self.position, &self.momentum)
+    }
+}
@@ -0,0 +1,584 @@
+// Butcher tableaux of explicit Runge-Kutta methods.
+//
+// A method with `s` stages computes
+//
+//     k_i = f(t + c_i h, y + h * sum_{j < i} a_ij k_j)
+//     y_next = y + h * sum_i b_i k_i
+//
+// Only the strictly lower triangle of `A` is stored: row `i` holds the `i`
+// coefficients `a_i0 .. a_i(i-1)`. The coefficients are given in double
+// precision and rounded when a solver uses them.
+
+// Coefficients are kept with the digits they were published with
+#![allow(clippy::excessive_precision)]
+
+pub trait ButcherTableau {
+    /// Order of consistency of the method.
+    const ORDER: usize;
+    const A: &'static [&'static [f64]];
+    const B: &'static [f64];
+    const C: &'static [f64];
+
+    fn stages() -> usize {
+        Self::B.len()
+    }
+}
+
+/// Forward Euler, the one-stage method of order one.
+pub struct Euler;
+
+impl ButcherTableau for Euler {
+    const ORDER: usize = 1;
+    const A: &'static [&'static [f64]] = &[&[]];
+    const B: &'static [f64] = &[1.0];
+    const C: &'static [f64] = &[0.0];
+}
+
+/// Heun's method (explicit trapezoidal rule) of order two.
+pub struct Heun;
+
+impl ButcherTableau for Heun {
+    const ORDER: usize = 2;
+    const A: &'static [&'static [f64]] = &[&[], &[1.0]];
+    const B: &'static [f64] = &[0.5, 0.5];
+    const C: &'static [f64] = &[0.0, 1.0];
+}
+
+/// Explicit midpoint method of order two.
+pub struct Midpoint;
+
+impl ButcherTableau for Midpoint {
+    const ORDER: usize = 2;
+    const A: &'static [&'static [f64]] = &[&[], &[0.5]];
+    const B: &'static [f64] = &[0.0, 1.0];
+    const C: &'static [f64] = &[0.0, 0.5];
+}
+
+/// Ralston's second order method, which minimizes the truncation error bound.
+pub struct Ralston;
+
+impl ButcherTableau for Ralston {
+    const ORDER: usize = 2;
+    const A: &'static [&'static [f64]] = &[&[], &[2.0 / 3.0]];
+    const B: &'static [f64] = &[0.25, 0.75];
+    const C: &'static [f64] = &[0.0, 2.0 / 3.0];
+}
+
+/// The classic fourth order Runge-Kutta method.
+pub struct ClassicRk4;
+
+impl ButcherTableau for ClassicRk4 {
+    const ORDER: usize = 4;
+    const A: &'static [&'static [f64]] = &[&[], &[0.5], &[0.0, 0.5], &[0.0, 0.0, 1.0]];
+    const B: &'static [f64] = &[1.0 / 6.0, 1.0 / 3.0, 1.0 / 3.0, 1.0 / 6.0];
+    const C: &'static [f64] = &[0.0, 0.5, 0.5, 1.0];
+}
+
+/// Kutta's 3/8 rule, a fourth order method with a smaller error constant than [`ClassicRk4`].
+pub struct ThreeEighthsRule;
+
+impl ButcherTableau for ThreeEighthsRule {
+    const ORDER: usize = 4;
+    const A: &'static [&'static [f64]] =
+        &[&[], &[1.0 / 3.0], &[-1.0 / 3.0, 1.0], &[1.0, -1.0, 1.0]];
+    const B: &'static [f64] = &[1.0 / 8.0, 3.0 / 8.0, 3.0 / 8.0, 1.0 / 8.0];
+    const C: &'static [f64] = &[0.0, 1.0 / 3.0, 2.0 / 3.0, 1.0];
+}
+
+/// Tableau with an embedded solution of lower order, used to estimate the local error.
+///
+/// Following scipy, the error weights `E = B - B_hat` have one more entry
+/// than there are stages: the last one multiplies `f(t + h, y_next)`, which
+/// adaptive solvers evaluate anyway and reuse as the first stage of the next step.
+pub trait EmbeddedTableau: ButcherTableau {
+    /// Order of the error estimate, which determines how the step size is adapted.
+    const ERROR_ORDER: usize;
+    const E: &'static [f64];
+    /// Weights of an additional third order estimate that is combined with `E` (DOP853).
+    const E3: &'static [f64] = &[];
+    /// Continuous extension `b_i(theta) = sum_j P[i][j] theta^(j + 1)`, one row per
+    /// stage followed by `f(t + h, y_next)`. Methods without one are
+    /// interpolated with cubic Hermite polynomials.
+    const P: &'static [&'static [f64]] = &[];
+    /// Stages that are only evaluated for the DOP853 interpolant. Row `i` holds
+    /// the coefficients of all previous stages, `f(t + h, y_next)` included.
+    const EXTRA_A: &'static [&'static [f64]] = &[];
+    const EXTRA_C: &'static [f64] = &[];
+    /// Coefficients of the DOP853 interpolant, see [`super::dense`].
+    const D: &'static [&'static [f64]] = &[];
+}
+
+/// Bogacki-Shampine 3(2) pair, scipy's `RK23`.
+pub struct BogackiShampine;
+
+impl ButcherTableau for BogackiShampine {
+    const ORDER: usize = 3;
+    const A: &'static [&'static [f64]] = &[&[], &[1.0 / 2.0], &[0.0, 3.0 / 4.0]];
+    const B: &'static [f64] = &[2.0 / 9.0, 1.0 / 3.0, 4.0 / 9.0];
+    const C: &'static [f64] = &[0.0, 1.0 / 2.0, 3.0 / 4.0];
+}
+
+impl EmbeddedTableau for BogackiShampine {
+    const ERROR_ORDER: usize = 2;
+    const E: &'static [f64] = &[5.0 / 72.0, -1.0 / 12.0, -1.0 / 9.0, 1.0 / 8.0];
+    const P: &'static [&'static [f64]] = &[
+        &[1.0, -4.0 / 3.0, 5.0 / 9.0],
+        &[0.0, 1.0, -2.0 / 3.0],
+        &[0.0, 4.0 / 3.0, -8.0 / 9.0],
+        &[0.0, -1.0, 1.0],
+    ];
+}
+
+/// Dormand-Prince 5(4) pair, scipy's `RK45` and MATLAB's `ode45`.
+pub struct DormandPrince;
+
+impl ButcherTableau for DormandPrince {
+    const ORDER: usize = 5;
+    const A: &'static [&'static [f64]] = &[
+        &[],
+        &[1.0 / 5.0],
+        &[3.0 / 40.0, 9.0 / 40.0],
+        &[44.0 / 45.0, -56.0 / 15.0, 32.0 / 9.0],
+        &[
+            19372.0 / 6561.0,
+            -25360.0 / 2187.0,
+            64448.0 / 6561.0,
+            -212.0 / 729.0,
+        ],
+        &[
+            9017.0 / 3168.0,
+            -355.0 / 33.0,
+            46732.0 / 5247.0,
+            49.0 / 176.0,
+            -5103.0 / 18656.0,
+        ],
+    ];
+    const B: &'static [f64] = &[
+        35.0 / 384.0,
+        0.0,
+        500.0 / 1113.0,
+        125.0 / 192.0,
+        -2187.0 / 6784.0,
+        11.0 / 84.0,
+    ];
+    const C: &'static [f64] = &[0.0, 1.0 / 5.0, 3.0 / 10.0, 4.0 / 5.0, 8.0 / 9.0, 1.0];
+}
+
+impl EmbeddedTableau for DormandPrince {
+    const ERROR_ORDER: usize = 4;
+    const E: &'static [f64] = &[
+        -71.0 / 57600.0,
+        0.0,
+        71.0 / 16695.0,
+        -71.0 / 1920.0,
+        17253.0 / 339200.0,
+        -22.0 / 525.0,
+        1.0 / 40.0,
+    ];
+    // Shampine's fourth order interpolant, as in MATLAB's `ode45` and scipy's `RK45`
+    const P: &'static [&'static [f64]] = &[
+        &[
+            1.0,
+            -8048581381.0 / 2820520608.0,
+            8663915743.0 / 2820520608.0,
+            -12715105075.0 / 11282082432.0,
+        ],
+        &[0.0, 0.0, 0.0, 0.0],
+        &[
+            0.0,
+            131558114200.0 / 32700410799.0,
+            -68118460800.0 / 10900136933.0,
+            87487479700.0 / 32700410799.0,
+        ],
+        &[
+            0.0,
+            -1754552775.0 / 470086768.0,
+            14199869525.0 / 1410260304.0,
+            -10690763975.0 / 1880347072.0,
+        ],
+        &[
+            0.0,
+            127303824393.0 / 49829197408.0,
+            -318862633887.0 / 49829197408.0,
+            701980252875.0 / 199316789632.0,
+        ],
+        &[
+            0.0,
+            -282668133.0 / 205662961.0,
+            2019193451.0 / 616988883.0,
+            -1453857185.0 / 822651844.0,
+        ],
+        &[
+            0.0,
+            40617522.0 / 29380423.0,
+            -110615467.0 / 29380423.0,
+            69997945.0 / 29380423.0,
+        ],
+    ];
+}
+
+/// Tsitouras 5(4) pair, which has smaller error constants than Dormand-Prince.
+pub struct Tsitouras;
+
+impl ButcherTableau for Tsitouras {
+    const ORDER: usize = 5;
+    const A: &'static [&'static [f64]] = &[
+        &[],
+        &[0.161],
+        &[-0.008480655492356989, 0.335480655492357],
+        &[2.897153057105493, -6.359448489975075, 4.3622954328695815],
+        &[
+            5.325864828439257,
+            -11.748883564062828,
+            7.4955393428898365,
+            -0.09249506636175525,
+        ],
+        &[
+            5.86145544294642,
+            -12.92096931784711,
+            8.159367898576159,
+            -0.071584973281401,
+            -0.028269050394068383,
+        ],
+    ];
+    const B: &'static [f64] = &[
+        0.09646076681806523,
+        0.01,
+        0.4798896504144996,
+        1.379008574103742,
+        -3.290069515436081,
+        2.324710524099774,
+    ];
+    const C: &'static [f64] = &[0.0, 0.161, 0.327, 0.9, 0.9800255409045097, 1.0];
+}
+
+impl EmbeddedTableau for Tsitouras {
+    const ERROR_ORDER: usize = 4;
+    const E: &'static [f64] = &[
+        -0.00178001105222577714,
+        -0.0008164344596567469,
+        0.007880878010261995,
+        -0.1447110071732629,
+        0.5823571654525552,
+        -0.45808210592918697,
+        1.0 / 66.0,
+    ];
+    const P: &'static [&'static [f64]] = &[
+        &[
+            1.0,
+            -2.763706197274826,
+            2.9132554618219126,
+            -1.0530884977290216,
+        ],
+        &[0.0, 0.13169999999999998, -0.2234, 0.1017],
+        &[
+            0.0,
+            3.9302962368947516,
+            -5.941033872131505,
+            2.490627285651253,
+        ],
+        &[
+            0.0,
+            -12.411077166933676,
+            30.33818863028232,
+            -16.548102889244902,
+        ],
+        &[0.0, 37.50931341651104, -88.1789048947664, 47.37952196281928],
+        &[
+            0.0,
+            -27.896526289197286,
+            65.09189467479366,
+            -34.87065786149661,
+        ],
+        &[0.0, 1.5, -4.0, 2.5],
+    ];
+}
+
+/// Dormand-Prince 8(5,3) method of Hairer's `DOP853`.
+pub struct DormandPrince853;
+
+impl ButcherTableau for DormandPrince853 {
+    const ORDER: usize = 8;
+    const A: &'static [&'static [f64]] = &DOP853_A;
+    const B: &'static [f64] = &[
+        5.42937341165687622380535766363e-2,
+        0.0,
+        0.0,
+        0.0,
+        0.0,
+        4.45031289275240888144113950566,
+        1.89151789931450038304281599044,
+        -5.8012039600105847814672114227,
+        3.1116436695781989440891606237e-1,
+        -1.52160949662516078556178806805e-1,
+        2.01365400804030348374776537501e-1,
+        4.47106157277725905176885569043e-2,
+    ];
+    const C: &'static [f64] = &[
+        0.0,
+        0.526001519587677318785587544488e-01,
+        0.789002279381515978178381316732e-01,
+        0.118350341907227396726757197510,
+        0.281649658092772603273242802490,
+        0.333333333333333333333333333333,
+        0.25,
+        0.307692307692307692307692307692,
+        0.651282051282051282051282051282,
+        0.6,
+        0.857142857142857142857142857142,
+        1.0,
+    ];
+}
+
+impl EmbeddedTableau for DormandPrince853 {
+    const ERROR_ORDER: usize = 7;
+    const E: &'static [f64] = &[
+        0.1312004499419488073250102996e-1,
+        0.0,
+        0.0,
+        0.0,
+        0.0,
+        -0.1225156446376204440720569753e+1,
+        -0.4957589496572501915214079952,
+        0.1664377182454986536961530415e+1,
+        -0.3503288487499736816886487290,
+        0.3341791187130174790297318841,
+        0.8192320648511571246570742613e-1,
+        -0.2235530786388629525884427845e-1,
+        0.0,
+    ];
+    const E3: &'static [f64] = &[
+        5.42937341165687622380535766363e-2 - 0.244094488188976377952755905512,
+        0.0,
+        0.0,
+        0.0,
+        0.0,
+        4.45031289275240888144113950566,
+        1.89151789931450038304281599044,
+        -5.8012039600105847814672114227,
+        3.1116436695781989440891606237e-1 - 0.733846688281611857341361741547,
+        -1.52160949662516078556178806805e-1,
+        2.01365400804030348374776537501e-1,
+        4.47106157277725905176885569043e-2 - 0.220588235294117647058823529412e-1,
+        0.0,
+    ];
+    const EXTRA_A: &'static [&'static [f64]] = &DOP853_EXTRA_A;
+    const EXTRA_C: &'static [f64] = &[0.1, 0.2, 7.0 / 9.0];
+    const D: &'static [&'static [f64]] = &DOP853_D;
+}
+
+const DOP853_A: [&[f64]; 12] = [
+    &[],
+    &[5.26001519587677318785587544488e-2],
+    &[
+        1.97250569845378994544595329183e-2,
+        5.91751709536136983633785987549e-2,
+    ],
+    &[
+        2.95875854768068491816892993775e-2,
+        0.0,
+        8.87627564304205475450678981324e-2,
+    ],
+    &[
+        2.41365134159266685502369798665e-1,
+        0.0,
+        -8.84549479328286085344864962717e-1,
+        9.24834003261792003115737966543e-1,
+    ],
+    &[
+        3.7037037037037037037037037037e-2,
+        0.0,
+        0.0,
+        1.70828608729473871279604482173e-1,
+        1.25467687566822425016691814123e-1,
+    ],
+    &[
+        3.7109375e-2,
+        0.0,
+        0.0,
+        1.70252211019544039314978060272e-1,
+        6.02165389804559606850219397283e-2,
+        -1.7578125e-2,
+    ],
+    &[
+        3.70920001185047927108779319836e-2,
+        0.0,
+        0.0,
+        1.70383925712239993810214054705e-1,
+        1.07262030446373284651809199168e-1,
+        -1.53194377486244017527936158236e-2,
+        8.27378916381402288758473766002e-3,
+    ],
+    &[
+        6.24110958716075717114429577812e-1,
+        0.0,
+        0.0,
+        -3.36089262944694129406857109825,
+        -8.68219346841726006818189891453e-1,
+        2.75920996994467083049415600797e1,
+        2.01540675504778934086186788979e1,
+        -4.34898841810699588477366255144e1,
+    ],
+    &[
+        4.77662536438264365890433908527e-1,
+        0.0,
+        0.0,
+        -2.48811461997166764192642586468,
+        -5.90290826836842996371446475743e-1,
+        2.12300514481811942347288949897e1,
+        1.52792336328824235832596922938e1,
+        -3.32882109689848629194453265587e1,
+        -2.03312017085086261358222928593e-2,
+    ],
+    &[
+        -9.3714243008598732571704021658e-1,
+        0.0,
+        0.0,
+        5.18637242884406370830023853209,
+        1.09143734899672957818500254654,
+        -8.14978701074692612513997267357,
+        -1.85200656599969598641566180701e1,
+        2.27394870993505042818970056734e1,
+        2.49360555267965238987089396762,
+        -3.0467644718982195003823669022,
+    ],
+    &[
+        2.27331014751653820792359768449,
+        0.0,
+        0.0,
+        -1.05344954667372501984066689879e1,
+        -2.00087205822486249909675718444,
+        -1.79589318631187989172765950534e1,
+        2.79488845294199600508499808837e1,
+        -2.85899827713502369474065508674,
+        -8.87285693353062954433549289258,
+        1.23605671757943030647266201528e1,
+        6.43392746015763530355970484046e-1,
+    ],
+];
+
+const DOP853_EXTRA_A: [&[f64]; 3] = [
+    &[
+        0.056167502283047954,
+        0.0,
+        0.0,
+        0.0,
+        0.0,
+        0.0,
+        0.25350021021662483,
+        -0.2462390374708025,
+        -0.12419142326381637,
+        0.15329179827876568,
+        0.00820105229563469,
+        0.007567897660545699,
+        -0.008298,
+    ],
+    &[
+        0.03183464816350214,
+        0.0,
+        0.0,
+        0.0,
+        0.0,
+        0.028300909672366776,
+        0.053541988307438566,
+        -0.05492374857139099,
+        0.0,
+        0.0,
+        -0.00010834732869724932,
+        0.0003825710908356584,
+        -0.00034046500868740456,
+        0.1413124436746325,
+    ],
+    &[
+        -0.42889630158379194,
+        0.0,
+        0.0,
+        0.0,
+        0.0,
+        -4.697621415361164,
+        7.683421196062599,
+        4.06898981839711,
+        0.3567271874552811,
+        0.0,
+        0.0,
+        0.0,
+        -0.0013990241651590145,
+        2.9475147891527724,
+        -9.15095847217987,
+    ],
+];
+
+const DOP853_D: [&[f64]; 4] = [
+    &[
+        -8.428938276109013,
+        0.0,
+        0.0,
+        0.0,
+        0.0,
+        0.5667149535193777,
+        -3.0689499459498917,
+        2.38466765651207,
+        2.117034582445028,
+        -0.871391583777973,
+        2.2404374302607883,
+        0.6315787787694688,
+        -0.08899033645133331,
+        18.148505520854727,
+        -9.194632392478356,
+        -4.436036387594894,
+    ],
+    &[
+        10.427508642579134,
+        0.0,
+        0.0,
+        0.0,
+        0.0,
+        242.28349177525817,
+        165.20045171727028,
+        -374.5467547226902,
+        -22.113666853125306,
+        7.733432668472264,
+        -30.674084731089398,
+        -9.332130526430229,
+        15.697238121770845,
+        -31.139403219565178,
+        -9.35292435884448,
+        35.81684148639408,
+    ],
+    &[
+        19.985053242002433,
+        0.0,
+        0.0,
+        0.0,
+        0.0,
+        -387.0373087493518,
+        -189.17813819516758,
+        527.8081592054236,
+        -11.57390253995963,
+        6.8812326946963,
+        -1.0006050966910838,
+        0.7777137798053443,
+        -2.778205752353508,
+        -60.19669523126412,
+        84.32040550667716,
+        11.99229113618279,
+    ],
+    &[
+        -25.69393346270375,
+        0.0,
+        0.0,
+        0.0,
+        0.0,
+        -154.18974869023643,
+        -231.5293791760455,
+        357.6391179106141,
+        93.40532418362432,
+        -37.45832313645163,
+        104.0996495089623,
+        29.8402934266605,
+        -43.53345659001114,
+        96.32455395918828,
+        -39.17726167561544,
+        -149.72683625798564,
+    ],
+];
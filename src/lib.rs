@@ -1,6 +1,9 @@
 pub mod clifford;
 pub mod diffeq;
 pub mod linalg;
+pub mod nn;
+pub mod pde;
+pub mod random;
 
 #[cfg(feature = "plotting")]
 pub mod plot;
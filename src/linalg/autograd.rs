@@ -0,0 +1,410 @@
+// Reverse-mode automatic differentiation on top of `NdArray`.
+//
+// Every operation on a `Tensor` records its parents together with a closure
+// that maps the gradient of the output onto gradients of the inputs. Calling
+// `backward` walks this graph in reverse topological order and accumulates
+// gradients into the leaf tensors that were created with `Tensor::parameter`.
+
+use std::cell::{Ref, RefCell};
+use std::collections::{HashMap, HashSet};
+use std::ops::{Add, Div, Mul, Neg, Sub};
+use std::rc::Rc;
+
+use super::ndarray::NdArray;
+
+type BackwardFn = Box<dyn Fn(&NdArray) -> Vec<NdArray>>;
+
+struct Node {
+    value: RefCell<NdArray>,
+    grad: RefCell<Option<NdArray>>,
+    requires_grad: bool,
+    parents: Vec<Tensor>,
+    backward: Option<BackwardFn>,
+}
+
+/// Node in a computation graph that can be differentiated with [`Tensor::backward`].
+///
+/// Cloning a tensor is cheap and yields a handle to the same node.
+#[derive(Clone)]
+pub struct Tensor {
+    node: Rc<Node>,
+}
+
+impl Tensor {
+    fn leaf(value: NdArray, requires_grad: bool) -> Self {
+        Self {
+            node: Rc::new(Node {
+                value: RefCell::new(value),
+                grad: RefCell::new(None),
+                requires_grad,
+                parents: vec![],
+                backward: None,
+            }),
+        }
+    }
+
+    /// Creates a constant that does not take part in gradient computation.
+    pub fn new(value: NdArray) -> Self {
+        Self::leaf(value, false)
+    }
+
+    /// Creates a trainable leaf whose gradient is accumulated by [`Tensor::backward`].
+    pub fn parameter(value: NdArray) -> Self {
+        Self::leaf(value, true)
+    }
+
+    fn from_op(
+        value: NdArray,
+        parents: Vec<Tensor>,
+        backward: impl Fn(&NdArray) -> Vec<NdArray> + 'static,
+    ) -> Self {
+        let requires_grad = parents.iter().any(|parent| parent.requires_grad());
+        Self {
+            node: Rc::new(Node {
+                value: RefCell::new(value),
+                grad: RefCell::new(None),
+                requires_grad,
+                backward: requires_grad.then(|| Box::new(backward) as BackwardFn),
+                parents: if requires_grad { parents } else { vec![] },
+            }),
+        }
+    }
+
+    pub fn value(&self) -> Ref<'_, NdArray> {
+        self.node.value.borrow()
+    }
+
+    pub fn shape(&self) -> Vec<usize> {
+        self.value().shape().to_vec()
+    }
+
+    /// Value of a tensor holding exactly one element.
+    pub fn item(&self) -> f32 {
+        self.value().item()
+    }
+
+    pub fn requires_grad(&self) -> bool {
+        self.node.requires_grad
+    }
+
+    /// Gradient accumulated by the last calls to [`Tensor::backward`].
+    pub fn grad(&self) -> Option<NdArray> {
+        self.node.grad.borrow().clone()
+    }
+
+    pub fn zero_grad(&self) {
+        *self.node.grad.borrow_mut() = None;
+    }
+
+    /// Replaces the value of a leaf in place, e.g. when an optimizer updates a parameter.
+    pub fn update(&self, fun: impl FnOnce(&mut NdArray)) {
+        assert!(
+            self.node.backward.is_none(),
+            "only leaf tensors can be updated in place"
+        );
+        fun(&mut self.node.value.borrow_mut());
+    }
+
+    /// Returns a constant holding the same value, cut off from the graph.
+    pub fn detach(&self) -> Self {
+        Self::new(self.value().clone())
+    }
+
+    /// Back-propagates from this tensor, seeding its gradient with ones.
+    pub fn backward(&self) {
+        if !self.requires_grad() {
+            return;
+        }
+
+        let order = self.topological_order();
+        let mut grads: HashMap<*const Node, NdArray> = HashMap::new();
+        grads.insert(Rc::as_ptr(&self.node), NdArray::ones(self.value().shape()));
+
+        for tensor in order.iter().rev() {
+            let Some(grad) = grads.remove(&Rc::as_ptr(&tensor.node)) else {
+                continue;
+            };
+
+            match &tensor.node.backward {
+                Some(backward) => {
+                    for (parent, parent_grad) in tensor.node.parents.iter().zip(backward(&grad)) {
+                        if !parent.requires_grad() {
+                            continue;
+                        }
+                        grads
+                            .entry(Rc::as_ptr(&parent.node))
+                            .and_modify(|acc| *acc = &*acc + &parent_grad)
+                            .or_insert(parent_grad);
+                    }
+                }
+                None => {
+                    let mut leaf_grad = tensor.node.grad.borrow_mut();
+                    *leaf_grad = Some(match leaf_grad.take() {
+                        Some(acc) => acc + grad,
+                        None => grad,
+                    });
+                }
+            }
+        }
+    }
+
+    // Iterative depth-first search, so deep graphs (e.g. unrolled solvers) do not overflow the stack
+    fn topological_order(&self) -> Vec<Tensor> {
+        let mut order = vec![];
+        let mut visited = HashSet::new();
+        let mut stack = vec![(self.clone(), false)];
+
+        while let Some((tensor, expanded)) = stack.pop() {
+            if expanded {
+                order.push(tensor);
+                continue;
+            }
+            if !visited.insert(Rc::as_ptr(&tensor.node)) {
+                continue;
+            }
+            stack.push((tensor.clone(), true));
+            for parent in &tensor.node.parents {
+                if parent.requires_grad() && !visited.contains(&Rc::as_ptr(&parent.node)) {
+                    stack.push((parent.clone(), false));
+                }
+            }
+        }
+
+        order
+    }
+}
+
+impl Tensor {
+    pub fn matmul(&self, rhs: &Tensor) -> Tensor {
+        let (a, b) = (self.value().clone(), rhs.value().clone());
+        Tensor::from_op(a.matmul(&b), vec![self.clone(), rhs.clone()], move |grad| {
+            vec![grad.matmul(&b.transpose()), a.transpose().matmul(grad)]
+        })
+    }
+
+    pub fn transpose(&self) -> Tensor {
+        Tensor::from_op(self.value().transpose(), vec![self.clone()], |grad| {
+            vec![grad.transpose()]
+        })
+    }
+
+    pub fn reshape(&self, shape: &[usize]) -> Tensor {
+        let input_shape = self.shape();
+        Tensor::from_op(
+            self.value().reshape(shape),
+            vec![self.clone()],
+            move |grad| vec![grad.reshape(&input_shape)],
+        )
+    }
+
+    pub fn scale(&self, factor: f32) -> Tensor {
+        Tensor::from_op(
+            self.value().map(|x| factor * x),
+            vec![self.clone()],
+            move |grad| vec![grad * factor],
+        )
+    }
+
+    pub fn sum(&self) -> Tensor {
+        let shape = self.shape();
+        Tensor::from_op(
+            NdArray::scalar(self.value().sum()),
+            vec![self.clone()],
+            move |grad| vec![NdArray::full(&shape, grad.item())],
+        )
+    }
+
+    pub fn mean(&self) -> Tensor {
+        let len = self.value().len() as f32;
+        self.sum().scale(1.0 / len)
+    }
+
+    /// Sums over `axis`, keeping it as a dimension of length one.
+    pub fn sum_axis(&self, axis: usize) -> Tensor {
+        let shape = self.shape();
+        Tensor::from_op(
+            self.value().sum_axis(axis),
+            vec![self.clone()],
+            move |grad| vec![&NdArray::zeros(&shape) + grad],
+        )
+    }
+
+    fn unary(
+        &self,
+        fun: impl Fn(f32) -> f32,
+        derivative: impl Fn(f32, f32) -> f32 + 'static,
+    ) -> Tensor {
+        let input = self.value().clone();
+        let output = input.map(fun);
+        let saved_output = output.clone();
+        Tensor::from_op(output, vec![self.clone()], move |grad| {
+            let local = input.zip_map(&saved_output, &derivative);
+            vec![grad * &local]
+        })
+    }
+
+    pub fn relu(&self) -> Tensor {
+        self.unary(|x| x.max(0.0), |x, _| if x > 0.0 { 1.0 } else { 0.0 })
+    }
+
+    pub fn leaky_relu(&self, slope: f32) -> Tensor {
+        self.unary(
+            move |x| if x > 0.0 { x } else { slope * x },
+            move |x, _| if x > 0.0 { 1.0 } else { slope },
+        )
+    }
+
+    pub fn sigmoid(&self) -> Tensor {
+        self.unary(|x| 1.0 / (1.0 + (-x).exp()), |_, y| y * (1.0 - y))
+    }
+
+    pub fn tanh(&self) -> Tensor {
+        self.unary(f32::tanh, |_, y| 1.0 - y * y)
+    }
+
+    pub fn exp(&self) -> Tensor {
+        self.unary(f32::exp, |_, y| y)
+    }
+
+    pub fn ln(&self) -> Tensor {
+        self.unary(f32::ln, |x, _| 1.0 / x)
+    }
+
+    pub fn powf(&self, exponent: f32) -> Tensor {
+        self.unary(
+            move |x| x.powf(exponent),
+            move |x, _| exponent * x.powf(exponent - 1.0),
+        )
+    }
+
+    pub fn square(&self) -> Tensor {
+        self.unary(|x| x * x, |x, _| 2.0 * x)
+    }
+
+    /// Numerically stable `log(softmax(x))` over the last axis of a two-dimensional tensor.
+    pub fn log_softmax(&self) -> Tensor {
+        let input = self.value().clone();
+        assert_eq!(
+            input.ndim(),
+            2,
+            "log_softmax expects a [batch, classes] tensor"
+        );
+        let classes = input.shape()[1];
+
+        let mut output = input.clone();
+        for row in output.data_mut().chunks_mut(classes) {
+            let max = row.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+            let log_sum = row.iter().map(|x| (x - max).exp()).sum::<f32>().ln() + max;
+            row.iter_mut().for_each(|x| *x -= log_sum);
+        }
+
+        let saved_output = output.clone();
+        Tensor::from_op(output, vec![self.clone()], move |grad| {
+            // d/dx_j sum_i g_i * (x_i - lse(x)) = g_j - softmax_j * sum_i g_i
+            let mut input_grad = grad.clone();
+            for (grad_row, log_prob_row) in input_grad
+                .data_mut()
+                .chunks_mut(classes)
+                .zip(saved_output.data().chunks(classes))
+            {
+                let grad_sum: f32 = grad_row.iter().sum();
+                for (g, log_p) in grad_row.iter_mut().zip(log_prob_row) {
+                    *g -= log_p.exp() * grad_sum;
+                }
+            }
+            vec![input_grad]
+        })
+    }
+
+    pub fn softmax(&self) -> Tensor {
+        self.log_softmax().exp()
+    }
+}
+
+impl Add<&Tensor> for &Tensor {
+    type Output = Tensor;
+    fn add(self, rhs: &Tensor) -> Tensor {
+        let (a_shape, b_shape) = (self.shape(), rhs.shape());
+        let value = &*self.value() + &*rhs.value();
+        Tensor::from_op(value, vec![self.clone(), rhs.clone()], move |grad| {
+            vec![grad.sum_to_shape(&a_shape), grad.sum_to_shape(&b_shape)]
+        })
+    }
+}
+
+impl Sub<&Tensor> for &Tensor {
+    type Output = Tensor;
+    fn sub(self, rhs: &Tensor) -> Tensor {
+        let (a_shape, b_shape) = (self.shape(), rhs.shape());
+        let value = &*self.value() - &*rhs.value();
+        Tensor::from_op(value, vec![self.clone(), rhs.clone()], move |grad| {
+            vec![grad.sum_to_shape(&a_shape), (-grad).sum_to_shape(&b_shape)]
+        })
+    }
+}
+
+impl Mul<&Tensor> for &Tensor {
+    type Output = Tensor;
+    fn mul(self, rhs: &Tensor) -> Tensor {
+        let (a, b) = (self.value().clone(), rhs.value().clone());
+        Tensor::from_op(&a * &b, vec![self.clone(), rhs.clone()], move |grad| {
+            vec![
+                (grad * &b).sum_to_shape(a.shape()),
+                (grad * &a).sum_to_shape(b.shape()),
+            ]
+        })
+    }
+}
+
+impl Div<&Tensor> for &Tensor {
+    type Output = Tensor;
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, rhs: &Tensor) -> Tensor {
+        let (a, b) = (self.value().clone(), rhs.value().clone());
+        Tensor::from_op(&a / &b, vec![self.clone(), rhs.clone()], move |grad| {
+            let grad_a = grad / &b;
+            let grad_b = -(&(&grad_a * &a) / &b);
+            vec![
+                grad_a.sum_to_shape(a.shape()),
+                grad_b.sum_to_shape(b.shape()),
+            ]
+        })
+    }
+}
+
+impl Neg for &Tensor {
+    type Output = Tensor;
+    fn neg(self) -> Tensor {
+        self.scale(-1.0)
+    }
+}
+
+macro_rules! impl_tensor_owned_op {
+    ($trait:ident, $method:ident) => {
+        impl $trait for Tensor {
+            type Output = Tensor;
+            fn $method(self, rhs: Tensor) -> Tensor {
+                (&self).$method(&rhs)
+            }
+        }
+
+        impl $trait<&Tensor> for Tensor {
+            type Output = Tensor;
+            fn $method(self, rhs: &Tensor) -> Tensor {
+                (&self).$method(rhs)
+            }
+        }
+    };
+}
+
+impl_tensor_owned_op!(Add, add);
+impl_tensor_owned_op!(Sub, sub);
+impl_tensor_owned_op!(Mul, mul);
+impl_tensor_owned_op!(Div, div);
+
+impl Neg for Tensor {
+    type Output = Tensor;
+    fn neg(self) -> Tensor {
+        -&self
+    }
+}
@@ -0,0 +1,502 @@
+// Storage and solvers for matrices whose nonzero entries are confined to a
+// band around the diagonal, as produced by finite-difference discretizations.
+//
+// All solvers work in O(N) (tridiagonal) or O(N * bandwidth^2) (banded)
+// instead of the O(N^3) of a dense factorization.
+
+use std::ops::{Index, IndexMut, Mul};
+
+use super::{
+    error::LinalgError,
+    ndarray::{Matrix, Vector},
+};
+
+/// Tridiagonal `N x N` matrix.
+///
+/// Row `i` holds `lower[i]` at column `i - 1`, `diag[i]` at column `i` and
+/// `upper[i]` at column `i + 1`, so `lower[0]` and `upper[N - 1]` are unused.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Tridiagonal<const N: usize> {
+    pub lower: [f32; N],
+    pub diag: [f32; N],
+    pub upper: [f32; N],
+}
+
+impl<const N: usize> Tridiagonal<N> {
+    pub fn new(lower: [f32; N], diag: [f32; N], upper: [f32; N]) -> Self {
+        Self { lower, diag, upper }
+    }
+
+    /// Matrix with the same three values on every row, e.g. `(1, -2, 1)` for the 1D Laplacian.
+    pub fn from_constant(lower: f32, diag: f32, upper: f32) -> Self {
+        Self::new([lower; N], [diag; N], [upper; N])
+    }
+
+    /// Solves `self * x = rhs` with the Thomas algorithm.
+    ///
+    /// The algorithm does not pivot, so it is only guaranteed to succeed for
+    /// diagonally dominant or symmetric positive definite matrices.
+    pub fn solve(&self, rhs: &Vector<N>) -> Result<Vector<N>, LinalgError> {
+        let mut upper = [0.0; N];
+        let mut x = Vector { data: [[0.0]; N] };
+
+        for i in 0..N {
+            let lower = if i > 0 { self.lower[i] } else { 0.0 };
+            let previous_upper = if i > 0 { upper[i - 1] } else { 0.0 };
+            let previous_x = if i > 0 { x[i - 1] } else { 0.0 };
+
+            let pivot = self.diag[i] - lower * previous_upper;
+            if pivot == 0.0 {
+                return Err(LinalgError::Singular { row: i });
+            }
+            upper[i] = self.upper[i] / pivot;
+            x[i] = (rhs[i] - lower * previous_x) / pivot;
+        }
+
+        for i in (0..N.saturating_sub(1)).rev() {
+            let next = x[i + 1];
+            x[i] -= upper[i] * next;
+        }
+        Ok(x)
+    }
+
+    pub fn to_matrix(&self) -> Matrix<N, N> {
+        let mut output = Matrix {
+            data: [[0.0; N]; N],
+        };
+        for i in 0..N {
+            output[(i, i)] = self.diag[i];
+            if i > 0 {
+                output[(i, i - 1)] = self.lower[i];
+            }
+            if i + 1 < N {
+                output[(i, i + 1)] = self.upper[i];
+            }
+        }
+        output
+    }
+}
+
+impl<const N: usize> Mul<Vector<N>> for Tridiagonal<N> {
+    type Output = Vector<N>;
+    fn mul(self, rhs: Vector<N>) -> Vector<N> {
+        let mut output = Vector { data: [[0.0]; N] };
+        for i in 0..N {
+            let mut sum = self.diag[i] * rhs[i];
+            if i > 0 {
+                sum += self.lower[i] * rhs[i - 1];
+            }
+            if i + 1 < N {
+                sum += self.upper[i] * rhs[i + 1];
+            }
+            output[i] = sum;
+        }
+        output
+    }
+}
+
+/// Tridiagonal matrix with additional corner entries, as produced by periodic boundaries.
+///
+/// Uses the same layout as [`Tridiagonal`], except that `lower[0]` is the
+/// entry at `(0, N - 1)` and `upper[N - 1]` the entry at `(N - 1, 0)`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CyclicTridiagonal<const N: usize> {
+    pub lower: [f32; N],
+    pub diag: [f32; N],
+    pub upper: [f32; N],
+}
+
+impl<const N: usize> CyclicTridiagonal<N> {
+    pub fn new(lower: [f32; N], diag: [f32; N], upper: [f32; N]) -> Self {
+        assert!(
+            N >= 3,
+            "a cyclic tridiagonal matrix needs at least three rows"
+        );
+        Self { lower, diag, upper }
+    }
+
+    pub fn from_constant(lower: f32, diag: f32, upper: f32) -> Self {
+        Self::new([lower; N], [diag; N], [upper; N])
+    }
+
+    /// Solves `self * x = rhs` using the Sherman-Morrison formula on top of two Thomas solves.
+    pub fn solve(&self, rhs: &Vector<N>) -> Result<Vector<N>, LinalgError> {
+        let top_right = self.lower[0];
+        let bottom_left = self.upper[N - 1];
+
+        // Write the matrix as T + u v^T with u = (gamma, 0, .., bottom_left)
+        // and v = (1, 0, .., top_right / gamma), where T is purely tridiagonal
+        let gamma = if self.diag[0] == 0.0 {
+            1.0
+        } else {
+            -self.diag[0]
+        };
+        let mut reduced = Tridiagonal::new(self.lower, self.diag, self.upper);
+        reduced.diag[0] -= gamma;
+        reduced.diag[N - 1] -= bottom_left * top_right / gamma;
+
+        let x = reduced.solve(rhs)?;
+        let mut u = Vector { data: [[0.0]; N] };
+        u[0] = gamma;
+        u[N - 1] = bottom_left;
+        let z = reduced.solve(&u)?;
+
+        let denominator = 1.0 + z[0] + top_right * z[N - 1] / gamma;
+        if denominator == 0.0 {
+            return Err(LinalgError::Singular { row: 0 });
+        }
+        let factor = (x[0] + top_right * x[N - 1] / gamma) / denominator;
+        Ok(x - factor * z)
+    }
+
+    pub fn to_matrix(&self) -> Matrix<N, N> {
+        let mut output = Tridiagonal::new(self.lower, self.diag, self.upper).to_matrix();
+        output[(0, N - 1)] = self.lower[0];
+        output[(N - 1, 0)] = self.upper[N - 1];
+        output
+    }
+}
+
+impl<const N: usize> Mul<Vector<N>> for CyclicTridiagonal<N> {
+    type Output = Vector<N>;
+    fn mul(self, rhs: Vector<N>) -> Vector<N> {
+        let mut output = Vector { data: [[0.0]; N] };
+        for i in 0..N {
+            output[i] = self.lower[i] * rhs[(i + N - 1) % N]
+                + self.diag[i] * rhs[i]
+                + self.upper[i] * rhs[(i + 1) % N];
+        }
+        output
+    }
+}
+
+/// `N x N` matrix with `lower_bandwidth` sub- and `upper_bandwidth` superdiagonals.
+///
+/// Only the band is stored, row by row. Entries outside of the band read as zero.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BandedMatrix<const N: usize> {
+    lower_bandwidth: usize,
+    upper_bandwidth: usize,
+    data: Vec<f32>,
+}
+
+const ZERO: f32 = 0.0;
+
+impl<const N: usize> BandedMatrix<N> {
+    pub fn zeros(lower_bandwidth: usize, upper_bandwidth: usize) -> Self {
+        Self {
+            lower_bandwidth,
+            upper_bandwidth,
+            data: vec![0.0; N * (lower_bandwidth + upper_bandwidth + 1)],
+        }
+    }
+
+    /// Copies the band of a dense matrix, ignoring every entry outside of it.
+    pub fn from_matrix(
+        matrix: &Matrix<N, N>,
+        lower_bandwidth: usize,
+        upper_bandwidth: usize,
+    ) -> Self {
+        let mut output = Self::zeros(lower_bandwidth, upper_bandwidth);
+        for i in 0..N {
+            for j in output.row_range(i) {
+                output[(i, j)] = matrix[(i, j)];
+            }
+        }
+        output
+    }
+
+    /// Builds a matrix with constant diagonals, `diagonals[k]` being the
+    /// diagonal `k - lower_bandwidth` (negative offsets lie below the main diagonal).
+    pub fn from_diagonals(lower_bandwidth: usize, diagonals: &[f32]) -> Self {
+        assert!(
+            diagonals.len() > lower_bandwidth,
+            "the main diagonal is missing"
+        );
+        let upper_bandwidth = diagonals.len() - lower_bandwidth - 1;
+        let mut output = Self::zeros(lower_bandwidth, upper_bandwidth);
+        for i in 0..N {
+            for j in output.row_range(i) {
+                output[(i, j)] = diagonals[j + lower_bandwidth - i];
+            }
+        }
+        output
+    }
+
+    pub fn lower_bandwidth(&self) -> usize {
+        self.lower_bandwidth
+    }
+
+    pub fn upper_bandwidth(&self) -> usize {
+        self.upper_bandwidth
+    }
+
+    fn width(&self) -> usize {
+        self.lower_bandwidth + self.upper_bandwidth + 1
+    }
+
+    fn in_band(&self, i: usize, j: usize) -> bool {
+        j + self.lower_bandwidth >= i && j <= i + self.upper_bandwidth
+    }
+
+    // Columns of row `i` that lie inside the band
+    fn row_range(&self, i: usize) -> std::ops::Range<usize> {
+        i.saturating_sub(self.lower_bandwidth)..(i + self.upper_bandwidth + 1).min(N)
+    }
+
+    pub fn to_matrix(&self) -> Matrix<N, N> {
+        let mut output = Matrix {
+            data: [[0.0; N]; N],
+        };
+        for i in 0..N {
+            for j in self.row_range(i) {
+                output[(i, j)] = self[(i, j)];
+            }
+        }
+        output
+    }
+
+    /// LU factorization with partial pivoting, keeping the banded structure.
+    pub fn lu(&self) -> Result<BandedLu<N>, LinalgError> {
+        BandedLu::new(self)
+    }
+
+    /// Cholesky factorization of a symmetric positive definite matrix.
+    ///
+    /// Only the diagonal and the subdiagonals are read, the upper band is assumed to mirror them.
+    pub fn cholesky(&self) -> Result<BandedCholesky<N>, LinalgError> {
+        BandedCholesky::new(self)
+    }
+
+    pub fn solve(&self, rhs: &Vector<N>) -> Result<Vector<N>, LinalgError> {
+        Ok(self.lu()?.solve(rhs))
+    }
+}
+
+impl<const N: usize> Index<(usize, usize)> for BandedMatrix<N> {
+    type Output = f32;
+    fn index(&self, (i, j): (usize, usize)) -> &Self::Output {
+        assert!(i < N && j < N, "index ({}, {}) out of bounds", i, j);
+        if self.in_band(i, j) {
+            &self.data[i * self.width() + j + self.lower_bandwidth - i]
+        } else {
+            &ZERO
+        }
+    }
+}
+
+impl<const N: usize> IndexMut<(usize, usize)> for BandedMatrix<N> {
+    fn index_mut(&mut self, (i, j): (usize, usize)) -> &mut Self::Output {
+        assert!(i < N && j < N, "index ({}, {}) out of bounds", i, j);
+        assert!(
+            self.in_band(i, j),
+            "entry ({}, {}) lies outside of the band",
+            i,
+            j
+        );
+        let width = self.width();
+        &mut self.data[i * width + j + self.lower_bandwidth - i]
+    }
+}
+
+impl<const N: usize> Mul<Vector<N>> for &BandedMatrix<N> {
+    type Output = Vector<N>;
+    fn mul(self, rhs: Vector<N>) -> Vector<N> {
+        let mut output = Vector { data: [[0.0]; N] };
+        for i in 0..N {
+            output[i] = self.row_range(i).map(|j| self[(i, j)] * rhs[j]).sum();
+        }
+        output
+    }
+}
+
+impl<const N: usize> Mul<Vector<N>> for BandedMatrix<N> {
+    type Output = Vector<N>;
+    fn mul(self, rhs: Vector<N>) -> Vector<N> {
+        &self * rhs
+    }
+}
+
+impl<const N: usize> From<Tridiagonal<N>> for BandedMatrix<N> {
+    fn from(value: Tridiagonal<N>) -> Self {
+        let mut output = Self::zeros(1, 1);
+        for i in 0..N {
+            output[(i, i)] = value.diag[i];
+            if i > 0 {
+                output[(i, i - 1)] = value.lower[i];
+            }
+            if i + 1 < N {
+                output[(i, i + 1)] = value.upper[i];
+            }
+        }
+        output
+    }
+}
+
+/// Banded LU factorization with partial pivoting.
+///
+/// Row interchanges widen the upper band of `U` to `lower + upper` bandwidth,
+/// which is accounted for in the storage. The multipliers of `L` are kept in
+/// elimination order, like LAPACK's `gbtrf`.
+pub struct BandedLu<const N: usize> {
+    lower_bandwidth: usize,
+    // Row i stores columns i - lower_bandwidth ..= i + lower_bandwidth + upper_bandwidth
+    width: usize,
+    data: Vec<f32>,
+    pivots: Vec<usize>,
+}
+
+impl<const N: usize> BandedLu<N> {
+    fn new(matrix: &BandedMatrix<N>) -> Result<Self, LinalgError> {
+        let kl = matrix.lower_bandwidth;
+        let ku = matrix.upper_bandwidth;
+        let width = 2 * kl + ku + 1;
+        let mut lu = Self {
+            lower_bandwidth: kl,
+            width,
+            data: vec![0.0; N * width],
+            pivots: vec![0; N],
+        };
+        for i in 0..N {
+            for j in matrix.row_range(i) {
+                *lu.entry_mut(i, j) = matrix[(i, j)];
+            }
+        }
+
+        for k in 0..N {
+            let last_row = (k + kl).min(N - 1);
+            let last_column = (k + kl + ku).min(N - 1);
+
+            let pivot_row = (k..=last_row)
+                .max_by(|&a, &b| lu.entry(a, k).abs().total_cmp(&lu.entry(b, k).abs()))
+                .unwrap();
+            lu.pivots[k] = pivot_row;
+            if lu.entry(pivot_row, k) == 0.0 {
+                return Err(LinalgError::Singular { row: k });
+            }
+            if pivot_row != k {
+                for j in k..=last_column {
+                    let tmp = lu.entry(k, j);
+                    *lu.entry_mut(k, j) = lu.entry(pivot_row, j);
+                    *lu.entry_mut(pivot_row, j) = tmp;
+                }
+            }
+
+            let pivot = lu.entry(k, k);
+            for i in k + 1..=last_row {
+                let multiplier = lu.entry(i, k) / pivot;
+                *lu.entry_mut(i, k) = multiplier;
+                if multiplier == 0.0 {
+                    continue;
+                }
+                for j in k + 1..=last_column {
+                    *lu.entry_mut(i, j) -= multiplier * lu.entry(k, j);
+                }
+            }
+        }
+
+        Ok(lu)
+    }
+
+    fn entry(&self, i: usize, j: usize) -> f32 {
+        self.data[i * self.width + j + self.lower_bandwidth - i]
+    }
+
+    fn entry_mut(&mut self, i: usize, j: usize) -> &mut f32 {
+        &mut self.data[i * self.width + j + self.lower_bandwidth - i]
+    }
+
+    pub fn solve(&self, rhs: &Vector<N>) -> Vector<N> {
+        let kl = self.lower_bandwidth;
+        let mut x = *rhs;
+
+        for k in 0..N {
+            let pivot_row = self.pivots[k];
+            if pivot_row != k {
+                let tmp = x[k];
+                x[k] = x[pivot_row];
+                x[pivot_row] = tmp;
+            }
+            for i in k + 1..=(k + kl).min(N - 1) {
+                let update = self.entry(i, k) * x[k];
+                x[i] -= update;
+            }
+        }
+
+        for i in (0..N).rev() {
+            let last_column = (i + self.width - kl - 1).min(N - 1);
+            let sum: f32 = (i + 1..=last_column).map(|j| self.entry(i, j) * x[j]).sum();
+            x[i] = (x[i] - sum) / self.entry(i, i);
+        }
+        x
+    }
+}
+
+/// Cholesky factorization `L * L^T` of a symmetric positive definite banded matrix.
+pub struct BandedCholesky<const N: usize> {
+    bandwidth: usize,
+    // Row i stores columns i - bandwidth ..= i of L
+    data: Vec<f32>,
+}
+
+impl<const N: usize> BandedCholesky<N> {
+    fn new(matrix: &BandedMatrix<N>) -> Result<Self, LinalgError> {
+        let k = matrix.lower_bandwidth;
+        let mut cholesky = Self {
+            bandwidth: k,
+            data: vec![0.0; N * (k + 1)],
+        };
+
+        for j in 0..N {
+            let first = j.saturating_sub(k);
+            let diagonal = matrix[(j, j)]
+                - (first..j)
+                    .map(|m| cholesky.entry(j, m).powi(2))
+                    .sum::<f32>();
+            if diagonal <= 0.0 {
+                return Err(LinalgError::NotPositiveDefinite { row: j });
+            }
+            let diagonal = diagonal.sqrt();
+            *cholesky.entry_mut(j, j) = diagonal;
+
+            for i in j + 1..=(j + k).min(N.saturating_sub(1)) {
+                let first = i.saturating_sub(k);
+                let sum: f32 = (first..j)
+                    .map(|m| cholesky.entry(i, m) * cholesky.entry(j, m))
+                    .sum();
+                *cholesky.entry_mut(i, j) = (matrix[(i, j)] - sum) / diagonal;
+            }
+        }
+
+        Ok(cholesky)
+    }
+
+    fn entry(&self, i: usize, j: usize) -> f32 {
+        self.data[i * (self.bandwidth + 1) + j + self.bandwidth - i]
+    }
+
+    fn entry_mut(&mut self, i: usize, j: usize) -> &mut f32 {
+        &mut self.data[i * (self.bandwidth + 1) + j + self.bandwidth - i]
+    }
+
+    pub fn solve(&self, rhs: &Vector<N>) -> Vector<N> {
+        let k = self.bandwidth;
+        let mut x = *rhs;
+
+        // L y = rhs
+        for i in 0..N {
+            let sum: f32 = (i.saturating_sub(k)..i)
+                .map(|j| self.entry(i, j) * x[j])
+                .sum();
+            x[i] = (x[i] - sum) / self.entry(i, i);
+        }
+        // L^T x = y
+        for i in (0..N).rev() {
+            let sum: f32 = (i + 1..=(i + k).min(N - 1))
+                .map(|j| self.entry(j, i) * x[j])
+                .sum();
+            x[i] = (x[i] - sum) / self.entry(i, i);
+        }
+        x
+    }
+}
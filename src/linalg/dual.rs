@@ -0,0 +1,152 @@
+// Forward-mode automatic differentiation with dual numbers.
+//
+// A dual number `a + b e` with `e^2 = 0` carries a value together with its
+// derivative with respect to one input. Evaluating a function that is generic
+// over `Real` on `Dual::variable(x)` yields `f(x)` and `f'(x)`. Nesting,
+// `Dual<Dual<f64>>`, gives second derivatives. Unlike `autograd::Tensor`,
+// there is no graph, so the cost is a constant factor per operation.
+
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+use super::real::Real;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, PartialOrd)]
+pub struct Dual<R = f32> {
+    pub value: R,
+    pub derivative: R,
+}
+
+impl<R: Real> Dual<R> {
+    pub fn new(value: R, derivative: R) -> Self {
+        Self { value, derivative }
+    }
+
+    /// Number that does not depend on the input.
+    pub fn constant(value: R) -> Self {
+        Self::new(value, R::ZERO)
+    }
+
+    /// The input that derivatives are taken with respect to.
+    pub fn variable(value: R) -> Self {
+        Self::new(value, R::ONE)
+    }
+
+    /// Applies a function with the value `f(x)` and derivative `f'(x)` at the value.
+    fn chain(self, value: R, derivative: R) -> Self {
+        Self::new(value, derivative * self.derivative)
+    }
+}
+
+impl<R: Real> Add for Dual<R> {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self::Output {
+        Self::new(self.value + rhs.value, self.derivative + rhs.derivative)
+    }
+}
+
+impl<R: Real> Sub for Dual<R> {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self::new(self.value - rhs.value, self.derivative - rhs.derivative)
+    }
+}
+
+impl<R: Real> Mul for Dual<R> {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self::Output {
+        Self::new(
+            self.value * rhs.value,
+            self.derivative * rhs.value + self.value * rhs.derivative,
+        )
+    }
+}
+
+impl<R: Real> Div for Dual<R> {
+    type Output = Self;
+    fn div(self, rhs: Self) -> Self::Output {
+        let value = self.value / rhs.value;
+        Self::new(
+            value,
+            (self.derivative - value * rhs.derivative) / rhs.value,
+        )
+    }
+}
+
+impl<R: Real> Neg for Dual<R> {
+    type Output = Self;
+    fn neg(self) -> Self::Output {
+        Self::new(-self.value, -self.derivative)
+    }
+}
+
+macro_rules! impl_dual_assign_op {
+    ($trait:ident, $method:ident, $op:tt) => {
+        impl<R: Real> $trait for Dual<R> {
+            fn $method(&mut self, rhs: Self) {
+                *self = *self $op rhs;
+            }
+        }
+    };
+}
+
+impl_dual_assign_op!(AddAssign, add_assign, +);
+impl_dual_assign_op!(SubAssign, sub_assign, -);
+impl_dual_assign_op!(MulAssign, mul_assign, *);
+impl_dual_assign_op!(DivAssign, div_assign, /);
+
+impl<R: Real> Real for Dual<R> {
+    const ZERO: Self = Dual {
+        value: R::ZERO,
+        derivative: R::ZERO,
+    };
+    const ONE: Self = Dual {
+        value: R::ONE,
+        derivative: R::ZERO,
+    };
+
+    fn from_f64(value: f64) -> Self {
+        Self::constant(R::from_f64(value))
+    }
+
+    fn to_f64(self) -> f64 {
+        self.value.to_f64()
+    }
+
+    fn abs(self) -> Self {
+        if self.value < R::ZERO {
+            -self
+        } else {
+            self
+        }
+    }
+
+    fn sqrt(self) -> Self {
+        let root = self.value.sqrt();
+        self.chain(root, R::ONE / (root + root))
+    }
+
+    fn exp(self) -> Self {
+        let exp = self.value.exp();
+        self.chain(exp, exp)
+    }
+
+    fn ln(self) -> Self {
+        self.chain(self.value.ln(), R::ONE / self.value)
+    }
+
+    fn sin(self) -> Self {
+        self.chain(self.value.sin(), self.value.cos())
+    }
+
+    fn cos(self) -> Self {
+        self.chain(self.value.cos(), -self.value.sin())
+    }
+
+    fn powi(self, exponent: i32) -> Self {
+        let derivative = match exponent {
+            0 => R::ZERO,
+            _ => R::from_f64(exponent as f64) * self.value.powi(exponent - 1),
+        };
+        self.chain(self.value.powi(exponent), derivative)
+    }
+}
@@ -0,0 +1,534 @@
+// Einstein summation and general tensor contraction over `NdArray`.
+//
+// Multi-operand expressions are evaluated as a sequence of pairwise
+// contractions. The order of these contractions is chosen up front (see
+// `einsum_path`) and every pairwise contraction is reshaped into a (batched)
+// matrix product, so the heavy lifting ends up in `NdArray::matmul`. Only
+// expressions that take diagonals (a label repeated within one operand) fall
+// back to the direct summation loop.
+
+use std::collections::HashMap;
+
+use super::ndarray::NdArray;
+
+// Expressions with at most this many operands search all contraction orders,
+// larger ones use the greedy heuristic.
+const OPTIMAL_PATH_LIMIT: usize = 5;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PathStrategy {
+    /// Exhaustive search for the order with the fewest multiply-adds.
+    Optimal,
+    /// Repeatedly contracts the pair producing the smallest intermediate.
+    Greedy,
+}
+
+struct Subscripts {
+    inputs: Vec<Vec<char>>,
+    output: Vec<char>,
+}
+
+impl Subscripts {
+    fn parse(subscripts: &str, operands: usize) -> Self {
+        let subscripts: String = subscripts.chars().filter(|c| !c.is_whitespace()).collect();
+        assert!(
+            !subscripts.contains("..."),
+            "ellipsis subscripts are not supported"
+        );
+        let (inputs, output) = match subscripts.split_once("->") {
+            Some((inputs, output)) => (inputs, Some(output)),
+            None => (subscripts.as_str(), None),
+        };
+
+        let inputs: Vec<Vec<char>> = inputs
+            .split(',')
+            .map(|term| term.chars().collect())
+            .collect();
+        assert_eq!(
+            inputs.len(),
+            operands,
+            "subscripts describe {} operands but {} were given",
+            inputs.len(),
+            operands
+        );
+        for label in inputs.iter().flatten() {
+            assert!(
+                label.is_ascii_alphabetic(),
+                "invalid subscript label '{}'",
+                label
+            );
+        }
+
+        let output: Vec<char> = match output {
+            Some(output) => output.chars().collect(),
+            // Implicit mode: labels that occur exactly once, in alphabetical order
+            None => {
+                let mut counts: HashMap<char, usize> = HashMap::new();
+                for &label in inputs.iter().flatten() {
+                    *counts.entry(label).or_default() += 1;
+                }
+                let mut output: Vec<char> = counts
+                    .into_iter()
+                    .filter(|&(_, count)| count == 1)
+                    .map(|(label, _)| label)
+                    .collect();
+                output.sort_unstable();
+                output
+            }
+        };
+        for (i, label) in output.iter().enumerate() {
+            assert!(
+                inputs.iter().flatten().any(|l| l == label),
+                "output label '{}' does not occur in any input",
+                label
+            );
+            assert!(
+                !output[..i].contains(label),
+                "output label '{}' occurs more than once",
+                label
+            );
+        }
+
+        Self { inputs, output }
+    }
+
+    fn dimensions(&self, shapes: &[&[usize]]) -> HashMap<char, usize> {
+        let mut dims = HashMap::new();
+        for (labels, shape) in self.inputs.iter().zip(shapes) {
+            assert_eq!(
+                labels.len(),
+                shape.len(),
+                "subscript '{}' does not match operand of shape {:?}",
+                labels.iter().collect::<String>(),
+                shape
+            );
+            for (&label, &dim) in labels.iter().zip(shape.iter()) {
+                let expected = *dims.entry(label).or_insert(dim);
+                assert_eq!(
+                    expected, dim,
+                    "label '{}' has inconsistent dimensions {} and {}",
+                    label, expected, dim
+                );
+            }
+        }
+        dims
+    }
+}
+
+/// Evaluates an Einstein summation such as `"ij,jk->ik"` (matrix product),
+/// `"ii->"` (trace) or `"ijkl,kl->ij"` (double contraction).
+///
+/// Without `->` the output consists of the labels occurring exactly once, in
+/// alphabetical order, like in numpy.
+pub fn einsum(subscripts: &str, operands: &[&NdArray]) -> NdArray {
+    let strategy = if operands.len() <= OPTIMAL_PATH_LIMIT {
+        PathStrategy::Optimal
+    } else {
+        PathStrategy::Greedy
+    };
+    einsum_with_strategy(subscripts, operands, strategy)
+}
+
+pub fn einsum_with_strategy(
+    subscripts: &str,
+    operands: &[&NdArray],
+    strategy: PathStrategy,
+) -> NdArray {
+    let parsed = Subscripts::parse(subscripts, operands.len());
+    let shapes: Vec<&[usize]> = operands.iter().map(|operand| operand.shape()).collect();
+    let dims = parsed.dimensions(&shapes);
+
+    let mut terms: Vec<Term> = parsed
+        .inputs
+        .iter()
+        .zip(operands)
+        .map(|(labels, &array)| Term {
+            labels: labels.clone(),
+            array: array.clone(),
+        })
+        .collect();
+
+    for (i, j) in contraction_path(&parsed, &dims, strategy) {
+        let b = terms.remove(j);
+        let a = terms.remove(i);
+        let keep = needed_labels(&terms, &parsed.output);
+        terms.push(contract_pair(a, b, &keep, &dims));
+    }
+
+    let term = terms.pop().expect("einsum needs at least one operand");
+    finalize(term, &parsed.output, &dims)
+}
+
+/// Order in which `einsum` contracts the operands.
+///
+/// Each entry `(i, j)` (with `i < j`) removes operands `i` and `j` from the
+/// current list and appends their contraction at the end, like numpy's `einsum_path`.
+pub fn einsum_path(
+    subscripts: &str,
+    shapes: &[&[usize]],
+    strategy: PathStrategy,
+) -> Vec<(usize, usize)> {
+    let parsed = Subscripts::parse(subscripts, shapes.len());
+    let dims = parsed.dimensions(shapes);
+    contraction_path(&parsed, &dims, strategy)
+}
+
+/// Contracts `axes_a` of `a` with `axes_b` of `b`.
+///
+/// The result has the remaining axes of `a` followed by the remaining axes of `b`.
+pub fn tensordot(a: &NdArray, b: &NdArray, axes_a: &[usize], axes_b: &[usize]) -> NdArray {
+    assert_eq!(
+        axes_a.len(),
+        axes_b.len(),
+        "tensordot needs the same number of axes on both sides"
+    );
+    for (&axis_a, &axis_b) in axes_a.iter().zip(axes_b) {
+        assert_eq!(
+            a.shape()[axis_a],
+            b.shape()[axis_b],
+            "axis {} of {:?} does not match axis {} of {:?}",
+            axis_a,
+            a.shape(),
+            axis_b,
+            b.shape()
+        );
+    }
+
+    let free_a: Vec<usize> = (0..a.ndim())
+        .filter(|axis| !axes_a.contains(axis))
+        .collect();
+    let free_b: Vec<usize> = (0..b.ndim())
+        .filter(|axis| !axes_b.contains(axis))
+        .collect();
+
+    let rows: usize = free_a.iter().map(|&axis| a.shape()[axis]).product();
+    let inner: usize = axes_a.iter().map(|&axis| a.shape()[axis]).product();
+    let cols: usize = free_b.iter().map(|&axis| b.shape()[axis]).product();
+
+    let lhs = permuted(a, &[free_a.clone(), axes_a.to_vec()].concat()).reshape(&[rows, inner]);
+    let rhs = permuted(b, &[axes_b.to_vec(), free_b.clone()].concat()).reshape(&[inner, cols]);
+
+    let shape: Vec<usize> = free_a
+        .iter()
+        .map(|&axis| a.shape()[axis])
+        .chain(free_b.iter().map(|&axis| b.shape()[axis]))
+        .collect();
+    lhs.matmul(&rhs).reshape(&shape)
+}
+
+// Skips the copy when the permutation is the identity
+fn permuted(array: &NdArray, axes: &[usize]) -> NdArray {
+    if axes.iter().enumerate().all(|(i, &axis)| i == axis) {
+        array.clone()
+    } else {
+        array.permute(axes)
+    }
+}
+
+struct Term {
+    labels: Vec<char>,
+    array: NdArray,
+}
+
+fn has_repeated_labels(labels: &[char]) -> bool {
+    labels
+        .iter()
+        .enumerate()
+        .any(|(i, label)| labels[..i].contains(label))
+}
+
+// Labels that still have to be carried along after a contraction
+fn needed_labels(remaining: &[Term], output: &[char]) -> Vec<char> {
+    let mut keep = output.to_vec();
+    for label in remaining.iter().flat_map(|term| &term.labels) {
+        if !keep.contains(label) {
+            keep.push(*label);
+        }
+    }
+    keep
+}
+
+fn contract_pair(a: Term, b: Term, keep: &[char], dims: &HashMap<char, usize>) -> Term {
+    if has_repeated_labels(&a.labels) || has_repeated_labels(&b.labels) {
+        let mut labels: Vec<char> = vec![];
+        for &label in a.labels.iter().chain(&b.labels) {
+            if keep.contains(&label) && !labels.contains(&label) {
+                labels.push(label);
+            }
+        }
+        let array = direct_sum(&[&a, &b], &labels, dims);
+        return Term { labels, array };
+    }
+
+    let a = sum_out_unshared(a, &b.labels, keep);
+    let b = sum_out_unshared(b, &a.labels, keep);
+
+    let shared: Vec<char> = a
+        .labels
+        .iter()
+        .filter(|l| b.labels.contains(l))
+        .copied()
+        .collect();
+    let batch: Vec<char> = shared
+        .iter()
+        .filter(|l| keep.contains(l))
+        .copied()
+        .collect();
+    let contracted: Vec<char> = shared
+        .iter()
+        .filter(|l| !keep.contains(l))
+        .copied()
+        .collect();
+    let free_a: Vec<char> = a
+        .labels
+        .iter()
+        .filter(|l| !shared.contains(l))
+        .copied()
+        .collect();
+    let free_b: Vec<char> = b
+        .labels
+        .iter()
+        .filter(|l| !shared.contains(l))
+        .copied()
+        .collect();
+
+    let labels: Vec<char> = [batch.clone(), free_a.clone(), free_b.clone()].concat();
+
+    if batch.is_empty() {
+        let axes_a: Vec<usize> = contracted.iter().map(|l| position(&a.labels, *l)).collect();
+        let axes_b: Vec<usize> = contracted.iter().map(|l| position(&b.labels, *l)).collect();
+        let array = tensordot(&a.array, &b.array, &axes_a, &axes_b);
+        return Term { labels, array };
+    }
+
+    // Batched matrix product: [batch, rows, inner] x [batch, inner, cols]
+    let size = |labels: &[char]| labels.iter().map(|l| dims[l]).product::<usize>();
+    let (batches, rows, inner, cols) = (
+        size(&batch),
+        size(&free_a),
+        size(&contracted),
+        size(&free_b),
+    );
+
+    let order_a: Vec<usize> = [batch.clone(), free_a, contracted.clone()]
+        .concat()
+        .iter()
+        .map(|l| position(&a.labels, *l))
+        .collect();
+    let order_b: Vec<usize> = [batch, contracted, free_b]
+        .concat()
+        .iter()
+        .map(|l| position(&b.labels, *l))
+        .collect();
+    let lhs = permuted(&a.array, &order_a);
+    let rhs = permuted(&b.array, &order_b);
+
+    let mut data = Vec::with_capacity(batches * rows * cols);
+    for batch_index in 0..batches {
+        let lhs_block = NdArray::from_vec(
+            &[rows, inner],
+            lhs.data()[batch_index * rows * inner..(batch_index + 1) * rows * inner].to_vec(),
+        );
+        let rhs_block = NdArray::from_vec(
+            &[inner, cols],
+            rhs.data()[batch_index * inner * cols..(batch_index + 1) * inner * cols].to_vec(),
+        );
+        data.extend_from_slice(lhs_block.matmul(&rhs_block).data());
+    }
+
+    let shape: Vec<usize> = labels.iter().map(|l| dims[l]).collect();
+    Term {
+        labels,
+        array: NdArray::from_vec(&shape, data),
+    }
+}
+
+fn position(labels: &[char], label: char) -> usize {
+    labels.iter().position(|&l| l == label).unwrap()
+}
+
+// Sums over labels that neither the other operand nor anything later needs
+fn sum_out_unshared(mut term: Term, other: &[char], keep: &[char]) -> Term {
+    while let Some(axis) = term
+        .labels
+        .iter()
+        .position(|l| !other.contains(l) && !keep.contains(l))
+    {
+        term.labels.remove(axis);
+        let mut shape = term.array.shape().to_vec();
+        shape.remove(axis);
+        term.array = term.array.sum_axis(axis).reshape(&shape);
+    }
+    term
+}
+
+fn finalize(term: Term, output: &[char], dims: &HashMap<char, usize>) -> NdArray {
+    if has_repeated_labels(&term.labels) {
+        return direct_sum(&[&term], output, dims);
+    }
+
+    let term = sum_out_unshared(term, &[], output);
+    let axes: Vec<usize> = output.iter().map(|l| position(&term.labels, *l)).collect();
+    permuted(&term.array, &axes)
+}
+
+// Direct evaluation of sum over all non-output labels of the product of the terms
+fn direct_sum(terms: &[&Term], output: &[char], dims: &HashMap<char, usize>) -> NdArray {
+    let mut labels = output.to_vec();
+    for &label in terms.iter().flat_map(|term| &term.labels) {
+        if !labels.contains(&label) {
+            labels.push(label);
+        }
+    }
+    let extents: Vec<usize> = labels.iter().map(|l| dims[l]).collect();
+
+    // Stride of every label within every term (repeated labels add up their strides)
+    let term_strides: Vec<Vec<usize>> = terms
+        .iter()
+        .map(|term| {
+            let strides = term.array.strides();
+            labels
+                .iter()
+                .map(|label| {
+                    term.labels
+                        .iter()
+                        .zip(&strides)
+                        .filter(|(l, _)| *l == label)
+                        .map(|(_, stride)| stride)
+                        .sum()
+                })
+                .collect()
+        })
+        .collect();
+
+    let output_shape: Vec<usize> = extents[..output.len()].to_vec();
+    let summed: usize = extents[output.len()..].iter().product();
+    let mut result = NdArray::zeros(&output_shape);
+
+    let mut index = vec![0; labels.len()];
+    for value in result.data_mut() {
+        for _ in 0..summed {
+            let mut product = 1.0;
+            for (term, strides) in terms.iter().zip(&term_strides) {
+                let offset: usize = index.iter().zip(strides).map(|(i, s)| i * s).sum();
+                product *= term.array.data()[offset];
+            }
+            *value += product;
+            increment(&mut index, &extents);
+        }
+    }
+    result
+}
+
+fn increment(index: &mut [usize], extents: &[usize]) {
+    for axis in (0..index.len()).rev() {
+        index[axis] += 1;
+        if index[axis] < extents[axis] {
+            return;
+        }
+        index[axis] = 0;
+    }
+}
+
+fn contraction_path(
+    parsed: &Subscripts,
+    dims: &HashMap<char, usize>,
+    strategy: PathStrategy,
+) -> Vec<(usize, usize)> {
+    let terms = parsed.inputs.clone();
+    match strategy {
+        PathStrategy::Optimal => optimal_path(terms, &parsed.output, dims).1,
+        PathStrategy::Greedy => greedy_path(terms, &parsed.output, dims),
+    }
+}
+
+// Labels of the contraction of terms i and j, and its cost in multiply-adds
+fn pair_result(
+    terms: &[Vec<char>],
+    i: usize,
+    j: usize,
+    output: &[char],
+    dims: &HashMap<char, usize>,
+) -> (Vec<char>, usize) {
+    let mut keep = output.to_vec();
+    for (k, term) in terms.iter().enumerate() {
+        if k != i && k != j {
+            keep.extend(term);
+        }
+    }
+
+    let mut union: Vec<char> = vec![];
+    for &label in terms[i].iter().chain(&terms[j]) {
+        if !union.contains(&label) {
+            union.push(label);
+        }
+    }
+    let cost = union.iter().map(|l| dims[l]).product();
+    let labels = union.into_iter().filter(|l| keep.contains(l)).collect();
+    (labels, cost)
+}
+
+fn remove_pair(terms: &[Vec<char>], i: usize, j: usize, result: Vec<char>) -> Vec<Vec<char>> {
+    let mut next: Vec<Vec<char>> = terms
+        .iter()
+        .enumerate()
+        .filter(|&(k, _)| k != i && k != j)
+        .map(|(_, term)| term.clone())
+        .collect();
+    next.push(result);
+    next
+}
+
+fn optimal_path(
+    terms: Vec<Vec<char>>,
+    output: &[char],
+    dims: &HashMap<char, usize>,
+) -> (usize, Vec<(usize, usize)>) {
+    if terms.len() < 2 {
+        return (0, vec![]);
+    }
+
+    let mut best = (usize::MAX, vec![]);
+    for j in 1..terms.len() {
+        for i in 0..j {
+            let (labels, cost) = pair_result(&terms, i, j, output, dims);
+            let (rest_cost, rest_path) =
+                optimal_path(remove_pair(&terms, i, j, labels), output, dims);
+            let total = cost.saturating_add(rest_cost);
+            if total < best.0 {
+                best = (total, [vec![(i, j)], rest_path].concat());
+            }
+        }
+    }
+    best
+}
+
+fn greedy_path(
+    mut terms: Vec<Vec<char>>,
+    output: &[char],
+    dims: &HashMap<char, usize>,
+) -> Vec<(usize, usize)> {
+    let size = |labels: &[char]| labels.iter().map(|l| dims[l]).product::<usize>() as i64;
+
+    let mut path = vec![];
+    while terms.len() > 1 {
+        let mut best_score = (i64::MAX, usize::MAX);
+        let mut best_pair = (0, 1);
+        for j in 1..terms.len() {
+            for i in 0..j {
+                let (labels, cost) = pair_result(&terms, i, j, output, dims);
+                // Prefer contractions that shrink the data the most, then the cheapest ones
+                let score = (size(&labels) - size(&terms[i]) - size(&terms[j]), cost);
+                if score < best_score {
+                    best_score = score;
+                    best_pair = (i, j);
+                }
+            }
+        }
+        let (i, j) = best_pair;
+        let (labels, _) = pair_result(&terms, i, j, output, dims);
+        terms = remove_pair(&terms, i, j, labels);
+        path.push((i, j));
+    }
+    path
+}
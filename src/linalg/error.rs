@@ -0,0 +1,25 @@
+use std::fmt;
+
+/// Failure of a factorization or linear solve.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LinalgError {
+    /// A zero pivot was encountered in the given row.
+    Singular { row: usize },
+    /// A Cholesky factorization found a non-positive pivot in the given row.
+    NotPositiveDefinite { row: usize },
+}
+
+impl fmt::Display for LinalgError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LinalgError::Singular { row } => {
+                write!(f, "matrix is singular (zero pivot in row {})", row)
+            }
+            LinalgError::NotPositiveDefinite { row } => {
+                write!(f, "matrix is not positive definite (pivot in row {})", row)
+            }
+        }
+    }
+}
+
+impl std::error::Error for LinalgError {}
@@ -0,0 +1,134 @@
+// Geometric products and helpers for `Vector`, plus Kronecker products.
+
+use super::ndarray::{Matrix, NdArray, Vector};
+
+impl<const N: usize> Vector<N> {
+    pub fn dot(&self, rhs: &Vector<N>) -> f32 {
+        self.data
+            .iter()
+            .zip(&rhs.data)
+            .map(|(a, b)| a[0] * b[0])
+            .sum()
+    }
+
+    pub fn norm_squared(&self) -> f32 {
+        self.dot(self)
+    }
+
+    /// Euclidean length of the vector.
+    pub fn norm(&self) -> f32 {
+        self.norm_squared().sqrt()
+    }
+
+    /// Unit vector in the same direction; the components are NaN for the zero vector.
+    pub fn normalize(&self) -> Vector<N> {
+        *self / self.norm()
+    }
+
+    /// Unit vector in the same direction, or `None` if the length is (close to) zero.
+    pub fn try_normalize(&self) -> Option<Vector<N>> {
+        let norm = self.norm();
+        (norm > f32::EPSILON).then(|| *self / norm)
+    }
+
+    pub fn distance(&self, rhs: &Vector<N>) -> f32 {
+        (*self - *rhs).norm()
+    }
+
+    /// Angle between the two vectors in radians, in `[0, pi]`.
+    pub fn angle(&self, rhs: &Vector<N>) -> f32 {
+        let cosine = self.dot(rhs) / (self.norm() * rhs.norm());
+        cosine.clamp(-1.0, 1.0).acos()
+    }
+
+    /// Component of `self` parallel to `onto`.
+    pub fn project_onto(&self, onto: &Vector<N>) -> Vector<N> {
+        (self.dot(onto) / onto.norm_squared()) * *onto
+    }
+
+    /// Component of `self` perpendicular to `from`.
+    pub fn reject_from(&self, from: &Vector<N>) -> Vector<N> {
+        *self - self.project_onto(from)
+    }
+
+    /// Outer product `self * rhs^T`.
+    pub fn outer<const M: usize>(&self, rhs: &Vector<M>) -> Matrix<N, M> {
+        Matrix::from_fn(|i, j| self[i] * rhs[j])
+    }
+}
+
+impl Vector<3> {
+    pub fn cross(&self, rhs: &Vector<3>) -> Vector<3> {
+        Vector::from([
+            self[1] * rhs[2] - self[2] * rhs[1],
+            self[2] * rhs[0] - self[0] * rhs[2],
+            self[0] * rhs[1] - self[1] * rhs[0],
+        ])
+    }
+}
+
+impl<const M: usize, const N: usize> Matrix<M, N> {
+    /// Kronecker product of an `M x N` and a `P x Q` matrix.
+    ///
+    /// The output dimensions can not be computed from the input dimensions on
+    /// stable Rust, so they are given by the caller and checked at runtime:
+    /// `R` must equal `M * P` and `S` must equal `N * Q`.
+    pub fn kron<const P: usize, const Q: usize, const R: usize, const S: usize>(
+        &self,
+        rhs: &Matrix<P, Q>,
+    ) -> Matrix<R, S> {
+        assert!(
+            R == M * P && S == N * Q,
+            "the Kronecker product of a {}x{} and a {}x{} matrix is {}x{}, not {}x{}",
+            M,
+            N,
+            P,
+            Q,
+            M * P,
+            N * Q,
+            R,
+            S
+        );
+        Matrix::from_fn(|i, j| self[(i / P, j / Q)] * rhs[(i % P, j % Q)])
+    }
+}
+
+impl NdArray {
+    /// Kronecker product of two two-dimensional arrays.
+    pub fn kron(&self, rhs: &NdArray) -> NdArray {
+        assert!(
+            self.ndim() == 2 && rhs.ndim() == 2,
+            "kron expects two-dimensional arrays"
+        );
+        let (p, q) = (rhs.shape()[0], rhs.shape()[1]);
+        let shape = [self.shape()[0] * p, self.shape()[1] * q];
+        NdArray::from_fn(&shape, |index| {
+            self[&[index[0] / p, index[1] / q][..]] * rhs[&[index[0] % p, index[1] % q][..]]
+        })
+    }
+}
+
+/// Orthonormal basis of the span of `vectors` using modified Gram-Schmidt.
+///
+/// Vectors that are (numerically) linearly dependent on the previous ones are
+/// dropped, so the result may contain fewer vectors than the input.
+pub fn gram_schmidt<const N: usize>(vectors: &[Vector<N>]) -> Vec<Vector<N>> {
+    let scale = vectors.iter().map(|v| v.norm()).fold(0.0, f32::max);
+    let tolerance = 1e-5 * scale.max(f32::MIN_POSITIVE);
+
+    let mut basis: Vec<Vector<N>> = Vec::with_capacity(vectors.len().min(N));
+    for vector in vectors {
+        let mut residual = *vector;
+        // Orthogonalize twice, which restores orthogonality lost to rounding
+        for _ in 0..2 {
+            for q in &basis {
+                residual -= residual.dot(q) * *q;
+            }
+        }
+        let norm = residual.norm();
+        if norm > tolerance {
+            basis.push(residual / norm);
+        }
+    }
+    basis
+}
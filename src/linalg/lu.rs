@@ -0,0 +1,167 @@
+// Dense LU factorization with partial pivoting, for fixed size matrices and
+// for square matrices whose size is only known at runtime.
+
+use super::{
+    error::LinalgError,
+    ndarray::{Matrix, NdArray, Vector},
+};
+
+/// Factorization `P A = L U` of a square matrix stored row-major.
+///
+/// `L` has a unit diagonal and is stored below the diagonal of `U`. Row `k`
+/// was interchanged with row `pivots[k]` when eliminating column `k`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LuDecomposition {
+    size: usize,
+    data: Vec<f32>,
+    pivots: Vec<usize>,
+}
+
+impl LuDecomposition {
+    /// Factorizes the `size x size` row-major matrix `data`.
+    pub fn new(size: usize, mut data: Vec<f32>) -> Result<Self, LinalgError> {
+        assert_eq!(
+            data.len(),
+            size * size,
+            "expected {} entries for a {}x{} matrix",
+            size * size,
+            size,
+            size
+        );
+        let mut pivots = vec![0; size];
+        for k in 0..size {
+            let pivot_row = (k..size)
+                .max_by(|&a, &b| {
+                    data[a * size + k]
+                        .abs()
+                        .total_cmp(&data[b * size + k].abs())
+                })
+                .unwrap();
+            pivots[k] = pivot_row;
+            if data[pivot_row * size + k] == 0.0 {
+                return Err(LinalgError::Singular { row: k });
+            }
+            if pivot_row != k {
+                for j in 0..size {
+                    data.swap(k * size + j, pivot_row * size + j);
+                }
+            }
+
+            let pivot = data[k * size + k];
+            for i in k + 1..size {
+                let multiplier = data[i * size + k] / pivot;
+                data[i * size + k] = multiplier;
+                if multiplier == 0.0 {
+                    continue;
+                }
+                for j in k + 1..size {
+                    data[i * size + j] -= multiplier * data[k * size + j];
+                }
+            }
+        }
+        Ok(Self { size, data, pivots })
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Overwrites `rhs` with the solution `x` of `A x = rhs`.
+    pub fn solve_in_place(&self, rhs: &mut [f32]) {
+        let n = self.size;
+        assert_eq!(rhs.len(), n, "expected a right-hand side of length {}", n);
+        // Whole rows were interchanged, so the permutation is applied up front
+        for k in 0..n {
+            rhs.swap(k, self.pivots[k]);
+        }
+        for k in 0..n {
+            for i in k + 1..n {
+                rhs[i] -= self.data[i * n + k] * rhs[k];
+            }
+        }
+        for k in (0..n).rev() {
+            for j in k + 1..n {
+                rhs[k] -= self.data[k * n + j] * rhs[j];
+            }
+            rhs[k] /= self.data[k * n + k];
+        }
+    }
+
+    pub fn solve(&self, rhs: &[f32]) -> Vec<f32> {
+        let mut x = rhs.to_vec();
+        self.solve_in_place(&mut x);
+        x
+    }
+
+    pub fn determinant(&self) -> f32 {
+        let n = self.size;
+        let swaps = (0..n).filter(|&k| self.pivots[k] != k).count();
+        let sign = if swaps % 2 == 0 { 1.0 } else { -1.0 };
+        (0..n).map(|k| self.data[k * n + k]).product::<f32>() * sign
+    }
+}
+
+/// LU factorization of a fixed size `N x N` matrix.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Lu<const N: usize> {
+    decomposition: LuDecomposition,
+}
+
+impl<const N: usize> Lu<N> {
+    pub fn solve(&self, rhs: &Vector<N>) -> Vector<N> {
+        let mut x = *rhs;
+        self.decomposition.solve_in_place(x.data.as_flattened_mut());
+        x
+    }
+
+    pub fn determinant(&self) -> f32 {
+        self.decomposition.determinant()
+    }
+
+    /// Inverse of the factorized matrix, column by column.
+    pub fn inverse(&self) -> Matrix<N, N> {
+        let mut inverse = Matrix::zeros();
+        for j in 0..N {
+            let mut column = Vector::zeros();
+            column[j] = 1.0;
+            let column = self.solve(&column);
+            for i in 0..N {
+                inverse[(i, j)] = column[i];
+            }
+        }
+        inverse
+    }
+}
+
+impl<const N: usize> Matrix<N, N> {
+    pub fn lu(&self) -> Result<Lu<N>, LinalgError> {
+        let decomposition = LuDecomposition::new(N, self.data.as_flattened().to_vec())?;
+        Ok(Lu { decomposition })
+    }
+
+    /// Solves `self * x = rhs`.
+    pub fn solve(&self, rhs: &Vector<N>) -> Result<Vector<N>, LinalgError> {
+        Ok(self.lu()?.solve(rhs))
+    }
+
+    /// Determinant, which is zero for singular matrices.
+    pub fn determinant(&self) -> f32 {
+        self.lu().map_or(0.0, |lu| lu.determinant())
+    }
+
+    pub fn inverse(&self) -> Result<Matrix<N, N>, LinalgError> {
+        Ok(self.lu()?.inverse())
+    }
+}
+
+impl NdArray {
+    /// LU factorization of a square two-dimensional array.
+    pub fn lu(&self) -> Result<LuDecomposition, LinalgError> {
+        assert!(
+            self.ndim() == 2 && self.shape()[0] == self.shape()[1],
+            "lu expects a square two-dimensional array, got shape {:?}",
+            self.shape()
+        );
+        LuDecomposition::new(self.shape()[0], self.data().to_vec())
+    }
+}
@@ -1 +1,9 @@
+pub mod autograd;
+pub mod banded;
+pub mod dual;
+pub mod einsum;
+pub mod error;
+pub mod geometry;
+pub mod lu;
 pub mod ndarray;
+pub mod real;
@@ -1,33 +1,66 @@
-use std::ops::{Add, AddAssign, Index, IndexMut, Mul};
+use std::ops::{Add, AddAssign, Div, Index, IndexMut, Mul, MulAssign, Neg, Sub, SubAssign};
 
-#[derive(Clone, Copy)]
-pub struct Matrix<const M: usize, const N: usize> {
-    pub data: [[f32; N]; M], // TODO: This needs to be generalized with Ndarray<Ndarray, 1>
+use super::real::Real;
+
+/// Matrix of `f32` by default, or of another [`Real`] type such as `f64`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Matrix<const M: usize, const N: usize, T = f32> {
+    pub data: [[T; N]; M], // TODO: This needs to be generalized with Ndarray<Ndarray, 1>
 }
 
-pub type Vector<const N: usize> = Matrix<N, 1>;
+pub type Vector<const N: usize, T = f32> = Matrix<N, 1, T>;
+
+impl<const M: usize, const N: usize, T: Real> Matrix<M, N, T> {
+    pub fn zeros() -> Self {
+        Self {
+            data: [[T::ZERO; N]; M],
+        }
+    }
 
-impl<const L: usize, const M: usize, const N: usize> Mul<Matrix<M, N>> for Matrix<L, M> {
-    type Output = Matrix<L, N>;
-    fn mul(self, rhs: Matrix<M, N>) -> Self::Output {
+    pub fn from_fn(fun: impl Fn(usize, usize) -> T) -> Self {
+        let mut output = Self::zeros();
+        for (i, row) in output.data.iter_mut().enumerate() {
+            for (j, value) in row.iter_mut().enumerate() {
+                *value = fun(i, j);
+            }
+        }
+        output
+    }
+
+    pub fn transpose(&self) -> Matrix<N, M, T> {
+        Matrix::from_fn(|i, j| self.data[j][i])
+    }
+}
+
+impl<const N: usize, T: Real> Matrix<N, N, T> {
+    pub fn identity() -> Self {
+        Self::from_fn(|i, j| if i == j { T::ONE } else { T::ZERO })
+    }
+}
+
+impl<const L: usize, const M: usize, const N: usize, T: Real> Mul<Matrix<M, N, T>>
+    for Matrix<L, M, T>
+{
+    type Output = Matrix<L, N, T>;
+    fn mul(self, rhs: Matrix<M, N, T>) -> Self::Output {
         //TODO: THIS NEEDS TO BE REPLACED WITH A MORE SOPHISTICATED ALGORITHM
         //  to reduce O(N^3) and increase caching efficiency
-        let mut output = [[0.0; N]; L];
-        for i in 0..L {
-            for k in 0..N {
-                let mut sum = 0.0;
+        let mut output = [[T::ZERO; N]; L];
+        for (i, row) in output.iter_mut().enumerate() {
+            for (k, out) in row.iter_mut().enumerate() {
+                let mut sum = T::ZERO;
                 for j in 0..M {
                     sum += self.data[i][j] * rhs.data[j][k];
                 }
-                output[i][k] = sum;
+                *out = sum;
             }
         }
         Matrix { data: output }
     }
 }
 
-impl<const M: usize, const N: usize> Add for Matrix<M, N> {
-    type Output = Matrix<M, N>;
+impl<const M: usize, const N: usize, T: Real> Add for Matrix<M, N, T> {
+    type Output = Matrix<M, N, T>;
     fn add(mut self, rhs: Self) -> Self::Output {
         for i in 0..M {
             for j in 0..N {
@@ -38,7 +71,7 @@ impl<const M: usize, const N: usize> Add for Matrix<M, N> {
     }
 }
 
-impl<const M: usize, const N: usize> AddAssign for Matrix<M, N> {
+impl<const M: usize, const N: usize, T: Real> AddAssign for Matrix<M, N, T> {
     fn add_assign(&mut self, rhs: Self) {
         for i in 0..M {
             for j in 0..N {
@@ -48,6 +81,31 @@ impl<const M: usize, const N: usize> AddAssign for Matrix<M, N> {
     }
 }
 
+impl<const M: usize, const N: usize, T: Real> Sub for Matrix<M, N, T> {
+    type Output = Matrix<M, N, T>;
+    fn sub(mut self, rhs: Self) -> Self::Output {
+        self -= rhs;
+        self
+    }
+}
+
+impl<const M: usize, const N: usize, T: Real> SubAssign for Matrix<M, N, T> {
+    fn sub_assign(&mut self, rhs: Self) {
+        for i in 0..M {
+            for j in 0..N {
+                self[(i, j)] -= rhs[(i, j)];
+            }
+        }
+    }
+}
+
+impl<const M: usize, const N: usize, T: Real> Neg for Matrix<M, N, T> {
+    type Output = Matrix<M, N, T>;
+    fn neg(self) -> Self::Output {
+        self * -T::ONE
+    }
+}
+
 impl<const M: usize, const N: usize> Mul<Matrix<M, N>> for f32 {
     type Output = Matrix<M, N>;
     fn mul(self, mut rhs: Matrix<M, N>) -> Matrix<M, N> {
@@ -60,9 +118,9 @@ impl<const M: usize, const N: usize> Mul<Matrix<M, N>> for f32 {
     }
 }
 
-impl<const M: usize, const N: usize> Mul<f32> for Matrix<M, N> {
-    type Output = Matrix<M, N>;
-    fn mul(mut self, rhs: f32) -> Matrix<M, N> {
+impl<const M: usize, const N: usize, T: Real> Mul<T> for Matrix<M, N, T> {
+    type Output = Matrix<M, N, T>;
+    fn mul(mut self, rhs: T) -> Matrix<M, N, T> {
         for i in 0..M {
             for j in 0..N {
                 self[(i, j)] *= rhs;
@@ -72,32 +130,52 @@ impl<const M: usize, const N: usize> Mul<f32> for Matrix<M, N> {
     }
 }
 
-impl<const M: usize, const N: usize> Index<(usize, usize)> for Matrix<M, N> {
-    type Output = f32;
+impl<const M: usize, const N: usize, T: Real> MulAssign<T> for Matrix<M, N, T> {
+    fn mul_assign(&mut self, rhs: T) {
+        *self = *self * rhs;
+    }
+}
+
+impl<const M: usize, const N: usize, T: Real> Div<T> for Matrix<M, N, T> {
+    type Output = Matrix<M, N, T>;
+    fn div(mut self, rhs: T) -> Matrix<M, N, T> {
+        for i in 0..M {
+            for j in 0..N {
+                self[(i, j)] /= rhs;
+            }
+        }
+        self
+    }
+}
+
+impl<const M: usize, const N: usize, T> Index<(usize, usize)> for Matrix<M, N, T> {
+    type Output = T;
     fn index(&self, index: (usize, usize)) -> &Self::Output {
         &self.data[index.0][index.1]
     }
 }
 
-impl<const N: usize> Index<usize> for Vector<N> {
-    type Output = f32;
+impl<const N: usize, T> Index<usize> for Vector<N, T> {
+    type Output = T;
     fn index(&self, index: usize) -> &Self::Output {
         &self.data[index][0]
     }
 }
 
-impl<const N: usize> IndexMut<usize> for Vector<N> {
+impl<const N: usize, T> IndexMut<usize> for Vector<N, T> {
     fn index_mut(&mut self, index: usize) -> &mut Self::Output {
-        &mut self.data[index][1]
+        &mut self.data[index][0]
     }
 }
 
-impl<const M: usize, const N: usize> IndexMut<(usize, usize)> for Matrix<M, N> {
+impl<const M: usize, const N: usize, T> IndexMut<(usize, usize)> for Matrix<M, N, T> {
     fn index_mut(&mut self, index: (usize, usize)) -> &mut Self::Output {
         &mut self.data[index.0][index.1]
     }
 }
 
+/// Only `f32` arrays convert, so that `Vector::from([1.0])` stays `f32`.
+/// Vectors of other types are built with [`Matrix::from_fn`] or literals.
 impl<const N: usize> From<[f32; N]> for Vector<N> {
     fn from(value: [f32; N]) -> Self {
         Self {
@@ -105,3 +183,437 @@ impl<const N: usize> From<[f32; N]> for Vector<N> {
         }
     }
 }
+
+/// Dynamically shaped N-dimensional array stored in row-major order.
+///
+/// Unlike [`Matrix`], the shape is only known at runtime, which makes it the
+/// storage type for batched data, tensors of arbitrary rank and everything
+/// built on top of [`crate::linalg::autograd`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct NdArray {
+    shape: Vec<usize>,
+    data: Vec<f32>,
+}
+
+impl NdArray {
+    pub fn from_vec(shape: &[usize], data: Vec<f32>) -> Self {
+        assert_eq!(
+            shape.iter().product::<usize>(),
+            data.len(),
+            "shape {:?} does not match {} elements",
+            shape,
+            data.len()
+        );
+        Self {
+            shape: shape.to_vec(),
+            data,
+        }
+    }
+
+    pub fn full(shape: &[usize], value: f32) -> Self {
+        Self::from_vec(shape, vec![value; shape.iter().product()])
+    }
+
+    pub fn zeros(shape: &[usize]) -> Self {
+        Self::full(shape, 0.0)
+    }
+
+    pub fn ones(shape: &[usize]) -> Self {
+        Self::full(shape, 1.0)
+    }
+
+    /// Zero-dimensional array holding a single value.
+    pub fn scalar(value: f32) -> Self {
+        Self::from_vec(&[], vec![value])
+    }
+
+    pub fn from_fn(shape: &[usize], mut fun: impl FnMut(&[usize]) -> f32) -> Self {
+        let mut index = vec![0; shape.len()];
+        let len = shape.iter().product();
+        let mut data = Vec::with_capacity(len);
+        for _ in 0..len {
+            data.push(fun(&index));
+            increment_index(&mut index, shape);
+        }
+        Self::from_vec(shape, data)
+    }
+
+    pub fn shape(&self) -> &[usize] {
+        &self.shape
+    }
+
+    pub fn ndim(&self) -> usize {
+        self.shape.len()
+    }
+
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    pub fn data(&self) -> &[f32] {
+        &self.data
+    }
+
+    pub fn data_mut(&mut self) -> &mut [f32] {
+        &mut self.data
+    }
+
+    pub fn into_vec(self) -> Vec<f32> {
+        self.data
+    }
+
+    /// Value of an array holding exactly one element.
+    pub fn item(&self) -> f32 {
+        assert_eq!(
+            self.len(),
+            1,
+            "item() called on array of shape {:?}",
+            self.shape
+        );
+        self.data[0]
+    }
+
+    pub fn strides(&self) -> Vec<usize> {
+        strides_of(&self.shape)
+    }
+
+    fn offset(&self, index: &[usize]) -> usize {
+        assert_eq!(
+            index.len(),
+            self.ndim(),
+            "index rank does not match array rank"
+        );
+        index
+            .iter()
+            .zip(&self.shape)
+            .zip(self.strides())
+            .map(|((i, dim), stride)| {
+                assert!(
+                    i < dim,
+                    "index {:?} out of bounds for shape {:?}",
+                    index,
+                    self.shape
+                );
+                i * stride
+            })
+            .sum()
+    }
+
+    pub fn reshape(&self, shape: &[usize]) -> Self {
+        Self::from_vec(shape, self.data.clone())
+    }
+
+    /// Reorders the axes so that axis `axes[i]` of `self` becomes axis `i` of the result.
+    pub fn permute(&self, axes: &[usize]) -> Self {
+        assert_eq!(
+            axes.len(),
+            self.ndim(),
+            "permutation rank does not match array rank"
+        );
+        let strides = self.strides();
+        let shape: Vec<usize> = axes.iter().map(|&axis| self.shape[axis]).collect();
+        let permuted_strides: Vec<usize> = axes.iter().map(|&axis| strides[axis]).collect();
+        let mut index = vec![0; shape.len()];
+        let mut data = Vec::with_capacity(self.len());
+        for _ in 0..self.len() {
+            let offset: usize = index
+                .iter()
+                .zip(&permuted_strides)
+                .map(|(i, s)| i * s)
+                .sum();
+            data.push(self.data[offset]);
+            increment_index(&mut index, &shape);
+        }
+        Self::from_vec(&shape, data)
+    }
+
+    /// Swaps the two axes of a two-dimensional array.
+    pub fn transpose(&self) -> Self {
+        assert_eq!(self.ndim(), 2, "transpose expects a two-dimensional array");
+        self.permute(&[1, 0])
+    }
+
+    pub fn map(&self, fun: impl Fn(f32) -> f32) -> Self {
+        Self {
+            shape: self.shape.clone(),
+            data: self.data.iter().map(|&x| fun(x)).collect(),
+        }
+    }
+
+    /// Combines two arrays elementwise following numpy's broadcasting rules.
+    pub fn zip_map(&self, rhs: &NdArray, fun: impl Fn(f32, f32) -> f32) -> Self {
+        if self.shape == rhs.shape {
+            return Self {
+                shape: self.shape.clone(),
+                data: self
+                    .data
+                    .iter()
+                    .zip(&rhs.data)
+                    .map(|(&a, &b)| fun(a, b))
+                    .collect(),
+            };
+        }
+
+        let shape = broadcast_shape(&self.shape, &rhs.shape).unwrap_or_else(|| {
+            panic!(
+                "shapes {:?} and {:?} can not be broadcast together",
+                self.shape, rhs.shape
+            )
+        });
+        let lhs_strides = broadcast_strides(&self.shape, &shape);
+        let rhs_strides = broadcast_strides(&rhs.shape, &shape);
+
+        let len = shape.iter().product();
+        let mut index = vec![0; shape.len()];
+        let mut data = Vec::with_capacity(len);
+        for _ in 0..len {
+            let lhs_offset: usize = index.iter().zip(&lhs_strides).map(|(i, s)| i * s).sum();
+            let rhs_offset: usize = index.iter().zip(&rhs_strides).map(|(i, s)| i * s).sum();
+            data.push(fun(self.data[lhs_offset], rhs.data[rhs_offset]));
+            increment_index(&mut index, &shape);
+        }
+        Self::from_vec(&shape, data)
+    }
+
+    pub fn sum(&self) -> f32 {
+        self.data.iter().sum()
+    }
+
+    pub fn mean(&self) -> f32 {
+        self.sum() / self.len() as f32
+    }
+
+    pub fn max(&self) -> f32 {
+        self.data.iter().copied().fold(f32::NEG_INFINITY, f32::max)
+    }
+
+    /// Sums over `axis`, keeping it as a dimension of length one.
+    pub fn sum_axis(&self, axis: usize) -> Self {
+        assert!(
+            axis < self.ndim(),
+            "axis {} out of bounds for shape {:?}",
+            axis,
+            self.shape
+        );
+        let mut shape = self.shape.clone();
+        shape[axis] = 1;
+        self.sum_to_shape(&shape)
+    }
+
+    /// Sums broadcast dimensions away until the array has the given shape.
+    ///
+    /// This is the adjoint of broadcasting `shape` up to `self.shape()`.
+    pub fn sum_to_shape(&self, shape: &[usize]) -> Self {
+        if self.shape == shape {
+            return self.clone();
+        }
+        assert!(
+            broadcast_shape(shape, &self.shape).as_deref() == Some(&self.shape[..]),
+            "shape {:?} can not be reduced to {:?}",
+            self.shape,
+            shape
+        );
+
+        let strides = broadcast_strides(shape, &self.shape);
+        let mut output = Self::zeros(shape);
+        let mut index = vec![0; self.ndim()];
+        for value in &self.data {
+            let offset: usize = index.iter().zip(&strides).map(|(i, s)| i * s).sum();
+            output.data[offset] += value;
+            increment_index(&mut index, &self.shape);
+        }
+        output
+    }
+
+    /// Matrix product of two two-dimensional arrays.
+    pub fn matmul(&self, rhs: &NdArray) -> Self {
+        assert!(
+            self.ndim() == 2 && rhs.ndim() == 2 && self.shape[1] == rhs.shape[0],
+            "can not multiply arrays of shape {:?} and {:?}",
+            self.shape,
+            rhs.shape
+        );
+        let (m, k, n) = (self.shape[0], self.shape[1], rhs.shape[1]);
+        let mut data = vec![0.0; m * n];
+        // i-k-j ordering keeps the inner loop on contiguous rows of both operands
+        for i in 0..m {
+            let out_row = &mut data[i * n..(i + 1) * n];
+            for (p, &a) in self.data[i * k..(i + 1) * k].iter().enumerate() {
+                if a == 0.0 {
+                    continue;
+                }
+                for (out, &b) in out_row.iter_mut().zip(&rhs.data[p * n..(p + 1) * n]) {
+                    *out += a * b;
+                }
+            }
+        }
+        Self::from_vec(&[m, n], data)
+    }
+
+    /// Copies the elements into a [`Matrix`], panicking if the shape is not `[M, N]`.
+    pub fn to_matrix<const M: usize, const N: usize>(&self) -> Matrix<M, N> {
+        assert_eq!(
+            self.shape,
+            [M, N],
+            "array can not be converted to a {}x{} matrix",
+            M,
+            N
+        );
+        let mut output = Matrix {
+            data: [[0.0; N]; M],
+        };
+        for (row, chunk) in output.data.iter_mut().zip(self.data.chunks(N.max(1))) {
+            row.copy_from_slice(chunk);
+        }
+        output
+    }
+
+    /// Copies the elements into a [`Vector`], regardless of the array's shape.
+    pub fn to_vector<const N: usize>(&self) -> Vector<N> {
+        assert_eq!(
+            self.len(),
+            N,
+            "array with {} elements can not be converted to a vector of length {}",
+            self.len(),
+            N
+        );
+        let mut output = Vector { data: [[0.0]; N] };
+        for (row, value) in output.data.iter_mut().zip(&self.data) {
+            row[0] = *value;
+        }
+        output
+    }
+}
+
+fn strides_of(shape: &[usize]) -> Vec<usize> {
+    let mut strides = vec![1; shape.len()];
+    for i in (0..shape.len().saturating_sub(1)).rev() {
+        strides[i] = strides[i + 1] * shape[i + 1];
+    }
+    strides
+}
+
+fn increment_index(index: &mut [usize], shape: &[usize]) {
+    for axis in (0..index.len()).rev() {
+        index[axis] += 1;
+        if index[axis] < shape[axis] {
+            return;
+        }
+        index[axis] = 0;
+    }
+}
+
+/// Shape resulting from broadcasting `a` and `b` together, if they are compatible.
+pub fn broadcast_shape(a: &[usize], b: &[usize]) -> Option<Vec<usize>> {
+    let ndim = a.len().max(b.len());
+    let mut shape = vec![0; ndim];
+    for (i, dim) in shape.iter_mut().enumerate() {
+        let a_dim = if i + a.len() >= ndim {
+            a[i + a.len() - ndim]
+        } else {
+            1
+        };
+        let b_dim = if i + b.len() >= ndim {
+            b[i + b.len() - ndim]
+        } else {
+            1
+        };
+        *dim = match (a_dim, b_dim) {
+            (x, y) if x == y => x,
+            (1, y) => y,
+            (x, 1) => x,
+            _ => return None,
+        };
+    }
+    Some(shape)
+}
+
+// Strides that read an array of `shape` as if it had been broadcast to `target`
+fn broadcast_strides(shape: &[usize], target: &[usize]) -> Vec<usize> {
+    let strides = strides_of(shape);
+    let offset = target.len() - shape.len();
+    (0..target.len())
+        .map(|i| {
+            if i < offset || shape[i - offset] == 1 {
+                0
+            } else {
+                strides[i - offset]
+            }
+        })
+        .collect()
+}
+
+impl Index<&[usize]> for NdArray {
+    type Output = f32;
+    fn index(&self, index: &[usize]) -> &Self::Output {
+        &self.data[self.offset(index)]
+    }
+}
+
+impl IndexMut<&[usize]> for NdArray {
+    fn index_mut(&mut self, index: &[usize]) -> &mut Self::Output {
+        let offset = self.offset(index);
+        &mut self.data[offset]
+    }
+}
+
+impl<const M: usize, const N: usize> From<Matrix<M, N>> for NdArray {
+    fn from(value: Matrix<M, N>) -> Self {
+        Self::from_vec(&[M, N], value.data.iter().flatten().copied().collect())
+    }
+}
+
+macro_rules! impl_ndarray_binary_op {
+    ($trait:ident, $method:ident, $op:tt) => {
+        impl $trait<&NdArray> for &NdArray {
+            type Output = NdArray;
+            fn $method(self, rhs: &NdArray) -> NdArray {
+                self.zip_map(rhs, |a, b| a $op b)
+            }
+        }
+
+        impl $trait for NdArray {
+            type Output = NdArray;
+            fn $method(self, rhs: NdArray) -> NdArray {
+                &self $op &rhs
+            }
+        }
+
+        impl $trait<f32> for &NdArray {
+            type Output = NdArray;
+            fn $method(self, rhs: f32) -> NdArray {
+                self.map(|a| a $op rhs)
+            }
+        }
+
+        impl $trait<f32> for NdArray {
+            type Output = NdArray;
+            fn $method(self, rhs: f32) -> NdArray {
+                &self $op rhs
+            }
+        }
+    };
+}
+
+impl_ndarray_binary_op!(Add, add, +);
+impl_ndarray_binary_op!(Sub, sub, -);
+impl_ndarray_binary_op!(Mul, mul, *);
+impl_ndarray_binary_op!(Div, div, /);
+
+impl Neg for &NdArray {
+    type Output = NdArray;
+    fn neg(self) -> NdArray {
+        self.map(|a| -a)
+    }
+}
+
+impl Neg for NdArray {
+    type Output = NdArray;
+    fn neg(self) -> NdArray {
+        -&self
+    }
+}
@@ -0,0 +1,94 @@
+// Scalar types that numerical code can be generic over.
+//
+// Most of the crate works in `f32`. Code that is generic over `Real` also
+// runs in `f64` for long or ill-conditioned computations, and with
+// `Dual` numbers to differentiate through it.
+
+use std::{
+    fmt::Debug,
+    ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign},
+};
+
+/// Real number type with the arithmetic and elementary functions.
+pub trait Real:
+    Copy
+    + Debug
+    + PartialEq
+    + PartialOrd
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Neg<Output = Self>
+    + AddAssign
+    + SubAssign
+    + MulAssign
+    + DivAssign
+    + 'static
+{
+    const ZERO: Self;
+    const ONE: Self;
+
+    /// Rounds a double precision constant, such as a method coefficient.
+    fn from_f64(value: f64) -> Self;
+
+    /// Value in double precision, dropping any derivative part.
+    fn to_f64(self) -> f64;
+
+    fn abs(self) -> Self;
+    fn sqrt(self) -> Self;
+    fn exp(self) -> Self;
+    fn ln(self) -> Self;
+    fn sin(self) -> Self;
+    fn cos(self) -> Self;
+    fn powi(self, exponent: i32) -> Self;
+}
+
+macro_rules! impl_real {
+    ($($float:ty),*) => {
+        $(
+            impl Real for $float {
+                const ZERO: Self = 0.0;
+                const ONE: Self = 1.0;
+
+                fn from_f64(value: f64) -> Self {
+                    value as $float
+                }
+
+                fn to_f64(self) -> f64 {
+                    self as f64
+                }
+
+                fn abs(self) -> Self {
+                    <$float>::abs(self)
+                }
+
+                fn sqrt(self) -> Self {
+                    <$float>::sqrt(self)
+                }
+
+                fn exp(self) -> Self {
+                    <$float>::exp(self)
+                }
+
+                fn ln(self) -> Self {
+                    <$float>::ln(self)
+                }
+
+                fn sin(self) -> Self {
+                    <$float>::sin(self)
+                }
+
+                fn cos(self) -> Self {
+                    <$float>::cos(self)
+                }
+
+                fn powi(self, exponent: i32) -> Self {
+                    <$float>::powi(self, exponent)
+                }
+            }
+        )*
+    };
+}
+
+impl_real!(f32, f64);
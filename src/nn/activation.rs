@@ -0,0 +1,52 @@
+use crate::linalg::autograd::Tensor;
+
+use super::layer::Module;
+
+pub struct ReLU;
+
+impl Module for ReLU {
+    fn forward(&self, input: &Tensor) -> Tensor {
+        input.relu()
+    }
+}
+
+pub struct LeakyReLU {
+    pub slope: f32,
+}
+
+impl Default for LeakyReLU {
+    fn default() -> Self {
+        Self { slope: 0.01 }
+    }
+}
+
+impl Module for LeakyReLU {
+    fn forward(&self, input: &Tensor) -> Tensor {
+        input.leaky_relu(self.slope)
+    }
+}
+
+pub struct Sigmoid;
+
+impl Module for Sigmoid {
+    fn forward(&self, input: &Tensor) -> Tensor {
+        input.sigmoid()
+    }
+}
+
+pub struct Tanh;
+
+impl Module for Tanh {
+    fn forward(&self, input: &Tensor) -> Tensor {
+        input.tanh()
+    }
+}
+
+/// Softmax over the last axis of a `[batch, classes]` tensor.
+pub struct Softmax;
+
+impl Module for Softmax {
+    fn forward(&self, input: &Tensor) -> Tensor {
+        input.softmax()
+    }
+}
@@ -0,0 +1,39 @@
+// Parameter initialization for the layers in `nn`.
+//
+// Initialization draws from a thread-local generator so that models can be
+// created without threading a random source through every constructor.
+// `manual_seed` makes the drawn weights reproducible.
+
+use std::cell::RefCell;
+
+use crate::linalg::ndarray::NdArray;
+use crate::random::{distributions::Uniform, rng::SeedableRng, xoshiro::Xoshiro256StarStar};
+
+const DEFAULT_SEED: u64 = 0x5EED_0C51;
+
+thread_local! {
+    static RNG: RefCell<Xoshiro256StarStar> =
+        RefCell::new(Xoshiro256StarStar::seed_from_u64(DEFAULT_SEED));
+}
+
+/// Reseeds the generator used by the initializers on the current thread.
+pub fn manual_seed(seed: u64) {
+    RNG.with(|rng| *rng.borrow_mut() = Xoshiro256StarStar::seed_from_u64(seed));
+}
+
+pub fn uniform(shape: &[usize], low: f32, high: f32) -> NdArray {
+    let distribution = Uniform::new(low as f64, high as f64);
+    RNG.with(|rng| NdArray::random(shape, &mut *rng.borrow_mut(), &distribution))
+}
+
+/// Glorot/Xavier uniform initialization, suited for `tanh` and `sigmoid` activations.
+pub fn xavier_uniform(shape: &[usize], fan_in: usize, fan_out: usize) -> NdArray {
+    let bound = (6.0 / (fan_in + fan_out) as f32).sqrt();
+    uniform(shape, -bound, bound)
+}
+
+/// He/Kaiming uniform initialization, suited for `relu` activations.
+pub fn kaiming_uniform(shape: &[usize], fan_in: usize) -> NdArray {
+    let bound = (6.0 / fan_in as f32).sqrt();
+    uniform(shape, -bound, bound)
+}
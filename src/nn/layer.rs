@@ -0,0 +1,110 @@
+use crate::linalg::{autograd::Tensor, ndarray::NdArray};
+
+use super::init;
+
+/// Building block of a network mapping a `[batch, features]` tensor to another tensor.
+pub trait Module {
+    fn forward(&self, input: &Tensor) -> Tensor;
+
+    /// Trainable tensors of this module, in a stable order.
+    fn parameters(&self) -> Vec<Tensor> {
+        vec![]
+    }
+
+    fn zero_grad(&self) {
+        for parameter in self.parameters() {
+            parameter.zero_grad();
+        }
+    }
+}
+
+/// Fully connected layer computing `input * weight + bias`.
+pub struct Linear {
+    pub weight: Tensor,
+    pub bias: Tensor,
+}
+
+impl Linear {
+    pub fn new(in_features: usize, out_features: usize) -> Self {
+        Self::from_arrays(
+            init::xavier_uniform(&[in_features, out_features], in_features, out_features),
+            NdArray::zeros(&[1, out_features]),
+        )
+    }
+
+    /// Creates a layer from a `[in, out]` weight and a `[1, out]` bias.
+    pub fn from_arrays(weight: NdArray, bias: NdArray) -> Self {
+        assert_eq!(weight.ndim(), 2, "weight must be a [in, out] array");
+        assert_eq!(
+            bias.shape(),
+            [1, weight.shape()[1]],
+            "bias must be a [1, out] array"
+        );
+        Self {
+            weight: Tensor::parameter(weight),
+            bias: Tensor::parameter(bias),
+        }
+    }
+
+    pub fn in_features(&self) -> usize {
+        self.weight.shape()[0]
+    }
+
+    pub fn out_features(&self) -> usize {
+        self.weight.shape()[1]
+    }
+}
+
+impl Module for Linear {
+    fn forward(&self, input: &Tensor) -> Tensor {
+        &input.matmul(&self.weight) + &self.bias
+    }
+
+    fn parameters(&self) -> Vec<Tensor> {
+        vec![self.weight.clone(), self.bias.clone()]
+    }
+}
+
+/// Chain of modules applied one after another.
+#[derive(Default)]
+pub struct Sequential {
+    layers: Vec<Box<dyn Module>>,
+}
+
+impl Sequential {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with(mut self, layer: impl Module + 'static) -> Self {
+        self.layers.push(Box::new(layer));
+        self
+    }
+
+    pub fn push(&mut self, layer: impl Module + 'static) {
+        self.layers.push(Box::new(layer));
+    }
+
+    pub fn len(&self) -> usize {
+        self.layers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.layers.is_empty()
+    }
+}
+
+impl Module for Sequential {
+    fn forward(&self, input: &Tensor) -> Tensor {
+        self.layers
+            .iter()
+            .fold(input.clone(), |output, layer| layer.forward(&output))
+    }
+
+    fn parameters(&self) -> Vec<Tensor> {
+        self.layers
+            .iter()
+            .flat_map(|layer| layer.parameters())
+            .collect()
+    }
+}
@@ -0,0 +1,42 @@
+use crate::linalg::{autograd::Tensor, ndarray::NdArray};
+
+/// Mean squared error over all elements.
+pub fn mse_loss(prediction: &Tensor, target: &Tensor) -> Tensor {
+    assert_eq!(
+        prediction.shape(),
+        target.shape(),
+        "prediction and target must have the same shape"
+    );
+    (prediction - target).square().mean()
+}
+
+/// Cross-entropy between unnormalized `[batch, classes]` logits and target probabilities
+/// (e.g. one-hot rows), averaged over the batch.
+pub fn cross_entropy(logits: &Tensor, target: &Tensor) -> Tensor {
+    assert_eq!(
+        logits.shape(),
+        target.shape(),
+        "logits and target must have the same shape"
+    );
+    let batch = logits.shape()[0] as f32;
+    (&logits.log_softmax() * target).sum().scale(-1.0 / batch)
+}
+
+/// Cross-entropy against integer class labels, one per row of `logits`.
+pub fn cross_entropy_with_labels(logits: &Tensor, labels: &[usize]) -> Tensor {
+    let shape = logits.shape();
+    assert_eq!(shape.len(), 2, "logits must be a [batch, classes] tensor");
+    assert_eq!(
+        shape[0],
+        labels.len(),
+        "expected one label per row of logits"
+    );
+    let one_hot = NdArray::from_fn(&shape, |index| {
+        if labels[index[0]] == index[1] {
+            1.0
+        } else {
+            0.0
+        }
+    });
+    cross_entropy(logits, &Tensor::new(one_hot))
+}
@@ -0,0 +1,5 @@
+pub mod activation;
+pub mod init;
+pub mod layer;
+pub mod loss;
+pub mod optim;
@@ -0,0 +1,156 @@
+use crate::linalg::{autograd::Tensor, ndarray::NdArray};
+
+pub trait Optimizer {
+    /// Updates every parameter using its accumulated gradient.
+    fn step(&mut self);
+
+    fn parameters(&self) -> &[Tensor];
+
+    fn zero_grad(&self) {
+        for parameter in self.parameters() {
+            parameter.zero_grad();
+        }
+    }
+}
+
+/// Plain stochastic gradient descent.
+pub struct Sgd {
+    parameters: Vec<Tensor>,
+    pub learning_rate: f32,
+}
+
+impl Sgd {
+    pub fn new(parameters: Vec<Tensor>, learning_rate: f32) -> Self {
+        Self {
+            parameters,
+            learning_rate,
+        }
+    }
+}
+
+impl Optimizer for Sgd {
+    fn step(&mut self) {
+        for parameter in &self.parameters {
+            if let Some(grad) = parameter.grad() {
+                parameter.update(|value| *value = &*value - &(grad * self.learning_rate));
+            }
+        }
+    }
+
+    fn parameters(&self) -> &[Tensor] {
+        &self.parameters
+    }
+}
+
+/// Gradient descent with (optionally Nesterov) momentum.
+pub struct Momentum {
+    parameters: Vec<Tensor>,
+    velocities: Vec<NdArray>,
+    pub learning_rate: f32,
+    pub momentum: f32,
+    pub nesterov: bool,
+}
+
+impl Momentum {
+    pub fn new(parameters: Vec<Tensor>, learning_rate: f32, momentum: f32) -> Self {
+        Self {
+            velocities: parameters
+                .iter()
+                .map(|parameter| NdArray::zeros(&parameter.shape()))
+                .collect(),
+            parameters,
+            learning_rate,
+            momentum,
+            nesterov: false,
+        }
+    }
+
+    pub fn nesterov(mut self, nesterov: bool) -> Self {
+        self.nesterov = nesterov;
+        self
+    }
+}
+
+impl Optimizer for Momentum {
+    fn step(&mut self) {
+        for (parameter, velocity) in self.parameters.iter().zip(&mut self.velocities) {
+            let Some(grad) = parameter.grad() else {
+                continue;
+            };
+            *velocity = &(&*velocity * self.momentum) + &grad;
+            let direction = if self.nesterov {
+                &grad + &(&*velocity * self.momentum)
+            } else {
+                velocity.clone()
+            };
+            parameter.update(|value| *value = &*value - &(direction * self.learning_rate));
+        }
+    }
+
+    fn parameters(&self) -> &[Tensor] {
+        &self.parameters
+    }
+}
+
+/// Adam optimizer (Kingma & Ba, 2014) with bias-corrected moment estimates.
+pub struct Adam {
+    parameters: Vec<Tensor>,
+    first_moments: Vec<NdArray>,
+    second_moments: Vec<NdArray>,
+    steps: i32,
+    pub learning_rate: f32,
+    pub beta1: f32,
+    pub beta2: f32,
+    pub epsilon: f32,
+}
+
+impl Adam {
+    pub fn new(parameters: Vec<Tensor>, learning_rate: f32) -> Self {
+        let zeros: Vec<NdArray> = parameters
+            .iter()
+            .map(|parameter| NdArray::zeros(&parameter.shape()))
+            .collect();
+        Self {
+            parameters,
+            first_moments: zeros.clone(),
+            second_moments: zeros,
+            steps: 0,
+            learning_rate,
+            beta1: 0.9,
+            beta2: 0.999,
+            epsilon: 1e-8,
+        }
+    }
+}
+
+impl Optimizer for Adam {
+    fn step(&mut self) {
+        self.steps += 1;
+        let first_correction = 1.0 - self.beta1.powi(self.steps);
+        let second_correction = 1.0 - self.beta2.powi(self.steps);
+
+        for ((parameter, m), v) in self
+            .parameters
+            .iter()
+            .zip(&mut self.first_moments)
+            .zip(&mut self.second_moments)
+        {
+            let Some(grad) = parameter.grad() else {
+                continue;
+            };
+            *m = &(&*m * self.beta1) + &(&grad * (1.0 - self.beta1));
+            *v = &(&*v * self.beta2) + &grad.map(|g| (1.0 - self.beta2) * g * g);
+
+            let update = m.zip_map(v, |m, v| {
+                let m_hat = m / first_correction;
+                let v_hat = v / second_correction;
+                self.learning_rate * m_hat / (v_hat.sqrt() + self.epsilon)
+            });
+            parameter.update(|value| *value = &*value - &update);
+        }
+    }
+
+    fn parameters(&self) -> &[Tensor] {
+        &self.parameters
+    }
+}
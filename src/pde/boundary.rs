@@ -0,0 +1,92 @@
+/// Condition at one end of a grid line.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Boundary {
+    /// Prescribed value at the boundary point.
+    Dirichlet(f32),
+    /// Prescribed outward normal derivative.
+    Neumann(f32),
+    /// The grid wraps around to the other end.
+    Periodic,
+}
+
+impl Boundary {
+    /// Value `distance` points outside the boundary point, given the value
+    /// `mirror` the same distance inside. Dirichlet values are extended by
+    /// odd reflection, Neumann values by even reflection plus the slope.
+    fn ghost(&self, mirror: f32, distance: usize, spacing: f32) -> f32 {
+        match *self {
+            Boundary::Dirichlet(value) => 2.0 * value - mirror,
+            Boundary::Neumann(slope) => mirror + 2.0 * distance as f32 * spacing * slope,
+            Boundary::Periodic => unreachable!("periodic boundaries have no ghost values"),
+        }
+    }
+}
+
+/// Conditions at both ends of one axis.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Boundaries {
+    pub lower: Boundary,
+    pub upper: Boundary,
+}
+
+impl Boundaries {
+    pub fn new(lower: Boundary, upper: Boundary) -> Self {
+        assert_eq!(
+            lower == Boundary::Periodic,
+            upper == Boundary::Periodic,
+            "periodic boundaries must be periodic at both ends"
+        );
+        Self { lower, upper }
+    }
+
+    pub fn dirichlet(lower: f32, upper: f32) -> Self {
+        Self::new(Boundary::Dirichlet(lower), Boundary::Dirichlet(upper))
+    }
+
+    pub fn neumann(lower: f32, upper: f32) -> Self {
+        Self::new(Boundary::Neumann(lower), Boundary::Neumann(upper))
+    }
+
+    pub fn periodic() -> Self {
+        Self::new(Boundary::Periodic, Boundary::Periodic)
+    }
+
+    pub fn is_periodic(&self) -> bool {
+        self.lower == Boundary::Periodic
+    }
+
+    /// Value at `index` of the grid line `line`, extended past its ends.
+    pub(crate) fn value(&self, line: &[f32], index: isize, spacing: f32) -> f32 {
+        let n = line.len() as isize;
+        if (0..n).contains(&index) {
+            return line[index as usize];
+        }
+        if self.is_periodic() {
+            return line[index.rem_euclid(n) as usize];
+        }
+        let (boundary, distance, mirror) = if index < 0 {
+            (&self.lower, -index, -index)
+        } else {
+            (&self.upper, index - (n - 1), 2 * (n - 1) - index)
+        };
+        assert!(
+            mirror >= 0 && mirror < n,
+            "the stencil is too wide for a grid of {} points",
+            n
+        );
+        boundary.ghost(line[mirror as usize], distance as usize, spacing)
+    }
+}
+
+/// Conditions on the four sides of a rectangle.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Boundaries2d {
+    pub x: Boundaries,
+    pub y: Boundaries,
+}
+
+impl Boundaries2d {
+    pub fn new(x: Boundaries, y: Boundaries) -> Self {
+        Self { x, y }
+    }
+}
@@ -0,0 +1,117 @@
+use crate::linalg::ndarray::Vector;
+
+/// Uniform grid of `N` points on an interval.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Grid1d<const N: usize> {
+    start: f32,
+    spacing: f32,
+    periodic: bool,
+}
+
+impl<const N: usize> Grid1d<N> {
+    /// Points from `start` to `end`, both included.
+    pub fn new(start: f32, end: f32) -> Self {
+        assert!(N >= 2, "a grid needs at least two points");
+        assert!(end > start, "the interval must not be empty");
+        Self {
+            start,
+            spacing: (end - start) / (N - 1) as f32,
+            periodic: false,
+        }
+    }
+
+    /// Points on one period from `start` to `end`, where `end` is identified
+    /// with `start` and therefore not a point of the grid.
+    pub fn periodic(start: f32, end: f32) -> Self {
+        assert!(N >= 2, "a grid needs at least two points");
+        assert!(end > start, "the interval must not be empty");
+        Self {
+            start,
+            spacing: (end - start) / N as f32,
+            periodic: true,
+        }
+    }
+
+    pub fn spacing(&self) -> f32 {
+        self.spacing
+    }
+
+    pub fn is_periodic(&self) -> bool {
+        self.periodic
+    }
+
+    pub fn point(&self, index: usize) -> f32 {
+        self.start + index as f32 * self.spacing
+    }
+
+    pub fn points(&self) -> Vector<N> {
+        Vector::from_fn(|i, _| self.point(i))
+    }
+
+    /// Values of `fun` at the points.
+    pub fn sample(&self, fun: impl Fn(f32) -> f32) -> Vector<N> {
+        Vector::from_fn(|i, _| fun(self.point(i)))
+    }
+
+    /// Integral of the grid function `values` over the interval, by the
+    /// trapezoidal rule, which for a periodic grid weights all points equally.
+    pub fn integrate(&self, values: &Vector<N>) -> f32 {
+        let sum: f32 = (0..N).map(|i| values[i]).sum();
+        if self.periodic {
+            self.spacing * sum
+        } else {
+            self.spacing * (sum - 0.5 * (values[0] + values[N - 1]))
+        }
+    }
+}
+
+/// Tensor product of two uniform grids. Grid functions are vectors of size
+/// `M = NX NY` in which the `x` index runs fastest.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Grid2d<const NX: usize, const NY: usize> {
+    pub x: Grid1d<NX>,
+    pub y: Grid1d<NY>,
+}
+
+impl<const NX: usize, const NY: usize> Grid2d<NX, NY> {
+    pub fn new(x: Grid1d<NX>, y: Grid1d<NY>) -> Self {
+        Self { x, y }
+    }
+
+    /// Position of the point `(i, j)` in a grid function.
+    pub fn index(&self, i: usize, j: usize) -> usize {
+        j * NX + i
+    }
+
+    pub fn point(&self, i: usize, j: usize) -> (f32, f32) {
+        (self.x.point(i), self.y.point(j))
+    }
+
+    /// Values of `fun(x, y)` at the points.
+    pub fn sample<const M: usize>(&self, fun: impl Fn(f32, f32) -> f32) -> Vector<M> {
+        check_size::<NX, NY, M>();
+        Vector::from_fn(|k, _| fun(self.x.point(k % NX), self.y.point(k / NX)))
+    }
+
+    /// Integral of the grid function `values`, by the trapezoidal rule in
+    /// both directions.
+    pub fn integrate<const M: usize>(&self, values: &Vector<M>) -> f32 {
+        check_size::<NX, NY, M>();
+        let rows = Vector::<NY>::from_fn(|j, _| {
+            self.x
+                .integrate(&Vector::from_fn(|i, _| values[self.index(i, j)]))
+        });
+        self.y.integrate(&rows)
+    }
+}
+
+pub(crate) fn check_size<const NX: usize, const NY: usize, const M: usize>() {
+    assert_eq!(
+        M,
+        NX * NY,
+        "grid functions on a {}x{} grid have {} values",
+        NX,
+        NY,
+        NX * NY
+    );
+}
@@ -0,0 +1,11 @@
+// Method of lines for partial differential equations on uniform grids.
+//
+// Space is discretized by finite differences, which turns the PDE into a
+// system of ordinary differential equations for the values at the grid
+// points that any solver in `diffeq` can integrate. Boundary conditions are
+// imposed through ghost values outside the grid, so the same stencils apply
+// at every node.
+pub mod boundary;
+pub mod grid;
+pub mod mol;
+pub mod operator;
@@ -0,0 +1,200 @@
+// Semi-discretizations of PDEs, as right-hand sides for the `diffeq` solvers.
+//
+// The values at Dirichlet boundary points are prescribed, so their time
+// derivatives are held at zero and the initial state takes the boundary
+// values. All other points, including Neumann boundary points, evolve with
+// the discretized equation.
+
+use crate::linalg::ndarray::Vector;
+
+use super::{
+    boundary::{Boundaries, Boundaries2d, Boundary},
+    grid::{check_size, Grid1d, Grid2d},
+    operator::{Axis, Operator1d, Operator2d},
+};
+
+/// Sets the entries of `values` at the Dirichlet points in `fixed` to zero.
+fn hold<const N: usize>(fixed: &[Option<f32>], mut values: Vector<N>) -> Vector<N> {
+    for (i, value) in fixed.iter().enumerate() {
+        if value.is_some() {
+            values[i] = 0.0;
+        }
+    }
+    values
+}
+
+/// Sets the entries of `values` at the Dirichlet points to their values.
+fn impose<const N: usize>(fixed: &[Option<f32>], mut values: Vector<N>) -> Vector<N> {
+    for (i, value) in fixed.iter().enumerate() {
+        if let Some(value) = value {
+            values[i] = *value;
+        }
+    }
+    values
+}
+
+fn dirichlet_value(boundary: Boundary) -> Option<f32> {
+    match boundary {
+        Boundary::Dirichlet(value) => Some(value),
+        _ => None,
+    }
+}
+
+/// Method of lines on a 1D grid.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MethodOfLines1d<const N: usize> {
+    grid: Grid1d<N>,
+    boundaries: Boundaries,
+    // Prescribed value of every point
+    fixed: Vec<Option<f32>>,
+}
+
+impl<const N: usize> MethodOfLines1d<N> {
+    pub fn new(grid: Grid1d<N>, boundaries: Boundaries) -> Self {
+        assert_eq!(
+            grid.is_periodic(),
+            boundaries.is_periodic(),
+            "periodic boundaries need a periodic grid"
+        );
+        let mut fixed = vec![None; N];
+        fixed[0] = dirichlet_value(boundaries.lower);
+        fixed[N - 1] = dirichlet_value(boundaries.upper);
+        Self {
+            grid,
+            boundaries,
+            fixed,
+        }
+    }
+
+    pub fn grid(&self) -> &Grid1d<N> {
+        &self.grid
+    }
+
+    pub fn boundaries(&self) -> &Boundaries {
+        &self.boundaries
+    }
+
+    pub fn gradient(&self, order: usize) -> Operator1d<N> {
+        Operator1d::gradient(&self.grid, self.boundaries, order)
+    }
+
+    pub fn laplacian(&self, order: usize) -> Operator1d<N> {
+        Operator1d::laplacian(&self.grid, self.boundaries, order)
+    }
+
+    pub fn upwind(&self, velocity: f32, order: usize) -> Operator1d<N> {
+        Operator1d::upwind(&self.grid, self.boundaries, velocity, order)
+    }
+
+    /// Samples `fun` on the grid, with the Dirichlet values at the boundaries.
+    pub fn initial_state(&self, fun: impl Fn(f32) -> f32) -> Vector<N> {
+        impose(&self.fixed, self.grid.sample(fun))
+    }
+
+    /// Right-hand side of `u_t = rhs(t, u)` for the `diffeq` solvers.
+    pub fn system(
+        &self,
+        rhs: impl Fn(f32, &Vector<N>) -> Vector<N> + 'static,
+    ) -> impl Fn(f32, Vector<N>) -> Vector<N> + 'static {
+        let fixed = self.fixed.clone();
+        move |time, values| hold(&fixed, rhs(time, &values))
+    }
+
+    /// Acceleration of `u_tt = rhs(t, u, u_t)` for the second order solvers,
+    /// such as `solve_second_order` and the Runge-Kutta-Nystrom methods.
+    pub fn second_order_system(
+        &self,
+        rhs: impl Fn(f32, &Vector<N>, &Vector<N>) -> Vector<N> + 'static,
+    ) -> impl Fn(f32, Vector<N>, Vector<N>) -> Vector<N> + 'static {
+        let fixed = self.fixed.clone();
+        move |time, values, rates| hold(&fixed, rhs(time, &values, &rates))
+    }
+}
+
+/// Method of lines on a 2D grid, for grid functions of size `M = NX NY`.
+///
+/// A corner point is prescribed if either of its sides is a Dirichlet
+/// boundary, and takes the value of the `y` side when both are.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MethodOfLines2d<const NX: usize, const NY: usize> {
+    grid: Grid2d<NX, NY>,
+    boundaries: Boundaries2d,
+    fixed: Vec<Option<f32>>,
+}
+
+impl<const NX: usize, const NY: usize> MethodOfLines2d<NX, NY> {
+    pub fn new(grid: Grid2d<NX, NY>, boundaries: Boundaries2d) -> Self {
+        for (periodic_grid, periodic_boundaries) in [
+            (grid.x.is_periodic(), boundaries.x.is_periodic()),
+            (grid.y.is_periodic(), boundaries.y.is_periodic()),
+        ] {
+            assert_eq!(
+                periodic_grid, periodic_boundaries,
+                "periodic boundaries need a periodic grid"
+            );
+        }
+        let mut fixed = vec![None; NX * NY];
+        for j in 0..NY {
+            fixed[grid.index(0, j)] = dirichlet_value(boundaries.x.lower);
+            fixed[grid.index(NX - 1, j)] = dirichlet_value(boundaries.x.upper);
+        }
+        for i in 0..NX {
+            for (j, boundary) in [(0, boundaries.y.lower), (NY - 1, boundaries.y.upper)] {
+                if let Some(value) = dirichlet_value(boundary) {
+                    fixed[grid.index(i, j)] = Some(value);
+                }
+            }
+        }
+        Self {
+            grid,
+            boundaries,
+            fixed,
+        }
+    }
+
+    pub fn grid(&self) -> &Grid2d<NX, NY> {
+        &self.grid
+    }
+
+    pub fn boundaries(&self) -> &Boundaries2d {
+        &self.boundaries
+    }
+
+    pub fn gradient(&self, axis: Axis, order: usize) -> Operator2d<NX, NY> {
+        Operator2d::gradient(&self.grid, self.boundaries, axis, order)
+    }
+
+    pub fn laplacian(&self, order: usize) -> Operator2d<NX, NY> {
+        Operator2d::laplacian(&self.grid, self.boundaries, order)
+    }
+
+    pub fn upwind(&self, velocity: (f32, f32), order: usize) -> Operator2d<NX, NY> {
+        Operator2d::upwind(&self.grid, self.boundaries, velocity, order)
+    }
+
+    /// Samples `fun(x, y)` on the grid, with the Dirichlet values at the
+    /// boundaries.
+    pub fn initial_state<const M: usize>(&self, fun: impl Fn(f32, f32) -> f32) -> Vector<M> {
+        impose(&self.fixed, self.grid.sample(fun))
+    }
+
+    /// Right-hand side of `u_t = rhs(t, u)` for the `diffeq` solvers.
+    pub fn system<const M: usize>(
+        &self,
+        rhs: impl Fn(f32, &Vector<M>) -> Vector<M> + 'static,
+    ) -> impl Fn(f32, Vector<M>) -> Vector<M> + 'static {
+        check_size::<NX, NY, M>();
+        let fixed = self.fixed.clone();
+        move |time, values| hold(&fixed, rhs(time, &values))
+    }
+
+    /// Acceleration of `u_tt = rhs(t, u, u_t)` for the second order solvers.
+    pub fn second_order_system<const M: usize>(
+        &self,
+        rhs: impl Fn(f32, &Vector<M>, &Vector<M>) -> Vector<M> + 'static,
+    ) -> impl Fn(f32, Vector<M>, Vector<M>) -> Vector<M> + 'static {
+        check_size::<NX, NY, M>();
+        let fixed = self.fixed.clone();
+        move |time, values, rates| hold(&fixed, rhs(time, &values, &rates))
+    }
+}
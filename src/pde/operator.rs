@@ -0,0 +1,285 @@
+// Finite difference operators on uniform grids.
+//
+// The central differences are of order 2, 4 or 6 and the upwind differences
+// of order 1 to 3. Coefficients are listed from the leftmost point of the
+// stencil and scaled by the grid spacing when an operator is built.
+
+use crate::linalg::ndarray::Vector;
+
+use super::{
+    boundary::{Boundaries, Boundaries2d},
+    grid::{check_size, Grid1d, Grid2d},
+};
+
+/// Central differences for the first derivative, indexed by `order / 2 - 1`.
+const CENTRAL_FIRST: [&[f64]; 3] = [
+    &[-1.0 / 2.0, 0.0, 1.0 / 2.0],
+    &[1.0 / 12.0, -2.0 / 3.0, 0.0, 2.0 / 3.0, -1.0 / 12.0],
+    &[
+        -1.0 / 60.0,
+        3.0 / 20.0,
+        -3.0 / 4.0,
+        0.0,
+        3.0 / 4.0,
+        -3.0 / 20.0,
+        1.0 / 60.0,
+    ],
+];
+
+/// Central differences for the second derivative, indexed by `order / 2 - 1`.
+const CENTRAL_SECOND: [&[f64]; 3] = [
+    &[1.0, -2.0, 1.0],
+    &[-1.0 / 12.0, 4.0 / 3.0, -5.0 / 2.0, 4.0 / 3.0, -1.0 / 12.0],
+    &[
+        1.0 / 90.0,
+        -3.0 / 20.0,
+        3.0 / 2.0,
+        -49.0 / 18.0,
+        3.0 / 2.0,
+        -3.0 / 20.0,
+        1.0 / 90.0,
+    ],
+];
+
+/// Offset of the leftmost point and coefficients of the upwind first
+/// derivative for a positive velocity, indexed by `order - 1`.
+const UPWIND: [(isize, &[f64]); 3] = [
+    (-1, &[-1.0, 1.0]),
+    (-2, &[1.0 / 2.0, -2.0, 3.0 / 2.0]),
+    (-2, &[1.0 / 6.0, -1.0, 1.0 / 2.0, 1.0 / 3.0]),
+];
+
+fn central(coefficients: [&'static [f64]; 3], order: usize) -> (isize, &'static [f64]) {
+    assert!(
+        matches!(order, 2 | 4 | 6),
+        "central differences are of order 2, 4 or 6, not {}",
+        order
+    );
+    let coefficients = coefficients[order / 2 - 1];
+    (-(coefficients.len() as isize / 2), coefficients)
+}
+
+/// Upwind stencil for the sign of `velocity`, as offset and coefficients.
+fn upwind(velocity: f32, order: usize) -> (isize, Vec<f64>) {
+    assert!(
+        (1..=3).contains(&order),
+        "upwind differences are of order 1 to 3, not {}",
+        order
+    );
+    let (start, coefficients) = UPWIND[order - 1];
+    if velocity >= 0.0 {
+        return (start, coefficients.to_vec());
+    }
+    // The mirrored stencil leans the other way
+    let end = start + coefficients.len() as isize - 1;
+    (-end, coefficients.iter().rev().map(|c| -c).collect())
+}
+
+fn check_boundaries<const N: usize>(grid: &Grid1d<N>, boundaries: &Boundaries) {
+    assert_eq!(
+        grid.is_periodic(),
+        boundaries.is_periodic(),
+        "periodic boundaries need a periodic grid"
+    );
+}
+
+/// Stencil applied along the lines of one axis.
+#[derive(Clone, Debug, PartialEq)]
+struct LineStencil {
+    start: isize,
+    coefficients: Vec<f32>,
+    boundaries: Boundaries,
+    spacing: f32,
+}
+
+impl LineStencil {
+    fn new(
+        start: isize,
+        coefficients: &[f64],
+        scale: f32,
+        boundaries: Boundaries,
+        spacing: f32,
+    ) -> Self {
+        Self {
+            start,
+            coefficients: coefficients.iter().map(|&c| scale * c as f32).collect(),
+            boundaries,
+            spacing,
+        }
+    }
+
+    /// Adds the stencil applied to `line` onto `output`.
+    fn apply_line(&self, line: &[f32], output: &mut [f32]) {
+        let n = line.len() as isize;
+        let end = self.start + self.coefficients.len() as isize - 1;
+        for (i, output) in (0..n).zip(output.iter_mut()) {
+            if i + self.start >= 0 && i + end < n {
+                let first = (i + self.start) as usize;
+                *output += self
+                    .coefficients
+                    .iter()
+                    .zip(&line[first..])
+                    .map(|(c, u)| c * u)
+                    .sum::<f32>();
+            } else {
+                *output += (self.start..=end)
+                    .zip(&self.coefficients)
+                    .map(|(offset, c)| c * self.boundaries.value(line, i + offset, self.spacing))
+                    .sum::<f32>();
+            }
+        }
+    }
+}
+
+/// Finite difference operator on a 1D grid.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Operator1d<const N: usize> {
+    stencil: LineStencil,
+}
+
+impl<const N: usize> Operator1d<N> {
+    /// Central differences for `du/dx`.
+    pub fn gradient(grid: &Grid1d<N>, boundaries: Boundaries, order: usize) -> Self {
+        check_boundaries(grid, &boundaries);
+        let (start, coefficients) = central(CENTRAL_FIRST, order);
+        let h = grid.spacing();
+        Self {
+            stencil: LineStencil::new(start, coefficients, 1.0 / h, boundaries, h),
+        }
+    }
+
+    /// Central differences for `d^2u/dx^2`.
+    pub fn laplacian(grid: &Grid1d<N>, boundaries: Boundaries, order: usize) -> Self {
+        check_boundaries(grid, &boundaries);
+        let (start, coefficients) = central(CENTRAL_SECOND, order);
+        let h = grid.spacing();
+        Self {
+            stencil: LineStencil::new(start, coefficients, 1.0 / (h * h), boundaries, h),
+        }
+    }
+
+    /// Upwind differences for the advection term `a du/dx`, taken from the
+    /// side the constant velocity `a` comes from.
+    pub fn upwind(grid: &Grid1d<N>, boundaries: Boundaries, velocity: f32, order: usize) -> Self {
+        check_boundaries(grid, &boundaries);
+        let (start, coefficients) = upwind(velocity, order);
+        let h = grid.spacing();
+        Self {
+            stencil: LineStencil::new(start, &coefficients, velocity / h, boundaries, h),
+        }
+    }
+
+    pub fn apply(&self, values: &Vector<N>) -> Vector<N> {
+        let mut output = Vector::zeros();
+        self.stencil
+            .apply_line(values.data.as_flattened(), output.data.as_flattened_mut());
+        output
+    }
+}
+
+/// Direction of a 2D grid.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Axis {
+    X,
+    Y,
+}
+
+/// Finite difference operator on a 2D grid, a sum of stencils along the axes.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Operator2d<const NX: usize, const NY: usize> {
+    terms: Vec<(Axis, LineStencil)>,
+}
+
+impl<const NX: usize, const NY: usize> Operator2d<NX, NY> {
+    /// Central differences for the partial derivative along `axis`.
+    pub fn gradient(
+        grid: &Grid2d<NX, NY>,
+        boundaries: Boundaries2d,
+        axis: Axis,
+        order: usize,
+    ) -> Self {
+        let stencil = match axis {
+            Axis::X => Operator1d::gradient(&grid.x, boundaries.x, order).stencil,
+            Axis::Y => Operator1d::gradient(&grid.y, boundaries.y, order).stencil,
+        };
+        Self {
+            terms: vec![(axis, stencil)],
+        }
+    }
+
+    /// Central differences for `d^2u/dx^2 + d^2u/dy^2`.
+    pub fn laplacian(grid: &Grid2d<NX, NY>, boundaries: Boundaries2d, order: usize) -> Self {
+        Self {
+            terms: vec![
+                (
+                    Axis::X,
+                    Operator1d::laplacian(&grid.x, boundaries.x, order).stencil,
+                ),
+                (
+                    Axis::Y,
+                    Operator1d::laplacian(&grid.y, boundaries.y, order).stencil,
+                ),
+            ],
+        }
+    }
+
+    /// Upwind differences for the advection term `a du/dx + b du/dy` with
+    /// the constant velocity `(a, b)`.
+    pub fn upwind(
+        grid: &Grid2d<NX, NY>,
+        boundaries: Boundaries2d,
+        velocity: (f32, f32),
+        order: usize,
+    ) -> Self {
+        Self {
+            terms: vec![
+                (
+                    Axis::X,
+                    Operator1d::upwind(&grid.x, boundaries.x, velocity.0, order).stencil,
+                ),
+                (
+                    Axis::Y,
+                    Operator1d::upwind(&grid.y, boundaries.y, velocity.1, order).stencil,
+                ),
+            ],
+        }
+    }
+
+    /// Operator applying both `self` and `other` and adding the results.
+    pub fn plus(mut self, other: Self) -> Self {
+        self.terms.extend(other.terms);
+        self
+    }
+
+    /// Applies the operator to a grid function of size `M = NX NY`.
+    pub fn apply<const M: usize>(&self, values: &Vector<M>) -> Vector<M> {
+        check_size::<NX, NY, M>();
+        let values = values.data.as_flattened();
+        let mut output = Vector::<M>::zeros();
+        let result = output.data.as_flattened_mut();
+        let mut line = [0.0; NY];
+        let mut column = [0.0; NY];
+        for (axis, stencil) in &self.terms {
+            match axis {
+                Axis::X => {
+                    for (row, result) in values.chunks(NX).zip(result.chunks_mut(NX)) {
+                        stencil.apply_line(row, result);
+                    }
+                }
+                Axis::Y => {
+                    for i in 0..NX {
+                        for j in 0..NY {
+                            line[j] = values[j * NX + i];
+                        }
+                        column.fill(0.0);
+                        stencil.apply_line(&line, &mut column);
+                        for j in 0..NY {
+                            result[j * NX + i] += column[j];
+                        }
+                    }
+                }
+            }
+        }
+        output
+    }
+}
@@ -57,7 +57,7 @@ impl Quad {
         ebo.unbind(BufferType::ElementArray);
 
         Self {
-            vao: vao,
+            vao,
             _vbo: vbo,
             _ebo: ebo,
         }
@@ -126,7 +126,7 @@ impl Figure {
         // TODO: Graph offset
     }
 
-    pub fn recalculate_layout(&mut self, parent: &Box<dyn Layout>) {
+    pub fn recalculate_layout(&mut self, parent: &dyn Layout) {
         self.layout.calculate_size(Some(parent));
     }
 
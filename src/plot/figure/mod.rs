@@ -1,2 +1,3 @@
+#[allow(clippy::module_inception)]
 pub mod figure;
 pub mod figure_renderer;
\ No newline at end of file
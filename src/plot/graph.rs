@@ -5,6 +5,7 @@ use glam::{Vec2, Vec3};
 
 use super::{
     buffer::{Buffer, BufferType},
+    stream::PointStream,
     vao::VertexArray,
 };
 
@@ -14,6 +15,8 @@ pub type AnimationCallback = fn(&mut Vec<Point>) -> ();
 
 pub struct GraphProperties {
     pub anim: Option<AnimationCallback>,
+    /// Points appended to `data` while the window is open.
+    pub stream: Option<PointStream>,
     pub zindex: u32,
     pub data: Vec<Point>,
 }
@@ -22,6 +25,7 @@ impl Default for GraphProperties {
     fn default() -> Self {
         Self {
             anim: None,
+            stream: None,
             zindex: 1,
             data: vec![],
         }
@@ -33,12 +37,13 @@ pub struct Graph {
     pub graph_vao: VertexArray,
     pub graph_vbo: Buffer,
     pub animation: Option<AnimationCallback>,
+    pub stream: Option<PointStream>,
     pub pos: Vec3,
     pub size: Vec2,
 }
 
 impl Graph {
-    fn generate_quads_from_graph(data: &Vec<[f32; 2]>, dist: f32) -> Vec<[f32; 2]> {
+    fn generate_quads_from_graph(data: &[[f32; 2]], dist: f32) -> Vec<[f32; 2]> {
         // For every data point we generate 2 new vertex points
         let mut output = vec![[0.0, 0.0]; 2 * data.len()];
 
@@ -67,7 +72,7 @@ impl Graph {
             let delta_x = -f32::cos(beta) * dist; // This distance is negative, but should be positive
             let delta_y = f32::sin(beta) * dist;
 
-            output[i * 2 + 0] = [point_1[0] + delta_x, point_1[1] + delta_y]; // point_1 + delta
+            output[i * 2] = [point_1[0] + delta_x, point_1[1] + delta_y]; // point_1 + delta
             output[i * 2 + 1] = [point_1[0] - delta_x, point_1[1] - delta_y]; // point_1 - delta
             if !switched {
                 output[i * 2 + 2] = [point_2[0] + delta_x, point_2[1] + delta_y]; // point_2 + delta
@@ -117,14 +122,39 @@ impl Graph {
             graph_vao,
             graph_vbo,
             animation: properties.anim,
-            pos: Vec3::new(250.0, 250.0, -1.0 * properties.zindex as f32),
+            stream: properties.stream,
+            pos: Vec3::new(250.0, 250.0, -(properties.zindex as f32)),
             size: Vec2::new(250.0, 250.0)
         }
     }
 
     pub fn run_animation(&mut self) {
+        let mut changed = false;
         if let Some(animation) = self.animation {
             animation(&mut self.data);
+            changed = true;
         }
+        if let Some(stream) = &mut self.stream {
+            changed |= stream.poll(&mut self.data);
+        }
+        if changed {
+            self.upload_vertices();
+        }
+    }
+
+    /// Replaces the vertices on the GPU after `data` changed
+    fn upload_vertices(&self) {
+        // A line needs at least two points
+        if self.data.len() < 2 {
+            return;
+        }
+        let graph_vertices = Graph::generate_quads_from_graph(&self.data, 0.005);
+        self.graph_vbo.bind(BufferType::Array);
+        self.graph_vbo.buffer_data(
+            BufferType::Array,
+            bytemuck::cast_slice(&graph_vertices),
+            gl::DYNAMIC_DRAW,
+        );
+        self.graph_vbo.unbind(BufferType::Array);
     }
 }
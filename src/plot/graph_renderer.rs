@@ -59,6 +59,10 @@ impl GraphRenderer {
     }
 
     pub fn render(&self, graph: &Graph) {
+        // Streamed graphs start out without a line
+        if graph.data.len() < 2 {
+            return;
+        }
         graph.graph_vao.bind();
 
         let translation = glam::Mat4::from_scale_rotation_translation(
@@ -1 +1,2 @@
+#[allow(clippy::module_inception)]
 pub mod layout;
\ No newline at end of file
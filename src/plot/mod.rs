@@ -6,6 +6,7 @@ pub mod figure;
 pub mod graph;
 pub mod graph_renderer;
 pub mod shader;
+pub mod stream;
 pub mod vao;
 pub mod window;
 pub mod layout;
\ No newline at end of file
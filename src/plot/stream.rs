@@ -0,0 +1,60 @@
+use std::time::Instant;
+
+use super::graph::Point;
+
+/// Points that are appended to a graph while the window is open, each once
+/// the time attached to it has passed. Used to show e.g. an ODE solution
+/// from `diffeq::stream` growing in real time.
+pub struct PointStream {
+    points: Box<dyn Iterator<Item = (f32, Point)>>,
+    /// Next point, which is not due yet.
+    pending: Option<(f32, Point)>,
+    /// Wall-clock time and stream time of the first poll.
+    origin: Option<(Instant, f32)>,
+    time_scale: f32,
+}
+
+impl PointStream {
+    /// `points` yields the points with increasing times.
+    pub fn new(points: impl Iterator<Item = (f32, Point)> + 'static) -> Self {
+        Self {
+            points: Box::new(points),
+            pending: None,
+            origin: None,
+            time_scale: 1.0,
+        }
+    }
+
+    /// Trajectory of `(time, state)` pairs, such as `solver.into_steps(f, dt)`,
+    /// projected to the plane by `projection`.
+    pub fn trajectory<Y>(
+        steps: impl Iterator<Item = (f32, Y)> + 'static,
+        projection: impl Fn(f32, &Y) -> Point + 'static,
+    ) -> Self {
+        Self::new(steps.map(move |(time, state)| (time, projection(time, &state))))
+    }
+
+    /// Stream time that passes per second, 1 by default.
+    pub fn with_time_scale(mut self, time_scale: f32) -> Self {
+        assert!(time_scale > 0.0, "the time scale must be positive");
+        self.time_scale = time_scale;
+        self
+    }
+
+    /// Appends the points that are due to `data` and returns whether there
+    /// were any. The clock starts with the first call.
+    pub fn poll(&mut self, data: &mut Vec<Point>) -> bool {
+        let mut added = false;
+        while let Some((time, point)) = self.pending.take().or_else(|| self.points.next()) {
+            let (start, start_time) = *self.origin.get_or_insert((Instant::now(), time));
+            let due = start_time + start.elapsed().as_secs_f32() * self.time_scale;
+            if time > due {
+                self.pending = Some((time, point));
+                break;
+            }
+            data.push(point);
+            added = true;
+        }
+        added
+    }
+}
@@ -145,26 +145,24 @@ impl PlotWindow {
                         let pos = self.plot_context.window.get_pos();
                         self.plot_context.window.set_pos(pos.0 + 1, pos.1);
                     }
-                    glfw::WindowEvent::MouseButton(btn, action, _) => {
-                        if btn == MouseButton::Button1 {
-                            if action == Action::Press {
-                                if init_pos.is_none() {
-                                    let c_pos = self.plot_context.window.get_cursor_pos();
-                                    let window_pos = self.plot_context.window.get_pos();
-                                    let window_size = self.plot_context.window.get_size();
-
-                                    let x_scaled = (c_pos.0 as f32 - window_pos.0 as f32)
-                                        / window_size.0 as f32;
-                                    let y_scaled = (c_pos.1 as f32 - window_pos.1 as f32)
-                                        / window_size.1 as f32;
-                                    init_pos = Some(Vec2 {
-                                        x: x_scaled + off_x,
-                                        y: y_scaled + off_y,
-                                    });
-                                }
-                            } else if action == Action::Release {
-                                init_pos = None;
+                    glfw::WindowEvent::MouseButton(MouseButton::Button1, action, _) => {
+                        if action == Action::Press {
+                            if init_pos.is_none() {
+                                let c_pos = self.plot_context.window.get_cursor_pos();
+                                let window_pos = self.plot_context.window.get_pos();
+                                let window_size = self.plot_context.window.get_size();
+
+                                let x_scaled = (c_pos.0 as f32 - window_pos.0 as f32)
+                                    / window_size.0 as f32;
+                                let y_scaled = (c_pos.1 as f32 - window_pos.1 as f32)
+                                    / window_size.1 as f32;
+                                init_pos = Some(Vec2 {
+                                    x: x_scaled + off_x,
+                                    y: y_scaled + off_y,
+                                });
                             }
+                        } else if action == Action::Release {
+                            init_pos = None;
                         }
                     }
                     glfw::WindowEvent::CursorPos(x, y) => {
@@ -198,6 +196,10 @@ impl PlotWindow {
                 }
             }
 
+            for figure in &mut self.figures {
+                figure.update();
+            }
+
             for figure in &self.figures {
                 self.figure_renderer.render(figure);
                 for graph in &figure.graphs {
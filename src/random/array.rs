@@ -0,0 +1,25 @@
+// Constructors for arrays filled with random samples.
+
+use crate::linalg::ndarray::{Matrix, NdArray};
+
+use super::{distributions::Distribution, rng::Rng};
+
+impl<const M: usize, const N: usize> Matrix<M, N> {
+    pub fn random<R: Rng + ?Sized>(rng: &mut R, distribution: &impl Distribution<f32>) -> Self {
+        let mut output = Self::zeros();
+        for value in output.data.iter_mut().flatten() {
+            *value = distribution.sample(rng);
+        }
+        output
+    }
+}
+
+impl NdArray {
+    pub fn random<R: Rng + ?Sized>(
+        shape: &[usize],
+        rng: &mut R,
+        distribution: &impl Distribution<f32>,
+    ) -> Self {
+        NdArray::from_fn(shape, |_| distribution.sample(rng))
+    }
+}
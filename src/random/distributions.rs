@@ -0,0 +1,323 @@
+// Probability distributions that turn the raw bits of an `Rng` into samples.
+//
+// Continuous distributions are computed in double precision and can be
+// sampled as `f64` or `f32`; discrete distributions yield `u64` counts.
+
+use std::f64::consts::PI;
+
+use super::rng::Rng;
+
+pub trait Distribution<T> {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> T;
+
+    fn sample_n<R: Rng + ?Sized>(&self, rng: &mut R, count: usize) -> Vec<T> {
+        (0..count).map(|_| self.sample(rng)).collect()
+    }
+}
+
+// Every continuous distribution implements `f64` sampling, `f32` follows by rounding
+macro_rules! impl_f32_sampling {
+    ($($distribution:ty),*) => {
+        $(
+            impl Distribution<f32> for $distribution {
+                fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> f32 {
+                    Distribution::<f64>::sample(self, rng) as f32
+                }
+            }
+        )*
+    };
+}
+
+/// Continuous uniform distribution on `[low, high)`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Uniform {
+    pub low: f64,
+    pub high: f64,
+}
+
+impl Uniform {
+    pub fn new(low: f64, high: f64) -> Self {
+        assert!(low < high, "Uniform needs low < high");
+        Self { low, high }
+    }
+}
+
+impl Distribution<f64> for Uniform {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> f64 {
+        self.low + (self.high - self.low) * rng.next_f64()
+    }
+}
+
+/// Normal (Gaussian) distribution.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Normal {
+    pub mean: f64,
+    pub std_dev: f64,
+}
+
+impl Normal {
+    pub fn new(mean: f64, std_dev: f64) -> Self {
+        assert!(
+            std_dev >= 0.0,
+            "Normal needs a non-negative standard deviation"
+        );
+        Self { mean, std_dev }
+    }
+
+    pub fn standard() -> Self {
+        Self::new(0.0, 1.0)
+    }
+}
+
+// Marsaglia's polar method
+fn standard_normal<R: Rng + ?Sized>(rng: &mut R) -> f64 {
+    loop {
+        let u = 2.0 * rng.next_f64() - 1.0;
+        let v = 2.0 * rng.next_f64() - 1.0;
+        let s = u * u + v * v;
+        if s > 0.0 && s < 1.0 {
+            return u * (-2.0 * s.ln() / s).sqrt();
+        }
+    }
+}
+
+impl Distribution<f64> for Normal {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> f64 {
+        self.mean + self.std_dev * standard_normal(rng)
+    }
+}
+
+/// Exponential distribution with the given rate (inverse mean).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Exponential {
+    pub rate: f64,
+}
+
+impl Exponential {
+    pub fn new(rate: f64) -> Self {
+        assert!(rate > 0.0, "Exponential needs a positive rate");
+        Self { rate }
+    }
+}
+
+impl Distribution<f64> for Exponential {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> f64 {
+        -(1.0 - rng.next_f64()).ln() / self.rate
+    }
+}
+
+/// Gamma distribution with shape `k` and scale `theta`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Gamma {
+    pub shape: f64,
+    pub scale: f64,
+}
+
+impl Gamma {
+    pub fn new(shape: f64, scale: f64) -> Self {
+        assert!(
+            shape > 0.0 && scale > 0.0,
+            "Gamma needs a positive shape and scale"
+        );
+        Self { shape, scale }
+    }
+}
+
+// Marsaglia and Tsang (2000), with the boost for shapes below one
+fn standard_gamma<R: Rng + ?Sized>(rng: &mut R, shape: f64) -> f64 {
+    if shape < 1.0 {
+        let boost = (1.0 - rng.next_f64()).powf(1.0 / shape);
+        return standard_gamma(rng, shape + 1.0) * boost;
+    }
+
+    let d = shape - 1.0 / 3.0;
+    let c = 1.0 / (9.0 * d).sqrt();
+    loop {
+        let x = standard_normal(rng);
+        let v = (1.0 + c * x).powi(3);
+        if v <= 0.0 {
+            continue;
+        }
+        let u = rng.next_f64();
+        if u < 1.0 - 0.0331 * x.powi(4) || u.ln() < 0.5 * x * x + d * (1.0 - v + v.ln()) {
+            return d * v;
+        }
+    }
+}
+
+impl Distribution<f64> for Gamma {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> f64 {
+        self.scale * standard_gamma(rng, self.shape)
+    }
+}
+
+/// Beta distribution on `[0, 1]`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Beta {
+    pub alpha: f64,
+    pub beta: f64,
+}
+
+impl Beta {
+    pub fn new(alpha: f64, beta: f64) -> Self {
+        assert!(alpha > 0.0 && beta > 0.0, "Beta needs positive parameters");
+        Self { alpha, beta }
+    }
+}
+
+impl Distribution<f64> for Beta {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> f64 {
+        let x = standard_gamma(rng, self.alpha);
+        let y = standard_gamma(rng, self.beta);
+        x / (x + y)
+    }
+}
+
+impl_f32_sampling!(Uniform, Normal, Exponential, Gamma, Beta);
+
+/// Poisson distribution with mean `lambda`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Poisson {
+    pub lambda: f64,
+}
+
+impl Poisson {
+    pub fn new(lambda: f64) -> Self {
+        assert!(lambda >= 0.0, "Poisson needs a non-negative mean");
+        Self { lambda }
+    }
+}
+
+impl Distribution<u64> for Poisson {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> u64 {
+        if self.lambda < 10.0 {
+            // Multiply uniforms until the product drops below exp(-lambda)
+            let limit = (-self.lambda).exp();
+            let mut count = 0;
+            let mut product = rng.next_f64();
+            while product > limit {
+                count += 1;
+                product *= rng.next_f64();
+            }
+            return count;
+        }
+
+        // Transformed rejection with squeeze (Hoermann, 1993)
+        let lambda = self.lambda;
+        let sqrt_lambda = lambda.sqrt();
+        let log_lambda = lambda.ln();
+        let b = 0.931 + 2.53 * sqrt_lambda;
+        let a = -0.059 + 0.02483 * b;
+        let inv_alpha = 1.1239 + 1.1328 / (b - 3.4);
+        let v_r = 0.9277 - 3.6224 / (b - 2.0);
+        loop {
+            let u = rng.next_f64() - 0.5;
+            let v = rng.next_f64();
+            let us = 0.5 - u.abs();
+            let k = ((2.0 * a / us + b) * u + lambda + 0.43).floor();
+            if us >= 0.07 && v <= v_r {
+                return k as u64;
+            }
+            if k < 0.0 || (us < 0.013 && v > us) {
+                continue;
+            }
+            if v.ln() + inv_alpha.ln() - (a / (us * us) + b).ln()
+                <= -lambda + k * log_lambda - ln_gamma(k + 1.0)
+            {
+                return k as u64;
+            }
+        }
+    }
+}
+
+/// Number of successes in `trials` independent Bernoulli experiments.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Binomial {
+    pub trials: u64,
+    pub probability: f64,
+}
+
+impl Binomial {
+    pub fn new(trials: u64, probability: f64) -> Self {
+        assert!(
+            (0.0..=1.0).contains(&probability),
+            "Binomial needs a probability in [0, 1]"
+        );
+        Self {
+            trials,
+            probability,
+        }
+    }
+}
+
+fn binomial<R: Rng + ?Sized>(rng: &mut R, trials: u64, probability: f64) -> u64 {
+    if trials == 0 || probability <= 0.0 {
+        return 0;
+    }
+    if probability >= 1.0 {
+        return trials;
+    }
+    if probability > 0.5 {
+        return trials - binomial(rng, trials, 1.0 - probability);
+    }
+
+    if trials as f64 * probability < 10.0 {
+        // Count geometric waiting times until they exceed the number of trials
+        let log_q = (1.0 - probability).ln();
+        let mut successes = 0;
+        let mut position = 0.0;
+        loop {
+            position += ((1.0 - rng.next_f64()).ln() / log_q).floor() + 1.0;
+            if position > trials as f64 {
+                return successes;
+            }
+            successes += 1;
+        }
+    }
+
+    // The a-th order statistic of the uniforms splits the trials in two (Knuth, TAOCP 3.4.1)
+    let a = 1 + trials / 2;
+    let b = trials + 1 - a;
+    let x: f64 = Beta::new(a as f64, b as f64).sample(rng);
+    if x >= probability {
+        binomial(rng, a - 1, probability / x)
+    } else {
+        a + binomial(rng, b - 1, (probability - x) / (1.0 - x))
+    }
+}
+
+impl Distribution<u64> for Binomial {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> u64 {
+        binomial(rng, self.trials, self.probability)
+    }
+}
+
+/// Natural logarithm of the gamma function (Lanczos approximation, g = 7).
+pub fn ln_gamma(x: f64) -> f64 {
+    const COEFFICIENTS: [f64; 9] = [
+        0.999_999_999_999_809_9,
+        676.520_368_121_885_1,
+        -1_259.139_216_722_402_8,
+        771.323_428_777_653_1,
+        -176.615_029_162_140_6,
+        12.507_343_278_686_905,
+        -0.138_571_095_265_720_12,
+        9.984_369_578_019_572e-6,
+        1.505_632_735_149_311_6e-7,
+    ];
+
+    if x < 0.5 {
+        // Reflection formula
+        return (PI / (PI * x).sin()).ln() - ln_gamma(1.0 - x);
+    }
+
+    let x = x - 1.0;
+    let t = x + 7.5;
+    let series = COEFFICIENTS[1..]
+        .iter()
+        .enumerate()
+        .fold(COEFFICIENTS[0], |acc, (i, c)| {
+            acc + c / (x + i as f64 + 1.0)
+        });
+    0.5 * (2.0 * PI).ln() + (x + 0.5) * t.ln() - t + series.ln()
+}
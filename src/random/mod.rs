@@ -0,0 +1,6 @@
+pub mod array;
+pub mod distributions;
+pub mod pcg;
+pub mod rng;
+pub mod seq;
+pub mod xoshiro;
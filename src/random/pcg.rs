@@ -0,0 +1,76 @@
+use super::rng::{Rng, SeedableRng, SplitMix64};
+
+const MULTIPLIER: u128 = 0x2360_ED05_1FC6_5DA4_4385_DF64_9FCC_F645;
+
+/// PCG64 (XSL-RR 128/64) by O'Neill: a 128 bit LCG with a permuted 64 bit output.
+///
+/// Every odd increment selects a different stream, so generators created with
+/// the same seed but different `stream` values are independent.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Pcg64 {
+    state: u128,
+    increment: u128,
+}
+
+impl Pcg64 {
+    pub fn new(seed: u128, stream: u128) -> Self {
+        let increment = (stream << 1) | 1;
+        let mut pcg = Self {
+            state: seed.wrapping_add(increment),
+            increment,
+        };
+        pcg.step();
+        pcg
+    }
+
+    /// Generator on another stream, seeded from this one.
+    pub fn split(&mut self) -> Self {
+        let seed = ((self.next_u64() as u128) << 64) | self.next_u64() as u128;
+        let stream = ((self.next_u64() as u128) << 64) | self.next_u64() as u128;
+        Self::new(seed, stream)
+    }
+
+    /// Advances the generator by `delta` steps in `O(log delta)`.
+    pub fn advance(&mut self, mut delta: u128) {
+        // Brown's algorithm: compose the affine map x -> a * x + c with itself
+        let mut acc_mult: u128 = 1;
+        let mut acc_plus: u128 = 0;
+        let mut cur_mult = MULTIPLIER;
+        let mut cur_plus = self.increment;
+        while delta > 0 {
+            if delta & 1 == 1 {
+                acc_mult = acc_mult.wrapping_mul(cur_mult);
+                acc_plus = acc_plus.wrapping_mul(cur_mult).wrapping_add(cur_plus);
+            }
+            cur_plus = cur_mult.wrapping_add(1).wrapping_mul(cur_plus);
+            cur_mult = cur_mult.wrapping_mul(cur_mult);
+            delta >>= 1;
+        }
+        self.state = acc_mult.wrapping_mul(self.state).wrapping_add(acc_plus);
+    }
+
+    fn step(&mut self) {
+        self.state = self
+            .state
+            .wrapping_mul(MULTIPLIER)
+            .wrapping_add(self.increment);
+    }
+}
+
+impl Rng for Pcg64 {
+    fn next_u64(&mut self) -> u64 {
+        self.step();
+        let rotation = (self.state >> 122) as u32;
+        let xored = ((self.state >> 64) as u64) ^ (self.state as u64);
+        xored.rotate_right(rotation)
+    }
+}
+
+impl SeedableRng for Pcg64 {
+    fn seed_from_u64(seed: u64) -> Self {
+        let mut seeder = SplitMix64::new(seed);
+        let state = ((seeder.next_u64() as u128) << 64) | seeder.next_u64() as u128;
+        let stream = ((seeder.next_u64() as u128) << 64) | seeder.next_u64() as u128;
+        Self::new(state, stream)
+    }
+}
@@ -0,0 +1,56 @@
+use super::rng::Rng;
+
+/// Shuffles the slice in place (Fisher-Yates).
+pub fn shuffle<T, R: Rng + ?Sized>(rng: &mut R, values: &mut [T]) {
+    for i in (1..values.len()).rev() {
+        values.swap(i, rng.gen_index(i + 1));
+    }
+}
+
+/// Uniformly chosen element, or `None` for an empty slice.
+pub fn choice<'a, T, R: Rng + ?Sized>(rng: &mut R, values: &'a [T]) -> Option<&'a T> {
+    if values.is_empty() {
+        None
+    } else {
+        Some(&values[rng.gen_index(values.len())])
+    }
+}
+
+/// `count` distinct elements in random order, drawn without replacement.
+pub fn choose_multiple<'a, T, R: Rng + ?Sized>(
+    rng: &mut R,
+    values: &'a [T],
+    count: usize,
+) -> Vec<&'a T> {
+    assert!(
+        count <= values.len(),
+        "can not choose {} out of {} elements",
+        count,
+        values.len()
+    );
+    // Partial Fisher-Yates over the indices
+    let mut indices: Vec<usize> = (0..values.len()).collect();
+    for i in 0..count {
+        let j = i + rng.gen_index(values.len() - i);
+        indices.swap(i, j);
+    }
+    indices[..count].iter().map(|&i| &values[i]).collect()
+}
+
+/// Index drawn with probability proportional to its (non-negative) weight.
+pub fn weighted_index<R: Rng + ?Sized>(rng: &mut R, weights: &[f64]) -> usize {
+    let total: f64 = weights.iter().sum();
+    assert!(
+        total > 0.0 && weights.iter().all(|&w| w >= 0.0),
+        "weights must be non-negative with a positive sum"
+    );
+    let mut target = rng.next_f64() * total;
+    for (i, weight) in weights.iter().enumerate() {
+        if target < *weight {
+            return i;
+        }
+        target -= weight;
+    }
+    // Only reachable through rounding, fall back to the last non-zero weight
+    weights.iter().rposition(|&w| w > 0.0).unwrap()
+}
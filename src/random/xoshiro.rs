@@ -0,0 +1,97 @@
+use super::rng::{Rng, SeedableRng, SplitMix64};
+
+/// xoshiro256** by Blackman and Vigna: fast, 256 bits of state and a period of `2^256 - 1`.
+///
+/// Independent streams for parallel use are obtained with [`Xoshiro256StarStar::jump`],
+/// which advances the generator by `2^128` steps.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Xoshiro256StarStar {
+    state: [u64; 4],
+}
+
+const JUMP: [u64; 4] = [
+    0x180E_C6D3_3CFD_0ABA,
+    0xD5A6_1266_F0C9_392C,
+    0xA958_2618_E03F_C9AA,
+    0x39AB_DC45_29B1_661C,
+];
+
+const LONG_JUMP: [u64; 4] = [
+    0x76E1_5D3E_FEFD_CBBF,
+    0xC500_4E44_1C52_2FB3,
+    0x7771_0069_854E_E241,
+    0x3910_9BB0_2ACB_E635,
+];
+
+impl Xoshiro256StarStar {
+    /// Creates a generator from its raw state, which must not be all zeros.
+    pub fn from_state(state: [u64; 4]) -> Self {
+        assert!(
+            state.iter().any(|&word| word != 0),
+            "the state of xoshiro256** must not be all zeros"
+        );
+        Self { state }
+    }
+
+    /// Advances the generator by `2^128` steps.
+    pub fn jump(&mut self) {
+        self.apply_jump(&JUMP);
+    }
+
+    /// Advances the generator by `2^192` steps.
+    pub fn long_jump(&mut self) {
+        self.apply_jump(&LONG_JUMP);
+    }
+
+    /// Returns a generator for the current stream and moves `self` `2^128` steps ahead,
+    /// so repeated calls hand out non-overlapping streams.
+    pub fn split(&mut self) -> Self {
+        let stream = self.clone();
+        self.jump();
+        stream
+    }
+
+    fn apply_jump(&mut self, polynomial: &[u64; 4]) {
+        let mut state = [0; 4];
+        for word in polynomial {
+            for bit in 0..64 {
+                if word & (1 << bit) != 0 {
+                    for (acc, s) in state.iter_mut().zip(self.state) {
+                        *acc ^= s;
+                    }
+                }
+                self.next_u64();
+            }
+        }
+        self.state = state;
+    }
+}
+
+impl Rng for Xoshiro256StarStar {
+    fn next_u64(&mut self) -> u64 {
+        let result = self.state[1].wrapping_mul(5).rotate_left(7).wrapping_mul(9);
+        let t = self.state[1] << 17;
+
+        self.state[2] ^= self.state[0];
+        self.state[3] ^= self.state[1];
+        self.state[1] ^= self.state[2];
+        self.state[0] ^= self.state[3];
+        self.state[2] ^= t;
+        self.state[3] = self.state[3].rotate_left(45);
+
+        result
+    }
+}
+
+impl SeedableRng for Xoshiro256StarStar {
+    fn seed_from_u64(seed: u64) -> Self {
+        let mut seeder = SplitMix64::new(seed);
+        // SplitMix64 never yields four zeros in a row
+        Self::from_state([
+            seeder.next_u64(),
+            seeder.next_u64(),
+            seeder.next_u64(),
+            seeder.next_u64(),
+        ])
+    }
+}
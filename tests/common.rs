@@ -1 +1,22 @@
+mod diffeq;
 mod linalg;
+mod nn;
+mod pde;
+mod random;
+
+/// Asserts that `error(steps)` shrinks at least with `order`, up to `slack`,
+/// when the number of steps is doubled from `coarse_steps`.
+pub fn assert_convergence_order(
+    error: impl Fn(usize) -> f32,
+    order: usize,
+    coarse_steps: usize,
+    slack: f32,
+) {
+    let observed = (error(coarse_steps) / error(2 * coarse_steps)).log2();
+    assert!(
+        observed > order as f32 - slack,
+        "expected order {}, observed {}",
+        order,
+        observed
+    );
+}
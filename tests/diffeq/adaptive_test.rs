@@ -0,0 +1,153 @@
+use csl::{
+    diffeq::{
+        adaptive::{AdaptiveRungeKutta, Dop853, Rk23, Rk45, Tsit5},
+        control::{StepController, Tolerances},
+        error::SolverError,
+        ivp::SolveFun,
+        tableau::{
+            BogackiShampine, ButcherTableau, DormandPrince, DormandPrince853, EmbeddedTableau,
+            Tsitouras,
+        },
+    },
+    linalg::ndarray::Vector,
+};
+
+use super::{oscillator, oscillator_deviation};
+
+fn assert_embedded_consistent<T: EmbeddedTableau>() {
+    assert_eq!(T::A.len(), T::stages());
+    assert_eq!(T::C.len(), T::stages());
+    assert_eq!(T::E.len(), T::stages() + 1);
+    assert!((T::B.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+    // The embedded weights B - E also sum to one
+    assert!(T::E.iter().sum::<f64>().abs() < 1e-12);
+    for (i, row) in T::A.iter().enumerate() {
+        assert_eq!(row.len(), i);
+        assert!((row.iter().sum::<f64>() - T::C[i]).abs() < 1e-12);
+    }
+}
+
+#[test]
+fn embedded_tableau_consistency_test() {
+    assert_embedded_consistent::<BogackiShampine>();
+    assert_embedded_consistent::<DormandPrince>();
+    assert_embedded_consistent::<Tsitouras>();
+    assert_embedded_consistent::<DormandPrince853>();
+    assert_eq!(DormandPrince853::E3.len(), DormandPrince853::stages() + 1);
+    assert!(DormandPrince853::E3.iter().sum::<f64>().abs() < 1e-12);
+}
+
+fn oscillator_error<T: EmbeddedTableau>(tolerances: Tolerances<Vector<2>>) -> (f32, usize) {
+    let end_time = 10.0;
+    let mut solver =
+        AdaptiveRungeKutta::<T, Vector<2>>::new(0.0, Vector::from([1.0, 0.0]), tolerances);
+    let state = *solver.integrate_to(&oscillator, end_time).unwrap();
+    assert_eq!(solver.time(), end_time);
+    let error = oscillator_deviation(&state, end_time);
+    (error, solver.statistics().evaluations)
+}
+
+#[test]
+fn tolerance_is_met_test() {
+    let tolerances = Tolerances::new(1e-5, 1e-7);
+    for (error, _) in [
+        oscillator_error::<BogackiShampine>(tolerances),
+        oscillator_error::<DormandPrince>(tolerances),
+        oscillator_error::<Tsitouras>(tolerances),
+        oscillator_error::<DormandPrince853>(tolerances),
+    ] {
+        assert!(error < 1e-3, "global error {}", error);
+    }
+}
+
+#[test]
+fn higher_order_is_cheaper_test() {
+    let tolerances = Tolerances::new(1e-6, 1e-8);
+    let (_, rk23) = oscillator_error::<BogackiShampine>(tolerances);
+    let (_, rk45) = oscillator_error::<DormandPrince>(tolerances);
+    let (_, dop853) = oscillator_error::<DormandPrince853>(tolerances);
+    assert!(rk45 < rk23, "rk45 {} rk23 {}", rk45, rk23);
+    assert!(dop853 < rk45, "dop853 {} rk45 {}", dop853, rk45);
+}
+
+#[test]
+fn tighter_tolerance_is_more_accurate_test() {
+    let (loose, loose_evaluations) = oscillator_error::<DormandPrince>(Tolerances::new(1e-3, 1e-6));
+    let (tight, tight_evaluations) = oscillator_error::<DormandPrince>(Tolerances::new(1e-6, 1e-8));
+    assert!(tight < loose);
+    assert!(tight_evaluations > loose_evaluations);
+}
+
+#[test]
+fn statistics_test() {
+    let mut solver = Rk45::new(0.0, Vector::from([1.0, 0.0]), Tolerances::default());
+    solver.integrate_to(&oscillator, 5.0).unwrap();
+    let statistics = solver.statistics();
+    // f(t0, y0) and the initial step estimate, then six new stages per attempt
+    let attempts = statistics.accepted_steps + statistics.rejected_steps;
+    assert_eq!(statistics.evaluations, 2 + 6 * attempts);
+    assert!(statistics.accepted_steps > 0);
+}
+
+#[test]
+fn step_rejection_test() {
+    // A large first step on a fast decaying problem has to be rejected
+    let fun: Box<SolveFun<Vector<1>>> = Box::new(|_, y| -50.0 * y);
+    let mut solver =
+        Rk23::new(0.0, Vector::from([1.0]), Tolerances::new(1e-4, 1e-7)).with_first_step(0.3);
+    solver.step(&fun, 1.0).unwrap();
+    assert!(solver.statistics().rejected_steps > 0);
+    assert!(solver.last_step_size() < 0.3);
+    let exact = (-50.0 * solver.time()).exp();
+    assert!((solver.state()[0] - exact).abs() < 1e-3);
+}
+
+#[test]
+fn per_component_tolerances_test() {
+    // Decoupled components with very different scales
+    let fun: Box<SolveFun<Vector<2>>> = Box::new(|_, y| Vector::from([-y[0], -y[1]]));
+    let tolerances =
+        Tolerances::per_component(Vector::from([1e-6, 1e-6]), Vector::from([1e-9, 1e-3]));
+    let mut solver = Tsit5::new(0.0, Vector::from([1e-3, 1e3]), tolerances);
+    let state = *solver.integrate_to(&fun, 2.0).unwrap();
+    let decay = (-2.0f32).exp();
+    assert!((state[0] - 1e-3 * decay).abs() < 1e-7);
+    assert!((state[1] - 1e3 * decay).abs() / (1e3 * decay) < 1e-4);
+}
+
+#[test]
+fn backward_integration_test() {
+    let mut solver = Dop853::new(0.0, Vector::from([1.0, 0.0]), Tolerances::new(1e-6, 1e-9));
+    let state = *solver.integrate_to(&oscillator, -3.0).unwrap();
+    assert_eq!(solver.time(), -3.0);
+    assert!((state[0] - 3.0f32.cos()).abs() < 1e-4);
+    assert!((state[1] - 3.0f32.sin()).abs() < 1e-4);
+}
+
+#[test]
+fn max_step_test() {
+    let mut solver =
+        Rk45::new(0.0, Vector::from([1.0, 0.0]), Tolerances::default()).with_max_step(0.1);
+    while solver.time() < 1.0 {
+        solver.step(&oscillator, 1.0).unwrap();
+        assert!(solver.last_step_size() <= 0.1 + 1e-6);
+    }
+}
+
+#[test]
+fn step_size_too_small_test() {
+    // y' = y^2 blows up at t = 1
+    let fun: Box<SolveFun<Vector<1>>> = Box::new(|_, y| Vector::from([y[0] * y[0]]));
+    let mut solver = AdaptiveRungeKutta::<DormandPrince, Vector<1>>::new(
+        0.0,
+        Vector::from([1.0]),
+        Tolerances::new(1e-6, 1e-9),
+    )
+    .with_controller(StepController {
+        max_factor: 5.0,
+        ..StepController::default()
+    });
+    let result = solver.integrate_to(&fun, 2.0);
+    assert!(matches!(result, Err(SolverError::StepSizeTooSmall { .. })));
+    assert!(solver.time() < 1.0);
+}
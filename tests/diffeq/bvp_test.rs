@@ -0,0 +1,124 @@
+use std::f32::consts::PI;
+
+use csl::{
+    diffeq::{
+        bvp::{BoundaryValueProblem, BvpOptions},
+        error::BvpError,
+    },
+    linalg::ndarray::Vector,
+};
+
+// y'' = -y with y(0) = 0 and y(pi / 2) = 1, solved by sin(x)
+fn sine_problem() -> BoundaryValueProblem<2> {
+    BoundaryValueProblem::new(
+        |_, y: Vector<2>, _: &[f32]| Vector::from([y[1], -y[0]]),
+        |a: Vector<2>, b: Vector<2>, _: &[f32]| vec![a[0], b[0] - 1.0],
+    )
+}
+
+fn assert_sine(evaluate: impl Fn(f32) -> Vector<2>, tolerance: f32) {
+    for i in 0..=10 {
+        let x = i as f32 * PI / 20.0;
+        let y = evaluate(x);
+        assert!((y[0] - x.sin()).abs() < tolerance, "{} at {}", y[0], x);
+        assert!((y[1] - x.cos()).abs() < tolerance, "{} at {}", y[1], x);
+    }
+}
+
+#[test]
+fn single_shooting_test() {
+    let problem = sine_problem();
+    let solution = problem
+        .shoot(
+            (0.0, PI / 2.0),
+            Vector::from([0.0, 0.0]),
+            &BvpOptions::new(),
+        )
+        .unwrap();
+    assert_eq!(solution.nodes, [0.0, PI / 2.0]);
+    assert_eq!(solution.states.len(), 2);
+    assert!((solution.states[0][1] - 1.0).abs() < 1e-4);
+    assert_sine(|x| solution.evaluate(x), 1e-4);
+}
+
+#[test]
+fn multiple_shooting_test() {
+    let problem = sine_problem();
+    let nodes = [0.0, 0.5, 1.0, PI / 2.0];
+    let guesses = [Vector::from([0.0, 0.0]); 3];
+    let solution = problem
+        .multiple_shoot(&nodes, &guesses, &BvpOptions::new())
+        .unwrap();
+    assert_eq!(solution.states.len(), 4);
+    for (x, state) in nodes.iter().zip(&solution.states) {
+        assert!((state[0] - x.sin()).abs() < 1e-4);
+    }
+    assert_sine(|x| solution.evaluate(x), 1e-4);
+}
+
+#[test]
+fn collocation_test() {
+    let problem = sine_problem();
+    let mesh: Vec<f32> = (0..=4).map(|i| i as f32 * PI / 8.0).collect();
+    let guess = vec![Vector::from([0.5, 0.0]); mesh.len()];
+    let options = BvpOptions::new().with_tolerance(1e-4);
+    let solution = problem.collocate(mesh, guess, &options).unwrap();
+    assert_eq!(solution.rms_residuals.len(), solution.nodes.len() - 1);
+    assert!(solution.rms_residuals.iter().all(|&r| r <= 1e-4));
+    assert_sine(|x| solution.evaluate(x), 1e-4);
+}
+
+#[test]
+fn mesh_refinement_test() {
+    // y'' = 100 y with y(0) = 1 and y(1) = 0 has a boundary layer at x = 0,
+    // y = sinh(10 (1 - x)) / sinh(10)
+    let problem = BoundaryValueProblem::new(
+        |_, y: Vector<2>, _: &[f32]| Vector::from([y[1], 100.0 * y[0]]),
+        |a: Vector<2>, b: Vector<2>, _: &[f32]| vec![a[0] - 1.0, b[0]],
+    );
+    let mesh: Vec<f32> = (0..=4).map(|i| i as f32 / 4.0).collect();
+    let guess = vec![Vector::zeros(); mesh.len()];
+    let solution = problem.collocate(mesh, guess, &BvpOptions::new()).unwrap();
+    assert!(solution.nodes.len() > 5);
+    // The nodes cluster near the boundary layer
+    let first_half = solution.nodes.iter().filter(|&&x| x < 0.5).count();
+    assert!(2 * first_half > solution.nodes.len());
+    for x in [0.0f32, 0.05, 0.1, 0.3, 0.7, 1.0] {
+        let exact = (10.0 * (1.0 - x)).sinh() / 10f32.sinh();
+        assert!((solution.evaluate(x)[0] - exact).abs() < 5e-3);
+    }
+
+    let error = problem
+        .collocate(
+            vec![0.0, 0.5, 1.0],
+            vec![Vector::zeros(); 3],
+            &BvpOptions::new().with_max_nodes(8),
+        )
+        .unwrap_err();
+    assert_eq!(error, BvpError::MaxNodesExceeded { nodes: 8 });
+}
+
+#[test]
+fn unknown_parameter_test() {
+    // Eigenvalue problem y'' + k^2 y = 0 with y(0) = y(1) = 0, normalized by
+    // y'(0) = k. The lowest eigenvalue is k = pi.
+    let problem = BoundaryValueProblem::new(
+        |_, y: Vector<2>, p: &[f32]| Vector::from([y[1], -p[0] * p[0] * y[0]]),
+        |a: Vector<2>, b: Vector<2>, p: &[f32]| vec![a[0], b[0], a[1] - p[0]],
+    )
+    .with_parameters(vec![2.5]);
+
+    let mesh: Vec<f32> = (0..=5).map(|i| i as f32 / 5.0).collect();
+    let guess = mesh
+        .iter()
+        .map(|&x| Vector::from([(PI * x).sin(), PI * (PI * x).cos()]))
+        .collect();
+    let collocation = problem.collocate(mesh, guess, &BvpOptions::new()).unwrap();
+    assert!((collocation.parameters[0] - PI).abs() < 1e-3);
+    assert!((collocation.evaluate(0.5)[0] - 1.0).abs() < 1e-2);
+
+    let shooting = problem
+        .shoot((0.0, 1.0), Vector::from([0.0, 2.5]), &BvpOptions::new())
+        .unwrap();
+    assert!((shooting.parameters[0] - PI).abs() < 1e-3);
+}
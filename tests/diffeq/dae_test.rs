@@ -0,0 +1,116 @@
+use csl::{
+    diffeq::{
+        control::Tolerances,
+        dae::{solve_dae, Dae, DaeOptions},
+        error::SolverError,
+        solve::Status,
+    },
+    linalg::ndarray::{Matrix, Vector},
+};
+
+// Robertson's kinetics with the conservation law replacing the third
+// equation, y1 + y2 + y3 = 1
+fn robertson_dae() -> Dae<3> {
+    let mass = Matrix {
+        data: [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 0.0]],
+    };
+    Dae::mass_matrix(mass, |_, y: Vector<3>| {
+        Vector::from([
+            -0.04 * y[0] + 1e4 * y[1] * y[2],
+            0.04 * y[0] - 1e4 * y[1] * y[2] - 3e7 * y[1] * y[1],
+            y[0] + y[1] + y[2] - 1.0,
+        ])
+    })
+}
+
+#[test]
+fn mass_matrix_test() {
+    let dae = robertson_dae();
+    assert!(!dae.is_algebraic(0) && !dae.is_algebraic(1) && dae.is_algebraic(2));
+    // The conservation law determines y3 only to the f32 resolution of one
+    let tolerances = Tolerances::per_component(
+        Vector::from([1e-4, 1e-4, 1e-4]),
+        Vector::from([1e-6, 1e-11, 1e-6]),
+    );
+    // The algebraic component starts from a poor guess
+    let solution = solve_dae(
+        &dae,
+        (0.0, 40.0),
+        Vector::from([1.0, 0.0, 0.5]),
+        Vector::zeros(),
+        DaeOptions::new().with_tolerances(tolerances),
+    );
+    assert!(solution.success());
+    assert!(solution.states[0][2].abs() < 1e-6);
+    assert_eq!(solution.derivatives[0][0], -0.04);
+    assert_eq!(solution.derivatives[0][1], 0.04);
+    let state = solution.states.last().unwrap();
+    assert_eq!(*solution.times.last().unwrap(), 40.0);
+    assert!((state[0] - 0.7158271).abs() < 1e-3, "y1 = {}", state[0]);
+    assert!((state[1] - 9.185535e-6).abs() < 1e-7, "y2 = {}", state[1]);
+    assert!((state[2] - 0.2841637).abs() < 1e-3, "y3 = {}", state[2]);
+    for state in &solution.states {
+        assert!((state[0] + state[1] + state[2] - 1.0).abs() < 1e-5);
+    }
+    // Far fewer steps than an explicit method would need
+    assert!(solution.statistics.accepted_steps < 300);
+}
+
+#[test]
+fn implicit_form_test() {
+    // y' + z = 0 with the constraint z = y, solved by y = z = exp(-t)
+    let dae = Dae::implicit(
+        |_, y: Vector<2>, dy: Vector<2>| Vector::from([dy[0] + y[1], y[1] - y[0]]),
+        [false, true],
+    );
+    let (state, derivative) = dae
+        .consistent_initial_values(
+            0.0,
+            Vector::from([1.0, 0.0]),
+            Vector::zeros(),
+            &Tolerances::default(),
+        )
+        .unwrap();
+    assert!((state[1] - 1.0).abs() < 1e-6);
+    assert!((derivative[0] + 1.0).abs() < 1e-6);
+
+    let solution = solve_dae(
+        &dae,
+        (0.0, 2.0),
+        Vector::from([1.0, 0.0]),
+        Vector::zeros(),
+        DaeOptions::new().with_tolerances(Tolerances::new(1e-5, 1e-7)),
+    );
+    assert!(solution.success());
+    for (t, state) in solution.times.iter().zip(&solution.states) {
+        assert!(
+            (state[0] - (-t).exp()).abs() < 1e-4,
+            "{} at {}",
+            state[0],
+            t
+        );
+        assert!((state[1] - state[0]).abs() < 1e-5);
+    }
+}
+
+#[test]
+fn index_two_test() {
+    // y' = z with the constraint 0 = y - sin(t) has index two: z only enters
+    // through the derivative of the constraint
+    let dae = Dae::implicit(
+        |t, y: Vector<2>, dy: Vector<2>| Vector::from([dy[0] - y[1], y[0] - t.sin()]),
+        [false, true],
+    );
+    let solution = solve_dae(
+        &dae,
+        (0.0, 1.0),
+        Vector::zeros(),
+        Vector::zeros(),
+        DaeOptions::new(),
+    );
+    assert_eq!(
+        solution.status,
+        Status::Failed(SolverError::SingularIterationMatrix { time: 0.0 })
+    );
+    assert!(solution.times.is_empty());
+}
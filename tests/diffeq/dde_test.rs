@@ -0,0 +1,103 @@
+use csl::{
+    diffeq::{
+        control::Tolerances,
+        dde::{solve_dde, DdeOptions, DdeSolution, Delay},
+        solve::Method,
+    },
+    linalg::ndarray::Vector,
+};
+
+// y' = -y(t - 1) with y = 1 before t = 0 is a polynomial on every [k, k + 1]
+fn exact_negative_feedback(t: f32) -> f32 {
+    match t {
+        t if t <= 1.0 => 1.0 - t,
+        t if t <= 2.0 => 1.0 - t + (t - 1.0).powi(2) / 2.0,
+        t => 1.0 - t + (t - 1.0).powi(2) / 2.0 - (t - 2.0).powi(3) / 6.0,
+    }
+}
+
+fn negative_feedback(delay: Delay<Vector<1>>) -> DdeSolution<Vector<1>> {
+    solve_dde(
+        |_, _, delayed: &[Vector<1>]| -delayed[0],
+        vec![delay],
+        |_| Vector::from([1.0]),
+        (0.0, 3.0),
+        DdeOptions::new().with_tolerances(Tolerances::new(1e-6, 1e-6)),
+    )
+}
+
+#[test]
+fn constant_delay_test() {
+    let solution = negative_feedback(Delay::constant(1.0));
+    assert!(solution.success());
+    assert_eq!(solution.discontinuities, [0.0, 1.0, 2.0, 3.0]);
+    // The steps end on the discontinuities
+    for xi in [1.0, 2.0] {
+        assert!(solution.times.contains(&xi));
+    }
+    assert_eq!(*solution.times.last().unwrap(), 3.0);
+    for i in 0..=30 {
+        let t = i as f32 / 10.0;
+        let y = solution.dense.evaluate(t)[0];
+        assert!(
+            (y - exact_negative_feedback(t)).abs() < 1e-4,
+            "{} at {}",
+            y,
+            t
+        );
+    }
+}
+
+#[test]
+fn state_dependent_delay_test() {
+    // The same problem with the delay given as a function, so that the
+    // discontinuities have to be located
+    let solution = negative_feedback(Delay::state_dependent(|_, _| 1.0));
+    assert!(solution.success());
+    for xi in [1.0, 2.0] {
+        assert!(solution
+            .discontinuities
+            .iter()
+            .any(|t| (t - xi).abs() < 1e-4));
+    }
+    let y = solution.states.last().unwrap()[0];
+    assert!((y - exact_negative_feedback(3.0)).abs() < 1e-4);
+
+    // Pantograph equation y' = y(t / 2) with y(0) = 1, whose delayed argument
+    // lies within the current step
+    let solution = solve_dde(
+        |_, _, delayed: &[Vector<1>]| delayed[0],
+        vec![Delay::state_dependent(|t, _| t / 2.0)],
+        |_| Vector::from([1.0]),
+        (0.0, 1.0),
+        DdeOptions::new().with_tolerances(Tolerances::new(1e-6, 1e-6)),
+    );
+    assert!(solution.success());
+    let exact = 1.0 + 1.0 + 1.0 / 4.0 + 1.0 / 48.0 + 1.0 / 1536.0 + 1.0 / 122880.0;
+    assert!((solution.states.last().unwrap()[0] - exact).abs() < 1e-4);
+}
+
+#[test]
+fn method_agreement_test() {
+    // Hutchinson's delayed logistic equation y' = r y (1 - y(t - 1))
+    let solve = |method| {
+        solve_dde(
+            |_, y: Vector<1>, delayed: &[Vector<1>]| y * 1.4 * (1.0 - delayed[0][0]),
+            vec![Delay::constant(1.0)],
+            |_| Vector::from([0.5]),
+            (0.0, 10.0),
+            DdeOptions::new()
+                .with_method(method)
+                .with_tolerances(Tolerances::new(1e-6, 1e-6)),
+        )
+    };
+    let rk45 = solve(Method::Rk45);
+    let dop853 = solve(Method::Dop853);
+    assert!(rk45.success() && dop853.success());
+    assert!(dop853.statistics.accepted_steps < rk45.statistics.accepted_steps);
+    for i in 0..=20 {
+        let t = i as f32 / 2.0;
+        let difference = rk45.dense.evaluate(t)[0] - dop853.dense.evaluate(t)[0];
+        assert!(difference.abs() < 1e-3, "{} at {}", difference, t);
+    }
+}
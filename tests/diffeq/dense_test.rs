@@ -0,0 +1,158 @@
+use csl::{
+    diffeq::{
+        adaptive::{AdaptiveRungeKutta, Dop853, Rk45},
+        control::Tolerances,
+        dense::{DenseSolution, Interpolant},
+        ivp::{RungeKutta4, SolveFun},
+        tableau::{BogackiShampine, DormandPrince, DormandPrince853, EmbeddedTableau, Tsitouras},
+    },
+    linalg::ndarray::Vector,
+};
+
+use super::{oscillator, oscillator_deviation};
+
+#[test]
+fn hermite_reproduces_cubic_test() {
+    // y = t^3 - t on [1, 3]
+    let interpolant = Interpolant::hermite(
+        1.0,
+        Vector::from([0.0]),
+        Vector::from([2.0]),
+        3.0,
+        Vector::from([24.0]),
+        Vector::from([26.0]),
+    );
+    assert_eq!(interpolant.degree(), 3);
+    for time in [1.0, 1.5, 2.0, 2.75, 3.0] {
+        let expected = time * time * time - time;
+        assert!((interpolant.evaluate(time)[0] - expected).abs() < 1e-4);
+    }
+}
+
+fn max_dense_error<T: EmbeddedTableau>(tolerances: Tolerances<Vector<2>>) -> f32 {
+    let fun = oscillator;
+    let mut solver =
+        AdaptiveRungeKutta::<T, Vector<2>>::new(0.0, Vector::from([1.0, 0.0]), tolerances);
+    let solution = solver.integrate_dense(&fun, 10.0).unwrap();
+    assert_eq!(solution.start_time(), Some(0.0));
+    assert_eq!(solution.end_time(), Some(10.0));
+    (0..=1000)
+        .map(|i| {
+            let time = i as f32 * 0.01;
+            oscillator_deviation(&solution.evaluate(time), time)
+        })
+        .fold(0.0, f32::max)
+}
+
+#[test]
+fn dense_output_accuracy_test() {
+    let tolerances = Tolerances::new(1e-5, 1e-7);
+    for error in [
+        max_dense_error::<BogackiShampine>(tolerances),
+        max_dense_error::<DormandPrince>(tolerances),
+        max_dense_error::<Tsitouras>(tolerances),
+        max_dense_error::<DormandPrince853>(tolerances),
+    ] {
+        assert!(error < 2e-4, "dense output error {}", error);
+    }
+}
+
+#[test]
+fn continuous_extension_beats_hermite_test() {
+    // With loose tolerances the steps are long and the fourth order
+    // interpolants are noticeably better than a cubic between the nodes.
+    let fun = oscillator;
+    let mut solver =
+        Rk45::new(0.0, Vector::from([1.0, 0.0]), Tolerances::new(1e-2, 1e-2)).with_first_step(0.8);
+    solver.step(&fun, 10.0).unwrap();
+    assert_eq!(solver.time(), 0.8);
+    let interpolant = solver.interpolant(&fun).unwrap();
+    let stages = solver.stages();
+    let hermite = Interpolant::hermite(
+        interpolant.start_time(),
+        interpolant.evaluate(interpolant.start_time()),
+        stages[0],
+        solver.time(),
+        *solver.state(),
+        stages[stages.len() - 1],
+    );
+    let time = 0.5 * (interpolant.start_time() + interpolant.end_time());
+    let error = oscillator_deviation(&interpolant.evaluate(time), time);
+    let hermite_error = oscillator_deviation(&hermite.evaluate(time), time);
+    assert!(
+        5.0 * error < hermite_error,
+        "{} vs {}",
+        error,
+        hermite_error
+    );
+}
+
+#[test]
+fn interpolant_matches_step_ends_test() {
+    let fun = oscillator;
+    let mut solver = Dop853::new(0.0, Vector::from([1.0, 0.0]), Tolerances::new(1e-6, 1e-8));
+    assert!(solver.interpolant(&fun).is_none());
+    let start = *solver.state();
+    solver.step(&fun, 5.0).unwrap();
+    let evaluations = solver.statistics().evaluations;
+    let interpolant = solver.interpolant(&fun).unwrap();
+    // The interpolant needs three extra stages
+    assert_eq!(solver.statistics().evaluations, evaluations + 3);
+    assert_eq!(interpolant.degree(), 7);
+    let at_start = interpolant.evaluate(0.0);
+    let at_end = interpolant.evaluate(solver.time());
+    for i in 0..2 {
+        assert!((at_start[i] - start[i]).abs() < 1e-6);
+        assert!((at_end[i] - solver.state()[i]).abs() < 1e-6);
+    }
+}
+
+#[test]
+fn backward_dense_output_test() {
+    let fun = oscillator;
+    let end_time = -6.0f32;
+    let mut solver = Rk45::new(0.0, Vector::from([1.0, 0.0]), Tolerances::new(1e-6, 1e-8));
+    let solution = solver.integrate_dense(&fun, end_time).unwrap();
+    assert!(solution.pieces().len() > 1);
+    let times = [0.0, -0.3, -2.5, -4.0, -6.0];
+    for (time, state) in times.iter().zip(solution.sample(&times)) {
+        assert!(oscillator_deviation(&state, *time) < 1e-4);
+    }
+}
+
+#[test]
+fn fixed_step_hermite_test() {
+    let fun = oscillator;
+    let mut solver = RungeKutta4::new(0.0, Vector::from([1.0, 0.0]));
+    let mut solution = DenseSolution::new();
+    for _ in 0..20 {
+        solver.next_step(&fun, 0.05);
+        solution.push(solver.interpolant(&fun).unwrap());
+    }
+    assert_eq!(solver.evaluations(), 20 * 5);
+    for time in [0.0, 0.125, 0.51, 0.99] {
+        assert!(oscillator_deviation(&solution.evaluate(time), time) < 1e-5);
+    }
+}
+
+#[test]
+#[should_panic]
+fn evaluate_outside_range_test() {
+    let fun = oscillator;
+    let mut solver = Rk45::new(0.0, Vector::from([1.0, 0.0]), Tolerances::default());
+    let solution = solver.integrate_dense(&fun, 1.0).unwrap();
+    solution.evaluate(1.5);
+}
+
+#[test]
+fn double_precision_hermite_test() {
+    let fun: Box<SolveFun<f64, f64>> = Box::new(|t, _| t.cos());
+    let mut solver = RungeKutta4::new(0.0, 0.0f64);
+    solver.next_step(&fun, 0.01);
+    let interpolant = solver.interpolant(&fun).unwrap();
+    assert_eq!(interpolant.end_time(), 0.01);
+    // Third order in the step size, far below single precision resolution
+    for time in [0.0025, 0.005, 0.0075] {
+        assert!((interpolant.evaluate(time) - time.sin()).abs() < 1e-9);
+    }
+}
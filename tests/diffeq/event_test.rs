@@ -0,0 +1,135 @@
+use std::f32::consts::PI;
+
+use csl::{
+    diffeq::{
+        adaptive::{Dop853, Rk45},
+        control::Tolerances,
+        event::{locate_root, Direction, Event},
+        ivp::SolveFun,
+    },
+    linalg::ndarray::Vector,
+};
+
+use super::oscillator;
+
+#[test]
+fn locate_root_test() {
+    let g = |t: f32| t * t - 2.0;
+    let root = locate_root(g, 0.0, g(0.0), 3.0, g(3.0), 1e-6);
+    assert!((root - 2f32.sqrt()).abs() < 1e-6);
+    // g has already changed sign at the returned time
+    assert!(g(root) >= 0.0);
+
+    // Backwards in time
+    let root = locate_root(g, 0.0, g(0.0), -3.0, g(-3.0), 1e-6);
+    assert!((root + 2f32.sqrt()).abs() < 1e-6);
+}
+
+#[test]
+fn direction_filter_test() {
+    let fun = oscillator;
+    let events = [
+        Event::new(|_, y: Vector<2>| y[0]),
+        Event::new(|_, y: Vector<2>| y[0]).with_direction(Direction::Rising),
+        Event::new(|_, y: Vector<2>| y[0]).with_direction(Direction::Falling),
+    ];
+    let mut solver = Rk45::new(0.0, Vector::from([1.0, 0.0]), Tolerances::new(1e-6, 1e-8));
+    let log = solver.integrate_with_events(&fun, 10.0, &events).unwrap();
+    assert_eq!(solver.time(), 10.0);
+    assert_eq!(log.terminated_by, None);
+
+    // cos t is zero at pi/2, 3pi/2, 5pi/2, the first one falling
+    let both: Vec<f32> = log.of(0).map(|occurrence| occurrence.time).collect();
+    let rising: Vec<f32> = log.of(1).map(|occurrence| occurrence.time).collect();
+    let falling: Vec<f32> = log.of(2).map(|occurrence| occurrence.time).collect();
+    assert_eq!(both.len(), 3);
+    assert_eq!(rising.len(), 1);
+    assert_eq!(falling.len(), 2);
+    for (time, k) in both.iter().zip(0..) {
+        assert!((time - (k as f32 + 0.5) * PI).abs() < 1e-4);
+    }
+    assert_eq!(rising[0], both[1]);
+    assert_eq!(falling, [both[0], both[2]]);
+    for window in log.occurrences.windows(2) {
+        assert!(window[0].time <= window[1].time);
+    }
+}
+
+#[test]
+fn terminal_event_test() {
+    let fun = oscillator;
+    let events = [Event::new(|_, y: Vector<2>| y[0] - 0.5).with_terminal(true)];
+    let mut solver = Dop853::new(0.0, Vector::from([1.0, 0.0]), Tolerances::new(1e-8, 1e-10));
+    let log = solver.integrate_with_events(&fun, 10.0, &events).unwrap();
+    assert_eq!(log.terminated_by, Some(0));
+    assert_eq!(log.occurrences.len(), 1);
+    // cos t = 0.5 at t = pi / 3
+    assert!((solver.time() - PI / 3.0).abs() < 1e-5);
+    assert_eq!(solver.time(), log.occurrences[0].time);
+    assert!((solver.state()[0] - 0.5).abs() < 1e-5);
+}
+
+#[test]
+fn bouncing_ball_test() {
+    let gravity = 9.81;
+    let restitution = 0.8;
+    let height = 10.0f32;
+    let fun: Box<SolveFun<Vector<2>>> = Box::new(move |_, y| Vector::from([y[1], -gravity]));
+    let events = [Event::new(|_, y: Vector<2>| y[0])
+        .with_direction(Direction::Falling)
+        .with_action(move |_, y| Vector::from([0.0, -restitution * y[1]]))];
+    let mut solver = Rk45::new(
+        0.0,
+        Vector::from([height, 0.0]),
+        Tolerances::new(1e-6, 1e-8),
+    );
+    let end_time = 5.0;
+    let log = solver
+        .integrate_with_events(&fun, end_time, &events)
+        .unwrap();
+    assert_eq!(solver.time(), end_time);
+
+    // The ball falls for sqrt(2 h / g), then every flight is shorter by the
+    // restitution coefficient
+    let fall = (2.0 * height / gravity).sqrt();
+    let mut expected = vec![fall];
+    let mut flight = 2.0 * restitution * fall;
+    while expected.last().unwrap() + flight < end_time {
+        expected.push(expected.last().unwrap() + flight);
+        flight *= restitution;
+    }
+    assert_eq!(log.occurrences.len(), expected.len());
+    for (occurrence, time) in log.occurrences.iter().zip(expected) {
+        assert!((occurrence.time - time).abs() < 1e-3);
+        assert!(occurrence.state[0].abs() < 1e-3);
+        assert!(occurrence.state[1] < 0.0);
+    }
+    assert!(solver.state()[0] >= -1e-3);
+}
+
+#[test]
+fn reflecting_bounce_test() {
+    // The action only reverses the velocity, so the restart state is within
+    // the root tolerance of the floor on either side of it, and a `Both`
+    // event must not count the same bounce again on the way up
+    let fun: Box<SolveFun<Vector<2>>> = Box::new(|_, y| Vector::from([y[1], -9.81]));
+    let events = [Event::new(|_, y: Vector<2>| y[0])
+        .with_action(|_, y: Vector<2>| Vector::from([y[0], -y[1]]))];
+    for (height, tolerances) in [
+        (10.0, Tolerances::new(1e-6, 1e-8)),
+        (3.0, Tolerances::new(1e-4, 1e-6)),
+        (7.3, Tolerances::new(1e-3, 1e-3)),
+    ] {
+        let mut solver = Rk45::new(0.0, Vector::from([height, 0.0]), tolerances);
+        let log = solver.integrate_with_events(&fun, 12.0, &events).unwrap();
+
+        // Without losses every bounce follows after twice the fall time
+        let fall = (2.0 * height / 9.81f32).sqrt();
+        let bounces = ((12.0 - fall) / (2.0 * fall)).floor() as usize + 1;
+        assert_eq!(log.occurrences.len(), bounces, "height {}", height);
+        for (k, occurrence) in log.occurrences.iter().enumerate() {
+            assert!((occurrence.time - (2 * k + 1) as f32 * fall).abs() < 1e-2);
+            assert!(occurrence.state[1] < 0.0);
+        }
+    }
+}
@@ -0,0 +1,174 @@
+use csl::{
+    diffeq::{
+        control::Tolerances,
+        error::FitError,
+        fit::{fit_parameters, FitOptions},
+        solve::{solve_ivp, SolveOptions},
+    },
+    linalg::ndarray::Vector,
+};
+
+// Deterministic noise in [-1, 1]
+fn noise(k: usize) -> f32 {
+    ((k * 7919 % 101) as f32 / 50.0) - 1.0
+}
+
+fn decay(_: f32, y: Vector<1>, p: &Vector<1>) -> Vector<1> {
+    Vector::from([-p[0] * y[0]])
+}
+
+// Lotka-Volterra with the parameters (alpha, beta, delta, gamma)
+fn lotka_volterra(_: f32, y: Vector<2>, p: &Vector<4>) -> Vector<2> {
+    Vector::from([
+        p[0] * y[0] - p[1] * y[0] * y[1],
+        p[2] * y[0] * y[1] - p[3] * y[1],
+    ])
+}
+
+fn lotka_volterra_data(parameters: Vector<4>, times: &[f32]) -> Vec<Vector<2>> {
+    solve_ivp(
+        move |t, y| lotka_volterra(t, y, &parameters),
+        (0.0, *times.last().unwrap()),
+        Vector::from([1.0, 0.5]),
+        SolveOptions::new()
+            .with_tolerances(Tolerances::new(1e-7, 1e-9))
+            .with_t_eval(times.to_vec()),
+    )
+    .states
+}
+
+#[test]
+fn exponential_decay_test() {
+    let times: Vec<f32> = (1..=20).map(|k| k as f32 * 0.25).collect();
+    let data: Vec<Vector<1>> = times
+        .iter()
+        .enumerate()
+        .map(|(k, &t)| Vector::from([2.0 * (-0.8 * t).exp() + 1e-3 * noise(k)]))
+        .collect();
+    let fit = fit_parameters(
+        decay,
+        0.0,
+        Vector::from([2.0]),
+        &times,
+        &data,
+        Vector::from([0.3]),
+        FitOptions::new(),
+    )
+    .unwrap();
+    assert!((fit.parameters[0] - 0.8).abs() < 1e-3);
+    assert_eq!(fit.residuals.len(), times.len());
+    let sum_of_squares: f32 = fit.residuals.iter().map(|r| r[0] * r[0]).sum();
+    assert!((fit.sum_of_squares - sum_of_squares).abs() <= 1e-6 * sum_of_squares);
+    // The noise is of size 1e-3, so the error of the rate is far smaller
+    let standard_error = fit.standard_errors().unwrap()[0];
+    assert!(standard_error > 0.0 && standard_error < 1e-3);
+}
+
+#[test]
+fn covariance_test() {
+    // y' = b gives y = y_0 + b t, a linear model whose estimate of b has the
+    // variance s^2 / sum t_k^2
+    let constant = |_: f32, _: Vector<1>, p: &Vector<1>| Vector::from([p[0]]);
+    let times: Vec<f32> = (1..=10).map(|k| k as f32).collect();
+    let data: Vec<Vector<1>> = times
+        .iter()
+        .enumerate()
+        .map(|(k, &t)| Vector::from([0.5 * t + 0.1 * noise(k)]))
+        .collect();
+    let fit = fit_parameters(
+        constant,
+        0.0,
+        Vector::zeros(),
+        &times,
+        &data,
+        Vector::from([0.0]),
+        FitOptions::new(),
+    )
+    .unwrap();
+
+    let sum_t2: f32 = times.iter().map(|t| t * t).sum();
+    let sum_ty: f32 = times.iter().zip(&data).map(|(t, y)| t * y[0]).sum();
+    let slope = sum_ty / sum_t2;
+    let variance: f32 = times
+        .iter()
+        .zip(&data)
+        .map(|(t, y)| (y[0] - slope * t).powi(2))
+        .sum::<f32>()
+        / (times.len() - 1) as f32;
+    assert!((fit.parameters[0] - slope).abs() < 1e-5);
+    let covariance = fit.covariance.unwrap()[(0, 0)];
+    assert!((covariance - variance / sum_t2).abs() < 1e-3 * covariance);
+}
+
+#[test]
+fn lotka_volterra_test() {
+    let exact = Vector::from([1.5, 1.0, 0.8, 3.0]);
+    let times: Vec<f32> = (1..=30).map(|k| k as f32 * 0.2).collect();
+    let data = lotka_volterra_data(exact, &times);
+    let fit = fit_parameters(
+        lotka_volterra,
+        0.0,
+        Vector::from([1.0, 0.5]),
+        &times,
+        &data,
+        Vector::from([1.2, 0.8, 1.0, 2.5]),
+        FitOptions::new(),
+    )
+    .unwrap();
+    for j in 0..4 {
+        assert!(
+            (fit.parameters[j] - exact[j]).abs() < 1e-3,
+            "{:?}",
+            fit.parameters
+        );
+    }
+    assert!(fit.sum_of_squares < 1e-7);
+    assert!(fit.iterations < 30);
+}
+
+#[test]
+fn unobserved_component_test() {
+    // The predators are not measured, but still determine the prey
+    let exact = Vector::from([1.5, 1.0, 0.8, 3.0]);
+    let times: Vec<f32> = (1..=30).map(|k| k as f32 * 0.2).collect();
+    let data: Vec<Vector<2>> = lotka_volterra_data(exact, &times)
+        .into_iter()
+        .map(|y| Vector::from([y[0], f32::NAN]))
+        .collect();
+    let fit = fit_parameters(
+        lotka_volterra,
+        0.0,
+        Vector::from([1.0, 0.5]),
+        &times,
+        &data,
+        Vector::from([1.4, 0.9, 0.9, 2.8]),
+        FitOptions::new().with_weights(Vector::from([1.0, 0.0])),
+    )
+    .unwrap();
+    for j in 0..4 {
+        assert!((fit.parameters[j] - exact[j]).abs() < 1e-2);
+    }
+    assert!(fit.residuals.iter().all(|r| r[1] == 0.0));
+}
+
+#[test]
+fn did_not_converge_test() {
+    let times = [1.0, 2.0, 3.0];
+    let data: Vec<Vector<1>> = times
+        .iter()
+        .map(|&t: &f32| Vector::from([(-0.8 * t).exp()]))
+        .collect();
+    let result = fit_parameters(
+        decay,
+        0.0,
+        Vector::from([1.0]),
+        &times,
+        &data,
+        Vector::from([5.0]),
+        FitOptions::new().with_max_iterations(1),
+    );
+    assert!(matches!(
+        result,
+        Err(FitError::DidNotConverge { iterations: 1, .. })
+    ));
+}
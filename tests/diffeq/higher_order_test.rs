@@ -0,0 +1,131 @@
+use csl::{
+    diffeq::{
+        higher_order::{reduce_order, reduce_second_order, solve_higher_order, solve_second_order},
+        ivp::{RungeKutta4, SolveFun},
+        solve::{Method, SolveOptions},
+        state::{OdeState, StateArray},
+    },
+    linalg::ndarray::Vector,
+};
+use glam::Vec3;
+
+#[test]
+fn stacked_state_test() {
+    let state = StateArray([Vector::from([1.0, 2.0]), Vector::from([3.0, 4.0])]);
+    assert_eq!(state.dimension(), 4);
+    assert_eq!(
+        (0..4).map(|i| state.component(i)).collect::<Vec<_>>(),
+        [1.0, 2.0, 3.0, 4.0]
+    );
+    let shifted = state.map_components(|i, x| x + i as f32);
+    assert_eq!(
+        shifted.0,
+        [Vector::from([1.0, 3.0]), Vector::from([5.0, 7.0])]
+    );
+
+    // z' = [y', y'']
+    let fun = reduce_second_order(|_, y: Vector<2>, v| -1.0 * y + -2.0 * v);
+    assert_eq!(
+        fun(0.0, state).0,
+        [
+            Vector::from([3.0, 4.0]),
+            Vector::from([-1.0 - 6.0, -2.0 - 8.0])
+        ]
+    );
+}
+
+// Damped oscillator y'' = -y - 2 zeta y' with zeta = 0.1
+fn damped_exact(time: f32) -> (f32, f32) {
+    let zeta: f32 = 0.1;
+    let omega = (1.0 - zeta * zeta).sqrt();
+    let decay = (-zeta * time).exp();
+    let position = decay * ((omega * time).cos() + zeta / omega * (omega * time).sin());
+    let velocity = -decay / omega * (omega * time).sin();
+    (position, velocity)
+}
+
+#[test]
+fn second_order_test() {
+    for method in [Method::Rk45, Method::Dop853, Method::Radau, Method::Rodas4] {
+        let solution = solve_second_order(
+            |_, y: Vector<1>, v: Vector<1>| -1.0 * y + -0.2 * v,
+            (0.0, 10.0),
+            Vector::from([1.0]),
+            Vector::from([0.0]),
+            SolveOptions::new()
+                .with_method(method)
+                .with_t_eval((0..=10).map(|i| i as f32).collect()),
+        );
+        assert!(solution.success());
+        assert_eq!(solution.times().len(), 11);
+        assert_eq!(solution.positions().len(), 11);
+        for ((time, position), velocity) in solution
+            .times()
+            .iter()
+            .zip(solution.positions())
+            .zip(solution.velocities())
+        {
+            let (exact_position, exact_velocity) = damped_exact(*time);
+            assert!((position[0] - exact_position).abs() < 1e-2, "{:?}", method);
+            assert!((velocity[0] - exact_velocity).abs() < 1e-2, "{:?}", method);
+        }
+    }
+}
+
+#[test]
+fn third_order_test() {
+    // y''' = y with y = y' = y'' = 1 at t = 0 is solved by exp(t)
+    let solution = solve_higher_order(
+        |_, derivatives: &[Vector<2>; 3]| derivatives[0],
+        (0.0, 1.0),
+        [Vector::from([1.0, 2.0]); 3],
+        SolveOptions::new(),
+    );
+    assert_eq!(solution.derivatives.len(), 3);
+    let e = 1f32.exp();
+    for derivative in &solution.derivatives {
+        let last = derivative.last().unwrap();
+        assert!((last[0] - e).abs() < 1e-3);
+        assert!((last[1] - 2.0 * e).abs() < 2e-3);
+    }
+}
+
+#[test]
+fn reduced_step_solver_test() {
+    // The reduced right-hand side works with the stepping solvers as well
+    let fun: Box<SolveFun<StateArray<f32, 3>>> =
+        Box::new(reduce_order(|_, derivatives: &[f32; 3]| -derivatives[1]));
+    // y''' = -y' with y = 0, y' = 1, y'' = 0 is solved by sin(t)
+    let mut solver = RungeKutta4::new(0.0, StateArray([0.0, 1.0, 0.0]));
+    for _ in 0..100 {
+        solver.next_step(&fun, 0.01);
+    }
+    let [position, velocity, acceleration] = solver.state().0;
+    assert!((position - 1f32.sin()).abs() < 1e-5);
+    assert!((velocity - 1f32.cos()).abs() < 1e-5);
+    assert!((acceleration + 1f32.sin()).abs() < 1e-5);
+}
+
+#[test]
+fn glam_second_order_test() {
+    // A projectile under gravity, y = v_0 t + g t^2 / 2
+    let gravity = Vec3::new(0.0, 0.0, -9.81);
+    let velocity = Vec3::new(1.0, 2.0, 10.0);
+    let solution = solve_second_order(
+        move |_, _: Vec3, _| gravity,
+        (0.0, 2.0),
+        Vec3::ZERO,
+        velocity,
+        SolveOptions::new().with_method(Method::Tsit5),
+    );
+    assert!(solution.success());
+    for ((&time, &position), &speed) in solution
+        .times()
+        .iter()
+        .zip(solution.positions())
+        .zip(solution.velocities())
+    {
+        assert!((position - (velocity * time + 0.5 * gravity * time * time)).length() < 1e-3);
+        assert!((speed - (velocity + gravity * time)).length() < 1e-3);
+    }
+}
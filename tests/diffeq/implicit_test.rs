@@ -0,0 +1,150 @@
+use csl::{
+    diffeq::{
+        bdf::Bdf,
+        control::Tolerances,
+        implicit::ThetaMethod,
+        ivp::{RungeKutta4, SolveFun},
+        jacobian::{finite_difference_jacobian, Jacobian},
+        radau::Radau,
+    },
+    linalg::ndarray::{Matrix, Vector},
+};
+
+use super::{
+    assert_robertson, prothero_robinson, robertson, robertson_jacobian, robertson_tolerances,
+};
+
+fn theta_error(mut solver: ThetaMethod<Vector<1>>, steps: usize) -> f32 {
+    let fun: Box<SolveFun<Vector<1>>> = Box::new(|_, y| -y);
+    let delta_time = 1.0 / steps as f32;
+    for _ in 0..steps {
+        solver.next_step(&fun, delta_time).unwrap();
+    }
+    (solver.state()[0] - (-1.0f32).exp()).abs()
+}
+
+#[test]
+fn theta_method_order_test() {
+    let initial = Vector::from([1.0]);
+    crate::assert_convergence_order(
+        |steps| theta_error(ThetaMethod::backward_euler(0.0, initial), steps),
+        1,
+        32,
+        0.1,
+    );
+    crate::assert_convergence_order(
+        |steps| theta_error(ThetaMethod::trapezoidal(0.0, initial), steps),
+        2,
+        16,
+        0.2,
+    );
+}
+
+#[test]
+fn backward_euler_is_stable_for_stiff_problems_test() {
+    let fun: Box<SolveFun<Vector<1>>> = Box::new(prothero_robinson);
+    let delta_time = 0.05;
+
+    let mut implicit = ThetaMethod::backward_euler(0.0, Vector::from([2.0]));
+    let mut explicit = RungeKutta4::new(0.0, Vector::from([2.0]));
+    for _ in 0..20 {
+        implicit.next_step(&fun, delta_time).unwrap();
+        explicit.next_step(&fun, delta_time);
+    }
+    assert!((implicit.state()[0] - 1.0f32.cos()).abs() < 1e-3);
+    // The explicit method blows up (or overflows to NaN) with the same step
+    assert!(explicit.state()[0].is_nan() || explicit.state()[0].abs() > 1e3);
+}
+
+#[test]
+fn jacobian_reuse_test() {
+    // A linear problem converges with the first Jacobian, which is never re-evaluated
+    let fun: Box<SolveFun<Vector<2>>> =
+        Box::new(|_, y| Vector::from([-y[0] + y[1], -100.0 * y[1]]));
+    let mut solver = ThetaMethod::trapezoidal(0.0, Vector::from([1.0, 1.0]));
+    for _ in 0..10 {
+        solver.next_step(&fun, 0.1).unwrap();
+    }
+    assert_eq!(solver.statistics().jacobian_evaluations, 1);
+    assert_eq!(solver.statistics().lu_decompositions, 1);
+    assert_eq!(solver.statistics().accepted_steps, 10);
+}
+
+#[test]
+fn finite_difference_jacobian_test() {
+    let fun: Box<SolveFun<Vector<3>>> = Box::new(robertson);
+    let state = Vector::from([0.9, 1e-5, 0.1]);
+    let approximation: Matrix<3, 3> = finite_difference_jacobian(
+        &fun,
+        0.0,
+        &state,
+        &fun(0.0, state),
+        &Vector::from([1e-8; 3]),
+    )
+    .to_matrix();
+    let exact = robertson_jacobian(0.0, state);
+    for i in 0..3 {
+        for j in 0..3 {
+            let tolerance = 1e-2 * exact[(i, j)].abs().max(1.0);
+            assert!((approximation[(i, j)] - exact[(i, j)]).abs() < tolerance);
+        }
+    }
+}
+
+#[test]
+fn bdf_robertson_test() {
+    let mut solver = Bdf::new(0.0, Vector::from([1.0, 0.0, 0.0]), robertson_tolerances());
+    assert_robertson(solver.integrate_to(&robertson, 40.0).unwrap());
+    let statistics = solver.statistics();
+    assert!(statistics.accepted_steps < 300, "{:?}", statistics);
+    assert!(statistics.jacobian_evaluations < statistics.accepted_steps);
+}
+
+#[test]
+fn radau_robertson_test() {
+    let mut solver = Radau::new(0.0, Vector::from([1.0, 0.0, 0.0]), robertson_tolerances());
+    assert_robertson(solver.integrate_to(&robertson, 40.0).unwrap());
+    assert!(
+        solver.statistics().accepted_steps < 200,
+        "{:?}",
+        solver.statistics()
+    );
+}
+
+#[test]
+fn analytic_jacobian_test() {
+    let mut numerical = Bdf::new(0.0, Vector::from([1.0, 0.0, 0.0]), robertson_tolerances());
+    let mut analytic = Bdf::new(0.0, Vector::from([1.0, 0.0, 0.0]), robertson_tolerances())
+        .with_jacobian(Jacobian::analytic(robertson_jacobian));
+    assert_robertson(numerical.integrate_to(&robertson, 40.0).unwrap());
+    assert_robertson(analytic.integrate_to(&robertson, 40.0).unwrap());
+    assert!(analytic.statistics().evaluations < numerical.statistics().evaluations);
+
+    let mut radau = Radau::new(0.0, Vector::from([1.0, 0.0, 0.0]), robertson_tolerances())
+        .with_jacobian(Jacobian::analytic(robertson_jacobian));
+    assert_robertson(radau.integrate_to(&robertson, 40.0).unwrap());
+}
+
+#[test]
+fn accuracy_on_smooth_problems_test() {
+    let fun: Box<SolveFun<Vector<1>>> = Box::new(prothero_robinson);
+    let tolerances = Tolerances::new(1e-5, 1e-7);
+
+    let mut bdf = Bdf::new(0.0, Vector::from([1.0]), tolerances);
+    let mut radau = Radau::new(0.0, Vector::from([1.0]), tolerances);
+    let end_time = 3.0f32;
+    assert!((bdf.integrate_to(&fun, end_time).unwrap()[0] - end_time.cos()).abs() < 1e-4);
+    assert!((radau.integrate_to(&fun, end_time).unwrap()[0] - end_time.cos()).abs() < 1e-4);
+    // The solution is smooth, so higher orders are selected
+    assert!(bdf.order() > 1);
+}
+
+#[test]
+fn implicit_backward_integration_test() {
+    let fun: Box<SolveFun<Vector<1>>> = Box::new(|_, y| -y);
+    let mut bdf = Bdf::new(0.0, Vector::from([1.0]), Tolerances::new(1e-5, 1e-8));
+    let mut radau = Radau::new(0.0, Vector::from([1.0]), Tolerances::new(1e-5, 1e-8));
+    let expected = 1.0f32.exp();
+    assert!((bdf.integrate_to(&fun, -1.0).unwrap()[0] - expected).abs() < 1e-3);
+    assert!((radau.integrate_to(&fun, -1.0).unwrap()[0] - expected).abs() < 1e-3);
+}
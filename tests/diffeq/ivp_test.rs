@@ -0,0 +1,109 @@
+use csl::{
+    diffeq::{
+        ivp::{ExplicitRungeKutta, RungeKutta4},
+        tableau::{ButcherTableau, ClassicRk4, Euler, Heun, Midpoint, Ralston, ThreeEighthsRule},
+    },
+    linalg::{
+        dual::Dual,
+        ndarray::{Matrix, Vector},
+        real::Real,
+    },
+};
+
+// y' = y cos(t), y(0) = 1 with the exact solution exp(sin(t))
+fn integrate<T: ButcherTableau>(steps: usize) -> f32 {
+    let end_time = 2.0;
+    let delta_time = end_time / steps as f32;
+    let mut solver = ExplicitRungeKutta::<T, Vector<1>>::new(0.0, Vector::from([1.0]));
+    for _ in 0..steps {
+        solver.next_step(&|time, y| time.cos() * y, delta_time);
+    }
+    assert!((solver.time() - end_time).abs() < 1e-5);
+    (solver.state()[0] - end_time.sin().exp()).abs()
+}
+
+fn assert_convergence_order<T: ButcherTableau>(coarse_steps: usize) {
+    crate::assert_convergence_order(integrate::<T>, T::ORDER, coarse_steps, 0.3);
+}
+
+#[test]
+fn convergence_order_test() {
+    assert_convergence_order::<Euler>(64);
+    assert_convergence_order::<Heun>(32);
+    assert_convergence_order::<Midpoint>(32);
+    assert_convergence_order::<Ralston>(32);
+    assert_convergence_order::<ClassicRk4>(8);
+    assert_convergence_order::<ThreeEighthsRule>(8);
+}
+
+fn assert_consistent<T: ButcherTableau>() {
+    assert_eq!(T::A.len(), T::stages());
+    assert_eq!(T::C.len(), T::stages());
+    assert!((T::B.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+    for (i, row) in T::A.iter().enumerate() {
+        assert_eq!(row.len(), i);
+        assert!((row.iter().sum::<f64>() - T::C[i]).abs() < 1e-12);
+    }
+}
+
+#[test]
+fn tableau_consistency_test() {
+    assert_consistent::<Euler>();
+    assert_consistent::<Heun>();
+    assert_consistent::<Midpoint>();
+    assert_consistent::<Ralston>();
+    assert_consistent::<ClassicRk4>();
+    assert_consistent::<ThreeEighthsRule>();
+}
+
+#[test]
+fn function_evaluations_test() {
+    let mut solver = RungeKutta4::new(0.0, Vector::from([100.0]));
+    for _ in 0..10 {
+        solver.next_step(&|_time, temp| -0.07 * (temp - Vector::from([20.0])), 0.1);
+    }
+
+    assert_eq!(solver.evaluations(), 40);
+    assert_eq!(solver.stages().len(), 4);
+    let exact = 20.0 + 80.0 * f32::exp(-0.07);
+    assert!((solver.state()[0] - exact).abs() < 1e-4);
+}
+
+// y' = cos(t) with many small steps, after which the time accumulated in
+// single precision is visibly off
+fn long_integration_error<R: Real>() -> (f64, f64) {
+    let steps = 100_000;
+    let end_time = 100.0;
+    let delta_time = R::from_f64(end_time / steps as f64);
+    let start: Vector<1, R> = Matrix::zeros();
+    let mut solver = RungeKutta4::new(R::ZERO, start);
+    for _ in 0..steps {
+        solver.next_step(&|t, _| Matrix { data: [[t.cos()]] }, delta_time);
+    }
+    (
+        (solver.time().to_f64() - end_time).abs(),
+        (solver.state()[0].to_f64() - end_time.sin()).abs(),
+    )
+}
+
+#[test]
+fn double_precision_test() {
+    let (single_time, single) = long_integration_error::<f32>();
+    let (double_time, double) = long_integration_error::<f64>();
+    assert!(single_time > 1e-3 && single > 1e-4);
+    assert!(double_time < 1e-9 && double < 1e-9);
+}
+
+#[test]
+fn dual_number_test() {
+    // y' = -k y gives y(t) = exp(-k t) and dy/dk = -t exp(-k t)
+    let rate = Dual::variable(0.5);
+    let mut solver = RungeKutta4::new(Dual::constant(0.0), Dual::constant(1.0));
+    for _ in 0..200 {
+        solver.next_step(&move |_, y| -rate * y, Dual::constant(0.01));
+    }
+    let exact = (-1.0f64).exp();
+    assert!((solver.state().value - exact).abs() < 1e-10);
+    assert!((solver.state().derivative + 2.0 * exact).abs() < 1e-9);
+    assert_eq!(solver.time().derivative, 0.0);
+}
@@ -0,0 +1,81 @@
+pub mod adaptive_test;
+pub mod bvp_test;
+pub mod dae_test;
+pub mod dde_test;
+pub mod dense_test;
+pub mod event_test;
+pub mod fit_test;
+pub mod higher_order_test;
+pub mod implicit_test;
+pub mod ivp_test;
+pub mod multistep_test;
+pub mod nystrom_test;
+pub mod rosenbrock_test;
+pub mod sde_test;
+pub mod sensitivity_test;
+pub mod solve_test;
+pub mod state_test;
+pub mod stream_test;
+pub mod symplectic_test;
+
+use csl::{
+    diffeq::{control::Tolerances, state::OdeState},
+    linalg::ndarray::{Matrix, Vector},
+};
+
+/// Harmonic oscillator with the exact solution `(cos t, -sin t)` from `(1, 0)`.
+pub fn oscillator(_: f32, y: Vector<2>) -> Vector<2> {
+    Vector::from([y[1], -y[0]])
+}
+
+/// Largest deviation of `state` from the exact solution of [`oscillator`] at `time`.
+pub fn oscillator_deviation(state: &Vector<2>, time: f32) -> f32 {
+    (state[0] - time.cos())
+        .abs()
+        .max((state[1] + time.sin()).abs())
+}
+
+/// Prothero-Robinson problem `y' = -1000 (y - cos t) - sin t` in every
+/// component, with the smooth solution `y = cos t` that explicit methods can
+/// only follow with tiny steps.
+pub fn prothero_robinson<Y: OdeState>(time: f32, y: Y) -> Y {
+    y.map_components(|_, y_i| -1000.0 * (y_i - time.cos()) - time.sin())
+}
+
+/// Robertson's chemical kinetics, the classic stiff test problem.
+pub fn robertson(_: f32, y: Vector<3>) -> Vector<3> {
+    Vector::from([
+        -0.04 * y[0] + 1e4 * y[1] * y[2],
+        0.04 * y[0] - 1e4 * y[1] * y[2] - 3e7 * y[1] * y[1],
+        3e7 * y[1] * y[1],
+    ])
+}
+
+/// Analytic Jacobian of [`robertson`].
+pub fn robertson_jacobian(_: f32, y: Vector<3>) -> Matrix<3, 3> {
+    Matrix {
+        data: [
+            [-0.04, 1e4 * y[2], 1e4 * y[1]],
+            [0.04, -1e4 * y[2] - 6e7 * y[1], -1e4 * y[1]],
+            [0.0, 6e7 * y[1], 0.0],
+        ],
+    }
+}
+
+/// Tolerances for [`robertson`], whose second component stays around `1e-5`.
+pub fn robertson_tolerances() -> Tolerances<Vector<3>> {
+    Tolerances::per_component(
+        Vector::from([1e-4, 1e-4, 1e-4]),
+        Vector::from([1e-8, 1e-11, 1e-8]),
+    )
+}
+
+/// Compares `state` with the reference solution of [`robertson`] from
+/// `(1, 0, 0)` at `t = 40`.
+pub fn assert_robertson(state: &Vector<3>) {
+    assert!((state[0] - 0.7158271).abs() < 1e-3, "y1 = {}", state[0]);
+    assert!((state[1] - 9.185535e-6).abs() < 1e-7, "y2 = {}", state[1]);
+    assert!((state[2] - 0.2841637).abs() < 1e-3, "y3 = {}", state[2]);
+    // The reactions conserve the total mass
+    assert!((state[0] + state[1] + state[2] - 1.0).abs() < 1e-4);
+}
@@ -0,0 +1,87 @@
+use csl::{
+    diffeq::{
+        adaptive::Rk45,
+        control::Tolerances,
+        ivp::SolveFun,
+        multistep::{Abm, AdamsBashforth, AdamsMoulton},
+    },
+    linalg::ndarray::Vector,
+};
+
+use super::oscillator;
+
+// y' = -2 t y with the solution exp(-t^2)
+fn gaussian() -> Box<SolveFun<Vector<1>>> {
+    Box::new(|t, y| -2.0 * t * y)
+}
+
+fn bashforth_error(order: usize, steps: usize) -> f32 {
+    let fun = gaussian();
+    let mut solver = AdamsBashforth::new(0.0, Vector::from([1.0]), order);
+    for _ in 0..steps {
+        solver.next_step(&fun, 1.5 / steps as f32);
+    }
+    (solver.state()[0] - (-2.25f32).exp()).abs()
+}
+
+fn moulton_error(order: usize, steps: usize) -> f32 {
+    let fun = gaussian();
+    let mut solver = AdamsMoulton::new(0.0, Vector::from([1.0]), order);
+    for _ in 0..steps {
+        solver.next_step(&fun, 1.5 / steps as f32);
+    }
+    (solver.state()[0] - (-2.25f32).exp()).abs()
+}
+
+#[test]
+fn convergence_order_test() {
+    for order in 1..=4 {
+        let bashforth = bashforth_error(order, 15) / bashforth_error(order, 30);
+        let moulton = moulton_error(order, 15) / moulton_error(order, 30);
+        let expected = 0.7 * 2f32.powi(order as i32);
+        assert!(bashforth > expected, "Adams-Bashforth of order {}", order);
+        assert!(moulton > expected, "Adams-Moulton of order {}", order);
+    }
+}
+
+#[test]
+fn evaluations_test() {
+    // The Runge-Kutta startup steps reuse the derivative of the last step
+    let fun = gaussian();
+    let mut bashforth = AdamsBashforth::new(0.0, Vector::from([1.0]), 4);
+    let mut moulton = AdamsMoulton::new(0.0, Vector::from([1.0]), 4).with_corrections(2);
+    for _ in 0..20 {
+        bashforth.next_step(&fun, 0.05);
+        moulton.next_step(&fun, 0.05);
+    }
+    assert_eq!(bashforth.evaluations(), 1 + 3 * 3 + 20);
+    assert_eq!(moulton.evaluations(), 1 + 2 * 4 + 18 * 3);
+    assert!((bashforth.time() - 1.0).abs() < 1e-6);
+    assert!((bashforth.state()[0] - (-1f32).exp()).abs() < 1e-4);
+    assert!((moulton.state()[0] - (-1f32).exp()).abs() < 1e-4);
+}
+
+#[test]
+fn variable_order_test() {
+    let fun = oscillator;
+    let tolerances = Tolerances::new(1e-5, 1e-7);
+    let mut solver = Abm::new(0.0, Vector::from([1.0, 0.0]), tolerances);
+    let mut max_order = 0;
+    while solver.time() != 20.0 {
+        solver.step(&fun, 20.0).unwrap();
+        max_order = max_order.max(solver.order());
+    }
+    let state = solver.state();
+    assert!((state[0] - 20f32.cos()).abs() < 1e-4);
+    assert!((state[1] + 20f32.sin()).abs() < 1e-4);
+    assert!(max_order > 4);
+
+    // Far fewer evaluations than a Runge-Kutta method at the same tolerances
+    let mut runge_kutta = Rk45::new(0.0, Vector::from([1.0, 0.0]), tolerances);
+    runge_kutta.integrate_to(&fun, 20.0).unwrap();
+    assert!(2 * solver.statistics().evaluations < runge_kutta.statistics().evaluations);
+
+    let interpolant = solver.interpolant().unwrap();
+    let time = 0.5 * (interpolant.start_time() + interpolant.end_time());
+    assert!((interpolant.evaluate(time)[0] - time.cos()).abs() < 1e-3);
+}
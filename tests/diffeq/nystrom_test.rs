@@ -0,0 +1,76 @@
+use std::f32::consts::PI;
+
+use csl::{
+    diffeq::{
+        ivp::{RungeKutta4, SolveFun},
+        nystrom::{
+            Nystrom4, Nystrom5, NystromTableau, RungeKuttaNystrom, RungeKuttaNystrom4,
+            RungeKuttaNystrom5,
+        },
+    },
+    linalg::ndarray::Vector,
+};
+
+fn assert_consistent<T: NystromTableau>() {
+    assert_eq!(T::A.len(), T::stages());
+    assert_eq!(T::B_BAR.len(), T::stages());
+    assert_eq!(T::C.len(), T::stages());
+    assert!((T::B.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+    assert!((T::B_BAR.iter().sum::<f64>() - 0.5).abs() < 1e-12);
+    for (row, c_i) in T::A.iter().zip(T::C) {
+        assert!((row.iter().sum::<f64>() - c_i * c_i / 2.0).abs() < 1e-12);
+    }
+}
+
+#[test]
+fn tableau_consistency_test() {
+    assert_consistent::<Nystrom4>();
+    assert_consistent::<Nystrom5>();
+}
+
+// Position and velocity error after a period of the oscillator y'' = -y
+fn oscillator_error<T: NystromTableau>(steps: usize) -> f32 {
+    let delta_time = 2.0 * PI / steps as f32;
+    let mut solver =
+        RungeKuttaNystrom::<T, Vector<1>>::new(0.0, Vector::from([1.0]), Vector::from([0.0]));
+    let fun: Box<SolveFun<Vector<1>>> = Box::new(|_, y| -1.0 * y);
+    for _ in 0..steps {
+        solver.next_step(&fun, delta_time);
+    }
+    (solver.position()[0] - 1.0)
+        .abs()
+        .max(solver.velocity()[0].abs())
+}
+
+#[test]
+fn convergence_order_test() {
+    crate::assert_convergence_order(oscillator_error::<Nystrom4>, Nystrom4::ORDER, 16, 0.3);
+    crate::assert_convergence_order(oscillator_error::<Nystrom5>, Nystrom5::ORDER, 8, 0.3);
+}
+
+#[test]
+fn pendulum_test() {
+    // A fourth order Nystrom method needs three evaluations per step where
+    // RK4 on the stacked system needs four, for a comparable error
+    let acceleration: Box<SolveFun<Vector<1>>> = Box::new(|_, q| Vector::from([-q[0].sin()]));
+    let mut nystrom = RungeKuttaNystrom4::new(0.0, Vector::from([1.0]), Vector::from([0.0]));
+    let mut nystrom5 = RungeKuttaNystrom5::new(0.0, Vector::from([1.0]), Vector::from([0.0]));
+    let mut rk4 = RungeKutta4::new(0.0, Vector::from([1.0, 0.0]));
+    let stacked: Box<SolveFun<Vector<2>>> = Box::new(|_, y| Vector::from([y[1], -y[0].sin()]));
+    for _ in 0..100 {
+        nystrom.next_step(&acceleration, 0.05);
+        nystrom5.next_step(&acceleration, 0.05);
+        rk4.next_step(&stacked, 0.05);
+    }
+    assert!((nystrom.time() - 5.0).abs() < 1e-4);
+    assert_eq!(nystrom.evaluations(), 300);
+    assert_eq!(nystrom5.evaluations(), 400);
+    assert_eq!(rk4.evaluations(), 400);
+    for solver_state in [
+        (nystrom.position()[0], nystrom.velocity()[0]),
+        (nystrom5.position()[0], nystrom5.velocity()[0]),
+    ] {
+        assert!((solver_state.0 - rk4.state()[0]).abs() < 1e-4);
+        assert!((solver_state.1 - rk4.state()[1]).abs() < 1e-4);
+    }
+}
@@ -0,0 +1,107 @@
+use csl::{
+    diffeq::{
+        control::Tolerances,
+        ivp::SolveFun,
+        jacobian::Jacobian,
+        rosenbrock::{
+            Rodas4, Rodas4Tableau, Rodas5, Rodas5Tableau, Ros3p, Ros3pTableau, Rosenbrock,
+            RosenbrockTableau,
+        },
+    },
+    linalg::ndarray::Vector,
+};
+
+use super::{
+    assert_robertson, prothero_robinson, robertson, robertson_jacobian, robertson_tolerances,
+};
+
+fn assert_consistent<T: RosenbrockTableau>() {
+    assert_eq!(T::A.len(), T::stages());
+    assert_eq!(T::COUPLING.len(), T::stages());
+    assert_eq!(T::C.len(), T::stages());
+    assert_eq!(T::D.len(), T::stages());
+    assert_eq!(T::E.len(), T::stages());
+    for i in 0..T::stages() {
+        assert_eq!(T::A[i].len(), i);
+        assert_eq!(T::COUPLING[i].len(), i);
+    }
+}
+
+#[test]
+fn tableau_consistency_test() {
+    assert_consistent::<Ros3pTableau>();
+    assert_consistent::<Rodas4Tableau>();
+    assert_consistent::<Rodas5Tableau>();
+}
+
+// Nonautonomous test problem y' = -y + t with the exact solution t - 1 + 2 exp(-t)
+fn linear() -> Box<SolveFun<Vector<1>>> {
+    Box::new(|time, y| Vector::from([time - y[0]]))
+}
+
+fn fixed_step_error<T: RosenbrockTableau>(steps: usize) -> f32 {
+    let delta_time = 2.0 / steps as f32;
+    // Loose tolerances accept every step, the maximum step keeps them equal
+    let mut solver =
+        Rosenbrock::<T, Vector<1>>::new(0.0, Vector::from([1.0]), Tolerances::new(1e3, 1e3))
+            .with_first_step(delta_time)
+            .with_max_step(delta_time);
+    let state = *solver.integrate_to(&linear(), 2.0).unwrap();
+    assert_eq!(solver.statistics().rejected_steps, 0);
+    (state[0] - (1.0 + 2.0 * (-2.0f32).exp())).abs()
+}
+
+#[test]
+fn convergence_order_test() {
+    // Few and large steps keep the errors well above single precision rounding,
+    // at the price of not quite reaching the asymptotic regime
+    crate::assert_convergence_order(
+        fixed_step_error::<Ros3pTableau>,
+        Ros3pTableau::ORDER,
+        8,
+        0.7,
+    );
+    crate::assert_convergence_order(
+        fixed_step_error::<Rodas4Tableau>,
+        Rodas4Tableau::ORDER,
+        4,
+        0.7,
+    );
+    crate::assert_convergence_order(
+        fixed_step_error::<Rodas5Tableau>,
+        Rodas5Tableau::ORDER,
+        2,
+        0.7,
+    );
+}
+
+#[test]
+fn stiff_nonautonomous_test() {
+    let fun: Box<SolveFun<Vector<1>>> = Box::new(prothero_robinson);
+    let tolerances = Tolerances::new(1e-5, 1e-7);
+    let end_time = 3.0f32;
+
+    let mut ros3p = Ros3p::new(0.0, Vector::from([1.0]), tolerances);
+    let mut rodas4 = Rodas4::new(0.0, Vector::from([1.0]), tolerances);
+    let mut rodas5 = Rodas5::new(0.0, Vector::from([1.0]), tolerances);
+    for state in [
+        *ros3p.integrate_to(&fun, end_time).unwrap(),
+        *rodas4.integrate_to(&fun, end_time).unwrap(),
+        *rodas5.integrate_to(&fun, end_time).unwrap(),
+    ] {
+        assert!((state[0] - end_time.cos()).abs() < 1e-4);
+    }
+    // Stiffness does not limit the step size
+    assert!(rodas4.statistics().accepted_steps < 100);
+}
+
+#[test]
+fn robertson_test() {
+    let mut solver = Rodas5::new(0.0, Vector::from([1.0, 0.0, 0.0]), robertson_tolerances())
+        .with_jacobian(Jacobian::analytic(robertson_jacobian));
+    assert_robertson(solver.integrate_to(&robertson, 40.0).unwrap());
+    // An analytic Jacobian costs no evaluations of f beyond the stages and df/dt
+    let statistics = solver.statistics();
+    let attempts = statistics.accepted_steps + statistics.rejected_steps;
+    assert!(statistics.evaluations <= 7 * attempts + 3 * statistics.accepted_steps + 1);
+}
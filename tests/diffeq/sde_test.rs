@@ -0,0 +1,217 @@
+use csl::{
+    diffeq::sde::{
+        ensemble, solve_sde, Calculus, Sde, SdeIntegrator, SdeMethod, WienerIncrement,
+        WienerProcess,
+    },
+    linalg::ndarray::{Matrix, Vector},
+};
+
+#[test]
+fn wiener_process_test() {
+    let mut first = WienerProcess::<2>::new(7);
+    let mut second = WienerProcess::<2>::new(7);
+    for _ in 0..10 {
+        assert_eq!(first.increment(0.1), second.increment(0.1));
+    }
+
+    // Joining increments adds the time integral of the first one's endpoint
+    let a = WienerIncrement {
+        step: 0.5,
+        dw: Vector::from([1.0]),
+        time_integral: Vector::from([0.25]),
+    };
+    let b = WienerIncrement {
+        step: 0.25,
+        dw: Vector::from([-1.0]),
+        time_integral: Vector::from([0.125]),
+    };
+    let joined = a.concat(&b);
+    assert_eq!(joined.step, 0.75);
+    assert_eq!(joined.dw, Vector::from([0.0]));
+    assert_eq!(joined.time_integral, Vector::from([0.25 + 0.125 + 0.25]));
+
+    // dW ~ N(0, h) and I_(1,0) ~ N(0, h^3 / 3)
+    let mut wiener = WienerProcess::<1>::new(1);
+    let samples: Vec<_> = (0..20000).map(|_| wiener.increment(0.5)).collect();
+    let variance = |values: Vec<f32>| values.iter().map(|v| v * v).sum::<f32>() / 20000.0;
+    let dw_variance = variance(samples.iter().map(|s| s.dw[0]).collect());
+    let integral_variance = variance(samples.iter().map(|s| s.time_integral[0]).collect());
+    assert!((dw_variance - 0.5).abs() < 0.02);
+    assert!((integral_variance - 0.125 / 3.0).abs() < 0.002);
+}
+
+// Geometric Brownian motion dy = mu y dt + sigma y dW
+const MU: f32 = 1.0;
+const SIGMA: f32 = 0.8;
+
+fn gbm(calculus: Calculus) -> Sde<1, 1> {
+    Sde::diagonal(|_, y| MU * y, |_, y| SIGMA * y).with_calculus(calculus)
+}
+
+// Mean absolute error at t = 1 against the exact solution, for steps of
+// 2^-levels[i], along paths sampled on a grid of 2^-9
+fn strong_errors(sde: &Sde<1, 1>, method: SdeMethod, levels: &[u32]) -> Vec<f32> {
+    let fine_steps = 1 << 9;
+    let paths = 200;
+    let mut wiener = WienerProcess::<1>::new(42);
+    let mut errors = vec![0.0; levels.len()];
+    for _ in 0..paths {
+        let fine: Vec<_> = (0..fine_steps)
+            .map(|_| wiener.increment(1.0 / fine_steps as f32))
+            .collect();
+        let w: f32 = fine.iter().map(|increment| increment.dw[0]).sum();
+        let exact = match sde.calculus() {
+            Calculus::Ito => ((MU - 0.5 * SIGMA * SIGMA) + SIGMA * w).exp(),
+            Calculus::Stratonovich => (MU + SIGMA * w).exp(),
+        };
+        for (error, level) in errors.iter_mut().zip(levels) {
+            let mut integrator = SdeIntegrator::new(method, 0.0, Vector::from([1.0]));
+            for chunk in fine.chunks(fine_steps >> level) {
+                let increment = chunk[1..]
+                    .iter()
+                    .fold(chunk[0], |joined, next| joined.concat(next));
+                integrator.next_step(sde, &increment);
+            }
+            assert!((integrator.time() - 1.0).abs() < 1e-5);
+            *error += (integrator.state()[0] - exact).abs() / paths as f32;
+        }
+    }
+    errors
+}
+
+fn assert_strong_order(sde: &Sde<1, 1>, method: SdeMethod) {
+    let levels = [4, 5, 6, 7];
+    let errors = strong_errors(sde, method, &levels);
+    let observed = (errors[0] / errors[3]).log2() / 3.0;
+    assert!(
+        observed > method.strong_order() - 0.25,
+        "{:?}: expected order {}, observed {}",
+        method,
+        method.strong_order(),
+        observed
+    );
+}
+
+#[test]
+fn strong_order_test() {
+    let sde = gbm(Calculus::Ito);
+    assert_strong_order(&sde, SdeMethod::EulerMaruyama);
+    assert_strong_order(&sde, SdeMethod::Milstein);
+    assert_strong_order(&sde, SdeMethod::Sri);
+}
+
+#[test]
+fn stratonovich_test() {
+    // The Stratonovich equation has the solution exp(mu t + sigma W)
+    let sde = gbm(Calculus::Stratonovich);
+    for method in [
+        SdeMethod::EulerMaruyama,
+        SdeMethod::Milstein,
+        SdeMethod::Sri,
+    ] {
+        assert_strong_order(&sde, method);
+    }
+    for method in [SdeMethod::Milstein, SdeMethod::Sri] {
+        let errors = strong_errors(&sde, method, &[7]);
+        assert!(errors[0] < 0.05, "{:?}: {}", method, errors[0]);
+    }
+}
+
+#[test]
+fn additive_noise_test() {
+    // Ornstein-Uhlenbeck dx = -theta x dt + sigma dW with the mean
+    // x0 exp(-theta t) and the variance sigma^2 / (2 theta) (1 - exp(-2 theta t))
+    let (theta, sigma) = (2.0f32, 0.5f32);
+    let sde = Sde::diagonal(move |_, x| -theta * x, move |_, _| Vector::from([sigma]));
+    // Euler-Maruyama needs smaller steps for the same weak error
+    for (method, step_size) in [(SdeMethod::EulerMaruyama, 0.01), (SdeMethod::Sra, 0.05)] {
+        let statistics = ensemble(
+            &sde,
+            method,
+            (0.0, 1.0),
+            Vector::from([1.0]),
+            step_size,
+            4000,
+            3,
+        );
+        assert_eq!(
+            statistics.times.len(),
+            (1.0 / step_size).round() as usize + 1
+        );
+        assert_eq!(statistics.final_states.len(), 4000);
+        for ((time, mean), variance) in statistics
+            .times
+            .iter()
+            .zip(&statistics.mean)
+            .zip(&statistics.variance)
+        {
+            let exact_mean = (-theta * time).exp();
+            let exact_variance =
+                sigma * sigma / (2.0 * theta) * (1.0 - (-2.0 * theta * time).exp());
+            assert!((mean[0] - exact_mean).abs() < 0.02, "{:?}", method);
+            assert!((variance[0] - exact_variance).abs() < 0.01, "{:?}", method);
+        }
+    }
+}
+
+#[test]
+fn general_noise_test() {
+    // Two states driven by three Wiener processes, dy = -y dt + B dW, with
+    // the variance sum_j B_ij^2 / 2 (1 - exp(-2 t))
+    let b = Matrix {
+        data: [[0.3, 0.4, 0.0], [0.0, 0.2, 0.1]],
+    };
+    let sde = Sde::general(|_, y| -1.0 * y, move |_, _| b);
+    let statistics = ensemble(
+        &sde,
+        SdeMethod::Sra,
+        (0.0, 2.0),
+        Vector::from([1.0, -1.0]),
+        0.1,
+        4000,
+        5,
+    );
+    let (mean, std_dev) = (
+        statistics.mean.last().unwrap(),
+        statistics.std_dev().pop().unwrap(),
+    );
+    let decay = (-2.0f32).exp();
+    let spread = (0.5 * (1.0 - decay * decay)).sqrt();
+    assert!((mean[0] - decay).abs() < 0.02);
+    assert!((mean[1] + decay).abs() < 0.02);
+    assert!((std_dev[0] - 0.5 * spread).abs() < 0.02);
+    assert!((std_dev[1] - 0.05f32.sqrt() * spread).abs() < 0.02);
+
+    // The same equation with Euler-Maruyama and Milstein, whose correction
+    // vanishes for additive noise
+    let mut wiener = WienerProcess::new(9);
+    let path = solve_sde(
+        &sde,
+        SdeMethod::Milstein,
+        (0.0, 1.0),
+        Vector::from([1.0, -1.0]),
+        0.3,
+        &mut wiener,
+    );
+    assert_eq!(path.times, [0.0, 0.3, 0.6, 0.90000004, 1.0]);
+    let mut wiener = WienerProcess::new(9);
+    let euler = solve_sde(
+        &sde,
+        SdeMethod::EulerMaruyama,
+        (0.0, 1.0),
+        Vector::from([1.0, -1.0]),
+        0.3,
+        &mut wiener,
+    );
+    for (a, b) in path.states.iter().zip(&euler.states) {
+        assert!((a[0] - b[0]).abs() < 1e-5 && (a[1] - b[1]).abs() < 1e-5);
+    }
+}
+
+#[test]
+#[should_panic]
+fn sri_general_noise_test() {
+    let sde = Sde::general(|_, y: Vector<1>| y, |_, _| Matrix { data: [[1.0, 1.0]] });
+    let mut integrator = SdeIntegrator::new(SdeMethod::Sri, 0.0, Vector::from([1.0]));
+    integrator.next_step(&sde, &WienerProcess::new(0).increment(0.1));
+}
@@ -0,0 +1,222 @@
+use csl::{
+    diffeq::{
+        control::Tolerances,
+        sensitivity::{
+            adjoint_gradient, central_difference_jacobians, forward_sensitivity, ParamJacobian,
+            SensitivityOptions,
+        },
+        state::StatePair,
+    },
+    linalg::ndarray::{Matrix, Vector},
+};
+
+// y' = -a y + b with the solution b / a + (y_0 - b / a) exp(-a t)
+fn relaxation(_: f32, y: Vector<1>, p: &Vector<2>) -> Vector<1> {
+    Vector::from([-p[0] * y[0] + p[1]])
+}
+
+fn exact_relaxation_sensitivity(t: f32, y0: f32, a: f32, b: f32) -> [f32; 2] {
+    let decay = (-a * t).exp();
+    let d_a = -b / (a * a) * (1.0 - decay) - (y0 - b / a) * t * decay;
+    let d_b = (1.0 - decay) / a;
+    [d_a, d_b]
+}
+
+// Lotka-Volterra with the parameters (alpha, beta, delta, gamma)
+fn lotka_volterra(_: f32, y: Vector<2>, p: &Vector<4>) -> Vector<2> {
+    Vector::from([
+        p[0] * y[0] - p[1] * y[0] * y[1],
+        p[2] * y[0] * y[1] - p[3] * y[1],
+    ])
+}
+
+fn lotka_volterra_jacobian() -> ParamJacobian<2, 4> {
+    ParamJacobian::analytic(
+        |_, y, p| Matrix {
+            data: [
+                [p[0] - p[1] * y[1], -p[1] * y[0]],
+                [p[2] * y[1], p[2] * y[0] - p[3]],
+            ],
+        },
+        |_, y, _| Matrix {
+            data: [
+                [y[0], -y[0] * y[1], 0.0, 0.0],
+                [0.0, 0.0, y[0] * y[1], -y[1]],
+            ],
+        },
+    )
+}
+
+fn tight_options<const N: usize, const P: usize>() -> SensitivityOptions<N, P> {
+    SensitivityOptions::new()
+        .with_tolerances(Tolerances::new(1e-6, 1e-8))
+        .with_adjoint_tolerances(Tolerances::new(1e-6, 1e-8))
+}
+
+#[test]
+fn central_difference_test() {
+    let (state, parameters) = (Vector::from([1.0, 0.5]), Vector::from([1.5, 1.0, 0.8, 3.0]));
+    let (state_jacobian, parameter_jacobian) =
+        central_difference_jacobians(&lotka_volterra, 0.0, &state, &parameters);
+    let jacobian = lotka_volterra_jacobian();
+    let (exact_state, exact_parameter) = match &jacobian {
+        ParamJacobian::Analytic {
+            state: state_fun,
+            parameters: parameter_fun,
+        } => (
+            state_fun(0.0, state, &parameters),
+            parameter_fun(0.0, state, &parameters),
+        ),
+        ParamJacobian::FiniteDifference => unreachable!(),
+    };
+    for i in 0..2 {
+        for j in 0..2 {
+            assert!((state_jacobian[(i, j)] - exact_state[(i, j)]).abs() < 1e-3);
+        }
+        for j in 0..4 {
+            assert!((parameter_jacobian[(i, j)] - exact_parameter[(i, j)]).abs() < 1e-3);
+        }
+    }
+}
+
+#[test]
+fn forward_sensitivity_test() {
+    let (y0, a, b) = (2.0, 0.7, 0.3);
+    let solution = forward_sensitivity(
+        relaxation,
+        (0.0, 5.0),
+        Vector::from([y0]),
+        Vector::from([a, b]),
+        tight_options(),
+    );
+    assert!(solution.success());
+    assert_eq!(*solution.times.last().unwrap(), 5.0);
+    assert_eq!(solution.sensitivities[0], Matrix::zeros());
+    for (&t, sensitivity) in solution.times.iter().zip(&solution.sensitivities) {
+        let exact = exact_relaxation_sensitivity(t, y0, a, b);
+        assert!((sensitivity[(0, 0)] - exact[0]).abs() < 1e-4);
+        assert!((sensitivity[(0, 1)] - exact[1]).abs() < 1e-4);
+    }
+}
+
+#[test]
+fn adjoint_matches_forward_test() {
+    let initial_state = Vector::from([1.0, 0.5]);
+    let parameters = Vector::from([1.5, 1.0, 0.8, 3.0]);
+    // g(y) = y_1 + y_2^2 / 2
+    let loss_gradient = |y: Vector<2>| Vector::from([1.0, y[1]]);
+
+    let forward = forward_sensitivity(
+        lotka_volterra,
+        (0.0, 4.0),
+        initial_state,
+        parameters,
+        tight_options(),
+    );
+    let sensitivity = *forward.sensitivities.last().unwrap();
+    let expected = sensitivity.transpose() * loss_gradient(*forward.states.last().unwrap());
+
+    let adjoint = adjoint_gradient(
+        lotka_volterra,
+        (0.0, 4.0),
+        initial_state,
+        parameters,
+        loss_gradient,
+        tight_options().with_checkpoint_steps(10),
+    );
+    assert!(adjoint.success());
+    assert!(adjoint.checkpoints > 2);
+    let state_error = adjoint.final_state - *forward.states.last().unwrap();
+    assert!(state_error[0].abs().max(state_error[1].abs()) < 1e-4);
+    for j in 0..4 {
+        assert!(
+            (adjoint.gradient[j] - expected[j]).abs() < 1e-3 * expected[j].abs().max(1.0),
+            "{:?} != {:?}",
+            adjoint.gradient,
+            expected
+        );
+    }
+}
+
+#[test]
+fn initial_adjoint_test() {
+    // For y' = -a y + b the sensitivity to the initial state is exp(-a T)
+    let (a, end_time) = (0.7, 2.0);
+    let adjoint = adjoint_gradient(
+        relaxation,
+        (0.0, end_time),
+        Vector::from([2.0]),
+        Vector::from([a, 0.3]),
+        |_| Vector::from([1.0]),
+        tight_options(),
+    );
+    assert!(adjoint.success());
+    assert!((adjoint.initial_adjoint[0] - (-a * end_time).exp()).abs() < 1e-5);
+    let exact = exact_relaxation_sensitivity(end_time, 2.0, a, 0.3);
+    assert!((adjoint.gradient[0] - exact[0]).abs() < 1e-4);
+    assert!((adjoint.gradient[1] - exact[1]).abs() < 1e-4);
+}
+
+#[test]
+fn checkpoints_test() {
+    let run = |checkpoint_steps| {
+        adjoint_gradient(
+            lotka_volterra,
+            (0.0, 4.0),
+            Vector::from([1.0, 0.5]),
+            Vector::from([1.5, 1.0, 0.8, 3.0]),
+            |_| Vector::from([1.0, 0.0]),
+            tight_options().with_checkpoint_steps(checkpoint_steps),
+        )
+    };
+    let single = run(usize::MAX);
+    let many = run(5);
+    assert_eq!(single.checkpoints, 1);
+    assert!(many.checkpoints > single.checkpoints);
+    // Recomputing from more checkpoints restarts the solver more often but
+    // gives the same gradient within the tolerances
+    let difference = single.gradient - many.gradient;
+    for j in 0..4 {
+        assert!(difference[j].abs() < 1e-3 * single.gradient[j].abs().max(1.0));
+    }
+}
+
+#[test]
+fn analytic_jacobian_test() {
+    let options = |jacobian| tight_options().with_jacobian(jacobian);
+    let run = |jacobian| {
+        forward_sensitivity(
+            lotka_volterra,
+            (0.0, 2.0),
+            Vector::from([1.0, 0.5]),
+            Vector::from([1.5, 1.0, 0.8, 3.0]),
+            options(jacobian),
+        )
+    };
+    let finite_difference = run(ParamJacobian::FiniteDifference);
+    let analytic = run(lotka_volterra_jacobian());
+    assert_eq!(
+        analytic.statistics.evaluations,
+        analytic.statistics.jacobian_evaluations
+    );
+    // Central differences cost 2 (N + P) evaluations per Jacobian
+    assert_eq!(
+        finite_difference.statistics.evaluations,
+        13 * finite_difference.statistics.jacobian_evaluations
+    );
+    let difference =
+        *finite_difference.sensitivities.last().unwrap() - *analytic.sensitivities.last().unwrap();
+    for j in 0..4 {
+        assert!(difference[(0, j)].abs().max(difference[(1, j)].abs()) < 1e-3);
+    }
+}
+
+#[test]
+fn state_pair_tolerances_test() {
+    let tolerances: Tolerances<StatePair<Vector<1>, Vector<2>>> = Tolerances::new(1e-3, 1e-6);
+    let state = StatePair(Vector::from([2.0]), Vector::from([0.0, -4.0]));
+    let scale = tolerances.scale(&state, &state);
+    assert_eq!(scale.0[0], 1e-6 + 2e-3);
+    assert_eq!(scale.1[0], 1e-6);
+    assert_eq!(scale.1[1], 1e-6 + 4e-3);
+}
@@ -0,0 +1,194 @@
+use std::{cell::Cell, rc::Rc};
+
+use csl::{
+    diffeq::{
+        control::Tolerances,
+        error::SolverError,
+        event::{Direction, Event},
+        solve::{solve_ivp, Method, SolveOptions, Status},
+    },
+    linalg::ndarray::Vector,
+};
+
+use super::oscillator;
+
+const METHODS: [Method; 10] = [
+    Method::Rk23,
+    Method::Rk45,
+    Method::Tsit5,
+    Method::Dop853,
+    Method::Abm,
+    Method::Radau,
+    Method::Bdf,
+    Method::Ros3p,
+    Method::Rodas4,
+    Method::Rodas5,
+];
+
+#[test]
+fn default_solve_test() {
+    let solution = solve_ivp(
+        |_, y: Vector<1>| -0.5 * y,
+        (0.0, 4.0),
+        Vector::from([2.0]),
+        SolveOptions::default(),
+    );
+    assert_eq!(solution.status, Status::Finished);
+    assert!(solution.success());
+    assert_eq!(solution.times[0], 0.0);
+    assert_eq!(solution.times.len(), solution.states.len());
+    assert_eq!(solution.statistics.accepted_steps + 1, solution.times.len());
+    assert!(solution.dense.is_none());
+    let (time, state) = solution.last().unwrap();
+    assert_eq!(time, 4.0);
+    assert!((state[0] - 2.0 * (-2f32).exp()).abs() < 1e-3);
+}
+
+#[test]
+fn fn_mut_closure_test() {
+    // The closure keeps its own state and is counted by the statistics
+    let calls = Rc::new(Cell::new(0));
+    let counter = Rc::clone(&calls);
+    let mut last_time = f32::NEG_INFINITY;
+    let fun = move |time: f32, y: Vector<2>| {
+        counter.set(counter.get() + 1);
+        last_time = last_time.max(time);
+        oscillator(time, y)
+    };
+    let solution = solve_ivp(
+        fun,
+        (0.0, 3.0),
+        Vector::from([1.0, 0.0]),
+        SolveOptions::new().with_method(Method::Tsit5),
+    );
+    assert!(solution.success());
+    assert_eq!(solution.statistics.evaluations, calls.get());
+}
+
+// y_0' = -y_0^2, y_1' = y_0 with the exact solution (1 / (1 + t), ln(1 + t)).
+// Nonlinear, since the embedded estimate of ROS3P vanishes for linear problems.
+fn decay(_: f32, y: Vector<2>) -> Vector<2> {
+    Vector::from([-y[0] * y[0], y[0]])
+}
+
+#[test]
+fn t_eval_test() {
+    let t_eval: Vec<f32> = (0..=20).map(|i| i as f32 * 0.25).collect();
+    for method in METHODS {
+        let solution = solve_ivp(
+            decay,
+            (0.0, 5.0),
+            Vector::from([1.0, 0.0]),
+            SolveOptions::new()
+                .with_method(method)
+                .with_tolerances(Tolerances::new(1e-5, 1e-7))
+                .with_t_eval(t_eval.clone()),
+        );
+        assert_eq!(solution.status, Status::Finished, "{:?}", method);
+        assert_eq!(solution.times, t_eval);
+        for (time, state) in solution.times.iter().zip(&solution.states) {
+            let error = (state[0] - 1.0 / (1.0 + time))
+                .abs()
+                .max((state[1] - time.ln_1p()).abs());
+            assert!(error < 1e-3, "{:?} error {} at {}", method, error, time);
+        }
+        if method.is_implicit() {
+            assert!(solution.statistics.jacobian_evaluations > 0);
+        }
+    }
+}
+
+#[test]
+fn backward_dense_output_test() {
+    let solution = solve_ivp(
+        oscillator,
+        (2.0, -3.0),
+        Vector::from([2f32.cos(), -2f32.sin()]),
+        SolveOptions::new()
+            .with_method(Method::Dop853)
+            .with_tolerances(Tolerances::new(1e-6, 1e-8))
+            .with_dense_output(true),
+    );
+    assert!(solution.success());
+    let dense = solution.dense.unwrap();
+    assert_eq!(dense.start_time(), Some(2.0));
+    assert_eq!(dense.end_time(), Some(-3.0));
+    for time in [1.9, 0.0, -1.234, -3.0] {
+        let state = dense.evaluate(time);
+        assert!((state[0] - time.cos()).abs() < 1e-4);
+    }
+}
+
+#[test]
+fn terminal_event_test() {
+    // A projectile hitting the ground at t = 2 v / g
+    let gravity = 9.81;
+    let speed = 12.0;
+    let solution = solve_ivp(
+        move |_, y: Vector<2>| Vector::from([y[1], -gravity]),
+        (0.0, 10.0),
+        Vector::from([0.0, speed]),
+        SolveOptions::new()
+            .with_method(Method::Radau)
+            .with_events(vec![Event::new(|_, y: Vector<2>| y[0])
+                .with_direction(Direction::Falling)
+                .with_terminal(true)]),
+    );
+    assert_eq!(solution.status, Status::Terminated);
+    assert!(solution.success());
+    assert_eq!(solution.events.terminated_by, Some(0));
+    let (time, _) = solution.last().unwrap();
+    assert!((time - 2.0 * speed / gravity).abs() < 1e-3);
+    assert_eq!(time, solution.events.occurrences[0].time);
+}
+
+#[test]
+fn failure_is_reported_test() {
+    // y' = y^2 with y(0) = 1 blows up at t = 1
+    let solution = solve_ivp(
+        |_, y: Vector<1>| Vector::from([y[0] * y[0]]),
+        (0.0, 2.0),
+        Vector::from([1.0]),
+        SolveOptions::default(),
+    );
+    assert!(!solution.success());
+    assert!(matches!(
+        solution.status,
+        Status::Failed(SolverError::StepSizeTooSmall { .. })
+    ));
+    let (time, _) = solution.last().unwrap();
+    assert!(time < 1.0 && time > 0.99);
+}
+
+#[test]
+fn event_action_output_test() {
+    // A ball bouncing off the ground, losing half its speed in every bounce
+    let solution = solve_ivp(
+        |_, y: Vector<2>| Vector::from([y[1], -9.81]),
+        (0.0, 3.0),
+        Vector::from([1.0, 0.0]),
+        SolveOptions::new().with_events(vec![Event::new(|_, y: Vector<2>| y[0])
+            .with_direction(Direction::Falling)
+            .with_action(|_, y| Vector::from([0.0, -0.5 * y[1]]))]),
+    );
+    assert_eq!(solution.status, Status::Finished);
+    assert!(solution.events.occurrences.len() > 1);
+    for occurrence in &solution.events.occurrences {
+        // The bounce is recorded before and after the action
+        let i = solution
+            .times
+            .iter()
+            .position(|&time| time == occurrence.time)
+            .unwrap();
+        assert_eq!(solution.times[i + 1], occurrence.time);
+        assert_eq!(solution.states[i], occurrence.state);
+        assert!(solution.states[i][1] < 0.0);
+        assert_eq!(solution.states[i + 1][0], 0.0);
+        assert_eq!(solution.states[i + 1][1], -0.5 * occurrence.state[1]);
+    }
+    let bounces = solution.events.occurrences.len();
+    assert_eq!(
+        solution.times.len(),
+        solution.statistics.accepted_steps + 1 + bounces
+    );
+}
@@ -0,0 +1,227 @@
+use csl::{
+    clifford::cliff_3d::Clifford,
+    diffeq::{
+        adaptive::Rk45,
+        bdf::Bdf,
+        control::{error_norm, Tolerances},
+        implicit::ThetaMethod,
+        ivp::{RungeKutta4, SolveFun},
+        jacobian::Jacobian,
+        multistep::Abm,
+        radau::Radau,
+        rosenbrock::{Rodas4, Rodas5},
+        solve::{solve_ivp, Method, SolveOptions},
+        state::OdeState,
+        symplectic::VelocityVerlet,
+    },
+    linalg::ndarray::{Matrix, NdArray, Vector},
+};
+use glam::Vec3;
+
+use super::prothero_robinson;
+
+#[test]
+fn error_norm_test() {
+    let error = Vector::from([3.0, -4.0]);
+    let scale = Vector::from([1.0, 2.0]);
+    assert!((error_norm(&error, &scale) - 6.5f32.sqrt()).abs() < 1e-6);
+    assert_eq!(error_norm(&3.0f32, &2.0), 1.5);
+
+    // The same components give the same norm for every state type
+    let glam_norm = Vec3::new(1.0, 2.0, 3.0).error_norm(&Vec3::splat(2.0));
+    let vector_norm = Vector::from([1.0, 2.0, 3.0]).error_norm(&Vector::splat(2.0));
+    assert_eq!(glam_norm, vector_norm);
+
+    let array = NdArray::from_vec(&[2, 2], vec![1.0, -1.0, 2.0, 0.0]);
+    let tolerances = Tolerances::new(0.0, 0.5);
+    let scale = tolerances.scale(&array, &array);
+    assert_eq!(scale.shape(), &[2, 2]);
+    assert!((array.error_norm(&scale) - 6.0f32.sqrt()).abs() < 1e-6);
+}
+
+#[test]
+fn scalar_state_test() {
+    let decay: Box<SolveFun<f32>> = Box::new(|_, y| -y);
+    let mut solver = RungeKutta4::new(0.0, 1.0);
+    for _ in 0..10 {
+        solver.next_step(&decay, 0.1);
+    }
+    assert!((solver.state() - (-1.0f32).exp()).abs() < 1e-6);
+
+    let mut solver = Rk45::new(0.0, 1.0, Tolerances::new(1e-6, 1e-8));
+    let state = *solver.integrate_to(&decay, 2.0).unwrap();
+    assert!((state - (-2.0f32).exp()).abs() < 1e-5);
+}
+
+#[test]
+fn glam_state_test() {
+    // Rotation about the z axis with unit angular velocity
+    let omega = Vec3::Z;
+    let rotation: Box<SolveFun<Vec3>> = Box::new(move |_, y| omega.cross(y));
+    let end_time = 3.0f32;
+    let exact = Vec3::new(end_time.cos(), end_time.sin(), 0.5);
+
+    let mut solver = Rk45::new(0.0, Vec3::new(1.0, 0.0, 0.5), Tolerances::new(1e-6, 1e-8));
+    let state = *solver.integrate_to(&rotation, end_time).unwrap();
+    assert!((state - exact).abs().max_element() < 1e-4);
+
+    let mut solver = Abm::new(0.0, Vec3::new(1.0, 0.0, 0.5), Tolerances::new(1e-6, 1e-8));
+    let state = *solver.integrate_to(&rotation, end_time).unwrap();
+    assert!((state - exact).abs().max_element() < 1e-4);
+}
+
+#[test]
+fn symplectic_glam_test() {
+    // Isotropic oscillator in three dimensions, which conserves the energy
+    let force = |_, q: Vec3| -q;
+    let velocity = |p: Vec3| p;
+    let energy = |q: Vec3, p: Vec3| 0.5 * (q.length_squared() + p.length_squared());
+    let (position, momentum) = (Vec3::new(1.0, 0.0, 0.0), Vec3::new(0.0, 0.5, 0.2));
+    let initial_energy = energy(position, momentum);
+
+    let mut integrator = VelocityVerlet::new(0.0, position, momentum);
+    for _ in 0..1000 {
+        integrator.next_step(&force, &velocity, 0.05);
+    }
+    let drift = energy(*integrator.position(), *integrator.momentum()) - initial_energy;
+    assert!(drift.abs() < 1e-3 * initial_energy);
+}
+
+#[test]
+fn ndarray_state_test() {
+    // Independent decays with the rates 1, 2, 3 and 4 in a 2x2 array
+    let rates = NdArray::from_vec(&[2, 2], vec![1.0, 2.0, 3.0, 4.0]);
+    let decay: Box<SolveFun<NdArray>> =
+        Box::new(move |_, y| y.map_components(|i, y_i| -rates.component(i) * y_i));
+    let initial = NdArray::from_vec(&[2, 2], vec![1.0; 4]);
+    let mut solver = Rk45::new(0.0, initial, Tolerances::new(1e-6, 1e-8));
+    let state = solver.integrate_to(&decay, 1.0).unwrap();
+    assert_eq!(state.shape(), &[2, 2]);
+    for (i, y_i) in state.data().iter().enumerate() {
+        assert!((y_i - (-(i as f32 + 1.0)).exp()).abs() < 1e-5);
+    }
+}
+
+#[test]
+fn clifford_state_test() {
+    // Every blade decays independently
+    let decay: Box<SolveFun<Clifford>> = Box::new(|_, y| y * -1.0);
+    let initial = Clifford::new([1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0]);
+    let mut solver = Rk45::new(0.0, initial, Tolerances::new(1e-6, 1e-8));
+    let state = solver.integrate_to(&decay, 1.0).unwrap();
+    for (y_i, y0_i) in state.factors().iter().zip(initial.factors()) {
+        assert!((y_i - y0_i * (-1.0f32).exp()).abs() < 1e-5);
+    }
+}
+
+#[test]
+fn stiff_scalar_state_test() {
+    let fun: Box<SolveFun<f32>> = Box::new(prothero_robinson);
+    let exact = 1.0f32.cos();
+    let tolerances = Tolerances::new(1e-6, 1e-8);
+
+    let mut solver = ThetaMethod::trapezoidal(0.0, 1.0);
+    for _ in 0..100 {
+        solver.next_step(&fun, 0.01).unwrap();
+    }
+    assert!((solver.state() - exact).abs() < 1e-4);
+
+    let mut solver = Bdf::new(0.0, 1.0, tolerances);
+    assert!((solver.integrate_to(&fun, 1.0).unwrap() - exact).abs() < 1e-4);
+
+    let jacobian = Jacobian::analytic(|_, _: f32| Matrix::<1, 1>::from_fn(|_, _| -1000.0));
+    let mut solver = Radau::new(0.0, 1.0, tolerances).with_jacobian(jacobian);
+    assert!((solver.integrate_to(&fun, 1.0).unwrap() - exact).abs() < 1e-5);
+}
+
+#[test]
+#[should_panic(expected = "expected a 1x1 Jacobian")]
+fn analytic_jacobian_shape_test() {
+    let fun: Box<SolveFun<f32>> = Box::new(prothero_robinson);
+    let jacobian = Jacobian::analytic(|_, _: f32| Matrix::<2, 2>::identity());
+    let mut solver = ThetaMethod::backward_euler(0.0, 1.0).with_jacobian(jacobian);
+    let _ = solver.next_step(&fun, 0.01);
+}
+
+#[test]
+fn stiff_ndarray_state_test() {
+    // Decays with rates spanning four orders of magnitude in a 2x2 array
+    let rates = NdArray::from_vec(&[2, 2], vec![1.0, 10.0, 100.0, 1000.0]);
+    let decay: Box<SolveFun<NdArray>> =
+        Box::new(move |_, y| y.map_components(|i, y_i| -rates.component(i) * y_i));
+    let initial = NdArray::from_vec(&[2, 2], vec![1.0; 4]);
+    let tolerances = Tolerances::new(1e-5, 1e-8);
+    let check = |state: &NdArray| {
+        assert_eq!(state.shape(), &[2, 2]);
+        assert!((state.data()[0] - (-1.0f32).exp()).abs() < 1e-4);
+        for y_i in &state.data()[1..] {
+            assert!(y_i.abs() < 1e-4);
+        }
+    };
+
+    let mut solver = Radau::new(0.0, initial.clone(), tolerances.clone());
+    check(solver.integrate_to(&decay, 1.0).unwrap());
+    let mut solver = Bdf::new(0.0, initial.clone(), tolerances.clone());
+    check(solver.integrate_to(&decay, 1.0).unwrap());
+    let mut solver = Rodas4::new(0.0, initial, tolerances);
+    check(solver.integrate_to(&decay, 1.0).unwrap());
+}
+
+#[test]
+fn stiff_state_types_agree_test() {
+    // The stiff solvers see the same components for Vec3 and Vector<3>
+    let rates = [1.0, 50.0, 2500.0];
+    let initial = [1.0, -1.0, 0.5];
+    let glam_fun: Box<SolveFun<Vec3>> =
+        Box::new(move |t, y| -Vec3::from_array(rates) * y + Vec3::splat(t.sin()));
+    let vector_fun: Box<SolveFun<Vector<3>>> =
+        Box::new(move |t, y| Vector::from_fn(|i, _| -rates[i] * y[i] + t.sin()));
+    let glam_tolerances = Tolerances::new(1e-6, 1e-8);
+    let vector_tolerances = Tolerances::new(1e-6, 1e-8);
+
+    let mut glam_solver = Rodas5::new(0.0, Vec3::from_array(initial), glam_tolerances);
+    let mut vector_solver = Rodas5::new(0.0, Vector::from(initial), vector_tolerances);
+    let glam_state = *glam_solver.integrate_to(&glam_fun, 2.0).unwrap();
+    let vector_state = *vector_solver.integrate_to(&vector_fun, 2.0).unwrap();
+    assert_eq!(
+        glam_state.to_array(),
+        [vector_state[0], vector_state[1], vector_state[2]]
+    );
+    assert_eq!(glam_solver.statistics(), vector_solver.statistics());
+
+    let mut glam_solver = Bdf::new(0.0, Vec3::from_array(initial), glam_tolerances);
+    let mut vector_solver = Bdf::new(0.0, Vector::from(initial), vector_tolerances);
+    let glam_state = *glam_solver.integrate_to(&glam_fun, 2.0).unwrap();
+    let vector_state = *vector_solver.integrate_to(&vector_fun, 2.0).unwrap();
+    assert_eq!(
+        glam_state.to_array(),
+        [vector_state[0], vector_state[1], vector_state[2]]
+    );
+}
+
+#[test]
+fn stiff_clifford_state_test() {
+    // Every blade relaxes to one at its own rate
+    let rates = [1.0, 10.0, 100.0, 1000.0, 1.0, 10.0, 100.0, 1000.0];
+    let relax = move |_, y: Clifford| y.map_components(|i, y_i| rates[i] * (1.0 - y_i));
+    let initial = Clifford::new([0.0; 8]);
+    let jacobian = Jacobian::analytic(move |_, _: Clifford| {
+        Matrix::<8, 8>::from_fn(|i, j| if i == j { -rates[i] } else { 0.0 })
+    });
+    let mut solver = Rodas5::new(0.0, initial, Tolerances::new(1e-4, 1e-6)).with_jacobian(jacobian);
+    let state = *solver.integrate_to(&relax, 5.0).unwrap();
+    for (y_i, rate) in state.factors().iter().zip(rates) {
+        assert!((y_i - (1.0 - (-5.0 * rate).exp())).abs() < 1e-3);
+    }
+
+    // solve_ivp takes the same states
+    let solution = solve_ivp(
+        relax,
+        (0.0, 5.0),
+        initial,
+        SolveOptions::new().with_method(Method::Radau),
+    );
+    assert!(solution.success());
+    let (_, state) = solution.last().unwrap();
+    assert!(state.factors().iter().all(|y_i| (y_i - 1.0).abs() < 1e-2));
+}
@@ -0,0 +1,112 @@
+use csl::{
+    diffeq::{
+        adaptive::Rk45,
+        control::Tolerances,
+        error::SolverError,
+        implicit::ThetaMethod,
+        ivp::{RungeKutta4, SolveFun},
+        multistep::AdamsBashforth,
+        radau::Radau,
+        stream::Stepper,
+    },
+    linalg::ndarray::Vector,
+};
+
+fn decay() -> Box<SolveFun<Vector<1>>> {
+    Box::new(|_, y| -1.0 * y)
+}
+
+// The solution at t = 1 from stepping with 0.1 through the iterator
+fn at_one(stepper: &mut impl Stepper<Vector<1>>) -> f32 {
+    let fun = decay();
+    let (time, state) = stepper
+        .iter(&fun, 0.1)
+        .find(|(time, _)| (time - 1.0).abs() < 1e-4)
+        .unwrap();
+    assert!((time - 1.0).abs() < 1e-4);
+    state[0]
+}
+
+#[test]
+fn fixed_step_iterator_test() {
+    let fun = decay();
+    let mut solver = RungeKutta4::new(0.0, Vector::from([1.0]));
+    let steps: Vec<(f32, Vector<1>)> = solver.iter(&fun, 0.1).take(11).collect();
+    assert_eq!(steps.len(), 11);
+    assert_eq!(steps[0], (0.0, Vector::from([1.0])));
+
+    // The same values as calling next_step directly
+    let mut reference = RungeKutta4::new(0.0, Vector::from([1.0]));
+    for (time, state) in &steps[1..] {
+        let expected = *reference.next_step(&fun, 0.1);
+        assert_eq!(*time, reference.time());
+        assert_eq!(*state, expected);
+    }
+    assert_eq!(solver.time(), reference.time());
+    assert!((steps[10].1[0] - (-1.0f32).exp()).abs() < 1e-6);
+}
+
+#[test]
+fn steppers_test() {
+    let exact = (-1.0f32).exp();
+    let tolerances = Tolerances::new(1e-6, 1e-8);
+    let start = Vector::from([1.0]);
+    assert!((at_one(&mut RungeKutta4::new(0.0, start)) - exact).abs() < 1e-6);
+    assert!((at_one(&mut AdamsBashforth::new(0.0, start, 4)) - exact).abs() < 1e-4);
+    assert!((at_one(&mut ThetaMethod::trapezoidal(0.0, start)) - exact).abs() < 1e-3);
+    assert!((at_one(&mut Rk45::new(0.0, start, tolerances)) - exact).abs() < 1e-5);
+    assert!((at_one(&mut Radau::new(0.0, start, tolerances)) - exact).abs() < 1e-5);
+}
+
+#[test]
+fn adaptive_output_times_test() {
+    // The adaptive solvers step to every output time
+    let fun = decay();
+    let mut solver = Rk45::new(0.0, Vector::from([1.0]), Tolerances::new(1e-6, 1e-8));
+    let steps: Vec<(f32, Vector<1>)> = solver
+        .iter(&fun, 0.5)
+        .take_while(|(time, _)| *time <= 3.0)
+        .collect();
+    assert_eq!(steps.len(), 7);
+    for (k, (time, state)) in steps.iter().enumerate() {
+        assert_eq!(*time, k as f32 * 0.5);
+        assert!((state[0] - (-time).exp()).abs() < 1e-5);
+    }
+    // One step beyond the last yielded value was taken to end the iteration
+    assert_eq!(solver.time(), 3.5);
+}
+
+#[test]
+fn owned_steps_test() {
+    let steps = {
+        let solver = RungeKutta4::new(0.0, 1.0f32);
+        solver.into_steps(|t, _| t.cos(), 0.05)
+    };
+    let (time, state) = steps.step_by(2).nth(10).unwrap();
+    assert!((time - 1.0).abs() < 1e-5);
+    assert!((state - (1.0 + time.sin())).abs() < 1e-5);
+}
+
+#[test]
+fn failure_ends_iteration_test() {
+    // y' = y^2 with y(0) = 1 blows up at t = 1
+    let fun: Box<SolveFun<f32>> = Box::new(|_, y| y * y);
+    let mut solver = Rk45::new(0.0, 1.0, Tolerances::new(1e-6, 1e-8));
+    let mut steps = solver.iter(&fun, 0.25);
+    let times: Vec<f32> = steps.by_ref().map(|(time, _)| time).collect();
+    assert_eq!(times, [0.0, 0.25, 0.5, 0.75]);
+    assert!(matches!(
+        steps.error(),
+        Some(SolverError::StepSizeTooSmall { .. })
+    ));
+    assert_eq!(steps.next(), None);
+}
+
+#[test]
+fn double_precision_steps_test() {
+    let fun: Box<SolveFun<f64, f64>> = Box::new(|_, y| -y);
+    let mut solver = RungeKutta4::new(0.0, 1.0f64);
+    let (time, state) = solver.iter(&fun, 0.001).nth(1000).unwrap();
+    assert!((time - 1.0).abs() < 1e-12);
+    assert!((state - (-1.0f64).exp()).abs() < 1e-12);
+}
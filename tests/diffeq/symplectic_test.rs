@@ -0,0 +1,256 @@
+use std::f32::consts::PI;
+
+use csl::{
+    diffeq::{
+        ivp::RungeKutta4,
+        symplectic::{
+            ForceFun, ForestRuthScheme, Leapfrog, SplittingScheme, SymplecticEulerScheme,
+            SymplecticIntegrator, VelocityFun, VelocityVerlet, VerletScheme, Yoshida4,
+            Yoshida4Scheme, Yoshida6, Yoshida6Scheme,
+        },
+    },
+    linalg::ndarray::Vector,
+};
+
+fn assert_consistent<S: SplittingScheme>() {
+    assert_eq!(S::KICK.len(), S::stages());
+    assert!((S::DRIFT.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+    assert!((S::KICK.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+}
+
+#[test]
+fn scheme_consistency_test() {
+    assert_consistent::<SymplecticEulerScheme>();
+    assert_consistent::<VerletScheme>();
+    assert_consistent::<ForestRuthScheme>();
+    assert_consistent::<Yoshida4Scheme>();
+    assert_consistent::<Yoshida6Scheme>();
+}
+
+// Pendulum with H = p^2 / 2 - cos(q)
+fn pendulum_force() -> Box<ForceFun<Vector<1>>> {
+    Box::new(|_, q| Vector::from([-q[0].sin()]))
+}
+
+fn unit_mass() -> Box<VelocityFun<Vector<1>>> {
+    Box::new(|p| p)
+}
+
+fn pendulum_energy(q: &Vector<1>, p: &Vector<1>) -> f32 {
+    0.5 * p[0] * p[0] - q[0].cos()
+}
+
+// Position error after a period of the harmonic oscillator q'' = -q
+fn oscillator_error<S: SplittingScheme>(steps: usize) -> f32 {
+    let delta_time = 2.0 * PI / steps as f32;
+    let mut integrator =
+        SymplecticIntegrator::<S, Vector<1>>::new(0.0, Vector::from([1.0]), Vector::from([0.0]));
+    let force: Box<ForceFun<Vector<1>>> = Box::new(|_, q| -1.0 * q);
+    for _ in 0..steps {
+        integrator.next_step(&force, &unit_mass(), delta_time);
+    }
+    (integrator.position()[0] - 1.0)
+        .abs()
+        .max(integrator.momentum()[0].abs())
+}
+
+#[test]
+fn convergence_order_test() {
+    crate::assert_convergence_order(
+        oscillator_error::<SymplecticEulerScheme>,
+        SymplecticEulerScheme::ORDER,
+        64,
+        0.3,
+    );
+    crate::assert_convergence_order(
+        oscillator_error::<VerletScheme>,
+        VerletScheme::ORDER,
+        32,
+        0.3,
+    );
+    crate::assert_convergence_order(
+        oscillator_error::<ForestRuthScheme>,
+        ForestRuthScheme::ORDER,
+        16,
+        0.3,
+    );
+    crate::assert_convergence_order(
+        oscillator_error::<Yoshida6Scheme>,
+        Yoshida6Scheme::ORDER,
+        8,
+        0.3,
+    );
+}
+
+// Determinant of the linear map `(q, p) -> flow(q, p)`, the factor by
+// which it scales phase-space areas
+fn area_factor(flow: impl Fn(f32, f32) -> (f32, f32)) -> f32 {
+    let (dq_dq, dp_dq) = flow(1.0, 0.0);
+    let (dq_dp, dp_dp) = flow(0.0, 1.0);
+    dq_dq * dp_dp - dq_dp * dp_dq
+}
+
+// A hundred coarse steps of the harmonic oscillator, whose flow is linear
+const STEPS: usize = 100;
+const DELTA_TIME: f32 = 0.5;
+
+fn oscillator_flow<S: SplittingScheme>(q: f32, p: f32) -> (f32, f32) {
+    let mut integrator =
+        SymplecticIntegrator::<S, Vector<1>>::new(0.0, Vector::from([q]), Vector::from([p]));
+    let force: Box<ForceFun<Vector<1>>> = Box::new(|_, q| -1.0 * q);
+    for _ in 0..STEPS {
+        integrator.next_step(&force, &unit_mass(), DELTA_TIME);
+    }
+    (integrator.position()[0], integrator.momentum()[0])
+}
+
+#[test]
+fn phase_area_test() {
+    for factor in [
+        area_factor(oscillator_flow::<SymplecticEulerScheme>),
+        area_factor(oscillator_flow::<VerletScheme>),
+        area_factor(oscillator_flow::<ForestRuthScheme>),
+        area_factor(oscillator_flow::<Yoshida4Scheme>),
+        area_factor(oscillator_flow::<Yoshida6Scheme>),
+    ] {
+        assert!((factor - 1.0).abs() < 1e-4, "area factor {}", factor);
+    }
+
+    // RK4 damps the oscillator by |R(ih)|^2 = 1 - h^6 / 72 + h^8 / 576 per step
+    let rk4 = area_factor(|q, p| {
+        let mut solver = RungeKutta4::new(0.0, Vector::from([q, p]));
+        for _ in 0..STEPS {
+            solver.next_step(&|_, y| Vector::from([y[1], -y[0]]), DELTA_TIME);
+        }
+        (solver.state()[0], solver.state()[1])
+    });
+    let h = DELTA_TIME;
+    let expected = (1.0 - h.powi(6) / 72.0 + h.powi(8) / 576.0).powi(STEPS as i32);
+    assert!((rk4 - expected).abs() < 1e-4, "{} vs {}", rk4, expected);
+    assert!(rk4 < 0.99);
+}
+
+/// Splitting scheme of position Verlet, drift half a step, kick a full
+/// step and drift again.
+struct PositionVerletScheme;
+
+impl SplittingScheme for PositionVerletScheme {
+    const ORDER: usize = 2;
+    const DRIFT: &'static [f64] = &[0.5, 0.5];
+    const KICK: &'static [f64] = &[1.0, 0.0];
+}
+
+// One step of `S`, or the triple jump of three steps of `S` with the
+// Yoshida weights, on the pendulum
+fn pendulum_step<S: SplittingScheme>(weights: &[f32], delta_time: f32) -> (f32, f32) {
+    let mut integrator =
+        SymplecticIntegrator::<S, Vector<1>>::new(0.0, Vector::from([1.2]), Vector::from([0.4]));
+    for weight in weights {
+        integrator.next_step(&pendulum_force(), &unit_mass(), weight * delta_time);
+    }
+    (integrator.position()[0], integrator.momentum()[0])
+}
+
+#[test]
+fn triple_jump_test() {
+    // Both fourth order schemes are the triple jump with the weights w1, w0, w1:
+    // Yoshida4 of velocity Verlet and Forest-Ruth of position Verlet
+    let w1 = 1.0 / (2.0 - 2f32.powf(1.0 / 3.0));
+    let weights = [w1, 1.0 - 2.0 * w1, w1];
+    for delta_time in [0.1, 0.4] {
+        let (q, p) = pendulum_step::<Yoshida4Scheme>(&[1.0], delta_time);
+        let (q_jump, p_jump) = pendulum_step::<VerletScheme>(&weights, delta_time);
+        assert!((q - q_jump).abs() < 1e-6 && (p - p_jump).abs() < 1e-6);
+
+        let (q, p) = pendulum_step::<ForestRuthScheme>(&[1.0], delta_time);
+        let (q_jump, p_jump) = pendulum_step::<PositionVerletScheme>(&weights, delta_time);
+        assert!((q - q_jump).abs() < 1e-6 && (p - p_jump).abs() < 1e-6);
+    }
+
+    // The two maps differ, but only in the fifth order local error
+    let difference = |delta_time| {
+        let (q, p) = pendulum_step::<Yoshida4Scheme>(&[1.0], delta_time);
+        let (q_fr, p_fr) = pendulum_step::<ForestRuthScheme>(&[1.0], delta_time);
+        (q - q_fr).abs().max((p - p_fr).abs())
+    };
+    let observed = (difference(0.4) / difference(0.2)).log2();
+    assert!(observed > 4.7, "observed {}", observed);
+}
+
+#[test]
+fn energy_is_conserved_test() {
+    // Integrate a large pendulum swing for many periods with a coarse step:
+    // the symplectic error stays bounded while RK4 slowly loses energy
+    let (q0, p0) = (Vector::from([2.0]), Vector::from([0.0]));
+    let initial_energy = pendulum_energy(&q0, &p0);
+    let delta_time = 0.2;
+    let steps = 5000;
+
+    let mut verlet = VelocityVerlet::new(0.0, q0, p0);
+    let mut max_drift: f32 = 0.0;
+    for _ in 0..steps {
+        let (q, p) = verlet.next_step(&pendulum_force(), &unit_mass(), delta_time);
+        max_drift = max_drift.max((pendulum_energy(q, p) - initial_energy).abs());
+    }
+    assert!(max_drift < 0.05, "energy drift {}", max_drift);
+    // Velocity Verlet needs one force evaluation per step
+    assert_eq!(verlet.force_evaluations(), steps + 1);
+
+    // A fourth order symplectic method against classical RK4
+    let mut yoshida = Yoshida4::new(0.0, q0, p0);
+    let mut yoshida_drift: f32 = 0.0;
+    for _ in 0..steps {
+        let (q, p) = yoshida.next_step(&pendulum_force(), &unit_mass(), delta_time);
+        yoshida_drift = yoshida_drift.max((pendulum_energy(q, p) - initial_energy).abs());
+    }
+    let mut rk4 = RungeKutta4::new(0.0, Vector::from([q0[0], p0[0]]));
+    for _ in 0..steps {
+        rk4.next_step(&|_, y| Vector::from([y[1], -y[0].sin()]), delta_time);
+    }
+    let state = rk4.state();
+    let rk4_drift = (pendulum_energy(&Vector::from([state[0]]), &Vector::from([state[1]]))
+        - initial_energy)
+        .abs();
+    assert!(
+        10.0 * yoshida_drift < rk4_drift,
+        "{} vs {}",
+        yoshida_drift,
+        rk4_drift
+    );
+}
+
+#[test]
+fn time_reversibility_test() {
+    let (q0, p0) = (Vector::from([1.0]), Vector::from([0.5]));
+    let mut leapfrog = Leapfrog::new(0.0, q0, p0);
+    let mut yoshida = Yoshida6::new(0.0, q0, p0);
+    for delta_time in [0.1, -0.1] {
+        for _ in 0..100 {
+            leapfrog.next_step(&pendulum_force(), &unit_mass(), delta_time);
+            yoshida.next_step(&pendulum_force(), &unit_mass(), delta_time);
+        }
+    }
+    assert!(leapfrog.time().abs() < 1e-5);
+    assert!((leapfrog.position()[0] - q0[0]).abs() < 1e-4);
+    assert!((leapfrog.momentum()[0] - p0[0]).abs() < 1e-4);
+    assert!((yoshida.position()[0] - q0[0]).abs() < 1e-4);
+    assert!((yoshida.momentum()[0] - p0[0]).abs() < 1e-4);
+}
+
+#[test]
+fn kepler_orbit_test() {
+    // Circular orbit of radius one around a unit mass at the origin
+    let force: Box<ForceFun<Vector<2>>> = Box::new(|_, q| {
+        let radius = (q[0] * q[0] + q[1] * q[1]).sqrt();
+        (-1.0 / (radius * radius * radius)) * q
+    });
+    let velocity: Box<VelocityFun<Vector<2>>> = Box::new(|p| p);
+    let mut integrator = Yoshida6::new(0.0, Vector::from([1.0, 0.0]), Vector::from([0.0, 1.0]));
+    let steps = 100;
+    for _ in 0..10 * steps {
+        integrator.next_step(&force, &velocity, 2.0 * PI / steps as f32);
+    }
+    let q = integrator.position();
+    assert!((q[0] - 1.0).abs() < 1e-3);
+    assert!(q[1].abs() < 1e-3);
+}
@@ -0,0 +1,70 @@
+use csl::linalg::{autograd::Tensor, ndarray::NdArray};
+
+// Compares the gradient from `backward` with central finite differences
+fn check_gradient(input: NdArray, fun: impl Fn(&Tensor) -> Tensor) {
+    let x = Tensor::parameter(input.clone());
+    fun(&x).backward();
+    let grad = x.grad().expect("gradient was not accumulated");
+
+    let h = 1e-2;
+    for i in 0..input.len() {
+        let mut plus = input.clone();
+        plus.data_mut()[i] += h;
+        let mut minus = input.clone();
+        minus.data_mut()[i] -= h;
+        let numeric =
+            (fun(&Tensor::new(plus)).item() - fun(&Tensor::new(minus)).item()) / (2.0 * h);
+        assert!(
+            (numeric - grad.data()[i]).abs() < 1e-2,
+            "component {}: numeric {} vs backward {}",
+            i,
+            numeric,
+            grad.data()[i]
+        );
+    }
+}
+
+#[test]
+fn elementwise_gradient_test() {
+    let input = NdArray::from_vec(&[2, 2], vec![0.5, -1.0, 1.5, 2.0]);
+    check_gradient(input.clone(), |x| (x * x).sum());
+    check_gradient(input.clone(), |x| x.tanh().sum());
+    check_gradient(input.clone(), |x| x.sigmoid().mean());
+    check_gradient(input.clone(), |x| {
+        (&x.exp() / &(x.square() + x.exp())).sum()
+    });
+}
+
+#[test]
+fn matmul_broadcast_gradient_test() {
+    let w = Tensor::new(NdArray::from_vec(
+        &[2, 3],
+        vec![1.0, -2.0, 0.5, 0.3, 0.7, -1.1],
+    ));
+    let b = Tensor::new(NdArray::from_vec(&[1, 3], vec![0.1, 0.2, 0.3]));
+    let input = NdArray::from_vec(&[4, 2], vec![0.1, 0.2, -0.3, 0.4, 0.5, -0.6, 0.7, 0.8]);
+
+    check_gradient(input, |x| (&x.matmul(&w) + &b).relu().square().sum());
+}
+
+#[test]
+fn log_softmax_gradient_test() {
+    let target = Tensor::new(NdArray::from_vec(
+        &[2, 3],
+        vec![0.0, 1.0, 0.0, 0.2, 0.3, 0.5],
+    ));
+    let input = NdArray::from_vec(&[2, 3], vec![1.0, 2.0, 0.5, -1.0, 0.0, 3.0]);
+
+    check_gradient(input, |x| (&x.log_softmax() * &target).sum());
+}
+
+#[test]
+fn shared_subexpression_gradient_test() {
+    let x = Tensor::parameter(NdArray::scalar(3.0));
+    let y = &x * &x;
+    let z = &y + &(&y * &x);
+    z.backward();
+
+    // z = x^2 + x^3 => dz/dx = 2x + 3x^2
+    assert_eq!(x.grad().unwrap().item(), 6.0 + 27.0);
+}
@@ -0,0 +1,111 @@
+use csl::linalg::{
+    banded::{BandedMatrix, CyclicTridiagonal, Tridiagonal},
+    error::LinalgError,
+    ndarray::{Matrix, Vector},
+};
+
+fn assert_residual_small<const N: usize>(matrix: Matrix<N, N>, x: Vector<N>, rhs: Vector<N>) {
+    let product = matrix * x;
+    for i in 0..N {
+        assert!(
+            (product[i] - rhs[i]).abs() < 1e-4,
+            "row {}: {} != {}",
+            i,
+            product[i],
+            rhs[i]
+        );
+    }
+}
+
+#[test]
+fn thomas_algorithm_test() {
+    let matrix = Tridiagonal::<6>::from_constant(-1.0, 4.0, -1.0);
+    let rhs = Vector::from([1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+
+    let x = matrix.solve(&rhs).unwrap();
+
+    assert_residual_small(matrix.to_matrix(), x, rhs);
+    let product = matrix * x;
+    for i in 0..6 {
+        assert!((product[i] - rhs[i]).abs() < 1e-4);
+    }
+}
+
+#[test]
+fn cyclic_tridiagonal_test() {
+    let matrix = CyclicTridiagonal::<5>::new(
+        [2.0, -1.0, 0.5, -1.0, 1.0],
+        [5.0, 4.0, 6.0, 5.0, 4.0],
+        [-1.0, 1.0, -1.0, 2.0, 1.5],
+    );
+    let rhs = Vector::from([1.0, -2.0, 0.5, 3.0, 1.0]);
+
+    let x = matrix.solve(&rhs).unwrap();
+
+    assert_eq!(matrix.to_matrix()[(0, 4)], 2.0);
+    assert_eq!(matrix.to_matrix()[(4, 0)], 1.5);
+    assert_residual_small(matrix.to_matrix(), x, rhs);
+    let product = matrix * x;
+    for i in 0..5 {
+        assert!((product[i] - rhs[i]).abs() < 1e-4);
+    }
+}
+
+#[test]
+fn banded_lu_with_pivoting_test() {
+    // A zero on the diagonal forces a row interchange
+    let dense = Matrix {
+        data: [
+            [0.0, 2.0, 1.0, 0.0, 0.0],
+            [3.0, 1.0, 0.0, 4.0, 0.0],
+            [0.0, 1.0, 2.0, 1.0, 1.0],
+            [0.0, 0.0, 5.0, 1.0, 2.0],
+            [0.0, 0.0, 0.0, 1.0, 3.0],
+        ],
+    };
+    let banded = BandedMatrix::from_matrix(&dense, 1, 2);
+    assert_eq!(banded.to_matrix().data, dense.data);
+
+    let rhs = Vector::from([1.0, 2.0, 3.0, 4.0, 5.0]);
+    let x = banded.solve(&rhs).unwrap();
+
+    assert_residual_small(dense, x, rhs);
+    let product = &banded * x;
+    for i in 0..5 {
+        assert!((product[i] - rhs[i]).abs() < 1e-4);
+    }
+}
+
+#[test]
+fn banded_cholesky_test() {
+    // Pentadiagonal discretization of the biharmonic operator plus a shift
+    let matrix = BandedMatrix::<8>::from_diagonals(2, &[1.0, -4.0, 7.0, -4.0, 1.0]);
+    let rhs = Vector::from([1.0, 0.0, -1.0, 2.0, 0.5, 0.0, 1.0, -1.0]);
+
+    let x = matrix.cholesky().unwrap().solve(&rhs);
+
+    assert_residual_small(matrix.to_matrix(), x, rhs);
+    let lu_x = matrix.solve(&rhs).unwrap();
+    for i in 0..8 {
+        assert!((x[i] - lu_x[i]).abs() < 1e-3);
+    }
+}
+
+#[test]
+fn banded_errors_test() {
+    let singular = BandedMatrix::<4>::from_diagonals(1, &[0.0, 0.0, 1.0]);
+    assert_eq!(
+        singular.solve(&Vector::from([1.0; 4])),
+        Err(LinalgError::Singular { row: 0 })
+    );
+
+    let indefinite = BandedMatrix::<4>::from_diagonals(1, &[1.0, -2.0, 1.0]);
+    assert!(matches!(
+        indefinite.cholesky(),
+        Err(LinalgError::NotPositiveDefinite { row: 0 })
+    ));
+
+    let tridiagonal: BandedMatrix<4> = Tridiagonal::from_constant(-1.0, 2.0, -1.0).into();
+    assert_eq!(tridiagonal[(2, 1)], -1.0);
+    assert_eq!(tridiagonal[(3, 0)], 0.0);
+}
@@ -0,0 +1,49 @@
+use csl::linalg::{dual::Dual, ndarray::Matrix, real::Real};
+
+// Generic over the scalar so that it runs on dual numbers
+fn fun<R: Real>(x: R) -> R {
+    x * x.sin() / x.sqrt() + (x.exp() + R::ONE).ln() - x.cos().powi(3)
+}
+
+fn derivative(x: f64) -> f64 {
+    x.sqrt().recip() * (0.5 * x.sin() + x * x.cos())
+        + x.exp() / (x.exp() + 1.0)
+        + 3.0 * x.cos().powi(2) * x.sin()
+}
+
+#[test]
+fn derivative_test() {
+    for x in [0.3, 1.0, 2.5] {
+        let result = fun(Dual::variable(x));
+        assert!((result.value - fun(x)).abs() < 1e-14);
+        assert!((result.derivative - derivative(x)).abs() < 1e-12);
+    }
+    assert_eq!(fun(Dual::constant(1.0)).derivative, 0.0);
+    assert_eq!(Dual::variable(-2.0f32).abs(), Dual::new(2.0, -1.0));
+}
+
+#[test]
+fn second_derivative_test() {
+    // d^2/dx^2 x^4 = 12 x^2
+    let x = Dual::new(Dual::variable(1.5), Dual::constant(1.0));
+    let result = x.powi(4);
+    assert_eq!(result.value.value, 1.5f64.powi(4));
+    assert!((result.derivative.value - 4.0 * 1.5f64.powi(3)).abs() < 1e-12);
+    assert!((result.derivative.derivative - 12.0 * 1.5f64.powi(2)).abs() < 1e-12);
+}
+
+#[test]
+fn matrix_test() {
+    // The derivative of A(x) v with A(x) = [[x, 1], [0, x^2]]
+    let x = Dual::variable(3.0);
+    let one = Dual::constant(1.0);
+    let a = Matrix {
+        data: [[x, one], [Dual::ZERO, x * x]],
+    };
+    let v = Matrix {
+        data: [[Dual::constant(2.0)], [Dual::constant(-1.0)]],
+    };
+    let product = a * v;
+    assert_eq!(product[0], Dual::new(5.0, 2.0));
+    assert_eq!(product[1], Dual::new(-9.0, -6.0));
+}
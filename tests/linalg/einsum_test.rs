@@ -0,0 +1,103 @@
+use csl::linalg::{
+    einsum::{einsum, einsum_path, einsum_with_strategy, tensordot, PathStrategy},
+    ndarray::NdArray,
+};
+
+fn arange(shape: &[usize]) -> NdArray {
+    let mut counter = 0.0;
+    NdArray::from_fn(shape, |_| {
+        counter += 1.0;
+        counter / 10.0
+    })
+}
+
+fn assert_close(a: &NdArray, b: &NdArray) {
+    assert_eq!(a.shape(), b.shape());
+    for (x, y) in a.data().iter().zip(b.data()) {
+        assert!((x - y).abs() <= 1e-4 * (1.0 + y.abs()), "{} != {}", x, y);
+    }
+}
+
+#[test]
+fn einsum_matmul_test() {
+    let a = arange(&[3, 4]);
+    let b = arange(&[4, 5]);
+
+    assert_close(&einsum("ij,jk->ik", &[&a, &b]), &a.matmul(&b));
+    assert_close(&einsum("ij,jk", &[&a, &b]), &a.matmul(&b));
+    assert_close(&einsum("ij,jk->ki", &[&a, &b]), &a.matmul(&b).transpose());
+}
+
+#[test]
+fn einsum_single_operand_test() {
+    let a = arange(&[3, 3]);
+
+    assert_close(&einsum("ii->", &[&a]), &NdArray::scalar(0.1 + 0.5 + 0.9));
+    assert_close(
+        &einsum("ii->i", &[&a]),
+        &NdArray::from_vec(&[3], vec![0.1, 0.5, 0.9]),
+    );
+    assert_close(&einsum("ij->ji", &[&a]), &a.transpose());
+    assert_close(&einsum("ij->i", &[&a]), &a.sum_axis(1).reshape(&[3]));
+}
+
+#[test]
+fn einsum_batched_and_rank_four_test() {
+    let a = arange(&[2, 3, 4]);
+    let b = arange(&[2, 4, 2]);
+    let batched = einsum("bij,bjk->bik", &[&a, &b]);
+    for batch in 0..2 {
+        for i in 0..3 {
+            for k in 0..2 {
+                let expected: f32 = (0..4)
+                    .map(|j| a[&[batch, i, j][..]] * b[&[batch, j, k][..]])
+                    .sum();
+                assert!((batched[&[batch, i, k][..]] - expected).abs() < 1e-4);
+            }
+        }
+    }
+
+    // Stress from a stiffness tensor and a strain: sigma_ij = C_ijkl eps_kl
+    let stiffness = arange(&[3, 3, 3, 3]);
+    let strain = arange(&[3, 3]);
+    let stress = einsum("ijkl,kl->ij", &[&stiffness, &strain]);
+    assert_close(&stress, &tensordot(&stiffness, &strain, &[2, 3], &[0, 1]));
+    let expected: f32 = (0..3)
+        .flat_map(|k| (0..3).map(move |l| (k, l)))
+        .map(|(k, l)| stiffness[&[1, 2, k, l][..]] * strain[&[k, l][..]])
+        .sum();
+    assert!((stress[&[1, 2][..]] - expected).abs() < 1e-3);
+}
+
+#[test]
+fn tensordot_test() {
+    let a = arange(&[2, 3, 4]);
+    let b = arange(&[4, 3, 5]);
+
+    let c = tensordot(&a, &b, &[1, 2], &[1, 0]);
+
+    assert_eq!(c.shape(), [2, 5]);
+    assert_close(&c, &einsum("ijk,kjl->il", &[&a, &b]));
+}
+
+#[test]
+fn einsum_path_test() {
+    // (a * b) * c creates a 10x10 intermediate, a * (b * c) only a 10x1 vector
+    let shapes: [&[usize]; 3] = [&[10, 100], &[100, 10], &[10, 1]];
+
+    assert_eq!(
+        einsum_path("ij,jk,kl->il", &shapes, PathStrategy::Optimal),
+        vec![(1, 2), (0, 1)]
+    );
+
+    let a = arange(&[10, 100]);
+    let b = arange(&[100, 10]);
+    let c = arange(&[10, 1]);
+    let expected = a.matmul(&b).matmul(&c);
+    for strategy in [PathStrategy::Optimal, PathStrategy::Greedy] {
+        assert_close(
+            &einsum_with_strategy("ij,jk,kl->il", &[&a, &b, &c], strategy),
+            &expected,
+        );
+    }
+}
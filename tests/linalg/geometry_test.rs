@@ -0,0 +1,86 @@
+use std::f32::consts::FRAC_PI_2;
+
+use csl::linalg::{
+    geometry::gram_schmidt,
+    ndarray::{Matrix, NdArray, Vector},
+};
+
+#[test]
+fn dot_and_cross_test() {
+    let a = Vector::from([1.0, 2.0, 3.0]);
+    let b = Vector::from([4.0, -5.0, 6.0]);
+
+    assert_eq!(a.dot(&b), 12.0);
+    let c = a.cross(&b);
+    assert_eq!(c, Vector::from([27.0, 6.0, -13.0]));
+    assert_eq!(c.dot(&a), 0.0);
+    assert_eq!(c.dot(&b), 0.0);
+    assert_eq!(Vector::from([3.0, 4.0]).norm(), 5.0);
+}
+
+#[test]
+fn angle_and_projection_test() {
+    let x = Vector::from([2.0, 0.0]);
+    let y = Vector::from([0.0, 3.0]);
+    let v = Vector::from([1.0, 1.0]);
+
+    assert!((x.angle(&y) - FRAC_PI_2).abs() < 1e-6);
+    assert_eq!(v.project_onto(&x), Vector::from([1.0, 0.0]));
+    assert_eq!(v.reject_from(&x), Vector::from([0.0, 1.0]));
+    assert!((v.normalize().norm() - 1.0).abs() < 1e-6);
+    assert!(Vector::<2>::zeros().try_normalize().is_none());
+}
+
+#[test]
+fn outer_and_kron_test() {
+    let a = Vector::from([1.0, 2.0]);
+    let b = Vector::from([3.0, 4.0, 5.0]);
+    assert_eq!(
+        a.outer(&b),
+        Matrix {
+            data: [[3.0, 4.0, 5.0], [6.0, 8.0, 10.0]]
+        }
+    );
+
+    let identity = Matrix::<2, 2>::identity();
+    let m = Matrix {
+        data: [[1.0, 2.0], [3.0, 4.0]],
+    };
+    let k: Matrix<4, 4> = identity.kron(&m);
+    assert_eq!(
+        k,
+        Matrix {
+            data: [
+                [1.0, 2.0, 0.0, 0.0],
+                [3.0, 4.0, 0.0, 0.0],
+                [0.0, 0.0, 1.0, 2.0],
+                [0.0, 0.0, 3.0, 4.0],
+            ]
+        }
+    );
+    assert_eq!(
+        NdArray::from(identity).kron(&NdArray::from(m)),
+        NdArray::from(k)
+    );
+}
+
+#[test]
+fn gram_schmidt_test() {
+    let vectors = [
+        Vector::from([1.0, 1.0, 0.0]),
+        Vector::from([2.0, 2.0, 0.0]),
+        Vector::from([1.0, 0.0, 1.0]),
+        Vector::from([0.0, 1.0, 1.0]),
+    ];
+
+    let basis = gram_schmidt(&vectors);
+
+    // The second vector is parallel to the first one
+    assert_eq!(basis.len(), 3);
+    for (i, a) in basis.iter().enumerate() {
+        for (j, b) in basis.iter().enumerate() {
+            let expected = if i == j { 1.0 } else { 0.0 };
+            assert!((a.dot(b) - expected).abs() < 1e-5);
+        }
+    }
+}
@@ -0,0 +1,66 @@
+use csl::linalg::{
+    error::LinalgError,
+    ndarray::{Matrix, NdArray, Vector},
+};
+
+#[test]
+fn solve_test() {
+    // Needs a row interchange in the first column
+    let matrix = Matrix {
+        data: [[0.0, 2.0, 1.0], [1.0, 1.0, 1.0], [2.0, 1.0, -1.0]],
+    };
+    let rhs = Vector::from([7.0, 6.0, 1.0]);
+
+    let x = matrix.solve(&rhs).unwrap();
+
+    let product = matrix * x;
+    for i in 0..3 {
+        assert!((product[i] - rhs[i]).abs() < 1e-5);
+    }
+    assert!((x[0] - 1.0).abs() < 1e-5);
+    assert!((x[1] - 2.0).abs() < 1e-5);
+    assert!((x[2] - 3.0).abs() < 1e-5);
+}
+
+#[test]
+fn determinant_and_inverse_test() {
+    let matrix = Matrix {
+        data: [[4.0, 7.0], [2.0, 6.0]],
+    };
+    assert!((matrix.determinant() - 10.0).abs() < 1e-5);
+    // The sign changes with a row interchange
+    let swapped = Matrix {
+        data: [[2.0, 6.0], [4.0, 7.0]],
+    };
+    assert!((swapped.determinant() + 10.0).abs() < 1e-5);
+
+    let product = matrix * matrix.inverse().unwrap();
+    let identity = Matrix::<2, 2>::identity();
+    for i in 0..2 {
+        for j in 0..2 {
+            assert!((product[(i, j)] - identity[(i, j)]).abs() < 1e-5);
+        }
+    }
+}
+
+#[test]
+fn singular_test() {
+    let matrix = Matrix {
+        data: [[1.0, 2.0], [2.0, 4.0]],
+    };
+    assert_eq!(matrix.lu().unwrap_err(), LinalgError::Singular { row: 1 });
+    assert_eq!(matrix.determinant(), 0.0);
+}
+
+#[test]
+fn dynamic_lu_test() {
+    let array = NdArray::from_vec(&[3, 3], vec![3.0, 1.0, 0.0, 1.0, 4.0, 1.0, 0.0, 1.0, 5.0]);
+    let lu = array.lu().unwrap();
+    assert_eq!(lu.size(), 3);
+
+    let x = lu.solve(&[4.0, 6.0, 6.0]);
+    for value in x {
+        assert!((value - 1.0).abs() < 1e-5);
+    }
+    assert!((lu.determinant() - 52.0).abs() < 1e-4);
+}
@@ -1 +1,9 @@
+pub mod autograd_test;
+pub mod banded_test;
+pub mod dual_test;
+pub mod einsum_test;
+pub mod geometry_test;
+pub mod lu_test;
+// `matrix_multiplication_test` clones a `Copy` matrix
+#[allow(clippy::clone_on_copy)]
 pub mod ndarray_test;
@@ -1,4 +1,4 @@
-use csl::linalg::ndarray::{Matrix, Vector};
+use csl::linalg::ndarray::{Matrix, NdArray, Vector};
 
 #[test]
 fn matrix_multiplication_test() {
@@ -76,3 +76,54 @@ fn vector_multiplication_test() {
         assert_eq!(c.data[i], out_vec.data[i]);
     }
 }
+
+#[test]
+fn vector_index_mut_test() {
+    let mut v = Vector::from([1.0, 2.0, 3.0]);
+    v[1] = 5.0;
+    v[2] += 1.0;
+
+    assert_eq!(v.data, [[1.0], [5.0], [4.0]]);
+    assert_eq!(v[1], 5.0);
+}
+
+#[test]
+fn ndarray_broadcast_test() {
+    let a = NdArray::from_vec(&[2, 3], vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+    let row = NdArray::from_vec(&[1, 3], vec![10.0, 20.0, 30.0]);
+    let column = NdArray::from_vec(&[2, 1], vec![100.0, 200.0]);
+
+    assert_eq!((&a + &row).data(), [11.0, 22.0, 33.0, 14.0, 25.0, 36.0]);
+    assert_eq!(
+        (&a * &column).data(),
+        [100.0, 200.0, 300.0, 800.0, 1000.0, 1200.0]
+    );
+    assert_eq!((&row + &column).shape(), [2, 3]);
+    assert_eq!(a.sum_axis(0).data(), [5.0, 7.0, 9.0]);
+    assert_eq!(a.sum_to_shape(&[2, 1]).data(), [6.0, 15.0]);
+}
+
+#[test]
+fn ndarray_matmul_matches_matrix_test() {
+    let a = Matrix {
+        data: [[3.0, 2.0, 1.0], [1.0, 0.0, 2.0]],
+    };
+    let b = Matrix {
+        data: [[1.0, 2.0], [0.0, 1.0], [4.0, 0.0]],
+    };
+
+    let c = NdArray::from(a).matmul(&NdArray::from(b));
+
+    assert_eq!(c.shape(), [2, 2]);
+    assert_eq!(c.to_matrix::<2, 2>().data, (a * b).data);
+}
+
+#[test]
+fn ndarray_permute_test() {
+    let a = NdArray::from_fn(&[2, 3, 4], |i| (100 * i[0] + 10 * i[1] + i[2]) as f32);
+    let p = a.permute(&[2, 0, 1]);
+
+    assert_eq!(p.shape(), [4, 2, 3]);
+    assert_eq!(p[&[3, 1, 2][..]], 123.0);
+    assert_eq!(p.permute(&[1, 2, 0]), a);
+}
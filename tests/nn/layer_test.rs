@@ -0,0 +1,60 @@
+use csl::{
+    linalg::{autograd::Tensor, ndarray::NdArray},
+    nn::{
+        activation::{ReLU, Tanh},
+        layer::{Linear, Module, Sequential},
+        loss::{cross_entropy_with_labels, mse_loss},
+    },
+};
+
+#[test]
+fn linear_forward_test() {
+    let layer = Linear::from_arrays(
+        NdArray::from_vec(&[2, 3], vec![1.0, 0.0, 2.0, 0.0, 1.0, -1.0]),
+        NdArray::from_vec(&[1, 3], vec![0.5, 0.5, 0.5]),
+    );
+    let input = Tensor::new(NdArray::from_vec(&[2, 2], vec![1.0, 2.0, 3.0, 4.0]));
+
+    let output = layer.forward(&input);
+
+    assert_eq!(output.shape(), [2, 3]);
+    assert_eq!(output.value().data(), [1.5, 2.5, 0.5, 3.5, 4.5, 2.5]);
+}
+
+#[test]
+fn sequential_parameters_test() {
+    let model = Sequential::new()
+        .with(Linear::new(3, 8))
+        .with(ReLU)
+        .with(Linear::new(8, 8))
+        .with(Tanh)
+        .with(Linear::new(8, 2));
+
+    let shapes: Vec<Vec<usize>> = model.parameters().iter().map(|p| p.shape()).collect();
+    assert_eq!(
+        shapes,
+        vec![
+            vec![3, 8],
+            vec![1, 8],
+            vec![8, 8],
+            vec![1, 8],
+            vec![8, 2],
+            vec![1, 2]
+        ]
+    );
+
+    let output = model.forward(&Tensor::new(NdArray::zeros(&[5, 3])));
+    assert_eq!(output.shape(), [5, 2]);
+}
+
+#[test]
+fn loss_value_test() {
+    let prediction = Tensor::new(NdArray::from_vec(&[2, 1], vec![1.0, 3.0]));
+    let target = Tensor::new(NdArray::from_vec(&[2, 1], vec![0.0, 1.0]));
+    assert_eq!(mse_loss(&prediction, &target).item(), 2.5);
+
+    // Uniform logits give a loss of ln(classes)
+    let logits = Tensor::new(NdArray::zeros(&[3, 4]));
+    let loss = cross_entropy_with_labels(&logits, &[0, 1, 3]).item();
+    assert!((loss - 4.0f32.ln()).abs() < 1e-6);
+}
@@ -0,0 +1,2 @@
+pub mod layer_test;
+pub mod optim_test;
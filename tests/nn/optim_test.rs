@@ -0,0 +1,71 @@
+use csl::{
+    linalg::{autograd::Tensor, ndarray::NdArray},
+    nn::{
+        activation::Tanh,
+        init::manual_seed,
+        layer::{Linear, Module, Sequential},
+        loss::{cross_entropy_with_labels, mse_loss},
+        optim::{Adam, Momentum, Optimizer, Sgd},
+    },
+};
+
+fn train(model: &impl Module, optimizer: &mut impl Optimizer, epochs: usize) -> f32 {
+    let input = Tensor::new(NdArray::from_fn(&[16, 1], |i| i[0] as f32 / 8.0 - 1.0));
+    let target = Tensor::new(input.value().map(|x| 2.0 * x - 0.5));
+
+    let mut loss = f32::INFINITY;
+    for _ in 0..epochs {
+        optimizer.zero_grad();
+        let output = mse_loss(&model.forward(&input), &target);
+        output.backward();
+        optimizer.step();
+        loss = output.item();
+    }
+    loss
+}
+
+#[test]
+fn sgd_linear_regression_test() {
+    let model = Linear::new(1, 1);
+    let mut optimizer = Sgd::new(model.parameters(), 0.1);
+
+    assert!(train(&model, &mut optimizer, 300) < 1e-6);
+    assert!((model.weight.item() - 2.0).abs() < 1e-3);
+    assert!((model.bias.item() + 0.5).abs() < 1e-3);
+}
+
+#[test]
+fn momentum_linear_regression_test() {
+    let model = Linear::new(1, 1);
+    let mut optimizer = Momentum::new(model.parameters(), 0.05, 0.9).nesterov(true);
+
+    assert!(train(&model, &mut optimizer, 300) < 1e-6);
+}
+
+#[test]
+fn adam_xor_classification_test() {
+    manual_seed(7);
+    let model = Sequential::new()
+        .with(Linear::new(2, 8))
+        .with(Tanh)
+        .with(Linear::new(8, 2));
+    let mut optimizer = Adam::new(model.parameters(), 0.05);
+
+    let input = Tensor::new(NdArray::from_vec(
+        &[4, 2],
+        vec![0.0, 0.0, 0.0, 1.0, 1.0, 0.0, 1.0, 1.0],
+    ));
+    let labels = [0, 1, 1, 0];
+
+    for _ in 0..300 {
+        optimizer.zero_grad();
+        cross_entropy_with_labels(&model.forward(&input), &labels).backward();
+        optimizer.step();
+    }
+
+    let logits = model.forward(&input);
+    for (row, label) in logits.value().data().chunks(2).zip(labels) {
+        let predicted = if row[0] > row[1] { 0 } else { 1 };
+        assert_eq!(predicted, label);
+    }
+}
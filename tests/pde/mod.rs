@@ -0,0 +1,2 @@
+pub mod mol_test;
+pub mod operator_test;
@@ -0,0 +1,138 @@
+use std::f32::consts::PI;
+
+use csl::{
+    diffeq::{
+        higher_order::solve_second_order,
+        ivp::{RungeKutta4, SolveFun},
+        solve::{solve_ivp, SolveOptions},
+    },
+    linalg::ndarray::Vector,
+    pde::{
+        boundary::{Boundaries, Boundaries2d},
+        grid::{Grid1d, Grid2d},
+        mol::{MethodOfLines1d, MethodOfLines2d},
+    },
+};
+
+#[test]
+fn heat_equation_test() {
+    // u_t = u_xx with u = 0 at both ends, solved by exp(-pi^2 t) sin(pi x)
+    let lines = MethodOfLines1d::new(Grid1d::<21>::new(0.0, 1.0), Boundaries::dirichlet(0.0, 0.0));
+    let laplacian = lines.laplacian(4);
+    let fun: Box<SolveFun<Vector<21>>> = Box::new(lines.system(move |_, u| laplacian.apply(u)));
+
+    let initial_state = lines.initial_state(|x| (PI * x).sin());
+    let delta_time = 0.25 * lines.grid().spacing().powi(2);
+    let mut solver = RungeKutta4::new(0.0, initial_state);
+    for _ in 0..160 {
+        solver.next_step(&fun, delta_time);
+    }
+    let time = solver.time();
+    let exact = lines
+        .grid()
+        .sample(|x| (-PI * PI * time).exp() * (PI * x).sin());
+    let state = solver.state();
+    assert_eq!((state[0], state[20]), (0.0, 0.0));
+    for i in 0..21 {
+        assert!((state[i] - exact[i]).abs() < 1e-4, "{} at {}", state[i], i);
+    }
+}
+
+#[test]
+fn insulated_heat_equation_test() {
+    // Without flux through the ends the heat is conserved and spreads evenly
+    let lines = MethodOfLines1d::new(Grid1d::<17>::new(0.0, 1.0), Boundaries::neumann(0.0, 0.0));
+    let laplacian = lines.laplacian(2);
+    let initial_state = lines.initial_state(|x| 1.0 + (PI * x).cos());
+    let solution = solve_ivp(
+        lines.system(move |_, u| laplacian.apply(u)),
+        (0.0, 0.5),
+        initial_state,
+        SolveOptions::new(),
+    );
+    assert!(solution.success());
+    let heat = lines.grid().integrate(&initial_state);
+    assert!((heat - 1.0).abs() < 1e-2);
+    for state in &solution.states {
+        assert!((lines.grid().integrate(state) - heat).abs() < 1e-4);
+    }
+    let (_, state) = solution.last().unwrap();
+    let amplitude = (-PI * PI * 0.5).exp();
+    assert!((state[0] - 1.0 - amplitude).abs() < 1e-3);
+    assert!((state[16] - 1.0 + amplitude).abs() < 1e-3);
+}
+
+#[test]
+fn advection_test() {
+    // u_t + u_x = 0 on a periodic grid carries the profile once around
+    let lines = MethodOfLines1d::new(Grid1d::<64>::periodic(0.0, 1.0), Boundaries::periodic());
+    let advection = lines.upwind(1.0, 3);
+    let fun: Box<SolveFun<Vector<64>>> = Box::new(lines.system(move |_, u| -advection.apply(u)));
+
+    let initial_state = lines.initial_state(|x| (2.0 * PI * x).sin());
+    let mut solver = RungeKutta4::new(0.0, initial_state);
+    for _ in 0..128 {
+        solver.next_step(&fun, 0.5 * lines.grid().spacing());
+    }
+    assert!((solver.time() - 1.0).abs() < 1e-5);
+    let state = solver.state();
+    for i in 0..64 {
+        assert!((state[i] - initial_state[i]).abs() < 2e-2);
+    }
+    let mass = lines.grid().integrate(state);
+    assert!(mass.abs() < 1e-4);
+}
+
+#[test]
+fn wave_equation_test() {
+    // u_tt = u_xx with fixed ends has the standing wave cos(pi t) sin(pi x)
+    let lines = MethodOfLines1d::new(Grid1d::<21>::new(0.0, 1.0), Boundaries::dirichlet(0.0, 0.0));
+    let laplacian = lines.laplacian(4);
+    let solution = solve_second_order::<Vector<21>>(
+        lines.second_order_system(move |_, u, _| laplacian.apply(u)),
+        (0.0, 1.0),
+        lines.initial_state(|x| (PI * x).sin()),
+        Vector::zeros(),
+        SolveOptions::new(),
+    );
+    assert!(solution.success());
+    let position = solution.positions().last().unwrap();
+    for i in 0..21 {
+        let exact = -(PI * lines.grid().point(i)).sin();
+        assert!(
+            (position[i] - exact).abs() < 2e-3,
+            "{} at {}",
+            position[i],
+            i
+        );
+    }
+}
+
+#[test]
+fn heat_equation_2d_test() {
+    // The product of the 1D modes decays with exp(-2 pi^2 t)
+    let grid = Grid2d::new(Grid1d::<11>::new(0.0, 1.0), Grid1d::<11>::new(0.0, 1.0));
+    let boundaries = Boundaries2d::new(
+        Boundaries::dirichlet(0.0, 0.0),
+        Boundaries::neumann(0.0, 0.0),
+    );
+    let lines = MethodOfLines2d::new(grid, boundaries);
+    let laplacian = lines.laplacian(2);
+    let initial_state: Vector<121> = lines.initial_state(|x, y| (PI * x).sin() * (PI * y).cos());
+    let solution = solve_ivp(
+        lines.system(move |_, u| laplacian.apply(u)),
+        (0.0, 0.05),
+        initial_state,
+        SolveOptions::new(),
+    );
+    assert!(solution.success());
+    let (_, state) = solution.last().unwrap();
+    let decay = (-2.0 * PI * PI * 0.05).exp();
+    for j in 0..11 {
+        assert_eq!(state[grid.index(0, j)], 0.0);
+        for i in 0..11 {
+            let exact = decay * initial_state[grid.index(i, j)];
+            assert!((state[grid.index(i, j)] - exact).abs() < 1e-2);
+        }
+    }
+}
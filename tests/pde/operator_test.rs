@@ -0,0 +1,119 @@
+use std::f32::consts::PI;
+
+use csl::{
+    linalg::ndarray::Vector,
+    pde::{
+        boundary::{Boundaries, Boundaries2d},
+        grid::{Grid1d, Grid2d},
+        operator::{Axis, Operator1d, Operator2d},
+    },
+};
+
+fn max_error<const N: usize>(approximation: &Vector<N>, exact: &Vector<N>) -> f32 {
+    (0..N)
+        .map(|i| (approximation[i] - exact[i]).abs())
+        .fold(0.0, f32::max)
+}
+
+// Errors of the operators applied to sin(x) on a periodic grid
+fn periodic_errors<const N: usize>(order: usize) -> (f32, f32, f32, f32) {
+    let grid = Grid1d::<N>::periodic(0.0, 2.0 * PI);
+    let boundaries = Boundaries::periodic();
+    let u = grid.sample(f32::sin);
+    let gradient = Operator1d::gradient(&grid, boundaries, order).apply(&u);
+    let laplacian = Operator1d::laplacian(&grid, boundaries, order).apply(&u);
+    let upwind_order = order / 2;
+    let forward = Operator1d::upwind(&grid, boundaries, 2.0, upwind_order).apply(&u);
+    let backward = Operator1d::upwind(&grid, boundaries, -2.0, upwind_order).apply(&u);
+    (
+        max_error(&gradient, &grid.sample(f32::cos)),
+        max_error(&laplacian, &(-u)),
+        max_error(&forward, &grid.sample(|x| 2.0 * x.cos())),
+        max_error(&backward, &grid.sample(|x| -2.0 * x.cos())),
+    )
+}
+
+#[test]
+fn convergence_order_test() {
+    for order in [2, 4, 6] {
+        let coarse = periodic_errors::<8>(order);
+        let fine = periodic_errors::<16>(order);
+        let central = 2f32.powi(order as i32);
+        assert!(
+            coarse.0 / fine.0 > 0.7 * central,
+            "gradient of order {}",
+            order
+        );
+        assert!(
+            coarse.1 / fine.1 > 0.7 * central,
+            "laplacian of order {}",
+            order
+        );
+        let upwind = 2f32.powi(order as i32 / 2);
+        assert!(
+            coarse.2 / fine.2 > 0.7 * upwind,
+            "upwind of order {}",
+            order / 2
+        );
+        assert!(
+            coarse.3 / fine.3 > 0.7 * upwind,
+            "upwind of order {}",
+            order / 2
+        );
+    }
+}
+
+#[test]
+fn boundary_conditions_test() {
+    let grid = Grid1d::<11>::new(0.0, 1.0);
+
+    // Odd reflection keeps linear functions linear
+    let boundaries = Boundaries::dirichlet(0.0, 1.0);
+    let u = grid.points();
+    for order in [2, 4] {
+        let gradient = Operator1d::gradient(&grid, boundaries, order).apply(&u);
+        let laplacian = Operator1d::laplacian(&grid, boundaries, order).apply(&u);
+        for i in 0..11 {
+            assert!((gradient[i] - 1.0).abs() < 1e-4);
+            assert!(laplacian[i].abs() < 1e-2);
+        }
+    }
+
+    // x^2 has the outward slopes 0 at x = 0 and 2 at x = 1, and the second
+    // order differences are exact for it
+    let boundaries = Boundaries::neumann(0.0, 2.0);
+    let u = grid.sample(|x| x * x);
+    let gradient = Operator1d::gradient(&grid, boundaries, 2).apply(&u);
+    let laplacian = Operator1d::laplacian(&grid, boundaries, 2).apply(&u);
+    for i in 0..11 {
+        assert!((gradient[i] - 2.0 * grid.point(i)).abs() < 1e-4);
+        assert!((laplacian[i] - 2.0).abs() < 1e-2);
+    }
+}
+
+#[test]
+fn operator_2d_test() {
+    let grid = Grid2d::new(
+        Grid1d::<16>::periodic(0.0, 2.0 * PI),
+        Grid1d::<20>::periodic(0.0, 2.0 * PI),
+    );
+    let boundaries = Boundaries2d::new(Boundaries::periodic(), Boundaries::periodic());
+    let u: Vector<320> = grid.sample(|x, y| x.sin() * (2.0 * y).cos());
+    assert_eq!(
+        u[grid.index(3, 2)],
+        grid.x.point(3).sin() * (2.0 * grid.y.point(2)).cos()
+    );
+
+    let laplacian = Operator2d::laplacian(&grid, boundaries, 6).apply(&u);
+    assert!(max_error(&laplacian, &(-5.0 * u)) < 1e-2);
+    let gradient_y = Operator2d::gradient(&grid, boundaries, Axis::Y, 6).apply(&u);
+    let exact_y: Vector<320> = grid.sample(|x, y| -2.0 * x.sin() * (2.0 * y).sin());
+    assert!(max_error(&gradient_y, &exact_y) < 1e-2);
+
+    // Advection-diffusion as a sum of operators
+    let combined = Operator2d::laplacian(&grid, boundaries, 4)
+        .plus(Operator2d::upwind(&grid, boundaries, (1.0, 0.0), 3))
+        .apply(&u);
+    let exact: Vector<320> = grid.sample(|x, y| (x.cos() - 5.0 * x.sin()) * (2.0 * y).cos());
+    assert!(max_error(&combined, &exact) < 0.1);
+}
@@ -0,0 +1,82 @@
+use csl::random::{
+    distributions::{
+        ln_gamma, Beta, Binomial, Distribution, Exponential, Gamma, Normal, Poisson, Uniform,
+    },
+    rng::SeedableRng,
+    xoshiro::Xoshiro256StarStar,
+};
+
+const SAMPLES: usize = 100_000;
+
+fn moments(samples: &[f64]) -> (f64, f64) {
+    let mean = samples.iter().sum::<f64>() / samples.len() as f64;
+    let variance =
+        samples.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (samples.len() - 1) as f64;
+    (mean, variance)
+}
+
+// Checks sample mean and variance against the exact values within a few standard errors
+fn check_moments(samples: Vec<f64>, mean: f64, variance: f64) {
+    let (sample_mean, sample_variance) = moments(&samples);
+    let tolerance = 5.0 * (variance / samples.len() as f64).sqrt();
+    assert!(
+        (sample_mean - mean).abs() < tolerance,
+        "mean {} != {}",
+        sample_mean,
+        mean
+    );
+    assert!(
+        (sample_variance - variance).abs() < 0.05 * variance,
+        "variance {} != {}",
+        sample_variance,
+        variance
+    );
+}
+
+#[test]
+fn continuous_distributions_test() {
+    let mut rng = Xoshiro256StarStar::seed_from_u64(1);
+
+    check_moments(
+        Uniform::new(-2.0, 4.0).sample_n(&mut rng, SAMPLES),
+        1.0,
+        3.0,
+    );
+    check_moments(Normal::new(3.0, 2.0).sample_n(&mut rng, SAMPLES), 3.0, 4.0);
+    check_moments(Exponential::new(0.5).sample_n(&mut rng, SAMPLES), 2.0, 4.0);
+    check_moments(Gamma::new(0.5, 2.0).sample_n(&mut rng, SAMPLES), 1.0, 2.0);
+    check_moments(Beta::new(2.0, 3.0).sample_n(&mut rng, SAMPLES), 0.4, 0.04);
+
+    let single: Vec<f32> = Normal::standard().sample_n(&mut rng, 10);
+    assert!(single.iter().all(|x| x.is_finite()));
+}
+
+#[test]
+fn discrete_distributions_test() {
+    let mut rng = Xoshiro256StarStar::seed_from_u64(2);
+    let as_f64 = |samples: Vec<u64>| samples.into_iter().map(|x| x as f64).collect();
+
+    for lambda in [3.5, 250.0] {
+        check_moments(
+            as_f64(Poisson::new(lambda).sample_n(&mut rng, SAMPLES)),
+            lambda,
+            lambda,
+        );
+    }
+    for (trials, probability) in [(20, 0.1), (1000, 0.3), (5000, 0.9)] {
+        let mean = trials as f64 * probability;
+        check_moments(
+            as_f64(Binomial::new(trials, probability).sample_n(&mut rng, SAMPLES)),
+            mean,
+            mean * (1.0 - probability),
+        );
+    }
+    assert_eq!(Binomial::new(10, 1.0).sample(&mut rng), 10);
+}
+
+#[test]
+fn ln_gamma_test() {
+    assert!(ln_gamma(1.0).abs() < 1e-12);
+    assert!((ln_gamma(5.0) - 24f64.ln()).abs() < 1e-12);
+    assert!((ln_gamma(0.5) - std::f64::consts::PI.sqrt().ln()).abs() < 1e-12);
+}
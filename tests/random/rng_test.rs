@@ -0,0 +1,109 @@
+use csl::{
+    linalg::ndarray::{Matrix, NdArray},
+    random::{
+        distributions::Uniform,
+        pcg::Pcg64,
+        rng::{Rng, SeedableRng, SplitMix64},
+        seq::{choice, choose_multiple, shuffle, weighted_index},
+        xoshiro::Xoshiro256StarStar,
+    },
+};
+
+#[test]
+fn xoshiro_reference_values_test() {
+    let mut rng = Xoshiro256StarStar::from_state([1, 2, 3, 4]);
+    let expected: [u64; 10] = [
+        11520,
+        0,
+        1509978240,
+        1215971899390074240,
+        1216172134540287360,
+        607988272756665600,
+        16172922978634559625,
+        8476171486693032832,
+        10595114339597558777,
+        2904607092377533576,
+    ];
+    for value in expected {
+        assert_eq!(rng.next_u64(), value);
+    }
+}
+
+#[test]
+fn pcg_reference_values_test() {
+    let mut rng = Pcg64::new(42, 54);
+    let expected: [u64; 6] = [
+        0x86b1da1d72062b68,
+        0x1304aa46c9853d39,
+        0xa3670e9e0dd50358,
+        0xf9090e529a7dae00,
+        0xc85b9fd837996f2c,
+        0x606121f8e3919196,
+    ];
+    for value in expected {
+        assert_eq!(rng.next_u64(), value);
+    }
+}
+
+#[test]
+fn splitmix_reference_value_test() {
+    assert_eq!(SplitMix64::new(0).next_u64(), 0xe220a8397b1dcdaf);
+}
+
+#[test]
+fn stream_splitting_test() {
+    let mut pcg = Pcg64::seed_from_u64(1);
+    let mut advanced = pcg.clone();
+    let skipped: Vec<u64> = (0..1000).map(|_| pcg.next_u64()).collect();
+    advanced.advance(1000);
+    assert_eq!(advanced.next_u64(), pcg.next_u64());
+    assert_ne!(skipped[0], skipped[1]);
+
+    let mut parent = Xoshiro256StarStar::seed_from_u64(7);
+    let mut first = parent.split();
+    let mut second = parent.split();
+    let mut jumped = first.clone();
+    jumped.jump();
+    assert_eq!(jumped, second);
+    assert_ne!(first.next_u64(), second.next_u64());
+
+    let mut pcg_stream = Pcg64::seed_from_u64(3).split();
+    let mut same = Pcg64::seed_from_u64(3).split();
+    assert_eq!(pcg_stream.next_u64(), same.next_u64());
+}
+
+#[test]
+fn sequence_helpers_test() {
+    let mut rng = Xoshiro256StarStar::seed_from_u64(11);
+
+    let mut values: Vec<u32> = (0..50).collect();
+    shuffle(&mut rng, &mut values);
+    assert_ne!(values, (0..50).collect::<Vec<u32>>());
+    values.sort_unstable();
+    assert_eq!(values, (0..50).collect::<Vec<u32>>());
+
+    assert!(choice(&mut rng, &[] as &[u32]).is_none());
+    assert!(values.contains(choice(&mut rng, &values).unwrap()));
+
+    let mut chosen = choose_multiple(&mut rng, &values, 10);
+    chosen.sort_unstable();
+    chosen.dedup();
+    assert_eq!(chosen.len(), 10);
+
+    for _ in 0..100 {
+        assert_ne!(weighted_index(&mut rng, &[1.0, 0.0, 3.0]), 1);
+    }
+}
+
+#[test]
+fn random_arrays_test() {
+    let distribution = Uniform::new(-1.0, 1.0);
+    let a: Matrix<3, 4> = Matrix::random(&mut Pcg64::seed_from_u64(5), &distribution);
+    let b: Matrix<3, 4> = Matrix::random(&mut Pcg64::seed_from_u64(5), &distribution);
+    assert_eq!(a, b);
+    assert!(a.data.iter().flatten().all(|x| (-1.0..1.0).contains(x)));
+
+    let array = NdArray::random(&[2, 3, 4], &mut Pcg64::seed_from_u64(5), &distribution);
+    assert_eq!(array.shape(), [2, 3, 4]);
+    assert_eq!(&array.data()[..12], NdArray::from(a).data());
+}